@@ -0,0 +1,58 @@
+//! Compares H3IndexSet with a HashSet for set operations over global res 4
+//! cell sets, such as the cells inside two large regions.
+
+use std::collections::HashSet;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use h3rs::{H3Index, H3IndexSet, Resolution};
+
+fn h3indexset(c: &mut Criterion) {
+    let res = Resolution::R4;
+    let cells: Vec<H3Index> = H3Index::getRes0Indexes()
+        .iter()
+        .flat_map(|h| h.h3ToChildren(res))
+        .collect();
+    // two overlapping halves of the globe
+    let a: Vec<H3Index> = cells.iter().copied().step_by(2).collect();
+    let b: Vec<H3Index> = cells.iter().copied().step_by(3).collect();
+
+    let hash_a: HashSet<H3Index> = a.iter().copied().collect();
+    let hash_b: HashSet<H3Index> = b.iter().copied().collect();
+    let dense_a = H3IndexSet::from_cells(res, a.iter().copied()).unwrap();
+    let dense_b = H3IndexSet::from_cells(res, b.iter().copied()).unwrap();
+
+    let mut group = c.benchmark_group("intersection");
+    group.bench_function("HashSet", |bench| {
+        bench.iter(|| black_box(&hash_a).intersection(&hash_b).count())
+    });
+    group.bench_function("H3IndexSet", |bench| {
+        bench.iter(|| {
+            let mut out = black_box(&dense_a).clone();
+            out.intersect_with(&dense_b).unwrap();
+            out.len()
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("contains");
+    group.bench_function("HashSet", |bench| {
+        bench.iter(|| {
+            cells
+                .iter()
+                .filter(|h| black_box(&hash_a).contains(h))
+                .count()
+        })
+    });
+    group.bench_function("H3IndexSet", |bench| {
+        bench.iter(|| {
+            cells
+                .iter()
+                .filter(|&&h| black_box(&dense_a).contains(h))
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, h3indexset);
+criterion_main!(benches);
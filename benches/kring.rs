@@ -0,0 +1,32 @@
+//! Compares kRing, which allocates its output on every call, with kRingInto
+//! reusing one buffer, for a hexagon origin (fast path) and a pentagon origin
+//! (fallback path).
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use h3rs::{Degrees, GeoCoord, H3Index, Resolution};
+
+fn kring(c: &mut Criterion) {
+    let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+    let origins = [
+        ("hexagon", sf.geoToH3(Resolution::R9)),
+        ("pentagon", Resolution::R9.getPentagonIndexes()[0]),
+    ];
+
+    for &(name, origin) in origins.iter() {
+        let mut group = c.benchmark_group(format!("kRing/{}", name));
+        for &k in [1, 5, 10].iter() {
+            group.bench_function(format!("alloc/k={}", k), |b| {
+                b.iter(|| black_box(origin).kRing(k))
+            });
+
+            let mut out: Vec<H3Index> = Vec::new();
+            group.bench_function(format!("reuse/k={}", k), |b| {
+                b.iter(|| black_box(origin).kRingInto(k, &mut out))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, kring);
+criterion_main!(benches);
@@ -0,0 +1,53 @@
+//! h3Distance and h3Line between cells on different base cells, where the
+//! offset between the base cells is scaled down to the cells' resolution.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use h3rs::{GeoCoord, H3Index, Radians, Resolution};
+
+/// An origin just inside a base cell's first vertex, and the cells
+/// around it on other base cells.
+fn across_base_cells(res: Resolution, k: u32) -> (H3Index, Vec<H3Index>) {
+    let base = H3Index::getRes0Indexes()[16];
+    let center = base.h3ToGeo();
+    let vertex = base.h3ToGeoBoundary().verts[0];
+    let near = GeoCoord::new(
+        Radians(vertex.lat + (center.lat - vertex.lat) * 1e-8),
+        Radians(vertex.lon + (center.lon - vertex.lon) * 1e-8),
+    );
+    let origin = near.geoToH3(res);
+    let destinations = origin
+        .kRing(k)
+        .into_iter()
+        .filter(|h| h.h3ToParent(Resolution::R0) != Ok(base))
+        .collect();
+    (origin, destinations)
+}
+
+fn localij(c: &mut Criterion) {
+    for &res in [Resolution::R9, Resolution::R15].iter() {
+        let (origin, destinations) = across_base_cells(res, 10);
+        assert!(!destinations.is_empty(), "no cells across base cells");
+
+        let mut group = c.benchmark_group(format!("localij/{:?}", res));
+        group.bench_function("h3Distance", |b| {
+            b.iter(|| {
+                destinations
+                    .iter()
+                    .map(|h| black_box(origin).h3Distance(h).unwrap_or(-1))
+                    .sum::<i32>()
+            })
+        });
+        group.bench_function("h3Line", |b| {
+            b.iter(|| {
+                destinations
+                    .iter()
+                    .map(|&h| H3Index::h3Line(black_box(origin), h).map_or(0, |l| l.len()))
+                    .sum::<usize>()
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, localij);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+//! Compares h3IndexesAreNeighbors, which checks the local IJ distance, with
+//! searching the kRing it previously built, for destinations outside the
+//! origin's parent (where the digit lookup does not apply).
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use h3rs::{Degrees, GeoCoord, H3Index, Resolution};
+
+fn neighbors(c: &mut Criterion) {
+    let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+    let origin = sf.geoToH3(Resolution::R9);
+    let parent = origin.h3ToParent(Resolution::R8).unwrap();
+    let destinations: Vec<H3Index> = origin
+        .grid_collar(0, 2)
+        .unwrap()
+        .into_iter()
+        .filter(|h| h.h3ToParent(Resolution::R8) != Ok(parent))
+        .collect();
+
+    let mut group = c.benchmark_group("h3IndexesAreNeighbors");
+    group.bench_function("localIj", |b| {
+        b.iter(|| {
+            destinations
+                .iter()
+                .filter(|&&h| black_box(origin).h3IndexesAreNeighbors(h))
+                .count()
+        })
+    });
+    group.bench_function("kRing", |b| {
+        b.iter(|| {
+            destinations
+                .iter()
+                .filter(|h| black_box(origin).kRing(1).contains(h))
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, neighbors);
+criterion_main!(benches);
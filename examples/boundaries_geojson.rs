@@ -0,0 +1,47 @@
+//! Writes the cells around a point as a GeoJSON FeatureCollection, one
+//! polygon per cell, to paste into a map viewer such as geojson.io.
+//!
+//! ```text
+//! cargo run --example boundaries_geojson > cells.geojson
+//! cargo run --example boundaries_geojson -- 37.7793 -122.4193 8 2
+//! ```
+
+use h3rs::prelude::*;
+use serde_json::json;
+
+fn main() {
+    let args: Vec<f64> = std::env::args()
+        .skip(1)
+        .map(|a| a.parse().expect("a number"))
+        .collect();
+    let (lat, lng, res, k) = match args.as_slice() {
+        &[lat, lng, res, k] => (lat, lng, res as usize, k as u32),
+        [] => (37.779265, -122.419277, 8, 2),
+        _ => panic!("usage: boundaries_geojson [LAT LNG RES K]"),
+    };
+    assert!(res <= 15, "resolution 0 to 15");
+
+    let origin = GeoCoord::new(Degrees(lat), Degrees(lng)).geoToH3(Resolution::from(res));
+    let mut cells = origin.kRingDistances(k);
+    // the same file on every run
+    cells.sort_by_key(|&(h, d)| (d, u64::from(h)));
+
+    let features: Vec<_> = cells
+        .iter()
+        .map(|&(h, distance)| {
+            let ring = h.h3ToGeoBoundary().to_lng_lat_ring();
+            assert_eq!(ring.first(), ring.last(), "GeoJSON rings are closed");
+            json!({
+                "type": "Feature",
+                "properties": { "h3": h.to_string(), "distance": distance },
+                "geometry": { "type": "Polygon", "coordinates": [ring] },
+            })
+        })
+        .collect();
+
+    let collection = json!({ "type": "FeatureCollection", "features": features });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&collection).expect("serializable")
+    );
+}
@@ -0,0 +1,54 @@
+//! Indexes points to cells, and shows each cell's parents and center.
+//!
+//! ```text
+//! cargo run --example geocode                    # a few sample points
+//! cargo run --example geocode -- 37.7793 -122.4193 9
+//! ```
+
+use h3rs::prelude::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (points, res) = match args.as_slice() {
+        [lat, lng, res] => (
+            vec![(
+                lat.parse().expect("latitude"),
+                lng.parse().expect("longitude"),
+            )],
+            res.parse::<usize>().expect("resolution 0 to 15"),
+        ),
+        [] => (
+            vec![
+                (37.779265, -122.419277),
+                (40.689167, -74.044444),
+                (-33.8568, 151.2153),
+            ],
+            9,
+        ),
+        _ => panic!("usage: geocode [LAT LNG RES]"),
+    };
+    assert!(res <= 15, "resolution 0 to 15");
+    let res = Resolution::from(res);
+
+    for (lat, lng) in points {
+        let point = GeoCoord::new(Degrees(lat), Degrees(lng));
+        let cell = point.geoToH3(res);
+        assert!(cell.is_valid(), "points index to valid cells");
+
+        let (center_lng, center_lat) = cell.h3ToGeo().lng_lat();
+        println!("({}, {}) -> {} at {:?}", lat, lng, cell, res);
+        println!(
+            "  center ({:.6}, {:.6}), {:.4} km2",
+            center_lat.0,
+            center_lng.0,
+            cell.cellAreaKm2()
+        );
+        for parent_res in Resolution::RESOLUTIONS.iter().filter(|&&r| r < res) {
+            let parent = cell.h3ToParent(*parent_res).expect("coarser resolution");
+            println!("  parent at {:?}: {}", parent_res, parent);
+        }
+
+        let parsed: H3Index = cell.to_string().parse().expect("round trip");
+        assert_eq!(parsed, cell, "the string form parses back");
+    }
+}
@@ -0,0 +1,78 @@
+//! Bins points into cells and spreads each cell's count over its kRing, the
+//! usual first step of a hexbin heatmap, then prints the hottest cells.
+//!
+//! ```text
+//! cargo run --example kring_heatmap
+//! ```
+
+use std::collections::HashMap;
+
+use h3rs::prelude::*;
+
+/// Points scattered around a few hot spots, from a fixed seed so every run
+/// prints the same map.
+fn sample_points(n: usize) -> Vec<GeoCoord> {
+    let spots = [
+        (37.7793, -122.4193),
+        (37.8044, -122.2712),
+        (37.3382, -121.8863),
+    ];
+    let mut state: u64 = 0x853c_49e6_748f_ea9b;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    };
+
+    (0..n)
+        .map(|i| {
+            let (lat, lng) = spots[i % spots.len()];
+            // within about 10 km, denser toward the spot
+            let spread = 0.1 * next().abs();
+            GeoCoord::new(
+                Degrees(lat + spread * next()),
+                Degrees(lng + spread * next()),
+            )
+        })
+        .collect()
+}
+
+fn main() {
+    let res = Resolution::R7;
+    let k = 2;
+
+    let mut counts: HashMap<H3Index, u32> = HashMap::new();
+    for point in sample_points(10_000) {
+        *counts.entry(point.geoToH3(res)).or_insert(0) += 1;
+    }
+    let total: u32 = counts.values().sum();
+    assert_eq!(total, 10_000, "every point lands in a cell");
+
+    // each count spreads to the cells around it, weighted by grid distance
+    let mut heat: HashMap<H3Index, f64> = HashMap::new();
+    for (&cell, &count) in counts.iter() {
+        for (neighbor, distance) in cell.kRingDistances(k) {
+            *heat.entry(neighbor).or_insert(0.0) += count as f64 / (1 + distance) as f64;
+        }
+    }
+
+    let mut hottest: Vec<(H3Index, f64)> = heat.into_iter().collect();
+    hottest.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap()
+            .then(u64::from(a.0).cmp(&u64::from(b.0)))
+    });
+
+    println!(
+        "{} points in {} cells at {:?}, smoothed over kRing({})",
+        total,
+        counts.len(),
+        res,
+        k
+    );
+    for (cell, value) in hottest.iter().take(10) {
+        let (lng, lat) = cell.h3ToGeo().lng_lat();
+        println!("{}  ({:.4}, {:.4})  {:.1}", cell, lat.0, lng.0, value);
+    }
+}
@@ -0,0 +1,78 @@
+//! Covers a GeoJSON polygon with cells, and prints the covering's size and
+//! area.
+//!
+//! ```text
+//! cargo run --example polyfill_geojson                 # a polygon of SF
+//! cargo run --example polyfill_geojson -- polygon.geojson 9
+//! ```
+
+use h3rs::prelude::*;
+use serde_json::Value;
+
+/// Golden Gate Park, with a hole in the middle of it.
+const SAMPLE: &str = r#"{
+  "type": "Polygon",
+  "coordinates": [
+    [[-122.511, 37.771], [-122.454, 37.773], [-122.454, 37.766],
+     [-122.511, 37.764], [-122.511, 37.771]],
+    [[-122.490, 37.770], [-122.480, 37.770], [-122.480, 37.767],
+     [-122.490, 37.767], [-122.490, 37.770]]
+  ]
+}"#;
+
+/// Reads the rings of a GeoJSON Polygon, or of a Feature holding one.
+fn read_polygon(geojson: &Value) -> Vec<Geofence> {
+    let geometry = match geojson["type"].as_str() {
+        Some("Feature") => &geojson["geometry"],
+        _ => geojson,
+    };
+    assert_eq!(geometry["type"], "Polygon", "a GeoJSON Polygon");
+
+    geometry["coordinates"]
+        .as_array()
+        .expect("coordinates")
+        .iter()
+        .map(|ring| {
+            let positions = ring.as_array().expect("a ring").iter().map(|p| {
+                [
+                    p[0].as_f64().expect("longitude"),
+                    p[1].as_f64().expect("latitude"),
+                ]
+            });
+            Geofence::from_lng_lat_ring(positions).expect("a valid ring")
+        })
+        .collect()
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (text, res) = match args.as_slice() {
+        [path, res] => (
+            std::fs::read_to_string(path).expect("readable file"),
+            res.parse::<usize>().expect("resolution 0 to 15"),
+        ),
+        [] => (SAMPLE.to_string(), 10),
+        _ => panic!("usage: polyfill_geojson [FILE RES]"),
+    };
+    assert!(res <= 15, "resolution 0 to 15");
+    let res = Resolution::from(res);
+
+    let mut rings = read_polygon(&serde_json::from_str(&text).expect("valid JSON"));
+    let exterior = rings.remove(0);
+    let polygon = GeoPolygon::from_geofences(exterior, rings).expect("holes inside");
+
+    let cells = polygon.polyfill(res);
+    assert!(
+        cells.len() <= polygon.maxPolyfillSize(res),
+        "within the bound"
+    );
+
+    let cells_km2: f64 = cells.iter().map(|h| h.cellAreaKm2()).sum();
+    println!("{} cells at {:?}, {:.3} km2", cells.len(), res, cells_km2);
+    for h in cells.iter().take(5) {
+        println!("  {}", h);
+    }
+    if cells.len() > 5 {
+        println!("  ...");
+    }
+}
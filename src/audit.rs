@@ -0,0 +1,223 @@
+//! Integrity checks for H3 ids read back from storage.
+
+use std::fmt;
+
+use crate::{H3Index, H3Mode, Resolution};
+
+/**
+ * A summary of a column of H3 ids, from `audit_ids`.
+ *
+ * Every id is counted in `modes` by the raw value of its mode bits, whether
+ * it is valid or not. Valid cells, unidirectional edges and vertexes are
+ * counted by kind and by resolution; anything else, including ids with stray
+ * high or reserved bits or an undefined mode, is counted as invalid.
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuditReport {
+    /// Number of ids audited.
+    pub total: usize,
+    /// Ids by the value of their 4 mode bits, valid or not; 1 is cells, 2
+    /// unidirectional edges and 4 vertexes.
+    pub modes: [usize; 16],
+    /// Valid cells.
+    pub cells: usize,
+    /// Valid cells that are pentagons.
+    pub pentagons: usize,
+    /// Valid unidirectional edges.
+    pub edges: usize,
+    /// Valid vertexes.
+    pub vertexes: usize,
+    /// Valid ids of any kind by resolution.
+    pub resolutions: [usize; Resolution::MAX_H3_RES + 1],
+    /// Ids that are not a valid cell, edge or vertex.
+    pub invalid: usize,
+    /// Position and value of the first invalid ids, up to
+    /// `MAX_INVALID_SAMPLES`, to track down where they came from.
+    pub invalid_samples: Vec<(usize, u64)>,
+}
+
+impl AuditReport {
+    /// Most invalid ids kept in `invalid_samples`.
+    pub const MAX_INVALID_SAMPLES: usize = 32;
+
+    /// Whether every id is a valid cell, edge or vertex.
+    pub fn is_clean(&self) -> bool {
+        self.invalid == 0
+    }
+
+    /// Counts one id.
+    fn add(&mut self, position: usize, id: u64) {
+        let h = H3Index::from(id);
+        self.total += 1;
+        self.modes[(id >> 59) as usize & 15] += 1;
+
+        let valid = match h.get_mode() {
+            H3Mode::H3_HEXAGON_MODE if h.is_valid() => {
+                self.cells += 1;
+                self.pentagons += h.is_pentagon() as usize;
+                true
+            }
+            H3Mode::H3_UNIEDGE_MODE if h.h3UnidirectionalEdgeIsValid() => {
+                self.edges += 1;
+                true
+            }
+            H3Mode::H3_VERTEX_MODE if h.isValidVertex() => {
+                self.vertexes += 1;
+                true
+            }
+            _ => false,
+        };
+
+        if valid {
+            self.resolutions[usize::from(h.get_resolution())] += 1;
+        } else {
+            self.invalid += 1;
+            if self.invalid_samples.len() < Self::MAX_INVALID_SAMPLES {
+                self.invalid_samples.push((position, id));
+            }
+        }
+    }
+}
+
+impl fmt::Display for AuditReport {
+    /// A few lines for a log: the totals, then the resolutions in use.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} ids: {} cells ({} pentagons), {} edges, {} vertexes, {} invalid",
+            self.total, self.cells, self.pentagons, self.edges, self.vertexes, self.invalid
+        )?;
+        for (res, &n) in self.resolutions.iter().enumerate().filter(|(_, &n)| n > 0) {
+            writeln!(f, "  res {:2}: {}", res, n)?;
+        }
+        if let Some(&(position, id)) = self.invalid_samples.first() {
+            writeln!(f, "  first invalid: {:x} at {}", id, position)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Checks a column of H3 ids, as loaded from storage written by this or any
+ * other H3 library, in one pass.
+ *
+ * Ids written by a newer H3 version, by a library that misuses the reserved
+ * bits, or by a bad cast from a signed column show up as invalid, with their
+ * positions in the report's samples.
+ *
+ * ```
+ * use h3rs::{audit_ids, prelude::*};
+ *
+ * let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+ * let column = vec![u64::from(sf.geoToH3(Resolution::R9)), 0, 0x8f28308280f18f2];
+ *
+ * let report = audit_ids(&column);
+ * assert_eq!(report.cells, 2);
+ * assert_eq!(report.resolutions[9], 1);
+ * assert_eq!(report.invalid_samples, vec![(1, 0)]);
+ * ```
+ *
+ * @param ids The raw ids
+ * @return Counts by mode, kind and resolution, and the invalid ids
+ */
+pub fn audit_ids(ids: &[u64]) -> AuditReport {
+    let mut report = AuditReport::default();
+    for (position, &id) in ids.iter().enumerate() {
+        report.add(position, id);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, GeoCoord};
+
+    #[test]
+    fn auditIds_kinds() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let cell = sf.geoToH3(Resolution::R9);
+        let pentagons = Resolution::R3.getPentagonIndexes();
+        let vertex = cell.cellToVertex(0);
+        let mut edge = cell;
+        edge.set_mode(H3Mode::H3_UNIEDGE_MODE);
+        edge.set_reserved_bits(Direction::J_AXES_DIGIT as u64);
+
+        let mut ids: Vec<u64> = pentagons.iter().map(|&p| u64::from(p)).collect();
+        ids.extend(
+            sf.geoToH3(Resolution::R3)
+                .kRing(1)
+                .into_iter()
+                .map(u64::from),
+        );
+        ids.extend([u64::from(cell), u64::from(edge), u64::from(vertex)].iter());
+
+        let report = audit_ids(&ids);
+        assert!(report.is_clean(), "{}", report);
+        assert_eq!(report.total, 22, "total");
+        assert_eq!(report.cells, 20, "cells");
+        assert_eq!(report.pentagons, 12, "pentagons");
+        assert_eq!((report.edges, report.vertexes), (1, 1), "edge and vertex");
+        assert_eq!(report.resolutions[3], 19, "res 3");
+        assert_eq!(report.resolutions[9], 3, "res 9");
+        assert_eq!(report.resolutions.iter().sum::<usize>(), 22, "every id");
+        assert_eq!(
+            (report.modes[1], report.modes[2], report.modes[4]),
+            (20, 1, 1)
+        );
+        assert_eq!(report.modes.iter().sum::<usize>(), 22, "every id");
+        assert_eq!(audit_ids(&[]), AuditReport::default(), "empty");
+    }
+
+    #[test]
+    fn auditIds_invalid() {
+        let cell = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+        let mut reserved = cell;
+        reserved.set_reserved_bits(1);
+        let mut highBit = cell;
+        highBit.set_high_bit(1);
+        let mut badDigit = cell;
+        badDigit.set_index_digit(Resolution::R10, 0);
+        let mut undirected = cell;
+        undirected.set_mode(H3Mode::H3_EDGE_MODE);
+
+        let bad = [
+            0,
+            u64::from(reserved),
+            u64::from(highBit),
+            u64::from(badDigit),
+            u64::from(undirected),
+            u64::MAX,
+        ];
+        let mut ids = vec![u64::from(cell)];
+        ids.extend(bad.iter());
+
+        let report = audit_ids(&ids);
+        assert!(!report.is_clean(), "dirty");
+        assert_eq!(report.cells, 1, "one good cell");
+        assert_eq!(report.invalid, bad.len(), "the rest");
+        assert_eq!(
+            report.invalid_samples,
+            bad.iter()
+                .enumerate()
+                .map(|(i, &id)| (i + 1, id))
+                .collect::<Vec<_>>(),
+            "positions"
+        );
+        assert_eq!(report.resolutions[9], 1, "only valid ids by resolution");
+        assert_eq!(report.modes[1], 4, "mode bits of the cell and broken cells");
+        assert_eq!(
+            (report.modes[0], report.modes[3], report.modes[15]),
+            (1, 1, 1)
+        );
+
+        let many = vec![0u64; 100];
+        let report = audit_ids(&many);
+        assert_eq!(report.invalid, 100, "all counted");
+        assert_eq!(
+            report.invalid_samples.len(),
+            AuditReport::MAX_INVALID_SAMPLES,
+            "samples capped"
+        );
+    }
+}
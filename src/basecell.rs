@@ -1,22 +1,58 @@
 use crate::{
     basecellrotation::faceIjkBaseCells, constants::NUM_ICOSA_FACES, faceijk::FaceIJK, Direction,
+    H3Error,
 };
 
+/// One of the 122 resolution 0 cells, numbered from 0 to `BaseCell::MAX`.
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub struct BaseCell(pub(crate) i32);
+pub struct BaseCell(i32);
 
 const INVALID_ROTATIONS: i32 = -1;
 
 impl BaseCell {
     /// The number of H3 base cells
-    pub(crate) const NUM_BASE_CELLS: usize = 122;
+    pub const NUM_BASE_CELLS: usize = 122;
+
+    /// The highest numbered base cell
+    pub const MAX: BaseCell = BaseCell(Self::NUM_BASE_CELLS as i32 - 1);
 
     pub(crate) const INVALID: BaseCell = BaseCell(-1);
 
-    pub(crate) const fn new(cellnum: i32) -> Self {
+    /**
+     * Checks a base cell number.
+     *
+     * @param cellnum The base cell number
+     * @return The base cell, or Domain if cellnum is not between 0 and 121
+     */
+    pub fn new(cellnum: i32) -> Result<Self, H3Error> {
+        if (0..=Self::MAX.0).contains(&cellnum) {
+            Ok(Self(cellnum))
+        } else {
+            Err(H3Error::Domain)
+        }
+    }
+
+    /// Wraps a base cell number without checking it, for table entries and
+    /// values read from index bits, which may be past the last base cell.
+    pub(crate) const fn new_unchecked(cellnum: i32) -> Self {
         Self(cellnum)
     }
 
+    /// Position of the base cell in the lookup tables, if it is in range.
+    #[inline]
+    pub(crate) fn index(&self) -> Option<usize> {
+        if (0..=Self::MAX.0).contains(&self.0) {
+            Some(self.0 as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Data for the base cell, if it is in range.
+    fn data(&self) -> Option<&'static BaseCellData> {
+        self.index().map(|i| &baseCellData[i])
+    }
+
     /*
         int H3_EXPORT(h3IsPentagon)(H3Index h) {
         return _isBaseCellPentagon(H3_GET_BASE_CELL(h)) &&
@@ -35,13 +71,8 @@ impl BaseCell {
 
     /// Return whether or not the indicated base cell is a pentagon.
     pub(crate) fn _isBaseCellPentagon(&self) -> bool {
-        if self.0 < 0 || self.0 >= Self::NUM_BASE_CELLS as i32 {
-            // LCOV_EXCL_BR_LINE
-            // Base cells less than zero can not be represented in an index
-            return false;
-        }
-
-        baseCellData[self.0 as usize].cwOffsetPent.is_some()
+        // Base cells less than zero can not be represented in an index
+        self.data().is_some_and(|d| d.cwOffsetPent.is_some())
     }
 
     /// Return whether the indicated base cell is a pentagon where all neighbors are oriented towards it.
@@ -49,18 +80,23 @@ impl BaseCell {
         self.0 == 4 || self.0 == 117
     }
 
-    /// Return the neighboring base cell in the given direction.
-    pub(crate) fn _getBaseCellNeighbor(&self, dir: &Direction) -> BaseCell {
-        let d: u64 = (*dir).into();
-        baseCellNeighbors[self.0 as usize][d as usize]
+    /// Return the neighboring base cell in the given direction, or None for a
+    /// pentagon's deleted k direction, INVALID_DIGIT, or a base cell that is
+    /// out of range.
+    pub(crate) fn _getBaseCellNeighbor(&self, dir: &Direction) -> Option<BaseCell> {
+        let neighbor = *baseCellNeighbors[self.index()?].get(*dir as usize)?;
+        if neighbor == BaseCell::INVALID {
+            None
+        } else {
+            Some(neighbor)
+        }
     }
 
     /// Return the direction from the origin base cell to the neighbor.
     /// Returns INVALID_DIGIT if the base cells are not neighbors.
     pub(crate) fn _getBaseCellDirection(&self, neighboringBaseCell: BaseCell) -> Direction {
         for dir in Direction::VALID_DIRECTIONS.iter() {
-            let testBaseCell: BaseCell = self._getBaseCellNeighbor(dir);
-            if testBaseCell == neighboringBaseCell {
+            if self._getBaseCellNeighbor(dir) == Some(neighboringBaseCell) {
                 return *dir;
             }
         }
@@ -76,7 +112,7 @@ impl BaseCell {
      *          cell is not found on the given face
      */
     pub(crate) fn _baseCellToCCWrot60(&self, face: usize) -> i32 {
-        if face > NUM_ICOSA_FACES {
+        if face >= NUM_ICOSA_FACES {
             return INVALID_ROTATIONS;
         }
 
@@ -97,33 +133,29 @@ impl BaseCell {
     pub(crate) fn _baseCellIsCwOffset(&self, testface: &FaceIJK) -> bool {
         //baseCellData[self.0 as usize].cwOffsetPent[0] == testFace
         //    || baseCellData[self.0 as usize].cwOffsetPent[1] == testFace
-        if let Some(bcd) = baseCellData[self.0 as usize].cwOffsetPent {
+        if let Some(bcd) = self.data().and_then(|d| d.cwOffsetPent) {
             bcd[0] == testface.face || bcd[1] == testface.face
         } else {
             false
         }
     }
 
-    /// Find the FaceIJK given a base cell.
-    pub(crate) fn _baseCellToFaceIjk(&self) -> FaceIJK {
-        baseCellData[self.0 as usize].homeFijk
+    /// Find the FaceIJK given a base cell, or CellInvalid if it is out of range.
+    pub(crate) fn _baseCellToFaceIjk(&self) -> Result<FaceIJK, H3Error> {
+        self.data().map(|d| d.homeFijk).ok_or(H3Error::CellInvalid)
     }
 }
 
+impl From<BaseCell> for i32 {
+    fn from(bc: BaseCell) -> i32 {
+        bc.0
+    }
+}
+
+// no conversion to usize, which would turn INVALID into a huge table index;
+// use index() instead
 macro_rules! basecell_impl {
     ($t: ty) => {
-        impl From<BaseCell> for $t {
-            fn from(bc: BaseCell) -> $t {
-                bc.0 as $t
-            }
-        }
-
-        impl From<$t> for BaseCell {
-            fn from(n: $t) -> Self {
-                BaseCell(n as i32)
-            }
-        }
-
         impl std::cmp::PartialEq<$t> for BaseCell {
             fn eq(&self, other: &$t) -> bool {
                 self.0.eq(&(*other as i32))
@@ -439,129 +471,129 @@ pub(crate) const baseCellNeighbors: [[BaseCell; 7]; BaseCell::NUM_BASE_CELLS] =
  * CCW rotations to the coordinate system of the neighbor is given.
  * -1 indicates there is no neighbor in that direction.
  */
-pub(crate) const baseCellNeighbor60CCWRots: [[BaseCell; 7]; BaseCell::NUM_BASE_CELLS] = [
-    bc7![0, 5, 0, 0, 1, 5, 1],  // base cell 0
-    bc7![0, 0, 1, 0, 1, 0, 1],  // base cell 1
-    bc7![0, 0, 0, 0, 0, 5, 0],  // base cell 2
-    bc7![0, 5, 0, 0, 2, 5, 1],  // base cell 3
-    bc7![0, -1, 1, 0, 3, 4, 2], // base cell 4 (pentagon)
-    bc7![0, 0, 1, 0, 1, 0, 1],  // base cell 5
-    bc7![0, 0, 0, 3, 5, 5, 0],  // base cell 6
-    bc7![0, 0, 0, 0, 0, 5, 0],  // base cell 7
-    bc7![0, 5, 0, 0, 0, 5, 1],  // base cell 8
-    bc7![0, 0, 1, 3, 0, 0, 1],  // base cell 9
-    bc7![0, 0, 1, 3, 0, 0, 1],  // base cell 10
-    bc7![0, 3, 3, 3, 0, 0, 0],  // base cell 11
-    bc7![0, 5, 0, 0, 3, 5, 1],  // base cell 12
-    bc7![0, 0, 1, 0, 1, 0, 1],  // base cell 13
-    bc7![0, -1, 3, 0, 5, 2, 0], // base cell 14 (pentagon)
-    bc7![0, 5, 0, 0, 4, 5, 1],  // base cell 15
-    bc7![0, 0, 0, 0, 0, 5, 0],  // base cell 16
-    bc7![0, 3, 3, 3, 3, 0, 3],  // base cell 17
-    bc7![0, 0, 0, 3, 5, 5, 0],  // base cell 18
-    bc7![0, 3, 3, 3, 0, 0, 0],  // base cell 19
-    bc7![0, 3, 3, 3, 0, 3, 0],  // base cell 20
-    bc7![0, 0, 0, 3, 5, 5, 0],  // base cell 21
-    bc7![0, 0, 1, 0, 1, 0, 1],  // base cell 22
-    bc7![0, 3, 3, 3, 0, 3, 0],  // base cell 23
-    bc7![0, -1, 3, 0, 5, 2, 0], // base cell 24 (pentagon)
-    bc7![0, 0, 0, 3, 0, 0, 3],  // base cell 25
-    bc7![0, 0, 0, 0, 0, 5, 0],  // base cell 26
-    bc7![0, 3, 0, 0, 0, 3, 3],  // base cell 27
-    bc7![0, 0, 1, 0, 1, 0, 1],  // base cell 28
-    bc7![0, 0, 1, 3, 0, 0, 1],  // base cell 29
-    bc7![0, 3, 3, 3, 0, 0, 0],  // base cell 30
-    bc7![0, 0, 0, 0, 0, 5, 0],  // base cell 31
-    bc7![0, 3, 3, 3, 3, 0, 3],  // base cell 32
-    bc7![0, 0, 1, 3, 0, 0, 1],  // base cell 33
-    bc7![0, 3, 3, 3, 3, 0, 3],  // base cell 34
-    bc7![0, 0, 3, 0, 3, 0, 3],  // base cell 35
-    bc7![0, 0, 0, 3, 0, 0, 3],  // base cell 36
-    bc7![0, 3, 0, 0, 0, 3, 3],  // base cell 37
-    bc7![0, -1, 3, 0, 5, 2, 0], // base cell 38 (pentagon)
-    bc7![0, 3, 0, 0, 3, 3, 0],  // base cell 39
-    bc7![0, 3, 0, 0, 3, 3, 0],  // base cell 40
-    bc7![0, 0, 0, 3, 5, 5, 0],  // base cell 41
-    bc7![0, 0, 0, 3, 5, 5, 0],  // base cell 42
-    bc7![0, 3, 3, 3, 0, 0, 0],  // base cell 43
-    bc7![0, 0, 1, 3, 0, 0, 1],  // base cell 44
-    bc7![0, 0, 3, 0, 0, 3, 3],  // base cell 45
-    bc7![0, 0, 0, 3, 0, 3, 0],  // base cell 46
-    bc7![0, 3, 3, 3, 0, 3, 0],  // base cell 47
-    bc7![0, 3, 3, 3, 0, 3, 0],  // base cell 48
-    bc7![0, -1, 3, 0, 5, 2, 0], // base cell 49 (pentagon)
-    bc7![0, 0, 0, 3, 0, 0, 3],  // base cell 50
-    bc7![0, 3, 0, 0, 0, 3, 3],  // base cell 51
-    bc7![0, 0, 3, 0, 3, 0, 3],  // base cell 52
-    bc7![0, 3, 3, 3, 0, 0, 0],  // base cell 53
-    bc7![0, 0, 3, 0, 3, 0, 3],  // base cell 54
-    bc7![0, 0, 3, 0, 0, 3, 3],  // base cell 55
-    bc7![0, 3, 3, 3, 0, 0, 3],  // base cell 56
-    bc7![0, 0, 0, 3, 0, 3, 0],  // base cell 57
-    bc7![0, -1, 3, 0, 5, 2, 0], // base cell 58 (pentagon)
-    bc7![0, 3, 3, 3, 3, 3, 0],  // base cell 59
-    bc7![0, 3, 3, 3, 3, 3, 0],  // base cell 60
-    bc7![0, 3, 3, 3, 3, 0, 3],  // base cell 61
-    bc7![0, 3, 3, 3, 3, 0, 3],  // base cell 62
-    bc7![0, -1, 3, 0, 5, 2, 0], // base cell 63 (pentagon)
-    bc7![0, 0, 0, 3, 0, 0, 3],  // base cell 64
-    bc7![0, 3, 3, 3, 0, 3, 0],  // base cell 65
-    bc7![0, 3, 0, 0, 0, 3, 3],  // base cell 66
-    bc7![0, 3, 0, 0, 3, 3, 0],  // base cell 67
-    bc7![0, 3, 3, 3, 0, 0, 0],  // base cell 68
-    bc7![0, 3, 0, 0, 3, 3, 0],  // base cell 69
-    bc7![0, 0, 3, 0, 0, 3, 3],  // base cell 70
-    bc7![0, 0, 0, 3, 0, 3, 0],  // base cell 71
-    bc7![0, -1, 3, 0, 5, 2, 0], // base cell 72 (pentagon)
-    bc7![0, 3, 3, 3, 0, 0, 3],  // base cell 73
-    bc7![0, 3, 3, 3, 0, 0, 3],  // base cell 74
-    bc7![0, 0, 0, 3, 0, 0, 3],  // base cell 75
-    bc7![0, 3, 0, 0, 0, 3, 3],  // base cell 76
-    bc7![0, 0, 0, 3, 0, 5, 0],  // base cell 77
-    bc7![0, 3, 3, 3, 0, 0, 0],  // base cell 78
-    bc7![0, 0, 1, 3, 1, 0, 1],  // base cell 79
-    bc7![0, 0, 1, 3, 1, 0, 1],  // base cell 80
-    bc7![0, 0, 3, 0, 3, 0, 3],  // base cell 81
-    bc7![0, 0, 3, 0, 3, 0, 3],  // base cell 82
-    bc7![0, -1, 3, 0, 5, 2, 0], // base cell 83 (pentagon)
-    bc7![0, 0, 3, 0, 0, 3, 3],  // base cell 84
-    bc7![0, 0, 0, 3, 0, 3, 0],  // base cell 85
-    bc7![0, 3, 0, 0, 3, 3, 0],  // base cell 86
-    bc7![0, 3, 3, 3, 3, 3, 0],  // base cell 87
-    bc7![0, 0, 0, 3, 0, 5, 0],  // base cell 88
-    bc7![0, 3, 3, 3, 3, 3, 0],  // base cell 89
-    bc7![0, 0, 0, 0, 0, 0, 1],  // base cell 90
-    bc7![0, 3, 3, 3, 0, 0, 0],  // base cell 91
-    bc7![0, 0, 0, 3, 0, 5, 0],  // base cell 92
-    bc7![0, 5, 0, 0, 5, 5, 0],  // base cell 93
-    bc7![0, 0, 3, 0, 0, 3, 3],  // base cell 94
-    bc7![0, 0, 0, 0, 0, 0, 1],  // base cell 95
-    bc7![0, 0, 0, 3, 0, 3, 0],  // base cell 96
-    bc7![0, -1, 3, 0, 5, 2, 0], // base cell 97 (pentagon)
-    bc7![0, 3, 3, 3, 0, 0, 3],  // base cell 98
-    bc7![0, 5, 0, 0, 5, 5, 0],  // base cell 99
-    bc7![0, 0, 1, 3, 1, 0, 1],  // base cell 100
-    bc7![0, 3, 3, 3, 0, 0, 3],  // base cell 101
-    bc7![0, 3, 3, 3, 0, 0, 0],  // base cell 102
-    bc7![0, 0, 1, 3, 1, 0, 1],  // base cell 103
-    bc7![0, 3, 3, 3, 3, 3, 0],  // base cell 104
-    bc7![0, 0, 0, 0, 0, 0, 1],  // base cell 105
-    bc7![0, 0, 1, 0, 3, 5, 1],  // base cell 106
-    bc7![0, -1, 3, 0, 5, 2, 0], // base cell 107 (pentagon)
-    bc7![0, 5, 0, 0, 5, 5, 0],  // base cell 108
-    bc7![0, 0, 1, 0, 4, 5, 1],  // base cell 109
-    bc7![0, 3, 3, 3, 0, 0, 0],  // base cell 110
-    bc7![0, 0, 0, 3, 0, 5, 0],  // base cell 111
-    bc7![0, 0, 0, 3, 0, 5, 0],  // base cell 112
-    bc7![0, 0, 1, 0, 2, 5, 1],  // base cell 113
-    bc7![0, 0, 0, 0, 0, 0, 1],  // base cell 114
-    bc7![0, 0, 1, 3, 1, 0, 1],  // base cell 115
-    bc7![0, 5, 0, 0, 5, 5, 0],  // base cell 116
-    bc7![0, -1, 1, 0, 3, 4, 2], // base cell 117 (pentagon)
-    bc7![0, 0, 1, 0, 0, 5, 1],  // base cell 118
-    bc7![0, 0, 0, 0, 0, 0, 1],  // base cell 119
-    bc7![0, 5, 0, 0, 5, 5, 0],  // base cell 120
-    bc7![0, 0, 1, 0, 1, 5, 1],  // base cell 121
+pub(crate) const baseCellNeighbor60CCWRots: [[i32; 7]; BaseCell::NUM_BASE_CELLS] = [
+    [0, 5, 0, 0, 1, 5, 1],  // base cell 0
+    [0, 0, 1, 0, 1, 0, 1],  // base cell 1
+    [0, 0, 0, 0, 0, 5, 0],  // base cell 2
+    [0, 5, 0, 0, 2, 5, 1],  // base cell 3
+    [0, -1, 1, 0, 3, 4, 2], // base cell 4 (pentagon)
+    [0, 0, 1, 0, 1, 0, 1],  // base cell 5
+    [0, 0, 0, 3, 5, 5, 0],  // base cell 6
+    [0, 0, 0, 0, 0, 5, 0],  // base cell 7
+    [0, 5, 0, 0, 0, 5, 1],  // base cell 8
+    [0, 0, 1, 3, 0, 0, 1],  // base cell 9
+    [0, 0, 1, 3, 0, 0, 1],  // base cell 10
+    [0, 3, 3, 3, 0, 0, 0],  // base cell 11
+    [0, 5, 0, 0, 3, 5, 1],  // base cell 12
+    [0, 0, 1, 0, 1, 0, 1],  // base cell 13
+    [0, -1, 3, 0, 5, 2, 0], // base cell 14 (pentagon)
+    [0, 5, 0, 0, 4, 5, 1],  // base cell 15
+    [0, 0, 0, 0, 0, 5, 0],  // base cell 16
+    [0, 3, 3, 3, 3, 0, 3],  // base cell 17
+    [0, 0, 0, 3, 5, 5, 0],  // base cell 18
+    [0, 3, 3, 3, 0, 0, 0],  // base cell 19
+    [0, 3, 3, 3, 0, 3, 0],  // base cell 20
+    [0, 0, 0, 3, 5, 5, 0],  // base cell 21
+    [0, 0, 1, 0, 1, 0, 1],  // base cell 22
+    [0, 3, 3, 3, 0, 3, 0],  // base cell 23
+    [0, -1, 3, 0, 5, 2, 0], // base cell 24 (pentagon)
+    [0, 0, 0, 3, 0, 0, 3],  // base cell 25
+    [0, 0, 0, 0, 0, 5, 0],  // base cell 26
+    [0, 3, 0, 0, 0, 3, 3],  // base cell 27
+    [0, 0, 1, 0, 1, 0, 1],  // base cell 28
+    [0, 0, 1, 3, 0, 0, 1],  // base cell 29
+    [0, 3, 3, 3, 0, 0, 0],  // base cell 30
+    [0, 0, 0, 0, 0, 5, 0],  // base cell 31
+    [0, 3, 3, 3, 3, 0, 3],  // base cell 32
+    [0, 0, 1, 3, 0, 0, 1],  // base cell 33
+    [0, 3, 3, 3, 3, 0, 3],  // base cell 34
+    [0, 0, 3, 0, 3, 0, 3],  // base cell 35
+    [0, 0, 0, 3, 0, 0, 3],  // base cell 36
+    [0, 3, 0, 0, 0, 3, 3],  // base cell 37
+    [0, -1, 3, 0, 5, 2, 0], // base cell 38 (pentagon)
+    [0, 3, 0, 0, 3, 3, 0],  // base cell 39
+    [0, 3, 0, 0, 3, 3, 0],  // base cell 40
+    [0, 0, 0, 3, 5, 5, 0],  // base cell 41
+    [0, 0, 0, 3, 5, 5, 0],  // base cell 42
+    [0, 3, 3, 3, 0, 0, 0],  // base cell 43
+    [0, 0, 1, 3, 0, 0, 1],  // base cell 44
+    [0, 0, 3, 0, 0, 3, 3],  // base cell 45
+    [0, 0, 0, 3, 0, 3, 0],  // base cell 46
+    [0, 3, 3, 3, 0, 3, 0],  // base cell 47
+    [0, 3, 3, 3, 0, 3, 0],  // base cell 48
+    [0, -1, 3, 0, 5, 2, 0], // base cell 49 (pentagon)
+    [0, 0, 0, 3, 0, 0, 3],  // base cell 50
+    [0, 3, 0, 0, 0, 3, 3],  // base cell 51
+    [0, 0, 3, 0, 3, 0, 3],  // base cell 52
+    [0, 3, 3, 3, 0, 0, 0],  // base cell 53
+    [0, 0, 3, 0, 3, 0, 3],  // base cell 54
+    [0, 0, 3, 0, 0, 3, 3],  // base cell 55
+    [0, 3, 3, 3, 0, 0, 3],  // base cell 56
+    [0, 0, 0, 3, 0, 3, 0],  // base cell 57
+    [0, -1, 3, 0, 5, 2, 0], // base cell 58 (pentagon)
+    [0, 3, 3, 3, 3, 3, 0],  // base cell 59
+    [0, 3, 3, 3, 3, 3, 0],  // base cell 60
+    [0, 3, 3, 3, 3, 0, 3],  // base cell 61
+    [0, 3, 3, 3, 3, 0, 3],  // base cell 62
+    [0, -1, 3, 0, 5, 2, 0], // base cell 63 (pentagon)
+    [0, 0, 0, 3, 0, 0, 3],  // base cell 64
+    [0, 3, 3, 3, 0, 3, 0],  // base cell 65
+    [0, 3, 0, 0, 0, 3, 3],  // base cell 66
+    [0, 3, 0, 0, 3, 3, 0],  // base cell 67
+    [0, 3, 3, 3, 0, 0, 0],  // base cell 68
+    [0, 3, 0, 0, 3, 3, 0],  // base cell 69
+    [0, 0, 3, 0, 0, 3, 3],  // base cell 70
+    [0, 0, 0, 3, 0, 3, 0],  // base cell 71
+    [0, -1, 3, 0, 5, 2, 0], // base cell 72 (pentagon)
+    [0, 3, 3, 3, 0, 0, 3],  // base cell 73
+    [0, 3, 3, 3, 0, 0, 3],  // base cell 74
+    [0, 0, 0, 3, 0, 0, 3],  // base cell 75
+    [0, 3, 0, 0, 0, 3, 3],  // base cell 76
+    [0, 0, 0, 3, 0, 5, 0],  // base cell 77
+    [0, 3, 3, 3, 0, 0, 0],  // base cell 78
+    [0, 0, 1, 3, 1, 0, 1],  // base cell 79
+    [0, 0, 1, 3, 1, 0, 1],  // base cell 80
+    [0, 0, 3, 0, 3, 0, 3],  // base cell 81
+    [0, 0, 3, 0, 3, 0, 3],  // base cell 82
+    [0, -1, 3, 0, 5, 2, 0], // base cell 83 (pentagon)
+    [0, 0, 3, 0, 0, 3, 3],  // base cell 84
+    [0, 0, 0, 3, 0, 3, 0],  // base cell 85
+    [0, 3, 0, 0, 3, 3, 0],  // base cell 86
+    [0, 3, 3, 3, 3, 3, 0],  // base cell 87
+    [0, 0, 0, 3, 0, 5, 0],  // base cell 88
+    [0, 3, 3, 3, 3, 3, 0],  // base cell 89
+    [0, 0, 0, 0, 0, 0, 1],  // base cell 90
+    [0, 3, 3, 3, 0, 0, 0],  // base cell 91
+    [0, 0, 0, 3, 0, 5, 0],  // base cell 92
+    [0, 5, 0, 0, 5, 5, 0],  // base cell 93
+    [0, 0, 3, 0, 0, 3, 3],  // base cell 94
+    [0, 0, 0, 0, 0, 0, 1],  // base cell 95
+    [0, 0, 0, 3, 0, 3, 0],  // base cell 96
+    [0, -1, 3, 0, 5, 2, 0], // base cell 97 (pentagon)
+    [0, 3, 3, 3, 0, 0, 3],  // base cell 98
+    [0, 5, 0, 0, 5, 5, 0],  // base cell 99
+    [0, 0, 1, 3, 1, 0, 1],  // base cell 100
+    [0, 3, 3, 3, 0, 0, 3],  // base cell 101
+    [0, 3, 3, 3, 0, 0, 0],  // base cell 102
+    [0, 0, 1, 3, 1, 0, 1],  // base cell 103
+    [0, 3, 3, 3, 3, 3, 0],  // base cell 104
+    [0, 0, 0, 0, 0, 0, 1],  // base cell 105
+    [0, 0, 1, 0, 3, 5, 1],  // base cell 106
+    [0, -1, 3, 0, 5, 2, 0], // base cell 107 (pentagon)
+    [0, 5, 0, 0, 5, 5, 0],  // base cell 108
+    [0, 0, 1, 0, 4, 5, 1],  // base cell 109
+    [0, 3, 3, 3, 0, 0, 0],  // base cell 110
+    [0, 0, 0, 3, 0, 5, 0],  // base cell 111
+    [0, 0, 0, 3, 0, 5, 0],  // base cell 112
+    [0, 0, 1, 0, 2, 5, 1],  // base cell 113
+    [0, 0, 0, 0, 0, 0, 1],  // base cell 114
+    [0, 0, 1, 3, 1, 0, 1],  // base cell 115
+    [0, 5, 0, 0, 5, 5, 0],  // base cell 116
+    [0, -1, 1, 0, 3, 4, 2], // base cell 117 (pentagon)
+    [0, 0, 1, 0, 0, 5, 1],  // base cell 118
+    [0, 0, 0, 0, 0, 0, 1],  // base cell 119
+    [0, 5, 0, 0, 5, 5, 0],  // base cell 120
+    [0, 0, 1, 0, 1, 5, 1],  // base cell 121
 ];
 
 #[cfg(test)]
@@ -572,17 +604,17 @@ mod tests {
     fn baseCellToCCWrot60() {
         // a few random spot-checks
         assert_eq!(
-            BaseCell::from(16)._baseCellToCCWrot60(0),
+            BaseCell::new_unchecked(16)._baseCellToCCWrot60(0),
             0,
             "got expected rotation"
         );
         assert_eq!(
-            BaseCell::from(32)._baseCellToCCWrot60(0),
+            BaseCell::new_unchecked(32)._baseCellToCCWrot60(0),
             3,
             "got expected rotation"
         );
         assert_eq!(
-            BaseCell::from(7)._baseCellToCCWrot60(3),
+            BaseCell::new_unchecked(7)._baseCellToCCWrot60(3),
             1,
             "got expected rotation"
         );
@@ -591,21 +623,143 @@ mod tests {
     #[test]
     fn baseCellToCCWrot60_invalid() {
         assert_eq!(
-            BaseCell::from(16)._baseCellToCCWrot60(42),
+            BaseCell::new_unchecked(16)._baseCellToCCWrot60(42),
             INVALID_ROTATIONS,
             "should return invalid rotation for invalid face"
         );
+        assert_eq!(
+            BaseCell::new_unchecked(16)._baseCellToCCWrot60(NUM_ICOSA_FACES),
+            INVALID_ROTATIONS,
+            "should return invalid rotation for the face past the last"
+        );
         /*
         assert_eq!(
-            BaseCell::from(16)._baseCellToCCWrot60(-1),
+            BaseCell::new_unchecked(16)._baseCellToCCWrot60(-1),
             INVALID_ROTATIONS,
             "should return invalid rotation for invalid face (negative)"
         );
         */
         assert_eq!(
-            BaseCell::from(1)._baseCellToCCWrot60(0),
+            BaseCell::new_unchecked(1)._baseCellToCCWrot60(0),
             INVALID_ROTATIONS,
             "should return invalid rotation for base cell not appearing on face"
         );
     }
+
+    #[test]
+    fn new() {
+        assert_eq!(BaseCell::new(0), Ok(BaseCell::new_unchecked(0)), "first");
+        assert_eq!(BaseCell::new(121), Ok(BaseCell::MAX), "last");
+        assert_eq!(BaseCell::new(122), Err(H3Error::Domain), "past the last");
+        assert_eq!(BaseCell::new(-1), Err(H3Error::Domain), "negative");
+        assert_eq!(i32::from(BaseCell::MAX), 121, "numbered from zero");
+    }
+
+    #[test]
+    fn outOfRangeLookups() {
+        for &bc in [
+            BaseCell::INVALID,
+            BaseCell::new_unchecked(122),
+            BaseCell::new_unchecked(127),
+        ]
+        .iter()
+        {
+            assert_eq!(bc.index(), None, "no table index for {:?}", bc);
+            assert!(!bc._isBaseCellPentagon(), "{:?} is not a pentagon", bc);
+            assert_eq!(
+                bc._getBaseCellNeighbor(&Direction::J_AXES_DIGIT),
+                None,
+                "{:?} has no neighbors",
+                bc
+            );
+            assert_eq!(
+                bc._baseCellToFaceIjk().err(),
+                Some(H3Error::CellInvalid),
+                "no home face"
+            );
+        }
+    }
+
+    #[test]
+    fn getBaseCellNeighbor() {
+        let pentagon = BaseCell::new_unchecked(4);
+        assert_eq!(
+            pentagon._getBaseCellNeighbor(&Direction::K_AXES_DIGIT),
+            None,
+            "deleted direction"
+        );
+        assert_eq!(
+            pentagon._getBaseCellNeighbor(&Direction::CENTER_DIGIT),
+            Some(pentagon),
+            "center is the base cell itself"
+        );
+        assert_eq!(
+            pentagon._getBaseCellNeighbor(&Direction::INVALID_DIGIT),
+            None,
+            "invalid direction"
+        );
+
+        for bc in 0..BaseCell::NUM_BASE_CELLS as i32 {
+            let bc = BaseCell::new_unchecked(bc);
+            for dir in Direction::VALID_DIRECTIONS.iter() {
+                if let Some(neighbor) = bc._getBaseCellNeighbor(dir) {
+                    assert!(neighbor.index().is_some(), "neighbor is in range");
+                    assert_ne!(
+                        neighbor._getBaseCellDirection(bc),
+                        Direction::INVALID_DIGIT,
+                        "neighbors are mutual"
+                    );
+                } else {
+                    assert!(bc._isBaseCellPentagon(), "only pentagons lack neighbors");
+                    assert_eq!(*dir, Direction::K_AXES_DIGIT, "in the k direction");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn baseCellNeighbor60CCWRots_roundTrip() {
+        for (i, (neighbors, rotations)) in baseCellNeighbors
+            .iter()
+            .zip(baseCellNeighbor60CCWRots.iter())
+            .enumerate()
+        {
+            let bc = BaseCell::new_unchecked(i as i32);
+            for dir in Direction::axes() {
+                let neighbor = neighbors[dir as usize];
+                // Pentagons remap the deleted k direction, so only pairs of
+                // hexagons are their own reverse in the tables.
+                if bc._isBaseCellPentagon() || neighbor._isBaseCellPentagon() {
+                    continue;
+                }
+                let j = neighbor.index().unwrap();
+
+                // The opposite direction, turned into the neighbor's frame.
+                let mut reverse = Direction::from(7 - dir as usize);
+                for _ in 0..rotations[dir as usize] {
+                    reverse = reverse.rotate60ccw();
+                }
+                assert_eq!(
+                    baseCellNeighbors[j][reverse as usize], bc,
+                    "{} {:?} to {} and back",
+                    i, dir, j
+                );
+                assert_eq!(
+                    (rotations[dir as usize] + baseCellNeighbor60CCWRots[j][reverse as usize]) % 6,
+                    0,
+                    "{} {:?} to {} rotations cancel",
+                    i,
+                    dir,
+                    j
+                );
+                assert_eq!(
+                    neighbor._getBaseCellDirection(bc),
+                    reverse,
+                    "{} from {}",
+                    i,
+                    j
+                );
+            }
+        }
+    }
 }
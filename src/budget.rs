@@ -0,0 +1,135 @@
+//! Limits on the work done by operations whose output can grow without bound,
+//! so servers can stop a runaway request instead of aborting its thread.
+
+use std::ops::ControlFlow;
+
+use crate::H3Error;
+
+/// How many cells an operation may produce before it gives up with
+/// `H3Error::Cancelled`, and an optional callback that can stop it early.
+///
+/// ```
+/// use std::ops::ControlFlow;
+/// use h3rs::{budget::Budget, flood_fill_budgeted, prelude::*};
+///
+/// let origin = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277)).geoToH3(Resolution::R9);
+///
+/// // a predicate that never says no would flood the whole planet
+/// let mut budget = Budget::max_cells(1000);
+/// assert_eq!(
+///     flood_fill_budgeted(vec![origin], |_| true, &mut budget),
+///     Err(H3Error::Cancelled)
+/// );
+///
+/// // or check a deadline, a cancellation token, ... as cells come in
+/// let deadline = std::time::Instant::now() + std::time::Duration::from_secs(1);
+/// let mut budget = Budget::unlimited().with_callback(|_cells| {
+///     if std::time::Instant::now() < deadline {
+///         ControlFlow::Continue(())
+///     } else {
+///         ControlFlow::Break(())
+///     }
+/// });
+/// let disk = origin.kRing(2);
+/// let region = flood_fill_budgeted(vec![origin], |h| disk.contains(&h), &mut budget);
+/// assert_eq!(region.map(|r| r.len()), Ok(19));
+/// ```
+pub struct Budget<'a> {
+    maxCells: usize,
+    spent: usize,
+    callback: Option<Box<dyn FnMut(usize) -> ControlFlow<()> + 'a>>,
+}
+
+impl<'a> Budget<'a> {
+    /// No limit; the operation runs to completion.
+    pub fn unlimited() -> Self {
+        Self::max_cells(usize::MAX)
+    }
+
+    /// Cancels the operation once it would produce more than `maxCells` cells.
+    pub fn max_cells(maxCells: usize) -> Self {
+        Budget {
+            maxCells,
+            spent: 0,
+            callback: None,
+        }
+    }
+
+    /// Also calls `callback` with the number of cells produced so far, each
+    /// time the operation makes progress, cancelling it on `Break`.
+    pub fn with_callback(mut self, callback: impl FnMut(usize) -> ControlFlow<()> + 'a) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Number of cells charged against the budget so far.
+    pub fn spent(&self) -> usize {
+        self.spent
+    }
+
+    /// Accounts for `cells` more cells of output, failing with
+    /// `H3Error::Cancelled` if the budget is exhausted or the callback breaks.
+    pub(crate) fn charge(&mut self, cells: usize) -> Result<(), H3Error> {
+        self.spent = self.spent.saturating_add(cells);
+        if self.spent > self.maxCells {
+            return Err(H3Error::Cancelled);
+        }
+        let spent = self.spent;
+        match self.callback.as_mut().map(|f| f(spent)) {
+            Some(ControlFlow::Break(())) => Err(H3Error::Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for Budget<'_> {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+impl std::fmt::Debug for Budget<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Budget")
+            .field("maxCells", &self.maxCells)
+            .field("spent", &self.spent)
+            .field("callback", &self.callback.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_maxCells() {
+        let mut budget = Budget::max_cells(10);
+        assert_eq!(budget.charge(4), Ok(()), "within");
+        assert_eq!(budget.charge(6), Ok(()), "exactly spent");
+        assert_eq!(budget.charge(1), Err(H3Error::Cancelled), "over");
+        assert_eq!(budget.spent(), 11, "spent");
+
+        let mut budget = Budget::default();
+        assert_eq!(budget.charge(usize::MAX), Ok(()), "unlimited");
+        assert_eq!(budget.charge(1), Ok(()), "saturates");
+    }
+
+    #[test]
+    fn budget_callback() {
+        let mut seen = Vec::new();
+        let mut budget = Budget::unlimited().with_callback(|n| {
+            seen.push(n);
+            if n < 5 {
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            }
+        });
+        assert_eq!(budget.charge(2), Ok(()), "continue");
+        assert_eq!(budget.charge(2), Ok(()), "continue");
+        assert_eq!(budget.charge(2), Err(H3Error::Cancelled), "break");
+        drop(budget);
+        assert_eq!(seen, vec![2, 4, 6], "called with the running total");
+    }
+}
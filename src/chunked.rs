@@ -0,0 +1,181 @@
+//! Processing of cell streams too large to hold in memory, such as the
+//! children of a big compacted region, in fixed size chunks handed to an
+//! async callback.
+//!
+//! Nothing here depends on a particular runtime: the futures are plain std
+//! futures, to be awaited from tokio, async-std or anything else.
+
+use std::future::Future;
+
+use crate::H3Index;
+
+/**
+ * Passes the cells of an iterator to an async callback in chunks of up to
+ * `chunk_size` cells, awaiting each call before pulling the next chunk. The
+ * iterator therefore only runs as fast as the callback takes chunks, so a
+ * slow sink such as an upload to object storage pushes back on a lazy
+ * producer instead of having its output pile up in memory.
+ *
+ * The last chunk may be shorter; no chunk is empty. A `chunk_size` of 0 is
+ * taken as 1.
+ *
+ * ```no_run
+ * use h3rs::{chunked::for_each_chunk, prelude::*};
+ *
+ * async fn upload(part: usize, cells: Vec<H3Index>) -> Result<(), std::io::Error> {
+ *     // write `cells` to object storage as part `part`
+ *     # let _ = (part, cells);
+ *     Ok(())
+ * }
+ *
+ * async fn export(region: H3Index) -> Result<usize, std::io::Error> {
+ *     let mut part = 0;
+ *     for_each_chunk(region.h3ToChildren(Resolution::R12), 100_000, |cells| {
+ *         part += 1;
+ *         upload(part, cells)
+ *     })
+ *     .await
+ * }
+ * ```
+ *
+ * @param cells The cells, in the order they are to be processed
+ * @param chunk_size Most cells passed to one call
+ * @param process The callback, given ownership of each chunk
+ * @return The number of cells processed, or the first error from the
+ *         callback, after which no more chunks are pulled
+ */
+pub async fn for_each_chunk<I, F, Fut, E>(
+    cells: I,
+    chunk_size: usize,
+    mut process: F,
+) -> Result<usize, E>
+where
+    I: IntoIterator<Item = H3Index>,
+    F: FnMut(Vec<H3Index>) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    let chunkSize = chunk_size.max(1);
+    let mut cells = cells.into_iter();
+    let mut processed = 0;
+
+    loop {
+        let chunk: Vec<H3Index> = cells.by_ref().take(chunkSize).collect();
+        if chunk.is_empty() {
+            return Ok(processed);
+        }
+        processed += chunk.len();
+        process(chunk).await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeoCoord, Resolution};
+    use std::{
+        cell::Cell,
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll, Wake},
+    };
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    /// Polls a future to completion on this thread; the futures here are
+    /// woken straight away, so there is nothing to wait for.
+    fn block_on<T>(future: impl Future<Output = T>) -> T {
+        let waker = Arc::new(NoopWaker).into();
+        let mut context = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            if let Poll::Ready(out) = future.as_mut().poll(&mut context) {
+                return out;
+            }
+        }
+    }
+
+    /// A future that is pending once before it is ready, like a write that
+    /// has to wait for the network.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                context.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn forEachChunk() {
+        let origin = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R5);
+        let cells = origin.h3ToChildren(Resolution::R7);
+        assert_eq!(cells.len(), 49);
+
+        let mut chunks: Vec<Vec<H3Index>> = Vec::new();
+        let processed = block_on(for_each_chunk(cells.clone(), 10, |chunk| {
+            chunks.push(chunk);
+            async {
+                YieldOnce(false).await;
+                Ok::<(), ()>(())
+            }
+        }));
+        assert_eq!(processed, Ok(49), "all cells");
+        assert_eq!(
+            chunks.iter().map(|c| c.len()).collect::<Vec<_>>(),
+            vec![10, 10, 10, 10, 9],
+            "chunk sizes"
+        );
+        assert_eq!(chunks.concat(), cells, "in order");
+
+        let none = block_on(for_each_chunk(Vec::new(), 10, |_| async { Err("called") }));
+        assert_eq!(none, Ok(0), "no empty chunk");
+        let mut calls = 0;
+        let ones = block_on(for_each_chunk(cells.clone(), 0, |chunk| {
+            calls += 1;
+            assert_eq!(chunk.len(), 1);
+            async { Ok::<(), ()>(()) }
+        }));
+        assert_eq!((ones, calls), (Ok(49), 49), "chunk size 0");
+    }
+
+    #[test]
+    fn forEachChunk_backpressure() {
+        let origin = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R5);
+        let pulled = Cell::new(0);
+        let cells = origin
+            .h3ToChildren(Resolution::R7)
+            .into_iter()
+            .inspect(|_| {
+                pulled.set(pulled.get() + 1);
+            });
+
+        // Fails on the second chunk: the third is never pulled.
+        let mut calls = 0;
+        let result = block_on(for_each_chunk(cells, 10, |_| {
+            calls += 1;
+            let failed = calls == 2;
+            async move {
+                YieldOnce(false).await;
+                if failed {
+                    Err("storage is down")
+                } else {
+                    Ok(())
+                }
+            }
+        }));
+        assert_eq!(result, Err("storage is down"));
+        assert_eq!(calls, 2, "stopped at the error");
+        assert_eq!(pulled.get(), 20, "pulled no further than needed");
+    }
+}
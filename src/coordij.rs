@@ -1,9 +1,19 @@
-use crate::coordijk::CoordIJK;
+use std::fmt;
+
+use crate::{coordijk::CoordIJK, H3Error};
 
 #[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// IJ hexagon coordinates
 ///
 /// Each axis is spaced 120 degrees apart.
+///
+/// Generic hex grid algorithms (e.g. those described at
+/// <https://www.redblobgames.com/grids/hexagons/>) usually work in axial
+/// `(q, r)` or cube `(q, r, s)` coordinates, where the axes are spaced 60
+/// degrees apart. `to_axial`, `to_cube` and their inverses convert between
+/// the two, so local IJ coordinates can be fed to such algorithms and the
+/// results brought back with `experimentalLocalIjToH3`.
 pub struct CoordIJ {
     /// i component
     pub i: i32,
@@ -33,6 +43,88 @@ impl CoordIJ {
 
         ijk
     }
+
+    /**
+     * Converts to axial coordinates, `q = -i` and `r = j`. This is the same
+     * mapping used internally to convert IJK to cube coordinates, so the q
+     * axis points away from the i axis and the r axis along the j axis.
+     *
+     * Overflows if i is `i32::MIN`; see `checked_to_axial`.
+     *
+     * @return The `(q, r)` axial coordinates
+     */
+    pub fn to_axial(&self) -> (i32, i32) {
+        (-self.i, self.j)
+    }
+
+    /**
+     * Converts to axial coordinates like `to_axial`, failing instead of
+     * overflowing.
+     *
+     * @return The `(q, r)` axial coordinates, or Domain if q does not fit in
+     *         an i32
+     */
+    pub fn checked_to_axial(&self) -> Result<(i32, i32), H3Error> {
+        let q = self.i.checked_neg().ok_or(H3Error::Domain)?;
+        Ok((q, self.j))
+    }
+
+    /**
+     * Converts from axial coordinates; the inverse of `to_axial`.
+     *
+     * @param q The axial q coordinate
+     * @param r The axial r coordinate
+     * @return The coordinates, or Domain if q is `i32::MIN`, so that i does
+     *         not fit in an i32
+     */
+    pub fn from_axial(q: i32, r: i32) -> Result<Self, H3Error> {
+        let i = q.checked_neg().ok_or(H3Error::Domain)?;
+        Ok(Self { i, j: r })
+    }
+
+    /**
+     * Converts to cube coordinates, the axial coordinates plus `s = -q - r`.
+     * The grid distance between two cells is the largest absolute difference
+     * of their cube components.
+     *
+     * Overflows if `i - j` does not fit in an i32; see `checked_to_cube`.
+     *
+     * @return The `(q, r, s)` cube coordinates, which sum to zero
+     */
+    pub fn to_cube(&self) -> (i32, i32, i32) {
+        let (q, r) = self.to_axial();
+        (q, r, -q - r)
+    }
+
+    /**
+     * Converts to cube coordinates like `to_cube`, failing instead of
+     * overflowing.
+     *
+     * @return The `(q, r, s)` cube coordinates, or Domain if a component does
+     *         not fit in an i32
+     */
+    pub fn checked_to_cube(&self) -> Result<(i32, i32, i32), H3Error> {
+        let (q, r) = self.checked_to_axial()?;
+        let s = self.i.checked_sub(self.j).ok_or(H3Error::Domain)?;
+        Ok((q, r, s))
+    }
+
+    /**
+     * Converts from cube coordinates; the inverse of `to_cube`.
+     *
+     * @param q The cube q coordinate
+     * @param r The cube r coordinate
+     * @param s The cube s coordinate
+     * @return The coordinates, or Domain if the components do not sum to zero
+     *         or q is `i32::MIN`
+     */
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Result<Self, H3Error> {
+        // summed wider, so it cannot overflow
+        if i64::from(q) + i64::from(r) + i64::from(s) != 0 {
+            return Err(H3Error::Domain);
+        }
+        Self::from_axial(q, r)
+    }
 }
 
 impl From<(i32, i32)> for CoordIJ {
@@ -41,3 +133,109 @@ impl From<(i32, i32)> for CoordIJ {
         CoordIJ { i, j }
     }
 }
+
+impl fmt::Display for CoordIJ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.i, self.j)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Direction;
+
+    #[test]
+    fn display() {
+        assert_eq!(CoordIJ::new(3, -2).to_string(), "(3, -2)");
+    }
+
+    #[test]
+    fn axialRoundtrip() {
+        for i in -3..=3 {
+            for j in -3..=3 {
+                let ij = CoordIJ::new(i, j);
+                let (q, r) = ij.to_axial();
+                assert_eq!(CoordIJ::from_axial(q, r), Ok(ij), "axial roundtrip");
+
+                let (q, r, s) = ij.to_cube();
+                assert_eq!(q + r + s, 0, "cube coordinates sum to zero");
+                assert_eq!(CoordIJ::from_cube(q, r, s), Ok(ij), "cube roundtrip");
+            }
+        }
+
+        assert_eq!(
+            CoordIJ::from_cube(1, 1, 1),
+            Err(H3Error::Domain),
+            "not on the plane"
+        );
+    }
+
+    #[test]
+    fn checkedAxialAndCube() {
+        let ij = CoordIJ::new(3, -2);
+        assert_eq!(ij.checked_to_axial(), Ok(ij.to_axial()), "axial");
+        assert_eq!(ij.checked_to_cube(), Ok(ij.to_cube()), "cube");
+
+        let min = CoordIJ::new(i32::MIN, 0);
+        assert_eq!(min.checked_to_axial(), Err(H3Error::Domain), "q overflows");
+        assert_eq!(min.checked_to_cube(), Err(H3Error::Domain), "cube too");
+
+        let wide = CoordIJ::new(i32::MAX, -1);
+        assert_eq!(wide.checked_to_axial(), Ok((-i32::MAX, -1)), "axial fits");
+        assert_eq!(wide.checked_to_cube(), Err(H3Error::Domain), "s overflows");
+
+        assert_eq!(
+            CoordIJ::from_cube(i32::MAX, i32::MAX, 2),
+            Err(H3Error::Domain),
+            "sum overflows i32 but is not zero"
+        );
+        assert_eq!(
+            CoordIJ::from_cube(-i32::MAX, i32::MAX, 0),
+            Ok(CoordIJ::new(i32::MAX, i32::MAX)),
+            "largest"
+        );
+        assert_eq!(
+            CoordIJ::from_cube(i32::MIN, i32::MAX, 1),
+            Err(H3Error::Domain),
+            "i cannot be negated"
+        );
+        assert_eq!(
+            CoordIJ::from_cube(i32::MAX, 1, i32::MIN),
+            Ok(CoordIJ::new(-i32::MAX, 1)),
+            "q + r overflows i32 but the sum is zero"
+        );
+        assert_eq!(
+            CoordIJ::from_axial(i32::MIN, 0),
+            Err(H3Error::Domain),
+            "axial i cannot be negated"
+        );
+        assert_eq!(
+            CoordIJ::from_axial(-i32::MAX, i32::MIN),
+            Ok(CoordIJ::new(i32::MAX, i32::MIN)),
+            "largest axial"
+        );
+    }
+
+    #[test]
+    fn cubeDistanceMatchesIjk() {
+        let origin = CoordIJ::default();
+        for dir in Direction::VALID_DIRECTIONS.iter().skip(1) {
+            let mut ijk = CoordIJK::default();
+            ijk._neighbor(*dir);
+            let neighbor = CoordIJ::new(ijk.i - ijk.k, ijk.j - ijk.k);
+
+            let (q, r, s) = neighbor.to_cube();
+            assert_eq!(
+                q.abs().max(r.abs()).max(s.abs()),
+                1,
+                "neighbors are one step apart in cube coordinates"
+            );
+            assert_eq!(
+                origin.ijToIjk().ijkDistance(&neighbor.ijToIjk()),
+                1,
+                "and in ijk coordinates"
+            );
+        }
+    }
+}
@@ -1,10 +1,10 @@
-use std::ops;
+use std::{convert::TryFrom, ops};
 
 use crate::{
     constants::{M_SIN60, M_SQRT3_2},
     coordij::CoordIJ,
     vec2d::Vec2d,
-    Direction,
+    Direction, H3Error, Resolution,
 };
 
 #[derive(Copy, Clone, Debug, PartialEq, Default)]
@@ -12,8 +12,11 @@ use crate::{
 ///
 /// Each axis is spaced 120 degrees apart.
 pub struct CoordIJK {
+    /// i component
     pub i: i32,
+    /// j component
     pub j: i32,
+    /// k component
     pub k: i32,
 }
 
@@ -74,8 +77,18 @@ impl CoordIJK {
         }
     }
 
+    /// Normalizes like `normalize`, but returns Domain rather than overflowing
+    /// when a component does not fit in an i32.
+    pub(crate) fn checkedNormalize(&mut self) -> Result<(), H3Error> {
+        // Normalizing subtracts the smallest component from each of them.
+        let min = self.i.min(self.j).min(self.k) as i64;
+        let fit = |c: i32| i32::try_from(c as i64 - min).map_err(|_| H3Error::Domain);
+        *self = Self::new(fit(self.i)?, fit(self.j)?, fit(self.k)?);
+        Ok(())
+    }
+
     /// @brief CoordIJK unit vectors corresponding to the 7 H3 digits.
-    pub(crate) const UNIT_VECS: [(CoordIJK, Direction); 7] = [
+    pub const UNIT_VECS: [(CoordIJK, Direction); 7] = [
         (CoordIJK::new(0, 0, 0), Direction::CENTER_DIGIT), // direction 0
         (CoordIJK::new(0, 0, 1), Direction::K_AXES_DIGIT), // direction 1
         (CoordIJK::new(0, 1, 0), Direction::J_AXES_DIGIT), // direction 2
@@ -172,6 +185,23 @@ impl CoordIJK {
         self.normalize();
     }
 
+    /**
+     * The unit vector in direction `digit` at resolution `res`: the offset
+     * between a base cell and its neighbor, scaled down through every
+     * aperture 7 step to `res` coordinates. Same as `_neighbor` followed by a
+     * `_downAp7` or `_downAp7r` per resolution from `res` up to 1, looked up
+     * in a table built at compile time.
+     *
+     * @param res The resolution to scale to.
+     * @param digit The direction; the center and invalid digits give zero.
+     */
+    pub(crate) fn scaledUnitVec(res: Resolution, digit: Direction) -> CoordIJK {
+        SCALED_UNIT_VECS[usize::from(res)]
+            .get(digit as usize)
+            .copied()
+            .unwrap_or(CoordIJK::ZERO)
+    }
+
     /**
      * Find the normalized ijk coordinates of the hex centered on the indicated
      * hex at the next finer aperture 3 counter-clockwise resolution. Works in
@@ -257,7 +287,7 @@ impl CoordIJK {
     /// Convert IJK coordinates to cube coordinates, in place
     pub(crate) fn ijkToCube(&mut self) {
         self.i = -self.i + self.k;
-        self.j = self.j - self.k;
+        self.j -= self.k;
         self.k = -self.i - self.j;
     }
 
@@ -335,23 +365,27 @@ impl CoordIJK {
     }
 }
 
-impl From<&crate::coordij::CoordIJ> for CoordIJK {
+impl TryFrom<&crate::coordij::CoordIJ> for CoordIJK {
+    type Error = H3Error;
+
     /**
      * Transforms coordinates from the IJ coordinate system to the IJK+ coordinate
      * system.
      *
      * @param ij The input IJ coordinates
-     * @param ijk The output IJK+ coordinates
+     * @return The IJK+ coordinates, or Domain if a component overflows, which
+     *         happens when i and j have opposite signs and are both near the
+     *         i32 limits
      */
-    fn from(ij: &crate::coordij::CoordIJ) -> Self {
+    fn try_from(ij: &crate::coordij::CoordIJ) -> Result<Self, Self::Error> {
         let mut ijk = Self {
             i: ij.i,
             j: ij.j,
             k: 0,
         };
 
-        ijk.normalize();
-        ijk
+        ijk.checkedNormalize()?;
+        Ok(ijk)
     }
 }
 
@@ -438,17 +472,17 @@ impl From<Vec2d> for CoordIJK {
                 // even
                 let axisi = j / 2;
                 let diff = i - axisi;
-                i = i - 2 * diff;
+                i -= 2 * diff;
             } else {
                 let axisi = (j + 1) / 2;
                 let diff = i - axisi;
-                i = i - (2 * diff + 1);
+                i -= 2 * diff + 1;
             }
         }
 
         if v.y < 0.0 {
-            i = i - (2 * j + 1) / 2;
-            j = -1 * j;
+            i -= (2 * j + 1) / 2;
+            j = -j;
         }
 
         let mut h = CoordIJK { i, j, k };
@@ -505,9 +539,143 @@ impl ops::MulAssign<i32> for CoordIJK {
     }
 }
 
+/// `normalize`, for building tables at compile time.
+const fn constNormalize(c: CoordIJK) -> CoordIJK {
+    let (mut i, mut j, mut k) = (c.i, c.j, c.k);
+    if i < 0 {
+        j -= i;
+        k -= i;
+        i = 0;
+    }
+    if j < 0 {
+        i -= j;
+        k -= j;
+        j = 0;
+    }
+    if k < 0 {
+        i -= k;
+        j -= k;
+        k = 0;
+    }
+    let mut min = i;
+    if j < min {
+        min = j;
+    }
+    if k < min {
+        min = k;
+    }
+    CoordIJK::new(i - min, j - min, k - min)
+}
+
+/// `_downAp7` if `ccw`, else `_downAp7r`, for building tables at compile time.
+const fn constDownAp7(c: CoordIJK, ccw: bool) -> CoordIJK {
+    let (i, j, k) = (c.i, c.j, c.k);
+    let down = if ccw {
+        CoordIJK::new(3 * i + j, 3 * j + k, i + 3 * k)
+    } else {
+        CoordIJK::new(3 * i + k, i + 3 * j, j + 3 * k)
+    };
+    constNormalize(down)
+}
+
+/// `CoordIJK::UNIT_VECS` scaled down to each resolution, indexed by
+/// resolution then digit; see `CoordIJK::scaledUnitVec`.
+const SCALED_UNIT_VECS: [[CoordIJK; 7]; Resolution::MAX_H3_RES + 1] = {
+    let mut table = [[CoordIJK::ZERO; 7]; Resolution::MAX_H3_RES + 1];
+    let mut res = 0;
+    while res <= Resolution::MAX_H3_RES {
+        let mut digit = 0;
+        while digit < 7 {
+            let mut offset = CoordIJK::UNIT_VECS[digit].0;
+            // finest first, as the aperture changes with the resolution
+            let mut r = res;
+            while r > 0 {
+                // class III resolutions are the odd ones
+                offset = constDownAp7(offset, r % 2 == 1);
+                r -= 1;
+            }
+            table[res][digit] = offset;
+            digit += 1;
+        }
+        res += 1;
+    }
+    table
+};
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn scaledUnitVec() {
+        for res in 0..=Resolution::MAX_H3_RES {
+            let res: Resolution = res.into();
+            for (unit, digit) in CoordIJK::UNIT_VECS.iter() {
+                let mut expected = CoordIJK::ZERO;
+                expected._neighbor(*digit);
+                for r in (1..=usize::from(res)).rev() {
+                    if Resolution::from(r).isResClassIII() {
+                        expected._downAp7();
+                    } else {
+                        expected._downAp7r();
+                    }
+                }
+                assert_eq!(
+                    CoordIJK::scaledUnitVec(res, *digit),
+                    expected,
+                    "{:?} at {:?}",
+                    unit,
+                    res
+                );
+            }
+            assert_eq!(
+                CoordIJK::scaledUnitVec(res, Direction::INVALID_DIGIT),
+                CoordIJK::ZERO,
+                "invalid digit"
+            );
+        }
+    }
+
+    #[test]
+    fn checkedNormalize() {
+        let mut ijk = CoordIJK::new(-2, 3, 1);
+        let mut expected = ijk;
+        expected.normalize();
+        assert_eq!(ijk.checkedNormalize(), Ok(()), "in range");
+        assert_eq!(ijk, expected, "same as normalize");
+
+        let mut ijk = CoordIJK::new(i32::MIN, i32::MAX, 0);
+        assert_eq!(ijk.checkedNormalize(), Err(H3Error::Domain), "overflows");
+
+        let mut ijk = CoordIJK::new(i32::MIN, -1, i32::MIN);
+        assert_eq!(ijk.checkedNormalize(), Ok(()), "same signs");
+        assert_eq!(ijk, CoordIJK::new(0, i32::MAX, 0), "normalized");
+    }
+
+    #[test]
+    fn tryFromCoordIJ() {
+        assert_eq!(
+            CoordIJK::try_from(&CoordIJ::new(-1, 2)),
+            Ok(CoordIJ::new(-1, 2).ijToIjk()),
+            "same as ijToIjk"
+        );
+        assert_eq!(
+            CoordIJK::try_from(&CoordIJ::new(i32::MAX, i32::MAX)),
+            Ok(CoordIJK::new(i32::MAX, i32::MAX, 0)),
+            "largest"
+        );
+        assert_eq!(
+            CoordIJK::try_from(&CoordIJ::new(i32::MAX, i32::MIN)),
+            Err(H3Error::Domain),
+            "opposite signs overflow"
+        );
+        assert_eq!(
+            CoordIJK::try_from(&CoordIJ::new(1, i32::MIN)),
+            Err(H3Error::Domain),
+            "i32::MIN cannot be negated"
+        );
+    }
 
     #[test]
     fn test_unitIjkToDigit() {
@@ -589,7 +757,7 @@ mod tests {
             let mut ijk = CoordIJK::default();
             ijk._neighbor(*dir);
 
-            let original = ijk.clone();
+            let original = ijk;
 
             ijk.ijkToCube();
             ijk.cubeToIjk();
@@ -1,3 +1,5 @@
+use crate::coordijk::CoordIJK;
+
 #[derive(Copy, Clone, PartialEq, Debug, PartialOrd)]
 /// H3 digit representing ijk+ axes direction.
 /// Values will be within the lowest 3 bits of an integer.
@@ -36,6 +38,27 @@ impl Direction {
         Direction::IJ_AXES_DIGIT,
     ];
 
+    /// The six directions to the neighbors of a hexagon, in digit order.
+    /// Pentagons have no neighbor in the K_AXES_DIGIT direction.
+    pub fn axes() -> impl Iterator<Item = Direction> {
+        Self::VALID_DIRECTIONS[1..].iter().copied()
+    }
+
+    /// The unit IJK vector of this direction, from `CoordIJK::UNIT_VECS`; the
+    /// zero vector for CENTER_DIGIT, and None for INVALID_DIGIT.
+    pub fn to_ijk(&self) -> Option<CoordIJK> {
+        CoordIJK::UNIT_VECS.get(*self as usize).map(|(ijk, _)| *ijk)
+    }
+
+    /// The direction of an IJK unit vector, or None if the vector does not
+    /// normalize to one of `CoordIJK::UNIT_VECS`.
+    pub fn from_unit_ijk(ijk: &CoordIJK) -> Option<Direction> {
+        match ijk._unitIjkToDigit() {
+            Direction::INVALID_DIGIT => None,
+            dir => Some(dir),
+        }
+    }
+
     /// Rotates indexing digit 60 degrees counter-clockwise. Returns result.
     ///
     /// @param digit Indexing digit (between 1 and 6 inclusive)
@@ -164,3 +187,43 @@ impl std::ops::AddAssign<usize> for Direction {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axes() {
+        let axes: Vec<Direction> = Direction::axes().collect();
+        assert_eq!(axes.len(), 6, "six neighbors");
+        assert_eq!(axes[0], Direction::K_AXES_DIGIT, "k first");
+        assert!(!axes.contains(&Direction::CENTER_DIGIT), "no center");
+        assert!(!axes.contains(&Direction::INVALID_DIGIT), "no invalid");
+    }
+
+    #[test]
+    fn unitIjk() {
+        for &dir in Direction::VALID_DIRECTIONS.iter() {
+            let ijk = dir.to_ijk().unwrap();
+            assert_eq!(Direction::from_unit_ijk(&ijk), Some(dir), "round trip");
+
+            let shifted = CoordIJK::new(ijk.i + 2, ijk.j + 2, ijk.k + 2);
+            assert_eq!(
+                Direction::from_unit_ijk(&shifted),
+                Some(dir),
+                "unnormalized unit vector"
+            );
+        }
+        assert_eq!(
+            Direction::I_AXES_DIGIT.to_ijk(),
+            Some(CoordIJK::new(1, 0, 0)),
+            "i axis"
+        );
+        assert_eq!(Direction::INVALID_DIGIT.to_ijk(), None, "invalid digit");
+        assert_eq!(
+            Direction::from_unit_ijk(&CoordIJK::new(2, 0, 0)),
+            None,
+            "not a unit vector"
+        );
+    }
+}
@@ -0,0 +1,44 @@
+/// Errors returned by H3 functions, modeled after the error codes of H3 v4.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum H3Error {
+    /// A resolution argument is incompatible with the resolution of the
+    /// index, such as asking for a parent finer than the cell itself
+    ResolutionMismatch,
+
+    /// The index is not a valid cell
+    CellInvalid,
+
+    /// An argument was outside of its acceptable range, or malformed, such
+    /// as text that does not parse as an index or coordinates too far from
+    /// an origin
+    Domain,
+
+    /// The operation ran out of its `Budget`, or its callback asked it to stop
+    Cancelled,
+
+    /// A pentagon or its distortion was in the way of an operation asked not
+    /// to handle them
+    Pentagon,
+
+    /// The same cell appears more than once in a set that must not repeat
+    /// cells, such as the input to `compact`
+    DuplicateInput,
+}
+
+impl std::fmt::Display for H3Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            H3Error::ResolutionMismatch => {
+                write!(f, "resolution is incompatible with the index's resolution")
+            }
+            H3Error::CellInvalid => write!(f, "index is not a valid cell"),
+            H3Error::Domain => write!(f, "argument is out of range"),
+            H3Error::Cancelled => write!(f, "operation was cancelled by its budget"),
+            H3Error::Pentagon => write!(f, "pentagon distortion was encountered"),
+            H3Error::DuplicateInput => write!(f, "input contains duplicate cells"),
+        }
+    }
+}
+
+impl std::error::Error for H3Error {}
@@ -1,11 +1,12 @@
 use crate::{
     basecell::BaseCell,
     basecellrotation::faceIjkBaseCells,
-    constants::{NUM_HEX_VERTS, NUM_ICOSA_FACES, NUM_PENT_VERTS},
+    constants::{M_SQRT3_2, NUM_HEX_VERTS, NUM_ICOSA_FACES, NUM_PENT_VERTS},
     coordijk::CoordIJK,
     geopolygon::GeoBoundary,
     h3index::H3Mode,
-    vec2d::Overage,
+    resolution::{maxDimByCIIres, unitScaleByCIIres},
+    vec2d::{adjacentFaceDir, Overage, Vec2d, IJ, JK, KI},
     Direction, GeoCoord, H3Index, Resolution,
 };
 
@@ -43,31 +44,27 @@ impl FaceIJK {
         start: i32,
         length: i32,
     ) -> GeoBoundary {
-        let mut adjRes = res;
+        let mut adjRes = res as usize;
         let mut centerIJK = *self;
         let fijkVerts = centerIJK._faceIjkToVerts(&mut adjRes);
-        //[NUM_HEX_VERTS];
 
-        todo!()
-        /*
+        // If we're returning the entire loop, we need one more iteration in case
+        // of a distortion vertex on the last edge
+        let additionalIteration = if length == NUM_HEX_VERTS { 1 } else { 0 };
 
-            // If we're returning the entire loop, we need one more iteration in case
-            // of a distortion vertex on the last edge
-            int additionalIteration = length == NUM_HEX_VERTS ? 1 : 0;
+        // convert each vertex to lat/lon
+        // adjust the face of each vertex as appropriate and introduce
+        // edge-crossing vertices as needed
+        let mut g = GeoBoundary::default();
+        let mut lastFace = -1;
+        let mut lastOverage = Overage::NO_OVERAGE;
+        for vert in start..start + length + additionalIteration {
+            let v = (vert % NUM_HEX_VERTS) as usize;
 
-            // convert each vertex to lat/lon
-            // adjust the face of each vertex as appropriate and introduce
-            // edge-crossing vertices as needed
-            g->numVerts = 0;
-            int lastFace = -1;
-            Overage lastOverage = NO_OVERAGE;
-            for (int vert = start; vert < start + length + additionalIteration; vert++) {
-            int v = vert % NUM_HEX_VERTS;
+            let mut fijk = fijkVerts[v];
 
-            FaceIJK fijk = fijkVerts[v];
-
-            const int pentLeading4 = 0;
-            Overage overage = _adjustOverageClassII(&fijk, adjRes, pentLeading4, 1);
+            let pentLeading4 = false;
+            let overage = fijk._adjustOverageClassII(adjRes, pentLeading4, true);
 
             /*
             Check for edge-crossing. Each face of the underlying icosahedron is a
@@ -78,73 +75,58 @@ impl FaceIJK {
             projection. Note that Class II cell edges have vertices on the face
             edge, with no edge line intersections.
             */
-            if (isResClassIII(res) && vert > start && fijk.face != lastFace &&
-                lastOverage != FACE_EDGE) {
+            if res.isResClassIII()
+                && vert > start
+                && fijk.face != lastFace
+                && lastOverage != Overage::FACE_EDGE
+            {
                 // find hex2d of the two vertexes on original face
-                int lastV = (v + 5) % NUM_HEX_VERTS;
-                Vec2d orig2d0;
-                _ijkToHex2d(&fijkVerts[lastV].coord, &orig2d0);
-
-                Vec2d orig2d1;
-                _ijkToHex2d(&fijkVerts[v].coord, &orig2d1);
+                let lastV = (v + 5) % NUM_HEX_VERTS as usize;
+                let orig2d0 = fijkVerts[lastV].coord._ijkToHex2d();
+                let orig2d1 = fijkVerts[v].coord._ijkToHex2d();
 
                 // find the appropriate icosa face edge vertexes
-                int maxDim = maxDimByCIIres[adjRes];
-                Vec2d v0 = {3.0 * maxDim, 0.0};
-                Vec2d v1 = {-1.5 * maxDim, 3.0 * M_SQRT3_2 * maxDim};
-                Vec2d v2 = {-1.5 * maxDim, -3.0 * M_SQRT3_2 * maxDim};
-
-                int face2 = ((lastFace == centerIJK.face) ? fijk.face : lastFace);
-                Vec2d* edge0;
-                Vec2d* edge1;
-                switch (adjacentFaceDir[centerIJK.face][face2]) {
-                    case IJ:
-                        edge0 = &v0;
-                        edge1 = &v1;
-                        break;
-                    case JK:
-                        edge0 = &v1;
-                        edge1 = &v2;
-                        break;
-                        // case KI:
-                    default:
-                        assert(adjacentFaceDir[centerIJK.face][face2] == KI);
-                        edge0 = &v2;
-                        edge1 = &v0;
-                        break;
-                }
+                let face2 = if lastFace == centerIJK.face {
+                    fijk.face
+                } else {
+                    lastFace
+                };
+                let (edge0, edge1) = icosaFaceEdge(adjRes, centerIJK.face, face2);
 
                 // find the intersection and add the lat/lon point to the result
-                Vec2d inter;
-                _v2dIntersect(&orig2d0, &orig2d1, edge0, edge1, &inter);
-                /*
-                   If a point of intersection occurs at a hexagon vertex, then each
-                   adjacent hexagon edge will lie completely on a single icosahedron
-                   face, and no additional vertex is required.
-                   */
-                bool isIntersectionAtVertex =
-                    _v2dEquals(&orig2d0, &inter) || _v2dEquals(&orig2d1, &inter);
-                if (!isIntersectionAtVertex) {
-                    _hex2dToGeo(&inter, centerIJK.face, adjRes, 1,
-                                &g->verts[g->numVerts]);
-                    g->numVerts++;
+                let inter = Vec2d::_v2dIntersect(&orig2d0, &orig2d1, &edge0, &edge1);
+
+                // If a point of intersection occurs at a hexagon vertex, then each
+                // adjacent hexagon edge will lie completely on a single icosahedron
+                // face, and no additional vertex is required.
+                let isIntersectionAtVertex =
+                    orig2d0._v2dAlmostEquals(&inter) || orig2d1._v2dAlmostEquals(&inter);
+                if !isIntersectionAtVertex {
+                    g.verts[g.numVerts] = faceEdgeCrossing(
+                        centerIJK.face,
+                        adjRes,
+                        (&orig2d0, &orig2d1),
+                        (&edge0, &edge1),
+                    )
+                    .unwrap_or_else(|| inter._hex2dToGeo(centerIJK.face, adjRes, true));
+                    g.numVerts += 1;
                 }
             }
 
             // convert vertex to lat/lon and add to the result
             // vert == start + NUM_HEX_VERTS is only used to test for possible
             // intersection on last edge
-            if (vert < start + NUM_HEX_VERTS) {
-                Vec2d vec;
-                _ijkToHex2d(&fijk.coord, &vec);
-                _hex2dToGeo(&vec, fijk.face, adjRes, 1, &g->verts[g->numVerts]);
-                g->numVerts++;
+            if vert < start + NUM_HEX_VERTS {
+                let vec = fijk.coord._ijkToHex2d();
+                g.verts[g.numVerts] = vec._hex2dToGeo(fijk.face, adjRes, true);
+                g.numVerts += 1;
             }
 
             lastFace = fijk.face;
             lastOverage = overage;
         }
-        */
+
+        g
     }
 
     /**
@@ -255,10 +237,11 @@ impl FaceIJK {
      * Valid ijk+ lookup coordinates are from (0, 0, 0) to (2, 2, 2).
      */
     pub(crate) fn _faceIjkToBaseCell(&self) -> BaseCell {
-        faceIjkBaseCells[self.face as usize][self.coord.i as usize][self.coord.j as usize]
-            [self.coord.k as usize]
-            .baseCell
-            .into()
+        BaseCell::new_unchecked(
+            faceIjkBaseCells[self.face as usize][self.coord.i as usize][self.coord.j as usize]
+                [self.coord.k as usize]
+                .baseCell,
+        )
     }
 
     /// Find base cell given FaceIJK.
@@ -284,7 +267,7 @@ impl FaceIJK {
      */
     pub(crate) fn _faceIjkToGeo(&self, res: Resolution) -> GeoCoord {
         let v = self.coord._ijkToHex2d();
-        v._hex2dToGeo(self.face, res, false)
+        v._hex2dToGeo(self.face, res as usize, false)
     }
 
     /**
@@ -303,106 +286,88 @@ impl FaceIJK {
         start: i32,
         length: i32,
     ) -> GeoBoundary {
-        todo!()
-        /*
-            int adjRes = res;
-            FaceIJK centerIJK = *h;
-            FaceIJK fijkVerts[NUM_PENT_VERTS];
-            _faceIjkPentToVerts(&centerIJK, &adjRes, fijkVerts);
-
-            // If we're returning the entire loop, we need one more iteration in case
-            // of a distortion vertex on the last edge
-            int additionalIteration = length == NUM_PENT_VERTS ? 1 : 0;
-
-            // convert each vertex to lat/lon
-            // adjust the face of each vertex as appropriate and introduce
-            // edge-crossing vertices as needed
-            g->numVerts = 0;
-            FaceIJK lastFijk;
-            for (int vert = start; vert < start + length + additionalIteration;
-                 vert++) {
-                int v = vert % NUM_PENT_VERTS;
-
-                FaceIJK fijk = fijkVerts[v];
-
-                _adjustPentVertOverage(&fijk, adjRes);
-
-                // all Class III pentagon edges cross icosa edges
-                // note that Class II pentagons have vertices on the edge,
-                // not edge intersections
-                if (isResClassIII(res) && vert > start) {
-                    // find hex2d of the two vertexes on the last face
-
-                    FaceIJK tmpFijk = fijk;
-
-                    Vec2d orig2d0;
-                    _ijkToHex2d(&lastFijk.coord, &orig2d0);
-
-                    int currentToLastDir = adjacentFaceDir[tmpFijk.face][lastFijk.face];
-
-                    const FaceOrientIJK* fijkOrient =
-                        &faceNeighbors[tmpFijk.face][currentToLastDir];
-
-                    tmpFijk.face = fijkOrient->face;
-                    CoordIJK* ijk = &tmpFijk.coord;
-
-                    // rotate and translate for adjacent face
-                    for (int i = 0; i < fijkOrient->ccwRot60; i++) _ijkRotate60ccw(ijk);
-
-                    CoordIJK transVec = fijkOrient->translate;
-                    _ijkScale(&transVec, unitScaleByCIIres[adjRes] * 3);
-                    _ijkAdd(ijk, &transVec, ijk);
-                    _ijkNormalize(ijk);
-
-                    Vec2d orig2d1;
-                    _ijkToHex2d(ijk, &orig2d1);
-
-                    // find the appropriate icosa face edge vertexes
-                    int maxDim = maxDimByCIIres[adjRes];
-                    Vec2d v0 = {3.0 * maxDim, 0.0};
-                    Vec2d v1 = {-1.5 * maxDim, 3.0 * M_SQRT3_2 * maxDim};
-                    Vec2d v2 = {-1.5 * maxDim, -3.0 * M_SQRT3_2 * maxDim};
-
-                    Vec2d* edge0;
-                    Vec2d* edge1;
-                    switch (adjacentFaceDir[tmpFijk.face][fijk.face]) {
-                        case IJ:
-                            edge0 = &v0;
-                            edge1 = &v1;
-                            break;
-                        case JK:
-                            edge0 = &v1;
-                            edge1 = &v2;
-                            break;
-                        case KI:
-                        default:
-                            assert(adjacentFaceDir[tmpFijk.face][fijk.face] == KI);
-                            edge0 = &v2;
-                            edge1 = &v0;
-                            break;
-                    }
+        let mut adjRes = res as usize;
+        let mut centerIJK = *self;
+        let fijkVerts = centerIJK._faceIjkPentToVerts(&mut adjRes);
 
-                    // find the intersection and add the lat/lon point to the result
-                    Vec2d inter;
-                    _v2dIntersect(&orig2d0, &orig2d1, edge0, edge1, &inter);
-                    _hex2dToGeo(&inter, tmpFijk.face, adjRes, 1,
-                                &g->verts[g->numVerts]);
-                    g->numVerts++;
-                }
+        // If we're returning the entire loop, we need one more iteration in case
+        // of a distortion vertex on the last edge
+        let additionalIteration = if length == NUM_PENT_VERTS as i32 {
+            1
+        } else {
+            0
+        };
 
-                // convert vertex to lat/lon and add to the result
-                // vert == start + NUM_PENT_VERTS is only used to test for possible
-                // intersection on last edge
-                if (vert < start + NUM_PENT_VERTS) {
-                    Vec2d vec;
-                    _ijkToHex2d(&fijk.coord, &vec);
-                    _hex2dToGeo(&vec, fijk.face, adjRes, 1, &g->verts[g->numVerts]);
-                    g->numVerts++;
+        // convert each vertex to lat/lon
+        // adjust the face of each vertex as appropriate and introduce
+        // edge-crossing vertices as needed
+        let mut g = GeoBoundary::default();
+        let mut lastFijk = FaceIJK::default();
+        for vert in start..start + length + additionalIteration {
+            let v = (vert % NUM_PENT_VERTS as i32) as usize;
+
+            let mut fijk = fijkVerts[v];
+
+            fijk._adjustPentVertOverage(adjRes);
+
+            // all Class III pentagon edges cross icosa edges
+            // note that Class II pentagons have vertices on the edge,
+            // not edge intersections
+            if res.isResClassIII() && vert > start {
+                // find hex2d of the two vertexes on the last face
+
+                let mut tmpFijk = fijk;
+
+                let orig2d0 = lastFijk.coord._ijkToHex2d();
+
+                let currentToLastDir =
+                    adjacentFaceDir[tmpFijk.face as usize][lastFijk.face as usize];
+
+                let fijkOrient = &faceNeighbors[tmpFijk.face as usize][currentToLastDir as usize];
+
+                tmpFijk.face = fijkOrient.face;
+                let ijk = &mut tmpFijk.coord;
+
+                // rotate and translate for adjacent face
+                for _ in 0..fijkOrient.ccwRot60 {
+                    ijk._ijkRotate60ccw();
                 }
 
-                lastFijk = fijk;
+                let transVec = fijkOrient.translate * (unitScaleByCIIres[adjRes] * 3);
+                *ijk += transVec;
+                ijk.normalize();
+
+                let orig2d1 = ijk._ijkToHex2d();
+
+                // find the appropriate icosa face edge vertexes
+                let (edge0, edge1) = icosaFaceEdge(adjRes, tmpFijk.face, fijk.face);
+
+                // find the intersection and add the lat/lon point to the result
+                g.verts[g.numVerts] =
+                    faceEdgeCrossing(tmpFijk.face, adjRes, (&orig2d0, &orig2d1), (&edge0, &edge1))
+                        .unwrap_or_else(|| {
+                            Vec2d::_v2dIntersect(&orig2d0, &orig2d1, &edge0, &edge1)._hex2dToGeo(
+                                tmpFijk.face,
+                                adjRes,
+                                true,
+                            )
+                        });
+                g.numVerts += 1;
+            }
+
+            // convert vertex to lat/lon and add to the result
+            // vert == start + NUM_PENT_VERTS is only used to test for possible
+            // intersection on last edge
+            if vert < start + NUM_PENT_VERTS as i32 {
+                let vec = fijk.coord._ijkToHex2d();
+                g.verts[g.numVerts] = vec._hex2dToGeo(fijk.face, adjRes, true);
+                g.numVerts += 1;
             }
-        */
+
+            lastFijk = fijk;
+        }
+
+        g
     }
 
     /**
@@ -413,10 +378,7 @@ impl FaceIJK {
      *            necessary for the substrate grid resolution.
      * @param fijkVerts Output array for the vertices
      */
-    pub(crate) fn _faceIjkToVerts(
-        &mut self,
-        res: &mut Resolution,
-    ) -> [FaceIJK; NUM_HEX_VERTS as usize] {
+    pub(crate) fn _faceIjkToVerts(&mut self, res: &mut usize) -> [FaceIJK; NUM_HEX_VERTS as usize] {
         // the vertexes of an origin-centered cell in a Class II resolution on a
         // substrate grid with aperture sequence 33r. The aperture 3 gets us the
         // vertices, and the 3r gets us back to Class II.
@@ -444,11 +406,7 @@ impl FaceIJK {
         ];
 
         // get the correct set of substrate vertices for this resolution
-        let verts = if res.isResClassIII() {
-            vertsCIII
-        } else {
-            vertsCII
-        };
+        let verts = if *res % 2 == 1 { vertsCIII } else { vertsCII };
 
         // adjust the center point to be in an aperture 33r substrate grid
         // these should be composed for speed
@@ -456,9 +414,9 @@ impl FaceIJK {
         self.coord._downAp3r();
 
         // if res is Class III we need to add a cw aperture 7 to get to icosahedral Class II
-        if res.isResClassIII() {
+        if *res % 2 == 1 {
             self.coord._downAp7r();
-            *res = *res + 1;
+            *res += 1;
         }
 
         // The center point is now in the same substrate grid as the origin
@@ -483,10 +441,7 @@ impl FaceIJK {
      * @param res The H3 resolution of the cell. This may be adjusted if necessary for the substrate grid resolution.
      * @param fijkVerts Output array for the vertices
      */
-    pub(crate) fn _faceIjkPentToVerts(
-        &mut self,
-        res: &mut Resolution,
-    ) -> [FaceIJK; NUM_PENT_VERTS] {
+    pub(crate) fn _faceIjkPentToVerts(&mut self, res: &mut usize) -> [FaceIJK; NUM_PENT_VERTS] {
         // the vertexes of an origin-centered pentagon in a Class II resolution on a
         // substrate grid with aperture sequence 33r. The aperture 3 gets us the
         // vertices, and the 3r gets us back to Class II.
@@ -512,11 +467,7 @@ impl FaceIJK {
         ];
 
         // get the correct set of substrate vertices for this resolution
-        let verts = if res.isResClassIII() {
-            vertsCIII
-        } else {
-            vertsCII
-        };
+        let verts = if *res % 2 == 1 { vertsCIII } else { vertsCII };
 
         // adjust the center point to be in an aperture 33r substrate grid
         // these should be composed for speed
@@ -524,9 +475,9 @@ impl FaceIJK {
         self.coord._downAp3r();
 
         // if res is Class III we need to add a cw aperture 7 to get to icosahedral Class II
-        if res.isResClassIII() {
+        if *res % 2 == 1 {
             self.coord._downAp7r();
-            *res = Resolution::from(*res as usize + 1);
+            *res += 1;
         }
 
         // The center point is now in the same substrate grid as the origin
@@ -550,10 +501,10 @@ impl FaceIJK {
      * @param fijk The FaceIJK address of the cell.
      * @param res The H3 resolution of the cell.
      */
-    pub(crate) fn _adjustPentVertOverage(&mut self, res: Resolution) -> Overage {
+    pub(crate) fn _adjustPentVertOverage(&mut self, res: usize) -> Overage {
         loop {
             let overage = self._adjustOverageClassII(res, false, true);
-            if overage == Overage::NEW_FACE {
+            if overage != Overage::NEW_FACE {
                 return overage;
             }
         }
@@ -573,7 +524,7 @@ impl FaceIJK {
      */
     pub(crate) fn _adjustOverageClassII(
         &mut self,
-        res: Resolution,
+        res: usize,
         pentLeading4: bool,
         substrate: bool,
     ) -> Overage {
@@ -583,9 +534,9 @@ impl FaceIJK {
 
         // get the maximum dimension value; scale if a substrate grid
         let maxDim = if substrate {
-            res.maxDimByCIIres() * 3
+            maxDimByCIIres[res] * 3
         } else {
-            res.maxDimByCIIres()
+            maxDimByCIIres[res]
         };
 
         // check for overage
@@ -629,7 +580,7 @@ impl FaceIJK {
                 ijk._ijkRotate60ccw();
             }
 
-            let mut unitScale = res.unitScaleByCIIres();
+            let mut unitScale = unitScaleByCIIres[res];
             if substrate {
                 unitScale *= 3;
             }
@@ -645,10 +596,50 @@ impl FaceIJK {
             }
         }
 
+        self.coord = ijk;
+
         overage
     }
 }
 
+/// Find the icosahedron face edge, in the hex2d system of `face` at the
+/// substrate resolution `adjRes`, that is shared with the adjacent face `face2`.
+fn icosaFaceEdge(adjRes: usize, face: i32, face2: i32) -> (Vec2d, Vec2d) {
+    let maxDim = maxDimByCIIres[adjRes] as f64;
+    let v0 = Vec2d::new(3.0 * maxDim, 0.0);
+    let v1 = Vec2d::new(-1.5 * maxDim, 3.0 * M_SQRT3_2 * maxDim);
+    let v2 = Vec2d::new(-1.5 * maxDim, -3.0 * M_SQRT3_2 * maxDim);
+
+    match adjacentFaceDir[face as usize][face2 as usize] {
+        IJ => (v0, v1),
+        JK => (v1, v2),
+        dir => {
+            debug_assert_eq!(dir, KI);
+            (v2, v0)
+        }
+    }
+}
+
+/// Where a cell edge crosses an icosahedron face edge, both given in the
+/// hex2d system of `face` at the substrate resolution `adjRes`. The face
+/// projection is gnomonic, so the straight edges are great arcs on the
+/// sphere, and their crossing is `GeoCoord::great_arc_intersection`. None if
+/// the edges do not cross.
+fn faceEdgeCrossing(
+    face: i32,
+    adjRes: usize,
+    cellEdge: (&Vec2d, &Vec2d),
+    faceEdge: (&Vec2d, &Vec2d),
+) -> Option<GeoCoord> {
+    let toGeo = |v: &Vec2d| v._hex2dToGeo(face, adjRes, true);
+    GeoCoord::great_arc_intersection(
+        &toGeo(cellEdge.0),
+        &toGeo(cellEdge.1),
+        &toGeo(faceEdge.0),
+        &toGeo(faceEdge.1),
+    )
+}
+
 /// Information to transform into an adjacent face IJK system
 pub(crate) struct FaceOrientIJK {
     /// face number
@@ -0,0 +1,146 @@
+use crate::{geopolygon::MAX_CELL_BNDRY_VERTS, GeoBoundary, GeoCoord};
+
+/// Largest distance, in radians, between a vertex of a `GeoBoundary` and the
+/// same vertex of its `GeoBoundaryF32`: about 0.85 m on the Earth's surface.
+///
+/// Each coordinate is rounded to the nearest `f32`, which is off by at most
+/// half an ulp: 2^-24 rad for a latitude, within ±π/2, and 2^-23 rad for a
+/// longitude, within ±π. The distance is at most the hypotenuse of the two,
+/// rounded up here.
+pub const F32_BOUNDARY_MAX_ERROR_RADS: f64 = 1.34e-7;
+
+/// Cell boundary in latitude/longitude, with `f32` coordinates.
+///
+/// This takes 84 bytes to `GeoBoundary`'s 168, for applications that keep
+/// millions of boundaries around to render them. Vertices are up to
+/// `F32_BOUNDARY_MAX_ERROR_RADS` from the exact ones, which is far below a
+/// pixel at any zoom a res 15 cell can be seen at, but is not enough to index
+/// the vertices back to their cells. Keep `GeoBoundary` for geometry.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct GeoBoundaryF32 {
+    numVerts: u8,
+    /// `[lat, lon]` in radians
+    verts: [[f32; 2]; MAX_CELL_BNDRY_VERTS],
+}
+
+impl GeoBoundaryF32 {
+    /// Number of vertices.
+    pub fn len(&self) -> usize {
+        self.numVerts as usize
+    }
+
+    /// Whether there are no vertices, as for an invalid cell.
+    pub fn is_empty(&self) -> bool {
+        self.numVerts == 0
+    }
+
+    /// The vertices in ccw order, widened back to `f64`.
+    pub fn verts(&self) -> impl Iterator<Item = GeoCoord> + '_ {
+        self.verts[..self.len()]
+            .iter()
+            .map(|&[lat, lon]| GeoCoord::from_rads(lat.into(), lon.into()))
+    }
+
+    /// The vertexes as `[lng, lat]` positions in degrees, closed like
+    /// `GeoBoundary::to_lng_lat_ring`, for handing to a renderer as is.
+    pub fn to_lng_lat_ring(&self) -> Vec<[f32; 2]> {
+        let verts = &self.verts[..self.len()];
+        verts
+            .iter()
+            .chain(verts.first())
+            .map(|&[lat, lon]| [lon.to_degrees(), lat.to_degrees()])
+            .collect()
+    }
+}
+
+impl From<&GeoBoundary> for GeoBoundaryF32 {
+    fn from(gb: &GeoBoundary) -> Self {
+        let mut verts = [[0.0; 2]; MAX_CELL_BNDRY_VERTS];
+        for (v, g) in verts.iter_mut().zip(gb.verts[..gb.numVerts].iter()) {
+            *v = [g.lat as f32, g.lon as f32];
+        }
+        GeoBoundaryF32 {
+            numVerts: gb.numVerts as u8,
+            verts,
+        }
+    }
+}
+
+impl From<&GeoBoundaryF32> for GeoBoundary {
+    fn from(gb: &GeoBoundaryF32) -> Self {
+        let mut out = GeoBoundary {
+            numVerts: gb.len(),
+            ..Default::default()
+        };
+        for (v, g) in out.verts.iter_mut().zip(gb.verts()) {
+            *v = g;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::EARTH_RADIUS_KM, Degrees, H3Index, Resolution};
+
+    #[test]
+    fn geoBoundaryF32_size() {
+        assert_eq!(std::mem::size_of::<GeoBoundaryF32>(), 84, "f32");
+        assert_eq!(
+            std::mem::size_of::<GeoBoundary>(),
+            2 * std::mem::size_of::<GeoBoundaryF32>(),
+            "half of f64"
+        );
+    }
+
+    #[test]
+    fn geoBoundaryF32_errorBound() {
+        let halfUlps = f64::hypot(2f64.powi(-24), 2f64.powi(-23));
+        assert!(
+            halfUlps <= F32_BOUNDARY_MAX_ERROR_RADS && halfUlps * EARTH_RADIUS_KM < 0.86e-3,
+            "bound of {} m",
+            halfUlps * EARTH_RADIUS_KM * 1000.
+        );
+
+        let mut cells: Vec<H3Index> = Resolution::R0.getPentagonIndexes().to_vec();
+        let near = GeoCoord::new(Degrees(-16.8), Degrees(179.9)).geoToH3(Resolution::R1);
+        cells.extend(near.kRing(2));
+        for res in [Resolution::R5, Resolution::R10, Resolution::R15].iter() {
+            cells.extend(cells.clone().iter().map(|h| h.h3ToCenterChild(*res)));
+        }
+
+        for h in cells.iter() {
+            let gb = h.h3ToGeoBoundary();
+            let small = GeoBoundaryF32::from(&gb);
+            assert_eq!(small.len(), gb.numVerts, "{} vertex count", h);
+
+            for (exact, rounded) in gb.verts[..gb.numVerts].iter().zip(small.verts()) {
+                let d = GeoCoord::pointDistRads(exact, &rounded);
+                assert!(d <= F32_BOUNDARY_MAX_ERROR_RADS, "{} off by {}", h, d);
+            }
+
+            let back = GeoBoundary::from(&small);
+            assert_eq!(back.numVerts, gb.numVerts, "{} round trip", h);
+            assert_eq!(GeoBoundaryF32::from(&back), small, "{} rounds to itself", h);
+        }
+    }
+
+    #[test]
+    fn geoBoundaryF32_ring() {
+        let h = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277)).geoToH3(Resolution::R9);
+        let gb = h.h3ToGeoBoundary();
+        let ring = GeoBoundaryF32::from(&gb).to_lng_lat_ring();
+
+        assert_eq!(ring.len(), gb.numVerts + 1, "closed");
+        assert_eq!(ring.first(), ring.last(), "closed");
+        for (p, q) in ring.iter().zip(gb.to_lng_lat_ring()) {
+            assert!((p[0] as f64 - q[0]).abs() < 1e-5, "lng");
+            assert!((p[1] as f64 - q[1]).abs() < 1e-5, "lat");
+        }
+
+        let empty = GeoBoundaryF32::from(&GeoBoundary::default());
+        assert!(empty.is_empty(), "no vertices");
+        assert!(empty.to_lng_lat_ring().is_empty(), "no ring");
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
     constants::*,
     faceijk::FaceIJK,
+    units::{Degrees, Radians},
     vec2d::{faceAxesAzRadsCII, faceCenterPoint, Vec2d},
     vec3d::Vec3d,
-    H3Index, Resolution,
+    BaseCell, H3Index, Resolution, SphereModel,
 };
 
 /// epsilon of ~0.1mm in degrees
@@ -13,29 +14,30 @@ const EPSILON_RAD: f64 = EPSILON_DEG * M_PI_180;
 
 /** @brief icosahedron face centers in lat/lon radians */
 pub(crate) const faceCenterGeo: [GeoCoord; NUM_ICOSA_FACES] = [
-    GeoCoord::new(0.803582649718989942, 1.248397419617396099), // face  0
-    GeoCoord::new(1.307747883455638156, 2.536945009877921159), // face  1
-    GeoCoord::new(1.054751253523952054, -1.347517358900396623), // face  2
-    GeoCoord::new(0.600191595538186799, -0.450603909469755746), // face  3
-    GeoCoord::new(0.491715428198773866, 0.401988202911306943), // face  4
-    GeoCoord::new(0.172745327415618701, 1.678146885280433686), // face  5
-    GeoCoord::new(0.605929321571350690, 2.953923329812411617), // face  6
-    GeoCoord::new(0.427370518328979641, -1.888876200336285401), // face  7
-    GeoCoord::new(-0.079066118549212831, -0.733429513380867741), // face  8
-    GeoCoord::new(-0.230961644455383637, 0.506495587332349035), // face  9
-    GeoCoord::new(0.079066118549212831, 2.408163140208925497), // face 10
-    GeoCoord::new(0.230961644455383637, -2.635097066257444203), // face 11
-    GeoCoord::new(-0.172745327415618701, -1.463445768309359553), // face 12
-    GeoCoord::new(-0.605929321571350690, -0.187669323777381622), // face 13
-    GeoCoord::new(-0.427370518328979641, 1.252716453253507838), // face 14
-    GeoCoord::new(-0.600191595538186799, 2.690988744120037492), // face 15
-    GeoCoord::new(-0.491715428198773866, -2.739604450678486295), // face 16
-    GeoCoord::new(-0.803582649718989942, -1.893195233972397139), // face 17
-    GeoCoord::new(-1.307747883455638156, -0.604647643711872080), // face 18
-    GeoCoord::new(-1.054751253523952054, 1.794075294689396615), // face 19
+    GeoCoord::from_rads(0.803582649718989942, 1.248397419617396099), // face  0
+    GeoCoord::from_rads(1.307747883455638156, 2.536945009877921159), // face  1
+    GeoCoord::from_rads(1.054751253523952054, -1.347517358900396623), // face  2
+    GeoCoord::from_rads(0.600191595538186799, -0.450603909469755746), // face  3
+    GeoCoord::from_rads(0.491715428198773866, 0.401988202911306943), // face  4
+    GeoCoord::from_rads(0.172745327415618701, 1.678146885280433686), // face  5
+    GeoCoord::from_rads(0.605929321571350690, 2.953923329812411617), // face  6
+    GeoCoord::from_rads(0.427370518328979641, -1.888876200336285401), // face  7
+    GeoCoord::from_rads(-0.079066118549212831, -0.733429513380867741), // face  8
+    GeoCoord::from_rads(-0.230961644455383637, 0.506495587332349035), // face  9
+    GeoCoord::from_rads(0.079066118549212831, 2.408163140208925497), // face 10
+    GeoCoord::from_rads(0.230961644455383637, -2.635097066257444203), // face 11
+    GeoCoord::from_rads(-0.172745327415618701, -1.463445768309359553), // face 12
+    GeoCoord::from_rads(-0.605929321571350690, -0.187669323777381622), // face 13
+    GeoCoord::from_rads(-0.427370518328979641, 1.252716453253507838), // face 14
+    GeoCoord::from_rads(-0.600191595538186799, 2.690988744120037492), // face 15
+    GeoCoord::from_rads(-0.491715428198773866, -2.739604450678486295), // face 16
+    GeoCoord::from_rads(-0.803582649718989942, -1.893195233972397139), // face 17
+    GeoCoord::from_rads(-1.307747883455638156, -0.604647643711872080), // face 18
+    GeoCoord::from_rads(-1.054751253523952054, 1.794075294689396615), // face 19
 ];
 
 #[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// latitude/longitude in radians
 pub struct GeoCoord {
     /// latitude in radians
@@ -92,7 +94,41 @@ impl PartialEq for GeoCoord {
 }
 
 impl GeoCoord {
-    pub(crate) const fn new(lat: f64, lon: f64) -> Self {
+    /**
+     * Creates spherical coordinates, with the unit of each angle named at the
+     * call site.
+     *
+     * @param lat The latitude, as `Radians` or `Degrees`
+     * @param lon The longitude, as `Radians` or `Degrees`
+     */
+    pub fn new(lat: impl Into<Radians>, lon: impl Into<Radians>) -> Self {
+        Self::from_rads(lat.into().0, lon.into().0)
+    }
+
+    /**
+     * Creates spherical coordinates from a longitude and latitude, in the
+     * order GeoJSON, WKB, FlatGeobuf and GeoParquet store them.
+     *
+     * @param lng The longitude, as `Radians` or `Degrees`
+     * @param lat The latitude, as `Radians` or `Degrees`
+     */
+    pub fn from_lng_lat(lng: impl Into<Radians>, lat: impl Into<Radians>) -> Self {
+        Self::new(lat, lng)
+    }
+
+    /// The longitude and latitude in degrees, in that order, for formats that
+    /// put x before y.
+    pub fn lng_lat(&self) -> (Degrees, Degrees) {
+        (Radians(self.lon).into(), Radians(self.lat).into())
+    }
+
+    /// The latitude and longitude in degrees, in the order `new` takes them.
+    pub fn lat_lng(&self) -> (Degrees, Degrees) {
+        (Radians(self.lat).into(), Radians(self.lon).into())
+    }
+
+    /// Creates spherical coordinates from a latitude and longitude in radians.
+    pub(crate) const fn from_rads(lat: f64, lon: f64) -> Self {
         Self { lat, lon }
     }
 
@@ -127,7 +163,7 @@ impl GeoCoord {
      */
     fn constrainLat(mut lat: f64) -> f64 {
         while lat > M_PI_2 {
-            lat = lat - M_PI;
+            lat -= M_PI;
         }
         lat
     }
@@ -140,10 +176,10 @@ impl GeoCoord {
      */
     pub(crate) fn constrainLng(mut lng: f64) -> f64 {
         while lng > M_PI {
-            lng = lng - (2. * M_PI);
+            lng -= 2. * M_PI;
         }
         while lng < -M_PI {
-            lng = lng + (2. * M_PI);
+            lng += 2. * M_PI;
         }
         lng
     }
@@ -151,10 +187,12 @@ impl GeoCoord {
     /**
      * The great circle distance in radians between two spherical coordinates.
      *
-     * This function uses the Haversine formula.
-     * For math details, see:
-     *     https://en.wikipedia.org/wiki/Haversine_formula
-     *     https://www.movable-type.co.uk/scripts/latlong.html
+     * This function uses the Vincenty formula, specialized to a sphere. Unlike
+     * the Haversine formula, which loses precision near antipodes and can give
+     * NaN for them, it is accurate for identical, nearby and antipodal points
+     * alike. For math details, see:
+     *     <https://en.wikipedia.org/wiki/Great-circle_distance#Computational_formulas>
+     *     <https://www.movable-type.co.uk/scripts/latlong.html>
      *
      * @param  a  the first lat/lng pair (in radians)
      * @param  b  the second lat/lng pair (in radians)
@@ -162,17 +200,22 @@ impl GeoCoord {
      * @return    the great circle distance in radians between a and b
      */
     pub fn pointDistRads(a: &Self, b: &Self) -> f64 {
-        let sinLat = ((b.lat - a.lat) / 2.0).sin();
-        let sinLng = ((b.lon - a.lon) / 2.0).sin();
+        let (sinLatA, cosLatA) = a.lat.sin_cos();
+        let (sinLatB, cosLatB) = b.lat.sin_cos();
+        let (sinLng, cosLng) = (b.lon - a.lon).sin_cos();
 
-        let a = sinLat * sinLat + a.lat.cos() * b.lat.cos() * sinLng * sinLng;
+        let y = f64::hypot(
+            cosLatB * sinLng,
+            cosLatA * sinLatB - sinLatA * cosLatB * cosLng,
+        );
+        let x = sinLatA * sinLatB + cosLatA * cosLatB * cosLng;
 
-        2. * f64::atan2(a.sqrt(), (1. - a).sqrt())
+        f64::atan2(y, x)
     }
 
     /// The great circle distance in kilometers between two spherical coordinates.
     pub fn pointDistKm(a: &Self, b: &Self) -> f64 {
-        Self::pointDistRads(a, b) * EARTH_RADIUS_KM
+        SphereModel::EARTH.point_dist_km(a, b)
     }
 
     /// The great circle distance in meters between two spherical coordinates.
@@ -187,7 +230,7 @@ impl GeoCoord {
      * @param p2 The second spherical coordinates.
      * @return The azimuth in radians from p1 to p2.
      */
-    fn _geoAzimuthRads(p1: &Self, p2: &Self) -> f64 {
+    pub(crate) fn _geoAzimuthRads(p1: &Self, p2: &Self) -> f64 {
         f64::atan2(
             p2.lat.cos() * (p2.lon - p1.lon).sin(),
             p1.lat.cos() * p2.lat.sin() - p1.lat.sin() * p2.lat.cos() * (p2.lon - p1.lon).cos(),
@@ -214,19 +257,26 @@ impl GeoCoord {
         let c = (s - c) / 2.0;
         let s = s / 2.0;
 
-        4. * f64::atan((s.tan() * a.tan() * b.tan() * c.tan()).sqrt())
+        // rounding can leave a degenerate triangle's product slightly negative
+        let product = s.tan() * a.tan() * b.tan() * c.tan();
+        4. * f64::atan(product.max(0.0).sqrt())
     }
 
     /**
      * Compute area in radians^2 of a spherical triangle, given its vertices.
      *
+     * Uses l'Huilier's formula for the spherical excess, which unlike Girard's
+     * theorem stays accurate for small triangles. The area of any simple
+     * spherical polygon is the sum over a fan of triangles, as in
+     * cellAreaRads2; multiply by the squared earth radius for km^2.
+     *
      * @param   a  vertex lat/lng in radians
      * @param   b  vertex lat/lng in radians
      * @param   c  vertex lat/lng in radians
      *
      * @return     area of triangle on unit sphere, in radians^2
      */
-    pub(crate) fn triangleArea(a: &Self, b: &Self, c: &Self) -> f64 {
+    pub fn triangleArea(a: &Self, b: &Self, c: &Self) -> f64 {
         Self::triangleEdgeLengthsToArea(
             Self::pointDistRads(a, b),
             Self::pointDistRads(b, c),
@@ -271,7 +321,6 @@ impl GeoCoord {
     ///
     ///@param geo The latitude and longitude of the point.
     ///@param v The 3D coordinate of the point.
-
     pub(crate) fn _geoToVec3d(&self) -> Vec3d {
         let r = self.lat.cos();
 
@@ -282,6 +331,62 @@ impl GeoCoord {
         Vec3d::new(x, y, z)
     }
 
+    /// The latitude and longitude of a point on the unit sphere, the inverse
+    /// of `_geoToVec3d`. The vector need not be of unit length.
+    pub(crate) fn _vec3dToGeo(v: &Vec3d) -> Self {
+        Self::from_rads(v.z.atan2(v.x.hypot(v.y)), v.y.atan2(v.x))
+    }
+
+    /**
+     * Finds where two great arcs cross, such as a cell edge and the edge of
+     * the icosahedron face it runs off of. Each arc is the shorter way
+     * between its endpoints.
+     *
+     * The crossing is found from the planes of the two great circles, so it
+     * is accurate to about 1e-15 radians anywhere on the sphere, including
+     * across the antimeridian and around the poles. Arcs that only touch,
+     * such as at a shared endpoint, cross there.
+     *
+     * @param a0 The first endpoint of the first arc.
+     * @param a1 The second endpoint of the first arc.
+     * @param b0 The first endpoint of the second arc.
+     * @param b1 The second endpoint of the second arc.
+     * @return The crossing, or None if the arcs do not cross, lie on the same
+     *         great circle, or either has equal or antipodal endpoints
+     */
+    pub fn great_arc_intersection(a0: &Self, a1: &Self, b0: &Self, b1: &Self) -> Option<Self> {
+        // relative tolerance for points on an arc's ends, and for parallel
+        // planes
+        const EPSILON: f64 = 1e-12;
+
+        let (a0, a1) = (a0._geoToVec3d(), a1._geoToVec3d());
+        let (b0, b1) = (b0._geoToVec3d(), b1._geoToVec3d());
+        let normalA = a0.cross(&a1);
+        let normalB = b0.cross(&b1);
+        let (normA, normB) = (normalA.norm(), normalB.norm());
+        if !(normA > EPSILON && normB > EPSILON) {
+            return None;
+        }
+
+        // the great circles cross at ±line
+        let line = normalA.cross(&normalB);
+        let normLine = line.norm();
+        if normLine <= EPSILON * normA * normB {
+            return None;
+        }
+        let line = line.scale(1.0 / normLine);
+
+        // a point is on an arc if it is on the inner side of both endpoints
+        let onArc = |p: &Vec3d, p0: &Vec3d, p1: &Vec3d, normal: &Vec3d, norm: f64| {
+            p0.cross(p).dot(normal) >= -EPSILON * norm && p.cross(p1).dot(normal) >= -EPSILON * norm
+        };
+        let opposite = line.scale(-1.0);
+        [line, opposite]
+            .iter()
+            .find(|p| onArc(p, &a0, &a1, &normalA, normA) && onArc(p, &b0, &b1, &normalB, normB))
+            .map(Self::_vec3dToGeo)
+    }
+
     /**
      * Encodes a coordinate on the sphere to the corresponding icosahedral face and
      * containing 2D hex coordinates relative to that face center.
@@ -360,10 +465,7 @@ impl GeoCoord {
      * @return The encoded H3Index (or H3_NULL on failure).
      */
     pub fn geoToH3(&self, res: Resolution) -> H3Index {
-        //if (res < 0 || res > MAX_H3_RES) {
-        //    return H3_NULL;
-        //}
-
+        // res is a Resolution, so it is always within 0..=MAX_H3_RES
         if !self.lat.is_finite() || !self.lon.is_finite() {
             return H3Index::H3_NULL;
         }
@@ -372,6 +474,40 @@ impl GeoCoord {
         fijk._faceIjkToH3(res) // TODO - or something wrong here?
     }
 
+    /**
+     * The icosahedron face a coordinate falls on, the first stage of `geoToH3`:
+     * the face whose center is nearest the coordinate.
+     *
+     * This can differ from the faces of the base cell containing the point,
+     * since base cells straddle face edges; see `geo_to_base_cell`.
+     *
+     * @return The face number, 0 to 19, or None if the coordinate is not finite
+     */
+    pub fn geo_to_face(&self) -> Option<i32> {
+        if !self.lat.is_finite() || !self.lon.is_finite() {
+            return None;
+        }
+
+        let (face, _) = self._geoToHex2d(Resolution::R0);
+        Some(face as i32)
+    }
+
+    /**
+     * The base cell a coordinate falls in: the base cell of its res 0 cell.
+     *
+     * Cells only approximately contain their children, so near the edge of a
+     * base cell, the cell containing the coordinate at a finer resolution may
+     * descend from a neighboring base cell instead.
+     *
+     * @return The base cell, or None if the coordinate is not finite
+     */
+    pub fn geo_to_base_cell(&self) -> Option<BaseCell> {
+        match self.geoToH3(Resolution::R0) {
+            H3Index::H3_NULL => None,
+            h => Some(h.get_base_cell()),
+        }
+    }
+
     /**
      * lineHexEstimate returns an estimated number of hexagons that trace
      *                 the cartesian-projected line
@@ -396,6 +532,77 @@ impl GeoCoord {
     }
 }
 
+impl std::fmt::Display for GeoCoord {
+    /// Writes the latitude and longitude in degrees as `lat,lng`, or as
+    /// `(lat lng)` with `{:#}`. A precision, as in `{:.6}`, sets the number of
+    /// decimals; without one, the shortest text that reads back as the same
+    /// degrees is written.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (Degrees(lat), Degrees(lng)) = self.lat_lng();
+        let (open, sep, close) = if f.alternate() {
+            ("(", " ", ")")
+        } else {
+            ("", ",", "")
+        };
+        match f.precision() {
+            Some(p) => write!(f, "{}{:.*}{}{:.*}{}", open, p, lat, sep, p, lng, close),
+            None => write!(f, "{}{}{}{}{}", open, lat, sep, lng, close),
+        }
+    }
+}
+
+/// Reasons text cannot be read as a `GeoCoord`
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ParseGeoCoordError {
+    /// The text is not two numbers as `lat,lng` or `(lat lng)`
+    Syntax,
+
+    /// The latitude is outside -90 to 90 degrees, or the longitude outside
+    /// -180 to 180
+    OutOfRange,
+}
+
+impl std::fmt::Display for ParseGeoCoordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseGeoCoordError::Syntax => write!(f, "expected \"lat,lng\" or \"(lat lng)\""),
+            ParseGeoCoordError::OutOfRange => write!(f, "latitude or longitude out of range"),
+        }
+    }
+}
+
+impl std::error::Error for ParseGeoCoordError {}
+
+impl std::str::FromStr for GeoCoord {
+    type Err = ParseGeoCoordError;
+
+    /// Reads a latitude and longitude in degrees, as `lat,lng` or
+    /// `(lat lng)`; a comma also separates them within parentheses, and
+    /// spaces may surround either number.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = match s.strip_prefix('(') {
+            Some(inner) => inner.strip_suffix(')').ok_or(ParseGeoCoordError::Syntax)?,
+            None => s,
+        };
+        let parts: Vec<&str> = if s.contains(',') {
+            s.split(',').map(str::trim).collect()
+        } else {
+            s.split_whitespace().collect()
+        };
+        if parts.len() != 2 {
+            return Err(ParseGeoCoordError::Syntax);
+        }
+        let lat: f64 = parts[0].parse().map_err(|_| ParseGeoCoordError::Syntax)?;
+        let lng: f64 = parts[1].parse().map_err(|_| ParseGeoCoordError::Syntax)?;
+
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+            return Err(ParseGeoCoordError::OutOfRange);
+        }
+        Ok(GeoCoord::new(Degrees(lat), Degrees(lng)))
+    }
+}
+
 /**
  * Computes the point on the sphere a specified azimuth and distance from
  * another point.
@@ -425,11 +632,11 @@ pub fn _geoAzDistanceRads(p1: &GeoCoord, az: f64, distance: f64) -> GeoCoord {
             lat = p1.lat - distance;
         }
 
-        if lat - M_PI_2.abs() < EPSILON {
+        if (lat - M_PI_2).abs() < EPSILON {
             // north pole
             lat = M_PI_2;
             lon = 0.0;
-        } else if lat + M_PI_2.abs() < EPSILON {
+        } else if (lat + M_PI_2).abs() < EPSILON {
             // south pole
             lat = -M_PI_2;
             lon = 0.0;
@@ -439,38 +646,22 @@ pub fn _geoAzDistanceRads(p1: &GeoCoord, az: f64, distance: f64) -> GeoCoord {
     } else {
         // not due north or south
         //double sinlat, sinlon, coslon;
-        let mut sinlat = p1.lat.sin() * distance.cos() + p1.lat.cos() * distance.sin() * az.cos();
-
-        if sinlat > 1.0 {
-            sinlat = 1.0;
-        } else if sinlat < -1.0 {
-            sinlat = -1.0;
-        }
+        let sinlat = (p1.lat.sin() * distance.cos() + p1.lat.cos() * distance.sin() * az.cos())
+            .clamp(-1.0, 1.0);
 
         lat = sinlat.asin();
-        if lat - M_PI_2.abs() < EPSILON {
+        if (lat - M_PI_2).abs() < EPSILON {
             // north pole
             lat = M_PI_2;
             lon = 0.0;
-        } else if lat + M_PI_2.abs() < EPSILON {
+        } else if (lat + M_PI_2).abs() < EPSILON {
             // south pole
             lat = -M_PI_2;
             lon = 0.0;
         } else {
-            let mut sinlon = az.sin() * distance.sin() / lat.cos();
-            let mut coslon = (distance.cos() - p1.lat.sin() * lat.sin()) / p1.lat.cos() / lat.cos();
-
-            if sinlon > 1.0 {
-                sinlon = 1.0;
-            } else if sinlon < -1.0 {
-                sinlon = -1.0;
-            }
-
-            if coslon > 1.0 {
-                coslon = 1.0;
-            } else if coslon < -1.0 {
-                coslon = -1.0;
-            }
+            let sinlon = (az.sin() * distance.sin() / lat.cos()).clamp(-1.0, 1.0);
+            let coslon = ((distance.cos() - p1.lat.sin() * lat.sin()) / p1.lat.cos() / lat.cos())
+                .clamp(-1.0, 1.0);
 
             lon = GeoCoord::constrainLng(p1.lon + f64::atan2(sinlon, coslon));
         }
@@ -485,4 +676,392 @@ mod tests {
 
     #[test]
     fn foo() {}
+
+    #[test]
+    fn triangleArea_octant() {
+        let northPole = GeoCoord::from_rads(M_PI_2, 0.0);
+        let a = GeoCoord::from_rads(0.0, 0.0);
+        let b = GeoCoord::from_rads(0.0, M_PI_2);
+
+        let area = GeoCoord::triangleArea(&northPole, &a, &b);
+        assert!((area - M_PI_2).abs() < 1e-12, "an eighth of the sphere");
+        assert_eq!(
+            area,
+            GeoCoord::triangleArea(&b, &a, &northPole),
+            "independent of winding"
+        );
+    }
+
+    #[test]
+    fn triangleArea_small() {
+        // a right triangle with 1e-6 radian legs, where the sphere is flat
+        let a = GeoCoord::from_rads(0.0, 0.0);
+        let b = GeoCoord::from_rads(0.0, 1e-6);
+        let c = GeoCoord::from_rads(1e-6, 0.0);
+
+        let area = GeoCoord::triangleArea(&a, &b, &c);
+        assert!((area / 0.5e-12 - 1.0).abs() < 1e-6, "planar area");
+    }
+
+    #[test]
+    fn triangleArea_degenerate() {
+        let a = GeoCoord::from_rads(0.1, 0.2);
+        let b = GeoCoord::from_rads(0.2, 0.3);
+        let azimuth = GeoCoord::_geoAzimuthRads(&a, &b);
+        let distance = GeoCoord::pointDistRads(&a, &b);
+
+        assert_eq!(GeoCoord::triangleArea(&a, &a, &b), 0.0, "repeated vertex");
+
+        // slivers keep only about half the digits of their side lengths, but
+        // must not come out as NaN
+        for i in 0..1000 {
+            let between = _geoAzDistanceRads(&a, azimuth, distance * i as f64 / 1000.0);
+            let area = GeoCoord::triangleArea(&a, &between, &b);
+            assert!(area.abs() < 1e-9, "collinear vertexes, got {}", area);
+        }
+    }
+
+    #[test]
+    fn lngLat() {
+        let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        let (lng, lat) = sf.lng_lat();
+        assert!((lng.0 - -122.419277).abs() < 1e-12, "longitude first");
+        assert!((lat.0 - 37.779265).abs() < 1e-12, "latitude second");
+        assert_eq!(sf.lat_lng(), (lat, lng), "latitude first");
+
+        let back = GeoCoord::from_lng_lat(lng, lat);
+        assert_eq!((back.lat, back.lon), (sf.lat, sf.lon), "round trip");
+        assert_eq!(
+            GeoCoord::from_lng_lat(Radians(1.0), Radians(0.5)),
+            GeoCoord::from_rads(0.5, 1.0),
+            "radians"
+        );
+    }
+
+    #[test]
+    fn pointDistRads_vectors() {
+        let a = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        assert_eq!(GeoCoord::pointDistRads(&a, &a), 0.0, "identical points");
+
+        let north = GeoCoord::from_rads(M_PI_2, 0.0);
+        let south = GeoCoord::from_rads(-M_PI_2, 1.0);
+        assert_eq!(GeoCoord::pointDistRads(&north, &south), M_PI, "poles");
+        assert_eq!(
+            GeoCoord::pointDistRads(
+                &GeoCoord::from_rads(0.0, 0.0),
+                &GeoCoord::from_rads(0.0, M_PI)
+            ),
+            M_PI,
+            "antipodes on the equator"
+        );
+        assert!(
+            (GeoCoord::pointDistRads(&north, &GeoCoord::from_rads(0.0, 2.0)) - M_PI_2).abs()
+                < 1e-15,
+            "pole to equator"
+        );
+
+        // Across the antimeridian, one degree apart.
+        let west = GeoCoord::new(Degrees(0.0), Degrees(179.5));
+        let east = GeoCoord::new(Degrees(0.0), Degrees(-179.5));
+        assert!(
+            (GeoCoord::pointDistRads(&west, &east) - degsToRads(1.0)).abs() < 1e-15,
+            "antimeridian"
+        );
+
+        // San Francisco to New York.
+        let b = GeoCoord::new(Degrees(40.712776), Degrees(-74.005974));
+        assert!(
+            (GeoCoord::pointDistKm(&a, &b) - 4128.917).abs() < 1e-3,
+            "{} km",
+            GeoCoord::pointDistKm(&a, &b)
+        );
+        assert_eq!(
+            GeoCoord::pointDistRads(&a, &b),
+            GeoCoord::pointDistRads(&b, &a),
+            "symmetric"
+        );
+
+        // A millimeter apart, where the law of cosines loses every digit. The
+        // offset itself is only represented to within an ulp of the latitude,
+        // about 1e-9 m.
+        let near = GeoCoord::from_rads(a.lat + 1e-3 / 1000. / EARTH_RADIUS_KM, a.lon);
+        assert!(
+            (GeoCoord::pointDistM(&a, &near) - 1e-3).abs() < 1e-9,
+            "{} m",
+            GeoCoord::pointDistM(&a, &near)
+        );
+    }
+
+    #[test]
+    fn pointDistRads_nearlyAntipodal() {
+        let mut state: u64 = 1213;
+        for _ in 0..100_000 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let lat = ((state >> 11) as f64 / (1u64 << 53) as f64 - 0.5) * M_PI;
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let lon = ((state >> 11) as f64 / (1u64 << 53) as f64 - 0.5) * 2.0 * M_PI;
+
+            let p = GeoCoord::from_rads(lat, lon);
+            let antipode = GeoCoord::from_rads(-lat, GeoCoord::constrainLng(lon + M_PI));
+            let d = GeoCoord::pointDistRads(&p, &antipode);
+            assert!((d - M_PI).abs() < 1e-7, "{:?} to its antipode is {}", p, d);
+        }
+    }
+
+    fn deg(lat: f64, lng: f64) -> GeoCoord {
+        GeoCoord::new(Degrees(lat), Degrees(lng))
+    }
+
+    fn assertNear(a: &GeoCoord, b: &GeoCoord, msg: &str) {
+        let d = GeoCoord::pointDistRads(a, b);
+        assert!(d < 1e-12, "{}: off by {} rad", msg, d);
+    }
+
+    #[test]
+    fn greatArcIntersection_crossings() {
+        let x = GeoCoord::great_arc_intersection(
+            &deg(0., -10.),
+            &deg(0., 10.),
+            &deg(-10., 0.),
+            &deg(10., 0.),
+        );
+        assertNear(&x.expect("cross"), &deg(0., 0.), "equator and meridian");
+
+        let x = GeoCoord::great_arc_intersection(
+            &deg(0., 170.),
+            &deg(0., -170.),
+            &deg(-5., 180.),
+            &deg(5., 180.),
+        );
+        assertNear(&x.expect("cross"), &deg(0., 180.), "antimeridian");
+
+        let x = GeoCoord::great_arc_intersection(
+            &deg(80., 0.),
+            &deg(80., 180.),
+            &deg(80., 90.),
+            &deg(80., -90.),
+        );
+        assertNear(&x.expect("cross"), &deg(90., 0.), "over the pole");
+
+        let x = GeoCoord::great_arc_intersection(
+            &deg(0., 0.),
+            &deg(10., 10.),
+            &deg(10., 10.),
+            &deg(0., 20.),
+        );
+        assertNear(&x.expect("touch"), &deg(10., 10.), "shared endpoint");
+    }
+
+    #[test]
+    fn greatArcIntersection_none() {
+        let a0 = deg(0., -10.);
+        let a1 = deg(0., 10.);
+        assert!(
+            GeoCoord::great_arc_intersection(&a0, &a1, &deg(5., 0.), &deg(10., 0.)).is_none(),
+            "meridian arc north of the equator arc"
+        );
+        assert!(
+            GeoCoord::great_arc_intersection(&a0, &a1, &deg(-10., 20.), &deg(10., 20.)).is_none(),
+            "the circles cross past the end of the arc"
+        );
+        assert!(
+            GeoCoord::great_arc_intersection(&a0, &a1, &deg(0., 5.), &deg(0., 15.)).is_none(),
+            "same great circle"
+        );
+        assert!(
+            GeoCoord::great_arc_intersection(&a0, &a0, &deg(-10., 0.), &deg(10., 0.)).is_none(),
+            "no arc"
+        );
+        assert!(
+            GeoCoord::great_arc_intersection(&a0, &deg(0., 170.), &deg(-10., 0.), &deg(10., 0.))
+                .is_none(),
+            "antipodal endpoints"
+        );
+    }
+
+    #[test]
+    fn greatArcIntersection_random() {
+        let mut state: u64 = 12345;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let mut point =
+            || GeoCoord::from_rads((2. * next() - 1.).asin(), M_PI * (2. * next() - 1.));
+        let onArc = |p: &GeoCoord, a: &GeoCoord, b: &GeoCoord| {
+            GeoCoord::pointDistRads(a, p) + GeoCoord::pointDistRads(p, b)
+                - GeoCoord::pointDistRads(a, b)
+                < 1e-12
+        };
+
+        let mut crossings = 0;
+        for _ in 0..10_000 {
+            let (a0, a1, b0, b1) = (point(), point(), point(), point());
+            let x = GeoCoord::great_arc_intersection(&a0, &a1, &b0, &b1);
+            let y = GeoCoord::great_arc_intersection(&b1, &b0, &a1, &a0);
+            assert_eq!(x.is_some(), y.is_some(), "symmetric");
+            if let (Some(x), Some(y)) = (x, y) {
+                crossings += 1;
+                assertNear(&x, &y, "symmetric");
+                assert!(onArc(&x, &a0, &a1) && onArc(&x, &b0, &b1), "on both arcs");
+            }
+        }
+        assert!(crossings > 500, "{} crossings", crossings);
+    }
+
+    #[test]
+    fn greatArcIntersection_distortionVertexes() {
+        // Class III cells crossing an icosahedron edge get an extra vertex
+        // where they cross it. The edge is on the plane of symmetry between
+        // the two faces, so the vertex is as far from both face centers.
+        let mut distorted = 0;
+        for h in H3Index::getRes0Indexes()
+            .iter()
+            .flat_map(|h| h.h3ToChildren(Resolution::R1))
+        {
+            let gb = h.h3ToGeoBoundary();
+            if gb.numVerts <= 6 || h.is_pentagon() {
+                continue;
+            }
+            distorted += 1;
+            let faces = h.h3GetFaces();
+            let onFaceEdge = gb.verts[..gb.numVerts].iter().any(|v| {
+                faces.iter().any(|f1| {
+                    faces.iter().filter(|&f2| f2 > f1).any(|f2| {
+                        let d1 = GeoCoord::pointDistRads(v, &faceCenterGeo[f1 as usize]);
+                        let d2 = GeoCoord::pointDistRads(v, &faceCenterGeo[f2 as usize]);
+                        (d1 - d2).abs() < 1e-12
+                    })
+                })
+            });
+            assert!(onFaceEdge, "{} has a vertex on the face edge", h);
+        }
+        assert!(distorted > 0, "some cells cross face edges");
+    }
+
+    #[test]
+    fn geoToFace() {
+        for (face, center) in faceCenterGeo.iter().enumerate() {
+            assert_eq!(
+                center.geo_to_face(),
+                Some(face as i32),
+                "face {} center",
+                face
+            );
+        }
+
+        let mut state: u64 = 1217;
+        for _ in 0..1000 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let lat = ((state >> 11) as f64 / (1u64 << 53) as f64 - 0.5) * M_PI;
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let lon = ((state >> 11) as f64 / (1u64 << 53) as f64 - 0.5) * M_2PI;
+            let g = GeoCoord::from_rads(lat, lon);
+
+            let face = g.geo_to_face().expect("finite");
+            assert_eq!(
+                face,
+                g._geoToFaceIjk(Resolution::R15).face,
+                "same face as geoToH3"
+            );
+            assert!(
+                g.geoToH3(Resolution::R15).h3GetFaces().contains(&face),
+                "{:?} on a face of its res 15 cell",
+                g
+            );
+
+            let bc = g.geo_to_base_cell().expect("finite");
+            let res0 = g.geoToH3(Resolution::R0);
+            assert_eq!(res0.get_base_cell(), bc, "{:?} base cell", g);
+            let near = res0.kRing(1);
+            for &res in Resolution::RESOLUTIONS.iter() {
+                let parent = g.geoToH3(res).h3ToParent(Resolution::R0).unwrap();
+                assert!(near.contains(&parent), "{:?} at res {:?}", g, res);
+            }
+        }
+
+        let nan = GeoCoord::from_rads(f64::NAN, 0.0);
+        assert_eq!(nan.geo_to_face(), None, "no face");
+        assert_eq!(nan.geo_to_base_cell(), None, "no base cell");
+    }
+
+    #[test]
+    fn geoCoord_display() {
+        let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        assert_eq!(format!("{:.3}", sf), "37.779,-122.419", "precision");
+        assert_eq!(format!("{:#.2}", sf), "(37.78 -122.42)", "alternate");
+        assert_eq!(
+            format!("{}", GeoCoord::new(Degrees(0.5), Degrees(-90.0))),
+            "0.5,-90",
+            "shortest"
+        );
+        assert_eq!(sf.to_string().parse::<GeoCoord>(), Ok(sf), "round trip");
+        assert_eq!(
+            format!("{:#}", sf).parse::<GeoCoord>(),
+            Ok(sf),
+            "round trip"
+        );
+    }
+
+    #[test]
+    fn geoCoord_fromStr() {
+        let expected = GeoCoord::new(Degrees(37.5), Degrees(-122.25));
+        for s in [
+            "37.5,-122.25",
+            " 37.5 , -122.25 ",
+            "(37.5 -122.25)",
+            "( 37.5   -122.25 )",
+            "(37.5, -122.25)",
+            "37.5 -122.25",
+            "3.75e1,-122.25",
+        ]
+        .iter()
+        {
+            assert_eq!(s.parse::<GeoCoord>(), Ok(expected), "{:?}", s);
+        }
+
+        for s in [
+            "",
+            "37.5",
+            "37.5,-122.25,0",
+            "37.5,,-122.25",
+            "(37.5 -122.25",
+            "37.5 -122.25)",
+            "north,west",
+            "37.5;-122.25",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                s.parse::<GeoCoord>(),
+                Err(ParseGeoCoordError::Syntax),
+                "{:?}",
+                s
+            );
+        }
+
+        for s in ["90.5,0", "0,-180.5", "NaN,0", "0,inf"].iter() {
+            assert_eq!(
+                s.parse::<GeoCoord>(),
+                Err(ParseGeoCoordError::OutOfRange),
+                "{:?}",
+                s
+            );
+        }
+        assert_eq!(
+            "-90,180".parse::<GeoCoord>().map(|g| g.lat_lng()),
+            Ok((Degrees(-90.0), Degrees(180.0))),
+            "bounds included"
+        );
+    }
 }
@@ -0,0 +1,223 @@
+//! GeoJSON polygons in, behind the `geojson` feature.
+//!
+//! Reads the polygons of a Polygon or MultiPolygon geometry, or of every such
+//! geometry in a Feature, FeatureCollection or GeometryCollection, so that a
+//! polygon file can go straight to `GeoMultiPolygon::polyfill`. Positions are
+//! `[lng, lat]` in degrees, and each ring is checked as
+//! `Geofence::from_lng_lat_ring` and `GeoPolygon::from_geofences` check them.
+
+use serde_json::Value;
+
+use crate::{GeoMultiPolygon, GeoPolygon, Geofence, H3Index, ParseGeometryError, Resolution};
+
+/// The elements of a JSON array.
+fn array(value: &Value) -> Result<&Vec<Value>, ParseGeometryError> {
+    value.as_array().ok_or(ParseGeometryError::Syntax)
+}
+
+/// A ring from its positions, each at least a longitude and a latitude.
+fn readRing(ring: &Value) -> Result<Geofence, ParseGeometryError> {
+    let positions = array(ring)?
+        .iter()
+        .map(|p| match (p[0].as_f64(), p[1].as_f64()) {
+            (Some(lng), Some(lat)) => Ok([lng, lat]),
+            _ => Err(ParseGeometryError::Syntax),
+        })
+        .collect::<Result<Vec<[f64; 2]>, ParseGeometryError>>()?;
+    Ok(Geofence::from_lng_lat_ring(positions)?)
+}
+
+/// A polygon from the coordinates of a GeoJSON Polygon: the exterior ring,
+/// then the holes.
+fn readPolygon(coordinates: &Value) -> Result<GeoPolygon, ParseGeometryError> {
+    let mut rings = array(coordinates)?
+        .iter()
+        .map(readRing)
+        .collect::<Result<Vec<Geofence>, ParseGeometryError>>()?;
+    if rings.is_empty() {
+        return Err(ParseGeometryError::Syntax);
+    }
+    let exterior = rings.remove(0);
+    Ok(GeoPolygon::from_geofences(exterior, rings)?)
+}
+
+/// Adds the polygons of a GeoJSON object to `out`.
+fn collectPolygons(value: &Value, out: &mut Vec<GeoPolygon>) -> Result<(), ParseGeometryError> {
+    match value["type"].as_str() {
+        Some("Polygon") => out.push(readPolygon(&value["coordinates"])?),
+        Some("MultiPolygon") => {
+            for coordinates in array(&value["coordinates"])? {
+                out.push(readPolygon(coordinates)?);
+            }
+        }
+        // A feature without a location has a null geometry.
+        Some("Feature") if value["geometry"].is_null() => {}
+        Some("Feature") => collectPolygons(&value["geometry"], out)?,
+        Some("FeatureCollection") => {
+            for feature in array(&value["features"])? {
+                collectPolygons(feature, out)?;
+            }
+        }
+        Some("GeometryCollection") => {
+            for geometry in array(&value["geometries"])? {
+                collectPolygons(geometry, out)?;
+            }
+        }
+        Some(_) => return Err(ParseGeometryError::UnsupportedGeometry),
+        None => return Err(ParseGeometryError::Syntax),
+    }
+    Ok(())
+}
+
+impl GeoMultiPolygon {
+    /**
+     * Reads the polygons of a GeoJSON document: a Polygon or MultiPolygon,
+     * or a Feature, FeatureCollection or GeometryCollection of them.
+     *
+     * @param text The GeoJSON text
+     * @return The polygons, in the order they appear; Syntax if the text is
+     *         not GeoJSON, UnsupportedGeometry if it holds another kind of
+     *         geometry, or Polygon if a ring is not valid
+     */
+    pub fn from_geojson(text: &str) -> Result<Self, ParseGeometryError> {
+        let value: Value = serde_json::from_str(text).map_err(|_| ParseGeometryError::Syntax)?;
+        let mut polygons = Vec::new();
+        collectPolygons(&value, &mut polygons)?;
+        Ok(GeoMultiPolygon { polygons })
+    }
+
+    /// The cells at a resolution whose centers are inside the polygons of a
+    /// GeoJSON document; see `from_geojson` and `polyfill`.
+    pub fn polyfill_geojson(
+        text: &str,
+        res: Resolution,
+    ) -> Result<Vec<H3Index>, ParseGeometryError> {
+        Ok(Self::from_geojson(text)?.polyfill(res))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PolygonError;
+
+    const PARK: &str = r#"{
+      "type": "Polygon",
+      "coordinates": [
+        [[-122.511, 37.771], [-122.454, 37.773], [-122.454, 37.766],
+         [-122.511, 37.764], [-122.511, 37.771]],
+        [[-122.490, 37.770], [-122.480, 37.770], [-122.480, 37.767],
+         [-122.490, 37.767], [-122.490, 37.770]]
+      ]
+    }"#;
+
+    #[test]
+    fn fromGeojson_polygon() {
+        let park = GeoMultiPolygon::from_geojson(PARK).unwrap();
+        assert_eq!(park.polygons.len(), 1, "one polygon");
+        assert_eq!(park.polygons[0].holes().count(), 1, "with a hole");
+
+        let exterior = Geofence::from_lng_lat_ring(vec![
+            [-122.511, 37.771],
+            [-122.454, 37.773],
+            [-122.454, 37.766],
+            [-122.511, 37.764],
+        ])
+        .unwrap();
+        let hole = Geofence::from_lng_lat_ring(vec![
+            [-122.490, 37.770],
+            [-122.480, 37.770],
+            [-122.480, 37.767],
+            [-122.490, 37.767],
+        ])
+        .unwrap();
+        let expected = GeoPolygon::from_geofences(exterior, vec![hole])
+            .unwrap()
+            .polyfill(Resolution::R10);
+        assert!(!expected.is_empty());
+        assert_eq!(
+            GeoMultiPolygon::polyfill_geojson(PARK, Resolution::R10),
+            Ok(expected),
+            "same cells as built by hand"
+        );
+    }
+
+    #[test]
+    fn fromGeojson_collections() {
+        let square = |x: f64| {
+            format!(
+                "[[[{x}, 0], [{y}, 0], [{y}, 1], [{x}, 1], [{x}, 0]]]",
+                x = x,
+                y = x + 1.0
+            )
+        };
+        let multi = format!(
+            r#"{{"type": "MultiPolygon", "coordinates": [{}, {}]}}"#,
+            square(0.0),
+            square(2.0)
+        );
+        let collection = format!(
+            r#"{{"type": "FeatureCollection", "features": [
+                {{"type": "Feature", "properties": {{}}, "geometry": {}}},
+                {{"type": "Feature", "properties": {{}}, "geometry": null}},
+                {{"type": "Feature", "properties": {{}}, "geometry":
+                    {{"type": "GeometryCollection", "geometries": [
+                        {{"type": "Polygon", "coordinates": {}}}]}}}}
+            ]}}"#,
+            multi,
+            square(1.0)
+        );
+        let polygons = GeoMultiPolygon::from_geojson(&collection).unwrap();
+        assert_eq!(polygons.polygons.len(), 3, "every polygon");
+
+        // The squares tile a strip, so their cells are the strip's.
+        let strip = GeoMultiPolygon::from_geojson(
+            r#"{"type": "Polygon", "coordinates": [[[0, 0], [3, 0], [3, 1], [0, 1]]]}"#,
+        )
+        .unwrap();
+        let cells = polygons.polyfill(Resolution::R5);
+        assert!(cells.windows(2).all(|w| w[0] < w[1]), "sorted, once each");
+        assert_eq!(cells, strip.polyfill(Resolution::R5), "same as the strip");
+    }
+
+    #[test]
+    fn fromGeojson_errors() {
+        let parse = GeoMultiPolygon::from_geojson;
+        assert_eq!(parse("{").err(), Some(ParseGeometryError::Syntax), "json");
+        assert_eq!(
+            parse(r#"{"coordinates": []}"#).err(),
+            Some(ParseGeometryError::Syntax),
+            "no type"
+        );
+        assert_eq!(
+            parse(r#"{"type": "Polygon", "coordinates": []}"#).err(),
+            Some(ParseGeometryError::Syntax),
+            "no rings"
+        );
+        assert_eq!(
+            parse(r#"{"type": "Polygon", "coordinates": [[[0, 0], [1], [1, 1]]]}"#).err(),
+            Some(ParseGeometryError::Syntax),
+            "short position"
+        );
+        assert_eq!(
+            parse(r#"{"type": "Point", "coordinates": [0, 0]}"#).err(),
+            Some(ParseGeometryError::UnsupportedGeometry),
+            "point"
+        );
+        assert_eq!(
+            parse(r#"{"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [0, 0]]]}"#).err(),
+            Some(ParseGeometryError::Polygon(PolygonError::TooFewVertices)),
+            "ring"
+        );
+        let holeOutside = r#"{"type": "Polygon", "coordinates": [
+            [[0, 0], [1, 0], [1, 1], [0, 1]],
+            [[5, 5], [6, 5], [6, 6]]]}"#;
+        assert_eq!(
+            parse(holeOutside).err(),
+            Some(ParseGeometryError::Polygon(
+                PolygonError::HoleOutsideGeofence
+            )),
+            "hole"
+        );
+    }
+}
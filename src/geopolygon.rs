@@ -1,9 +1,14 @@
-use crate::GeoCoord;
+use crate::{
+    _geoAzDistanceRads,
+    constants::{M_2PI, M_PI, M_PI_2},
+    BBox, Degrees, GeoCoord, SphereModel,
+};
 
 /// Maximum number of cell boundary vertices; worst case is pentagon: 5 original verts + 5 edge crossings
-const MAX_CELL_BNDRY_VERTS: usize = 10;
+pub(crate) const MAX_CELL_BNDRY_VERTS: usize = 10;
 
 /// cell boundary in latitude/longitude
+#[derive(Clone, Debug, Default)]
 pub struct GeoBoundary {
     /// number of vertices
     pub numVerts: usize,
@@ -12,21 +17,964 @@ pub struct GeoBoundary {
     pub verts: [GeoCoord; MAX_CELL_BNDRY_VERTS],
 }
 
-/// similar to GeoBoundary, but requires more alloc work
-pub(crate) struct Geofence {
-    pub verts: Vec<GeoCoord>,
+impl GeoBoundary {
+    /// The vertexes as `[lng, lat]` positions in degrees, with the first
+    /// repeated at the end to close the ring, the way GeoJSON, WKB and the
+    /// formats built on them store a polygon.
+    pub fn to_lng_lat_ring(&self) -> Vec<[f64; 2]> {
+        let verts = &self.verts[..self.numVerts];
+        verts
+            .iter()
+            .chain(verts.first())
+            .map(|v| {
+                let (lng, lat) = v.lng_lat();
+                [lng.0, lat.0]
+            })
+            .collect()
+    }
+}
+
+/// A simple loop of vertices, similar to GeoBoundary but of any length: the
+/// exterior or a hole of a `GeoPolygon`.
+///
+/// Build one with `new`, `from_degrees` or `from_lng_lat_ring`, which check
+/// that the loop has finite coordinates, at least three distinct vertices and
+/// some area. The loop is stored open, without a vertex repeating the first,
+/// and without runs of repeated vertices, which are merged into one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Geofence {
+    pub(crate) verts: Vec<GeoCoord>,
+}
+
+/// Largest ratio of a loop's area to the square of its extent, both in
+/// radians, for which the loop is taken to have no area: its vertices are
+/// collinear up to rounding.
+const ZERO_AREA_RATIO: f64 = 1e-12;
+
+/// Normalize longitude, dealing with transmeridian arcs
+pub(crate) fn normalizeLon(lon: f64, isTransmeridian: bool) -> f64 {
+    if isTransmeridian && lon < 0.0 {
+        lon + M_2PI
+    } else {
+        lon
+    }
+}
+
+impl Geofence {
+    /// Iterate over the edges of the loop, as (vertex, next vertex) pairs.
+    pub(crate) fn edges(&self) -> impl Iterator<Item = (&GeoCoord, &GeoCoord)> {
+        self.verts.iter().zip(self.verts.iter().cycle().skip(1))
+    }
+
+    /**
+     * Take a given geofence and determine whether a point is inside of it, using
+     * the ray casting algorithm.
+     * @param bbox The bbox for the loop being tested
+     * @param coord The coordinate to check
+     * @return Whether the point is contained
+     */
+    pub(crate) fn pointInsideGeofence(&self, bbox: &BBox, coord: &GeoCoord) -> bool {
+        // fail fast if we're outside the bounding box
+        if !bbox.bboxContains(coord) {
+            return false;
+        }
+        let isTransmeridian = bbox.bboxIsTransmeridian();
+        let mut contains = false;
+
+        let lat = coord.lat;
+        let mut lng = normalizeLon(coord.lon, isTransmeridian);
+
+        for (a, b) in self.edges() {
+            // Ray casting algo requires the second point to always be higher
+            // than the first, so swap if needed
+            let (a, b) = if a.lat > b.lat { (b, a) } else { (a, b) };
+
+            // If we're totally above or below the latitude ranges, the test
+            // ray cannot intersect the line segment, so let's move on
+            if lat < a.lat || lat > b.lat {
+                continue;
+            }
+
+            let aLng = normalizeLon(a.lon, isTransmeridian);
+            let bLng = normalizeLon(b.lon, isTransmeridian);
+
+            // Rays are cast in the longitudinal direction, in case a point
+            // exactly matches, to decide tiebreakers, bias westerly
+            if aLng == lng || bLng == lng {
+                lng -= f64::EPSILON;
+            }
+
+            // For the latitude of the point, compute the longitude of the
+            // point that lies on the line segment defined by a and b
+            // This is done by computing the percent above a the lat is,
+            // and traversing the same percent in the longitudinal direction
+            // of a to b
+            let ratio = (lat - a.lat) / (b.lat - a.lat);
+            let testLng = normalizeLon(aLng + (bLng - aLng) * ratio, isTransmeridian);
+
+            // Intersection of the ray
+            if testLng > lng {
+                contains = !contains;
+            }
+        }
+
+        contains
+    }
+
+    /**
+     * Create a bounding box from a simple polygon loop.
+     * Known limitations:
+     * - Does not support polygons with two adjacent points > 180 degrees of
+     *   longitude apart. These will be interpreted as crossing the antimeridian.
+     * - Does not currently support polygons containing a pole.
+     *
+     * @return The bounding box for the loop
+     */
+    pub(crate) fn bboxFromGeofence(&self) -> BBox {
+        // Early exit if there are no vertices
+        if self.verts.is_empty() {
+            return BBox::new(0.0, 0.0, 0.0, 0.0);
+        }
+
+        let mut bbox = BBox::new(-f64::MAX, f64::MAX, -f64::MAX, f64::MAX);
+        let mut minPosLon = f64::MAX;
+        let mut maxNegLon = -f64::MAX;
+        let mut isTransmeridian = false;
+
+        for (coord, next) in self.edges() {
+            let lat = coord.lat;
+            let lon = coord.lon;
+            bbox.south = bbox.south.min(lat);
+            bbox.west = bbox.west.min(lon);
+            bbox.north = bbox.north.max(lat);
+            bbox.east = bbox.east.max(lon);
+            // Save the min positive and max negative longitude for
+            // use in the transmeridian case
+            if lon > 0.0 && lon < minPosLon {
+                minPosLon = lon;
+            }
+            if lon < 0.0 && lon > maxNegLon {
+                maxNegLon = lon;
+            }
+            // check for arcs > 180 degrees longitude, flagging as transmeridian
+            if (lon - next.lon).abs() > M_PI {
+                isTransmeridian = true;
+            }
+        }
+        // Swap east and west if transmeridian
+        if isTransmeridian {
+            bbox.east = maxNegLon;
+            bbox.west = minPosLon;
+        }
+
+        bbox
+    }
+
+    /**
+     * Whether the winding order of a given loop is clockwise. In GeoJSON,
+     * clockwise loops are always inner loops (holes).
+     * @return Whether the loop is clockwise
+     */
+    pub(crate) fn isClockwise(&self) -> bool {
+        self.windingSum(false) > 0.0
+    }
+
+    /// Twice the area of the loop in the plane of latitude and longitude,
+    /// positive if it winds clockwise, with normalization for loops crossing
+    /// the antimeridian.
+    fn windingSum(&self, isTransmeridian: bool) -> f64 {
+        let mut sum = 0.0;
+        for (a, b) in self.edges() {
+            // If we identify a transmeridian arc (> 180 degrees longitude),
+            // start over with the transmeridian flag set
+            if !isTransmeridian && (a.lon - b.lon).abs() > M_PI {
+                return self.windingSum(true);
+            }
+            sum += (normalizeLon(b.lon, isTransmeridian) - normalizeLon(a.lon, isTransmeridian))
+                * (b.lat + a.lat);
+        }
+
+        sum
+    }
+
+    /**
+     * Area of the loop on the unit sphere, with every edge a straight line in
+     * latitude and longitude, as point in polygon and polyfill take them.
+     * Each edge contributes the integral of sin(lat) over its longitudes,
+     * which is exact for such edges.
+     *
+     * @return The area in radians^2
+     */
+    pub(crate) fn areaRads2(&self) -> f64 {
+        let mut sum = 0.0;
+        for (a, b) in self.edges() {
+            let mut dLng = b.lon - a.lon;
+            if dLng > M_PI {
+                dLng -= M_2PI;
+            } else if dLng < -M_PI {
+                dLng += M_2PI;
+            }
+            let dLat = b.lat - a.lat;
+            let meanSinLat = if dLat.abs() > 1e-12 {
+                (a.lat.cos() - b.lat.cos()) / dLat
+            } else {
+                ((a.lat + b.lat) / 2.0).sin()
+            };
+            sum += dLng * meanSinLat;
+        }
+        sum.abs()
+    }
+
+    /// Whether the loop encloses no area, as when its vertices all lie on a
+    /// line, which would leave point in polygon and polyfill with nothing to
+    /// work with.
+    fn isZeroArea(&self) -> bool {
+        let bbox = self.bboxFromGeofence();
+        let lngSpan = if bbox.bboxIsTransmeridian() {
+            bbox.east - bbox.west + M_2PI
+        } else {
+            bbox.east - bbox.west
+        };
+        let extent = (bbox.north - bbox.south).max(lngSpan);
+        (self.windingSum(false) / 2.0).abs() <= ZERO_AREA_RATIO * extent * extent
+    }
+}
+
+/// Reasons a set of rings cannot form a `GeoPolygon`
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PolygonError {
+    /// A ring has fewer than three distinct vertices once closed
+    TooFewVertices,
+
+    /// A vertex has a non-finite latitude or longitude
+    InvalidCoordinate,
+
+    /// A hole has a vertex outside of the exterior boundary
+    HoleOutsideGeofence,
+
+    /// A ring encloses no area, such as one whose vertices are collinear
+    ZeroArea,
+}
+
+impl std::fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolygonError::TooFewVertices => write!(f, "ring has fewer than 3 vertices"),
+            PolygonError::InvalidCoordinate => write!(f, "ring has a non-finite coordinate"),
+            PolygonError::HoleOutsideGeofence => write!(f, "hole is not inside the polygon"),
+            PolygonError::ZeroArea => write!(f, "ring encloses no area"),
+        }
+    }
+}
+
+impl std::error::Error for PolygonError {}
+
+/// Reasons a GeoJSON or WKB geometry cannot be read as polygons
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ParseGeometryError {
+    /// The input is malformed: not JSON, truncated bytes, or a member or
+    /// coordinate missing or of the wrong type
+    Syntax,
+
+    /// The geometry is not polygonal, such as a Point or LineString
+    UnsupportedGeometry,
+
+    /// The rings do not form a valid polygon
+    Polygon(PolygonError),
+}
+
+impl From<PolygonError> for ParseGeometryError {
+    fn from(e: PolygonError) -> Self {
+        ParseGeometryError::Polygon(e)
+    }
+}
+
+impl std::fmt::Display for ParseGeometryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseGeometryError::Syntax => write!(f, "malformed geometry"),
+            ParseGeometryError::UnsupportedGeometry => write!(f, "geometry is not a polygon"),
+            ParseGeometryError::Polygon(e) => write!(f, "invalid polygon: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParseGeometryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseGeometryError::Polygon(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Geofence {
+    /**
+     * Builds a validated loop from its vertices. The loop may be given open,
+     * as H3 does, or closed with a last vertex repeating the first, as GeoJSON
+     * does; the closing vertex is dropped. Consecutive identical vertices,
+     * common in real-world data, are merged into one.
+     *
+     * @param verts Vertices of the loop
+     * @return The loop, or TooFewVertices, InvalidCoordinate or ZeroArea
+     */
+    pub fn new(verts: impl IntoIterator<Item = GeoCoord>) -> Result<Self, PolygonError> {
+        Self::closedRing(verts.into_iter().collect())
+    }
+
+    /**
+     * Builds a validated loop from `(lat, lng)` pairs in degrees, open or
+     * closed like `new`.
+     *
+     * @param verts Latitude and longitude of each vertex, in degrees
+     * @return The loop, or TooFewVertices, InvalidCoordinate or ZeroArea
+     */
+    pub fn from_degrees(verts: impl IntoIterator<Item = (f64, f64)>) -> Result<Self, PolygonError> {
+        Self::new(
+            verts
+                .into_iter()
+                .map(|(lat, lng)| GeoCoord::new(Degrees(lat), Degrees(lng))),
+        )
+    }
+
+    /**
+     * Builds a validated loop from `[lng, lat]` positions in degrees, the
+     * order GeoJSON and `GeoBoundary::to_lng_lat_ring` use, open or closed
+     * like `new`.
+     *
+     * @param ring Longitude and latitude of each vertex, in degrees
+     * @return The loop, or TooFewVertices, InvalidCoordinate or ZeroArea
+     */
+    pub fn from_lng_lat_ring(
+        ring: impl IntoIterator<Item = [f64; 2]>,
+    ) -> Result<Self, PolygonError> {
+        Self::from_degrees(ring.into_iter().map(|[lng, lat]| (lat, lng)))
+    }
+
+    /// Vertices of the loop, without a closing vertex.
+    pub fn verts(&self) -> &[GeoCoord] {
+        &self.verts
+    }
+
+    /// Number of vertices, not counting a closing vertex.
+    pub fn len(&self) -> usize {
+        self.verts.len()
+    }
+
+    /// Whether the loop has no vertices; never the case for a validated loop.
+    pub fn is_empty(&self) -> bool {
+        self.verts.is_empty()
+    }
+
+    /// Whether a point is inside the loop. Loops around a pole are not
+    /// supported, and arcs spanning more than 180 degrees of longitude are
+    /// taken to cross the antimeridian.
+    pub fn contains(&self, coord: &GeoCoord) -> bool {
+        self.pointInsideGeofence(&self.bboxFromGeofence(), coord)
+    }
+
+    /// Whether the loop winds clockwise, as holes do in GeoJSON.
+    pub fn is_clockwise(&self) -> bool {
+        self.isClockwise()
+    }
+
+    /// Validates a loop; see `new`.
+    fn closedRing(mut verts: Vec<GeoCoord>) -> Result<Self, PolygonError> {
+        if verts
+            .iter()
+            .any(|v| !v.lat.is_finite() || !v.lon.is_finite())
+        {
+            return Err(PolygonError::InvalidCoordinate);
+        }
+
+        verts.dedup();
+        if verts.len() > 1 && verts.first() == verts.last() {
+            verts.pop();
+        }
+
+        if verts.len() < 3 {
+            return Err(PolygonError::TooFewVertices);
+        }
+
+        let ring = Self { verts };
+        if ring.isZeroArea() {
+            return Err(PolygonError::ZeroArea);
+        }
+        Ok(ring)
+    }
 }
 
 /// Simplified core of GeoJSON Polygon coordinates definition
-pub(crate) struct GeoPolygon {
+pub struct GeoPolygon {
     /// exterior boundary of the polygon
-    pub geofence: Geofence,
+    pub(crate) geofence: Geofence,
 
     /// interior boundaries (holes) in the polygon
-    pub holes: Vec<Geofence>,
+    pub(crate) holes: Vec<Geofence>,
+}
+
+impl GeoPolygon {
+    /**
+     * Builds a polygon from an exterior loop and its holes, validating every
+     * loop as `Geofence::new` does and checking that every hole lies inside
+     * the exterior loop.
+     *
+     * @param outer Vertices of the exterior loop, in radians
+     * @param holes Vertices of each hole, in radians
+     * @return The polygon, or the reason the input is malformed
+     */
+    pub fn new(outer: Vec<GeoCoord>, holes: Vec<Vec<GeoCoord>>) -> Result<Self, PolygonError> {
+        let geofence = Geofence::closedRing(outer)?;
+        let holes = holes
+            .into_iter()
+            .map(Geofence::closedRing)
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_geofences(geofence, holes)
+    }
+
+    /**
+     * Builds a polygon from loops already validated as `Geofence`s, checking
+     * that every hole lies inside the exterior loop.
+     *
+     * @param geofence The exterior loop
+     * @param holes The holes
+     * @return The polygon, or HoleOutsideGeofence
+     */
+    pub fn from_geofences(geofence: Geofence, holes: Vec<Geofence>) -> Result<Self, PolygonError> {
+        let bbox = geofence.bboxFromGeofence();
+        for hole in holes.iter() {
+            if !hole
+                .verts
+                .iter()
+                .all(|v| geofence.pointInsideGeofence(&bbox, v))
+            {
+                return Err(PolygonError::HoleOutsideGeofence);
+            }
+        }
+
+        Ok(Self { geofence, holes })
+    }
+
+    /**
+     * Builds the polygon covering a bounding box, with a vertex at each
+     * corner. Its north and south edges follow the parallels, as polyfill
+     * takes every edge to be a straight line in latitude and longitude.
+     *
+     * @param bbox The box, in radians; east of west crosses the antimeridian
+     * @return The polygon, InvalidCoordinate if a side is not finite or the
+     *         box reaches past a pole, or TooFewVertices if it has no area
+     */
+    pub fn from_bbox(bbox: &BBox) -> Result<Self, PolygonError> {
+        let sides = [bbox.north, bbox.south, bbox.east, bbox.west];
+        if sides.iter().any(|side| !side.is_finite()) || bbox.north > M_PI_2 || bbox.south < -M_PI_2
+        {
+            return Err(PolygonError::InvalidCoordinate);
+        }
+        if bbox.north <= bbox.south || bbox.east == bbox.west {
+            return Err(PolygonError::TooFewVertices);
+        }
+
+        let geofence = Geofence::new(vec![
+            GeoCoord::from_rads(bbox.south, bbox.west),
+            GeoCoord::from_rads(bbox.south, bbox.east),
+            GeoCoord::from_rads(bbox.north, bbox.east),
+            GeoCoord::from_rads(bbox.north, bbox.west),
+        ])?;
+        Ok(Self {
+            geofence,
+            holes: Vec::new(),
+        })
+    }
+
+    /**
+     * Builds a polygon approximating a circle on the Earth, with vertices
+     * evenly spaced around it, each at the given great circle distance from
+     * the center. The polygon is inscribed in the circle, so its edges cut
+     * slightly inside; use more vertices for a closer fit.
+     *
+     * Circles around a pole are not supported, as for any `Geofence`.
+     *
+     * @param center The center
+     * @param radiusKm Great circle distance from the center to each vertex,
+     *        in kilometers
+     * @param numVerts Number of vertices, at least 3
+     * @return The polygon, InvalidCoordinate if the center is not finite or
+     *         the radius not positive and finite, or TooFewVertices
+     */
+    pub fn from_circle(
+        center: &GeoCoord,
+        radiusKm: f64,
+        numVerts: usize,
+    ) -> Result<Self, PolygonError> {
+        if !(radiusKm > 0.0 && radiusKm.is_finite()) {
+            return Err(PolygonError::InvalidCoordinate);
+        }
+        if numVerts < 3 {
+            return Err(PolygonError::TooFewVertices);
+        }
+
+        let distance = radiusKm / SphereModel::EARTH.radius_km();
+        // Azimuths run clockwise from north; step backwards to wind the loop
+        // counterclockwise, as exterior loops are.
+        let verts = (0..numVerts).map(|i| {
+            let az = -M_2PI * i as f64 / numVerts as f64;
+            _geoAzDistanceRads(center, az, distance)
+        });
+        Ok(Self {
+            geofence: Geofence::new(verts)?,
+            holes: Vec::new(),
+        })
+    }
+
+    /// Whether a point is inside the exterior loop and outside every hole,
+    /// with the limitations of `Geofence::contains`.
+    pub fn contains(&self, coord: &GeoCoord) -> bool {
+        self.geofence.contains(coord) && !self.holes.iter().any(|hole| hole.contains(coord))
+    }
+
+    /// Area of the polygon in kilometers^2, with its edges straight lines in
+    /// latitude and longitude as for `contains`: the exterior's area less
+    /// that of the holes.
+    pub fn area_km2(&self) -> f64 {
+        let holes: f64 = self.holes.iter().map(|hole| hole.areaRads2()).sum();
+        SphereModel::EARTH.rads2_to_km2(self.geofence.areaRads2() - holes)
+    }
+
+    /// Vertices of the exterior loop, in radians, without a closing vertex.
+    pub fn exterior(&self) -> &[GeoCoord] {
+        &self.geofence.verts
+    }
+
+    /// Vertices of each hole, in radians, without a closing vertex.
+    pub fn holes(&self) -> impl Iterator<Item = &[GeoCoord]> {
+        self.holes.iter().map(|hole| hole.verts.as_slice())
+    }
 }
 
 /// Simplified core of GeoJSON MultiPolygon coordinates definition
-pub(crate) struct GeoMultiPolygon {
+pub struct GeoMultiPolygon {
+    /// the polygons, which do not overlap
     pub polygons: Vec<GeoPolygon>,
 }
+
+impl GeoMultiPolygon {
+    /**
+     * Groups loops into polygons, as GeoJSON wants them: counterclockwise
+     * loops are exteriors, and each clockwise loop is a hole of the smallest
+     * exterior around it.
+     *
+     * @param loops The loops, such as the outlines of a set of cells
+     * @return The polygons, in the order of their exteriors; a hole outside
+     *         every exterior is dropped
+     */
+    pub(crate) fn from_loops(loops: Vec<Geofence>) -> Self {
+        let (holes, outers): (Vec<Geofence>, Vec<Geofence>) =
+            loops.into_iter().partition(|fence| fence.isClockwise());
+        let bboxes: Vec<BBox> = outers.iter().map(|g| g.bboxFromGeofence()).collect();
+        let areas: Vec<f64> = outers.iter().map(|g| g.areaRads2()).collect();
+
+        let mut polygons: Vec<GeoPolygon> = outers
+            .into_iter()
+            .map(|geofence| GeoPolygon {
+                geofence,
+                holes: Vec::new(),
+            })
+            .collect();
+        for hole in holes {
+            let container = (0..polygons.len())
+                .filter(|&i| {
+                    polygons[i]
+                        .geofence
+                        .pointInsideGeofence(&bboxes[i], &hole.verts[0])
+                })
+                .min_by(|&a, &b| areas[a].total_cmp(&areas[b]));
+            if let Some(i) = container {
+                polygons[i].holes.push(hole);
+            }
+        }
+        Self { polygons }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Degrees, Radians, Resolution};
+
+    fn ring(verts: &[(f64, f64)]) -> Vec<GeoCoord> {
+        verts
+            .iter()
+            .map(|&(lat, lon)| GeoCoord::from_rads(lat, lon))
+            .collect()
+    }
+
+    const OUTER: [(f64, f64); 4] = [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)];
+
+    #[test]
+    fn newOpenAndClosedRings() {
+        let open = GeoPolygon::new(ring(&OUTER), vec![]).expect("open ring is valid");
+        assert_eq!(open.geofence.verts.len(), 4, "open ring kept as is");
+
+        let mut closed = OUTER.to_vec();
+        closed.push(OUTER[0]);
+        let closed = GeoPolygon::new(ring(&closed), vec![]).expect("closed ring is valid");
+        assert_eq!(closed.geofence.verts.len(), 4, "closing vertex is dropped");
+    }
+
+    #[test]
+    fn newTooFewVertices() {
+        assert_eq!(
+            GeoPolygon::new(ring(&OUTER[..2]), vec![]).err(),
+            Some(PolygonError::TooFewVertices),
+            "two vertices do not make a ring"
+        );
+        assert_eq!(
+            GeoPolygon::new(ring(&[(0.0, 0.0), (0.0, 1.0), (0.0, 0.0)]), vec![]).err(),
+            Some(PolygonError::TooFewVertices),
+            "closing vertex does not count"
+        );
+        assert_eq!(
+            GeoPolygon::new(ring(&OUTER), vec![vec![]]).err(),
+            Some(PolygonError::TooFewVertices),
+            "empty hole"
+        );
+    }
+
+    #[test]
+    fn newInvalidCoordinate() {
+        let mut outer = OUTER.to_vec();
+        outer[2].0 = f64::NAN;
+        assert_eq!(
+            GeoPolygon::new(ring(&outer), vec![]).err(),
+            Some(PolygonError::InvalidCoordinate),
+            "NaN latitude"
+        );
+    }
+
+    #[test]
+    fn newHoles() {
+        let inside = ring(&[(0.2, 0.2), (0.2, 0.8), (0.8, 0.8), (0.8, 0.2)]);
+        let polygon = GeoPolygon::new(ring(&OUTER), vec![inside]).expect("hole is inside");
+        assert_eq!(polygon.holes.len(), 1, "hole kept");
+
+        let crossing = ring(&[(0.2, 0.2), (0.2, 1.5), (0.8, 1.5), (0.8, 0.2)]);
+        assert_eq!(
+            GeoPolygon::new(ring(&OUTER), vec![crossing]).err(),
+            Some(PolygonError::HoleOutsideGeofence),
+            "hole crossing the exterior"
+        );
+    }
+
+    #[test]
+    fn toLngLatRing() {
+        let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        let gb = sf.geoToH3(Resolution::R9).h3ToGeoBoundary();
+        let ring = gb.to_lng_lat_ring();
+
+        assert_eq!(ring.len(), gb.numVerts + 1, "closed ring");
+        assert_eq!(ring.first(), ring.last(), "first vertex repeated");
+        for (position, vert) in ring.iter().zip(gb.verts[..gb.numVerts].iter()) {
+            assert_eq!(
+                GeoCoord::new(Degrees(position[1]), Degrees(position[0])),
+                *vert,
+                "[lng, lat]"
+            );
+        }
+        assert!(ring[0][0] < -122.0 && ring[0][1] > 37.0, "not swapped");
+
+        assert!(
+            GeoBoundary::default().to_lng_lat_ring().is_empty(),
+            "no vertexes"
+        );
+    }
+    #[test]
+    fn geofenceConstructors() {
+        let degrees = [
+            (37.8, -122.5),
+            (37.8, -122.3),
+            (37.7, -122.3),
+            (37.7, -122.5),
+        ];
+        let open = Geofence::from_degrees(degrees.iter().copied()).expect("open ring");
+        assert_eq!(open.len(), 4, "four vertices");
+        assert!(!open.is_empty(), "not empty");
+
+        let closed =
+            Geofence::from_degrees(degrees.iter().chain(&degrees[..1]).copied()).expect("closed");
+        assert_eq!(closed, open, "closing vertex dropped");
+
+        let positions = degrees.iter().map(|&(lat, lng)| [lng, lat]);
+        assert_eq!(
+            Geofence::from_lng_lat_ring(positions),
+            Ok(open.clone()),
+            "[lng, lat]"
+        );
+        assert_eq!(
+            Geofence::new(open.verts().iter().copied()),
+            Ok(open.clone()),
+            "radians"
+        );
+
+        let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        assert!(open.contains(&sf), "inside");
+        assert!(
+            !open.contains(&GeoCoord::new(Degrees(37.9), Degrees(-122.4))),
+            "outside"
+        );
+        assert!(open.is_clockwise(), "east then south is clockwise");
+
+        let gb = sf.geoToH3(Resolution::R9).h3ToGeoBoundary();
+        let cell = Geofence::from_lng_lat_ring(gb.to_lng_lat_ring()).expect("cell boundary");
+        assert_eq!(cell.len(), gb.numVerts, "boundary round trip");
+        assert!(cell.contains(&sf), "cell contains its point");
+        assert!(!cell.is_clockwise(), "boundaries are ccw");
+
+        let hole = Geofence::new(cell.verts().iter().copied()).expect("hole");
+        let polygon = GeoPolygon::from_geofences(open.clone(), vec![hole]).expect("polygon");
+        assert_eq!(polygon.exterior(), open.verts(), "exterior");
+        assert_eq!(polygon.holes().count(), 1, "one hole");
+        assert_eq!(
+            GeoPolygon::from_geofences(cell, vec![open]).err(),
+            Some(PolygonError::HoleOutsideGeofence),
+            "hole larger than the exterior"
+        );
+    }
+
+    #[test]
+    fn geofenceInvalid() {
+        assert_eq!(
+            Geofence::from_degrees(vec![(0.0, 0.0), (0.0, 1.0), (0.0, 0.0)]),
+            Err(PolygonError::TooFewVertices),
+            "closing vertex does not count"
+        );
+        assert_eq!(
+            Geofence::new(std::iter::empty()),
+            Err(PolygonError::TooFewVertices),
+            "empty"
+        );
+        assert_eq!(
+            Geofence::from_lng_lat_ring(vec![[0.0, 0.0], [1.0, f64::NAN], [1.0, 1.0]]),
+            Err(PolygonError::InvalidCoordinate),
+            "nan"
+        );
+    }
+
+    #[test]
+    fn geofenceRepeatedVertices() {
+        let square = [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)];
+        let expected = Geofence::from_degrees(square.iter().copied()).expect("square");
+        let repeated = vec![
+            (0.0, 0.0),
+            (0.0, 0.0),
+            (0.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 0.0),
+            (0.0, 0.0),
+            (0.0, 0.0),
+        ];
+        assert_eq!(
+            Geofence::from_degrees(repeated),
+            Ok(expected),
+            "runs merged, closing vertices dropped"
+        );
+        assert_eq!(
+            Geofence::from_degrees(vec![(0.0, 0.0), (0.0, 0.0), (0.0, 1.0), (0.0, 1.0)]),
+            Err(PolygonError::TooFewVertices),
+            "two distinct vertices"
+        );
+        assert_eq!(
+            GeoPolygon::new(ring(&[(0.0, 0.0); 5]), vec![]).err(),
+            Some(PolygonError::TooFewVertices),
+            "one distinct vertex"
+        );
+    }
+
+    #[test]
+    fn geofenceZeroArea() {
+        for collinear in [
+            vec![(0.0, 0.0), (0.0, 1.0), (0.0, 2.0)],
+            vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 3.0)],
+            vec![(0.0, 0.0), (0.0, 1.0), (0.0, 0.5)],
+            vec![(10.0, 179.0), (10.0, -179.0), (10.0, 179.5)],
+            vec![(0.1, 0.3), (0.2, 0.6), (0.3, 0.9)],
+        ] {
+            assert_eq!(
+                Geofence::from_degrees(collinear.iter().copied()),
+                Err(PolygonError::ZeroArea),
+                "{:?}",
+                collinear
+            );
+        }
+        let inside = ring(&[(0.2, 0.2), (0.2, 0.4), (0.2, 0.6)]);
+        assert_eq!(
+            GeoPolygon::new(ring(&OUTER), vec![inside]).err(),
+            Some(PolygonError::ZeroArea),
+            "flat hole"
+        );
+
+        let sliver = vec![(0.0, 0.0), (0.0, 1.0), (1e-4, 0.5)];
+        assert!(Geofence::from_degrees(sliver).is_ok(), "thin but not flat");
+        let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        let gb = sf.geoToH3(Resolution::R15).h3ToGeoBoundary();
+        assert!(
+            Geofence::from_lng_lat_ring(gb.to_lng_lat_ring()).is_ok(),
+            "smallest cell"
+        );
+    }
+
+    #[test]
+    fn fromBbox() {
+        let deg = |d: f64| Radians::from(Degrees(d)).0;
+        let bbox = BBox::new(deg(38.0), deg(37.0), deg(-122.0), deg(-123.0));
+        let polygon = GeoPolygon::from_bbox(&bbox).expect("box");
+        assert_eq!(polygon.exterior().len(), 4, "corners");
+        assert_eq!(polygon.geofence.bboxFromGeofence(), bbox, "same box");
+        assert!(!polygon.geofence.is_clockwise(), "counterclockwise");
+        assert!(
+            polygon
+                .geofence
+                .contains(&GeoCoord::new(Degrees(37.9), Degrees(-122.1))),
+            "inside"
+        );
+        assert!(
+            !polygon
+                .geofence
+                .contains(&GeoCoord::new(Degrees(38.1), Degrees(-122.5))),
+            "north of it"
+        );
+
+        let transmeridian = BBox::new(deg(10.0), deg(-10.0), deg(-175.0), deg(170.0));
+        let polygon = GeoPolygon::from_bbox(&transmeridian).expect("transmeridian box");
+        for &(lng, inside) in [(179.5, true), (-179.5, true), (171.0, true), (0.0, false)].iter() {
+            assert_eq!(
+                polygon
+                    .geofence
+                    .contains(&GeoCoord::new(Degrees(1.0), Degrees(lng))),
+                inside,
+                "{}",
+                lng
+            );
+        }
+
+        for (bad, error) in [
+            (BBox::new(0.1, 0.2, 0.2, 0.1), PolygonError::TooFewVertices),
+            (BBox::new(0.2, 0.1, 0.1, 0.1), PolygonError::TooFewVertices),
+            (
+                BBox::new(2.0, 0.1, 0.2, 0.1),
+                PolygonError::InvalidCoordinate,
+            ),
+            (
+                BBox::new(0.2, 0.1, f64::NAN, 0.1),
+                PolygonError::InvalidCoordinate,
+            ),
+        ]
+        .iter()
+        {
+            assert_eq!(GeoPolygon::from_bbox(bad).err(), Some(*error), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn areaAndContains() {
+        let deg = |d: f64| Radians::from(Degrees(d)).0;
+        let radius = SphereModel::EARTH.radius_km();
+        for bbox in [
+            BBox::new(deg(38.0), deg(37.0), deg(-122.0), deg(-123.0)),
+            BBox::new(deg(-60.0), deg(-70.0), deg(20.0), deg(-10.0)),
+            BBox::new(deg(10.0), deg(-10.0), deg(-175.0), deg(170.0)),
+        ] {
+            let polygon = GeoPolygon::from_bbox(&bbox).unwrap();
+            let span = if bbox.bboxIsTransmeridian() {
+                bbox.east - bbox.west + M_2PI
+            } else {
+                bbox.east - bbox.west
+            };
+            let expected = radius * radius * (bbox.north.sin() - bbox.south.sin()) * span;
+            let area = polygon.area_km2();
+            assert!((area - expected).abs() < 1e-9 * expected, "{:?}", bbox);
+
+            let mut reversed = polygon.exterior().to_vec();
+            reversed.reverse();
+            let reversed = GeoPolygon::new(reversed, vec![]).unwrap();
+            assert!(
+                (reversed.area_km2() - area).abs() < 1e-9 * area,
+                "clockwise"
+            );
+        }
+
+        // A diagonal edge, where latitude follows longitude: the integral of
+        // cos(lat) over lat from 0.1 to lng, for lng from 0.1 to 0.3.
+        let triangle =
+            GeoPolygon::new(ring(&[(0.1, 0.1), (0.1, 0.3), (0.3, 0.3)]), vec![]).unwrap();
+        let expected = radius * radius * ((0.1f64.cos() - 0.3f64.cos()) - 0.2 * 0.1f64.sin());
+        assert!(
+            (triangle.area_km2() - expected).abs() < 1e-9 * expected,
+            "triangle"
+        );
+
+        let outer = ring(&OUTER);
+        let hole = ring(&[(0.2, 0.2), (0.2, 0.4), (0.4, 0.4), (0.4, 0.2)]);
+        let holed = GeoPolygon::new(outer.clone(), vec![hole.clone()]).unwrap();
+        let exterior = GeoPolygon::new(outer, vec![]).unwrap();
+        let holeOnly = GeoPolygon::new(hole, vec![]).unwrap();
+        let difference = exterior.area_km2() - holeOnly.area_km2();
+        assert!(
+            (holed.area_km2() - difference).abs() < 1e-9 * difference,
+            "hole"
+        );
+        assert!(holed.contains(&GeoCoord::from_rads(0.6, 0.6)), "inside");
+        assert!(
+            !holed.contains(&GeoCoord::from_rads(0.3, 0.3)),
+            "in the hole"
+        );
+        assert!(!holed.contains(&GeoCoord::from_rads(1.5, 0.3)), "outside");
+    }
+
+    #[test]
+    fn fromCircle() {
+        for &(lat, lng) in [(37.779265, -122.419277), (-33.9, 18.4), (0.0, 179.9)].iter() {
+            let center = GeoCoord::new(Degrees(lat), Degrees(lng));
+            let polygon = GeoPolygon::from_circle(&center, 25.0, 64).expect("circle");
+            assert_eq!(polygon.exterior().len(), 64, "vertices");
+            assert!(!polygon.geofence.is_clockwise(), "counterclockwise");
+            for v in polygon.exterior() {
+                let d = GeoCoord::pointDistKm(&center, v);
+                assert!((d - 25.0).abs() < 1e-9, "{} km", d);
+            }
+
+            assert!(polygon.geofence.contains(&center), "center");
+            for i in 0..16 {
+                let az = M_2PI * i as f64 / 16.0;
+                let near = _geoAzDistanceRads(&center, az, 24.5 / SphereModel::EARTH.radius_km());
+                let far = _geoAzDistanceRads(&center, az, 25.5 / SphereModel::EARTH.radius_km());
+                assert!(polygon.geofence.contains(&near), "{:?} inside", near);
+                assert!(!polygon.geofence.contains(&far), "{:?} outside", far);
+            }
+        }
+
+        let center = GeoCoord::new(Degrees(10.0), Degrees(10.0));
+        for &(radius, verts, error) in [
+            (0.0, 8, PolygonError::InvalidCoordinate),
+            (f64::NAN, 8, PolygonError::InvalidCoordinate),
+            (-1.0, 8, PolygonError::InvalidCoordinate),
+            (10.0, 2, PolygonError::TooFewVertices),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                GeoPolygon::from_circle(&center, radius, verts).err(),
+                Some(error),
+                "{} km, {} vertices",
+                radius,
+                verts
+            );
+        }
+        assert_eq!(
+            GeoPolygon::from_circle(&GeoCoord::from_rads(f64::NAN, 0.0), 10.0, 8).err(),
+            Some(PolygonError::InvalidCoordinate),
+            "center"
+        );
+    }
+}
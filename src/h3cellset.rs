@@ -0,0 +1,224 @@
+//! A read-only set of cells at mixed resolutions, such as a compacted
+//! covering, kept as one sorted array.
+
+use crate::{GeoCoord, H3Error, H3Index, Resolution};
+
+/**
+ * A set of cells at any mix of resolutions, such as the output of
+ * `compact`, answering whether a cell or point falls inside it by binary
+ * search.
+ *
+ * The cells are kept in ascending index order in a single array. The
+ * resolution sits above the base cell and digits in an index, so that order
+ * groups the cells by resolution, and within a resolution each group is
+ * sorted too. A lookup takes the ancestor of the query at every resolution
+ * the set holds and searches for it in that resolution's group: at most 16
+ * searches, with no allocation and no pointers to chase. It is lighter than
+ * a hash set or a trie for coverings that are built once and queried many
+ * times, and takes 8 bytes per cell.
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct H3CellSet {
+    cells: Vec<H3Index>,
+    /// Where the cells of each resolution start in `cells`; those of res `r`
+    /// are `starts[r]..starts[r + 1]`.
+    starts: [usize; Resolution::MAX_H3_RES + 2],
+}
+
+impl H3CellSet {
+    /**
+     * Creates a set from cells already in ascending index order.
+     *
+     * @param cells Valid cells, in strictly ascending order of their `u64`
+     *        value; cells may overlap
+     * @return The set, or CellInvalid if a cell is not valid, or Domain if
+     *         the cells are not strictly ascending
+     */
+    pub fn from_sorted(cells: Vec<H3Index>) -> Result<Self, H3Error> {
+        if !cells.iter().all(H3Index::is_valid) {
+            return Err(H3Error::CellInvalid);
+        }
+        if cells.windows(2).any(|w| u64::from(w[0]) >= u64::from(w[1])) {
+            return Err(H3Error::Domain);
+        }
+
+        let mut starts = [cells.len(); Resolution::MAX_H3_RES + 2];
+        for (r, start) in starts
+            .iter_mut()
+            .enumerate()
+            .take(Resolution::MAX_H3_RES + 1)
+        {
+            *start = cells.partition_point(|h| usize::from(h.get_resolution()) < r);
+        }
+        Ok(H3CellSet { cells, starts })
+    }
+
+    /**
+     * Creates a set from cells in any order, sorting them and dropping
+     * duplicates.
+     *
+     * @param cells Valid cells
+     * @return The set, or CellInvalid if a cell is not valid
+     */
+    pub fn from_cells(cells: impl IntoIterator<Item = H3Index>) -> Result<Self, H3Error> {
+        let mut cells: Vec<H3Index> = cells.into_iter().collect();
+        cells.sort_unstable();
+        cells.dedup();
+        Self::from_sorted(cells)
+    }
+
+    /// Number of cells, counting overlapping cells separately.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether the set holds no cells.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The cells, in ascending index order.
+    pub fn as_slice(&self) -> &[H3Index] {
+        &self.cells
+    }
+
+    /// The cells at one resolution, in ascending index order.
+    pub fn cells_at(&self, res: Resolution) -> &[H3Index] {
+        let r = usize::from(res);
+        &self.cells[self.starts[r]..self.starts[r + 1]]
+    }
+
+    /// The finest resolution with any cells, or `None` for an empty set.
+    fn finestRes(&self) -> Option<Resolution> {
+        (0..=Resolution::MAX_H3_RES)
+            .rev()
+            .find(|&r| self.starts[r] < self.starts[r + 1])
+            .map(Resolution::from)
+    }
+
+    /**
+     * Whether a cell is inside the set: it, or one of its ancestors, is one
+     * of the cells.
+     *
+     * @param h A cell, at any resolution
+     * @return Whether it is covered; false for anything that is not a valid
+     *         cell
+     */
+    pub fn contains_cell(&self, h: H3Index) -> bool {
+        if !h.is_valid() {
+            return false;
+        }
+        let res = usize::from(h.get_resolution());
+        (0..=res)
+            .filter(|&r| self.starts[r] < self.starts[r + 1])
+            .any(|r| {
+                let parent = h.h3ToParent(r.into()).unwrap_or(H3Index::H3_NULL);
+                self.cells_at(r.into())
+                    .binary_search_by_key(&u64::from(parent), |&c| u64::from(c))
+                    .is_ok()
+            })
+    }
+
+    /**
+     * Whether a point is inside one of the cells.
+     *
+     * @param g The point
+     * @return Whether it is covered; false for a point that is not finite
+     */
+    pub fn contains_point(&self, g: &GeoCoord) -> bool {
+        match self.finestRes() {
+            Some(res) => self.contains_cell(g.geoToH3(res)),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Degrees;
+    use std::collections::HashSet;
+
+    /// A covering with cells at 3 resolutions: a res 3 cell, a disk of res 5
+    /// cells near it and the children of one more res 5 cell at res 7.
+    fn covering() -> Vec<H3Index> {
+        let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        let mut cells = vec![sf.geoToH3(Resolution::R3)];
+        let far = GeoCoord::new(Degrees(37.3), Degrees(-121.5)).geoToH3(Resolution::R5);
+        cells.extend(far.kRing(2));
+        let other = GeoCoord::new(Degrees(38.5), Degrees(-121.5)).geoToH3(Resolution::R5);
+        cells.extend(other.h3ToChildren(Resolution::R7));
+        cells
+    }
+
+    #[test]
+    fn h3CellSet_contains() {
+        let cells = covering();
+        let set = H3CellSet::from_cells(cells.iter().copied()).expect("valid cells");
+        assert_eq!(set.len(), cells.len(), "no duplicates");
+        assert_eq!(set.cells_at(Resolution::R3).len(), 1, "res 3");
+        assert_eq!(set.cells_at(Resolution::R5).len(), 19, "res 5");
+        assert_eq!(set.cells_at(Resolution::R7).len(), 49, "res 7");
+        assert!(set.cells_at(Resolution::R4).is_empty(), "res 4");
+
+        let naive: HashSet<H3Index> = cells.iter().copied().collect();
+        let naiveContains = |h: H3Index| {
+            (0..=usize::from(h.get_resolution()))
+                .any(|r| naive.contains(&h.h3ToParent(r.into()).unwrap()))
+        };
+
+        // Every res 8 cell in a wide disk around each cell of the covering.
+        for &c in cells.iter().step_by(7) {
+            let center = c.h3ToGeo().geoToH3(Resolution::R8);
+            for h in center.kRing(12) {
+                assert_eq!(set.contains_cell(h), naiveContains(h), "{}", h);
+                assert_eq!(
+                    set.contains_point(&h.h3ToGeo()),
+                    naiveContains(h),
+                    "center of {}",
+                    h
+                );
+            }
+            assert!(set.contains_cell(c), "{} itself", c);
+        }
+
+        let coarse = cells[0].h3ToParent(Resolution::R1).unwrap();
+        assert!(!set.contains_cell(coarse), "ancestors are not covered");
+        assert!(!set.contains_cell(H3Index::H3_NULL), "not a cell");
+        assert!(
+            !set.contains_point(&GeoCoord::new(Degrees(f64::NAN), Degrees(0.))),
+            "not a point"
+        );
+    }
+
+    #[test]
+    fn h3CellSet_fromSorted() {
+        let mut cells = covering();
+        cells.sort_by_key(|&h| u64::from(h));
+        let set = H3CellSet::from_sorted(cells.clone()).expect("sorted");
+        assert_eq!(set.as_slice(), &cells[..], "kept as given");
+        assert_eq!(
+            set,
+            H3CellSet::from_cells(cells.iter().rev().copied()).unwrap()
+        );
+
+        let mut unsorted = cells.clone();
+        unsorted.swap(3, 4);
+        assert_eq!(H3CellSet::from_sorted(unsorted), Err(H3Error::Domain));
+        let mut duplicated = cells.clone();
+        duplicated.insert(1, cells[1]);
+        assert_eq!(H3CellSet::from_sorted(duplicated), Err(H3Error::Domain));
+        assert_eq!(
+            H3CellSet::from_cells(vec![cells[0], H3Index::H3_NULL]),
+            Err(H3Error::CellInvalid)
+        );
+
+        let empty = H3CellSet::from_sorted(Vec::new()).expect("empty");
+        assert!(empty.is_empty(), "empty");
+        assert!(!empty.contains_cell(cells[0]), "nothing inside");
+        assert!(
+            !empty.contains_point(&GeoCoord::new(Degrees(0.), Degrees(0.))),
+            "nothing inside"
+        );
+    }
+}
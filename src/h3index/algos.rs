@@ -1,10 +1,15 @@
+use std::{cell::RefCell, collections::HashSet};
+
 use crate::{
-    basecell::{baseCellData, baseCellNeighbor60CCWRots, baseCellNeighbors, BaseCell},
+    basecell::{baseCellData, baseCellNeighbor60CCWRots, BaseCell},
     direction::{Direction, Direction::*},
-    H3Index, Resolution,
+    geopolygon::{GeoMultiPolygon, Geofence},
+    vertexgraph::VertexGraph,
+    GeoCoord, H3Error, H3Index, Resolution,
 };
 
 /// Return codes from hexRange and related functions.
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub(crate) enum HexRangeCode {
     HEX_RANGE_SUCCESS,       // 0
     HEX_RANGE_PENTAGON,      // 1
@@ -324,17 +329,656 @@ const NEW_ADJUSTMENT_III: [[Direction; 7]; 7] = [
     ],
 ];
 
+/// Scratch space of the kRing fallback algorithm.
+struct KRingScratch {
+    /// Open addressing hash set of the indexes found, H3_NULL where empty.
+    visited: Vec<H3Index>,
+    /// Indexes found with their distances, in the order found; the walk reads
+    /// it front to back as a FIFO queue.
+    queue: Vec<(H3Index, u32)>,
+}
+
+thread_local! {
+    /// Scratch space for the kRing fallback algorithm, kept so that repeated
+    /// calls on a thread do not allocate once it has grown.
+    static KRING_SCRATCH: RefCell<KRingScratch> = const {
+        RefCell::new(KRingScratch {
+            visited: Vec::new(),
+            queue: Vec::new(),
+        })
+    };
+}
+
 /**
  * Maximum number of cells that result from the kRing algorithm with the given
  * k. Formula source and proof: https://oeis.org/A003215
  *
  * @param  k   k value, k >= 0.
+ *
+ * # Panics
+ *
+ * If k is above `H3Index::MAX_KRING_K`, where the size no longer fits.
  */
 pub fn maxKringSize(k: u32) -> u32 {
+    assert!(
+        k <= H3Index::MAX_KRING_K,
+        "k {} is above H3Index::MAX_KRING_K",
+        k
+    );
     3 * k * (k + 1) + 1
 }
 
+/// The most cells within distance k of a cell, under the name H3 v4 gives
+/// `maxKringSize`.
+pub fn max_grid_disk_size(k: u32) -> u32 {
+    maxKringSize(k)
+}
+
+/// Largest disk `ring_of` walks when local IJ coordinates do not reach,
+/// about 30,000 cells.
+const RING_OF_MAX_K: u32 = 100;
+
+/**
+ * Adds an index to the kRing fallback's hash set, by linear probing.
+ *
+ * @param  set  Array treated as a hash set, with H3_NULL for empty slots; it
+ *              has room for every index of the k-ring, so it never fills
+ * @param  h    The index to add
+ * @return Whether the index was added, rather than already present
+ */
+fn _kRingInsert(set: &mut [H3Index], h: H3Index) -> bool {
+    let maxIdx = set.len();
+    let mut off = (h.0 % maxIdx as u64) as usize;
+    while set[off] != H3Index::H3_NULL {
+        if set[off] == h {
+            return false;
+        }
+        off = (off + 1) % maxIdx;
+    }
+    set[off] = h;
+    true
+}
+
 impl H3Index {
+    /// Largest k for which the cells within distance k can be counted in a
+    /// `u32`, as `maxKringSize` does: 4,294,802,197 cells. The k-ring
+    /// functions panic past it; `grid_ring`, `grid_collar`, `traversal` and
+    /// `buffer_km` return `H3Error::Domain` instead.
+    pub const MAX_KRING_K: u32 = 37_836;
+
+    /**
+     * Produce indexes within k distance of the origin index.
+     *
+     * k-ring 0 is defined as the origin index, k-ring 1 is defined as k-ring 0 and
+     * all neighboring indexes, and so on.
+     *
+     * Output is in no particular order.
+     *
+     * Memory is bounded by k alone: the output holds at most
+     * `maxKringSize(k)` indexes, and near a pentagon the fallback algorithm
+     * also uses this thread's scratch space, a hash set and a queue of
+     * `maxKringSize(k)` entries each, 24 bytes per entry in all. Neither
+     * algorithm recurses, so stack use does not grow with k.
+     *
+     * # Panics
+     *
+     * If k is above `MAX_KRING_K`. Callers taking k from input should check
+     * it, or use `traversal`, which returns an error instead.
+     *
+     * @param  k   k >= 0
+     * @return The indexes in the k-ring
+     */
+    pub fn kRing(&self, k: u32) -> Vec<H3Index> {
+        let mut out = Vec::with_capacity(maxKringSize(k) as usize);
+        self.kRingInto(k, &mut out);
+        out
+    }
+
+    /**
+     * Produce indexes within k distance of the origin index, into a buffer
+     * that is reused across calls.
+     *
+     * Once `out` and this thread's scratch space have grown to fit the
+     * largest k-ring seen, further calls do not allocate.
+     *
+     * @param  k   k >= 0
+     * @param  out Cleared, then filled with the indexes in the k-ring, in no
+     *             particular order
+     */
+    pub fn kRingInto(&self, k: u32, out: &mut Vec<H3Index>) {
+        out.clear();
+        if self.hexRangeVisit(k, |h, _| out.push(h)).is_ok() {
+            return;
+        }
+
+        out.clear();
+        self.kRingFallback(k, |h, _| out.push(h));
+    }
+
+    /**
+     * Produce indexes and their distances from the origin index, up to
+     * distance k.
+     *
+     * k-ring 0 is defined as the origin index, k-ring 1 is defined as k-ring 0 and
+     * all neighboring indexes, and so on.
+     *
+     * Output is in no particular order.
+     *
+     * @param  k   k >= 0
+     * @return The indexes in the k-ring, each with its distance from the origin
+     */
+    pub fn kRingDistances(&self, k: u32) -> Vec<(H3Index, u32)> {
+        let mut out = Vec::with_capacity(maxKringSize(k) as usize);
+        self.kRingDistancesInto(k, &mut out);
+        out
+    }
+
+    /**
+     * Produce indexes and their distances from the origin index, up to
+     * distance k, into a buffer that is reused across calls. See `kRingInto`.
+     *
+     * @param  k   k >= 0
+     * @param  out Cleared, then filled with the indexes in the k-ring, each
+     *             with its distance from the origin, in no particular order
+     */
+    pub fn kRingDistancesInto(&self, k: u32, out: &mut Vec<(H3Index, u32)>) {
+        out.clear();
+        // Optimistically try the faster hexRange algorithm first
+        if self.hexRangeVisit(k, |h, d| out.push((h, d))).is_ok() {
+            return;
+        }
+
+        // Fast algo failed, fall back to slower, correct algo
+        out.clear();
+        self.kRingFallback(k, |h, d| out.push((h, d)));
+    }
+
+    /// The indexes within distance k of the origin, under the name H3 v4
+    /// gives `kRing`; pairs with `grid_ring` and `grid_collar`.
+    pub fn grid_disk(&self, k: u32) -> Vec<H3Index> {
+        self.kRing(k)
+    }
+
+    /// The indexes within distance k of the origin with their distances,
+    /// under the name H3 v4 gives `kRingDistances`.
+    pub fn grid_disk_distances(&self, k: u32) -> Vec<(H3Index, u32)> {
+        self.kRingDistances(k)
+    }
+
+    /**
+     * The ring of `origin`'s k-ring this cell is on: its grid distance from
+     * the origin, as `kRingDistances` would give it, without building the
+     * whole disk.
+     *
+     * The distance comes from local IJ coordinates when they reach. Across a
+     * pentagon's distortion, or over more than one icosahedron face, it falls
+     * back to walking a disk around the origin just large enough to hold the
+     * cell, given the great circle distance between the centers.
+     *
+     * @param origin The focal cell
+     * @return The grid distance, or none if either cell is invalid, they are
+     *         at different resolutions, or they are too far apart for the
+     *         fallback, more than 100 steps
+     */
+    pub fn ring_of(&self, origin: H3Index) -> Option<u32> {
+        if !self.is_valid() || !origin.is_valid() {
+            return None;
+        }
+        let res = origin.get_resolution();
+        if self.get_resolution() != res {
+            return None;
+        }
+        if let Ok(d) = origin.h3Distance(self) {
+            return Some(d as u32);
+        }
+
+        // Grid distances are at most about 1.42 times the great circle
+        // distance in steps of the average center spacing.
+        let spacingKm = res.edgeLengthKm() * 3f64.sqrt();
+        let greatCircleKm = GeoCoord::pointDistKm(&origin.h3ToGeo(), &self.h3ToGeo());
+        let k = (1.5 * greatCircleKm / spacingKm).ceil() + 1.0;
+        if k > f64::from(RING_OF_MAX_K) {
+            return None;
+        }
+
+        let mut found = None;
+        origin.kRingFallback(k as u32, |h, d| {
+            if h == *self {
+                found = Some(d);
+            }
+        });
+        found
+    }
+
+    /**
+     * The slower kRing algorithm that handles pentagons, passing each index
+     * and its distance to `visit`, in order of increasing distance.
+     *
+     * A breadth first walk out from the origin: an index is queued the first
+     * time it is found, which is at its shortest distance, so each is visited
+     * once and the walk never recurses. Uses this thread's scratch space,
+     * whose hash set and queue each hold at most `maxKringSize(k)` entries.
+     *
+     * @param  k      k >= 0
+     * @param  visit  Called with each index in the k-ring and its distance
+     */
+    pub(crate) fn kRingFallback(&self, k: u32, mut visit: impl FnMut(H3Index, u32)) {
+        if *self == H3Index::H3_NULL {
+            return;
+        }
+
+        KRING_SCRATCH.with(|scratch| {
+            let scratch = &mut *scratch.borrow_mut();
+            let maxIdx = maxKringSize(k) as usize;
+            scratch.visited.clear();
+            scratch.visited.resize(maxIdx, H3Index::H3_NULL);
+            scratch.queue.clear();
+            scratch.queue.reserve(maxIdx);
+
+            _kRingInsert(&mut scratch.visited, *self);
+            scratch.queue.push((*self, 0));
+            let mut head = 0;
+            while head < scratch.queue.len() {
+                let (h, curK) = scratch.queue[head];
+                head += 1;
+                // Reached an index k away from the origin.
+                if curK >= k {
+                    continue;
+                }
+
+                for dir in DIRECTIONS.iter() {
+                    let mut rotations = 0;
+                    let neighbor = h.h3NeighborRotations(*dir, &mut rotations);
+                    // H3_NULL for the deleted direction of a pentagon.
+                    if neighbor != H3Index::H3_NULL && _kRingInsert(&mut scratch.visited, neighbor)
+                    {
+                        scratch.queue.push((neighbor, curK + 1));
+                    }
+                }
+            }
+
+            for &(h, d) in scratch.queue.iter() {
+                visit(h, d);
+            }
+        });
+    }
+
+    /**
+     * hexRangeDistances produces indexes within k distance of the origin index.
+     * Output behavior is undefined when one of the indexes returned by this
+     * function is a pentagon or is in the pentagon distortion area.
+     *
+     * k-ring 0 is defined as the origin index, k-ring 1 is defined as k-ring 0 and
+     * all neighboring indexes, and so on.
+     *
+     * Output is in order of increasing distance from the origin.
+     *
+     * @param k k >= 0
+     * @return The indexes in the k-ring, each with its distance from the origin,
+     *         or the reason the fast algorithm could not be used.
+     */
+    pub(crate) fn hexRangeDistances(&self, k: u32) -> Result<Vec<(H3Index, u32)>, HexRangeCode> {
+        let mut out = Vec::with_capacity(maxKringSize(k) as usize);
+        self.hexRangeVisit(k, |h, d| out.push((h, d)))?;
+        Ok(out)
+    }
+
+    /**
+     * The hexRangeDistances traversal, passing each index and its distance to
+     * `visit` instead of collecting them. On failure, `visit` will already have
+     * seen some of the indexes.
+     *
+     * @param k k >= 0
+     * @param visit Called with each index in the k-ring and its distance
+     * @return The reason the fast algorithm could not be used, if it failed
+     */
+    pub(crate) fn hexRangeVisit(
+        &self,
+        k: u32,
+        mut visit: impl FnMut(H3Index, u32),
+    ) -> Result<(), HexRangeCode> {
+        // Pentagon being encountered is not itself a problem; really the deleted
+        // k-subsequence is the problem, but for compatibility reasons we fail on
+        // the pentagon.
+
+        // k must be >= 0, so origin is always needed
+        visit(*self, 0);
+
+        if self.is_pentagon() {
+            // Pentagon was encountered; bail out as user doesn't want this.
+            return Err(HexRangeCode::HEX_RANGE_PENTAGON);
+        }
+
+        let mut origin = *self;
+        // 0 < ring <= k, current ring
+        let mut ring = 1;
+        // 0 <= direction < 6, current side of the ring
+        let mut direction = 0;
+        // 0 <= i < ring, current position on the side of the ring
+        let mut i = 0;
+        // Number of 60 degree ccw rotations to perform on the direction (based on
+        // which faces have been crossed.)
+        let mut rotations = 0;
+
+        while ring <= k {
+            if direction == 0 && i == 0 {
+                // Not putting in the output set as it will be done later, at
+                // the end of this ring.
+                origin = origin.h3NeighborRotations(NEXT_RING_DIRECTION, &mut rotations);
+                if origin == H3Index::H3_NULL {
+                    // Should not be possible because `origin` would have to be a
+                    // pentagon
+                    return Err(HexRangeCode::HEX_RANGE_K_SUBSEQUENCE);
+                }
+
+                if origin.is_pentagon() {
+                    // Pentagon was encountered; bail out as user doesn't want this.
+                    return Err(HexRangeCode::HEX_RANGE_PENTAGON);
+                }
+            }
+
+            origin = origin.h3NeighborRotations(DIRECTIONS[direction], &mut rotations);
+            if origin == H3Index::H3_NULL {
+                // Should not be possible because `origin` would have to be a
+                // pentagon
+                return Err(HexRangeCode::HEX_RANGE_K_SUBSEQUENCE);
+            }
+            visit(origin, ring);
+
+            i += 1;
+            // Check if end of this side of the k-ring
+            if i == ring {
+                i = 0;
+                direction += 1;
+                // Check if end of this ring.
+                if direction == 6 {
+                    direction = 0;
+                    ring += 1;
+                }
+            }
+
+            if origin.is_pentagon() {
+                // Pentagon was encountered; bail out as user doesn't want this.
+                return Err(HexRangeCode::HEX_RANGE_PENTAGON);
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Internal: Create a vertex graph from a set of hexagons. The graph holds
+     * every cell edge that is not shared with another cell of the set, i.e.
+     * the boundary of the region, and is the intermediate step of
+     * h3SetToLinkedGeo. Vertexes match within
+     * `VertexGraph::DEFAULT_EPSILON_RADS`.
+     *
+     * @param h3Set Set of hexagons, all at the same resolution
+     * @return The boundary edges of the set
+     */
+    pub fn h3SetToVertexGraph(h3Set: &[H3Index]) -> VertexGraph {
+        match Self::h3SetToVertexGraphEpsilon(h3Set, VertexGraph::DEFAULT_EPSILON_RADS) {
+            Ok(graph) => graph,
+            Err(_) => unreachable!("default epsilon is valid"),
+        }
+    }
+
+    /**
+     * Like `h3SetToVertexGraph`, with the tolerance within which the copies
+     * of a vertex computed from each cell around it are taken to be the same.
+     *
+     * @param h3Set Set of hexagons, all at the same resolution
+     * @param epsilon Tolerance in radians, for latitude and longitude each
+     * @return The boundary edges of the set, or `H3Error::Domain` unless
+     *         epsilon is finite and positive
+     */
+    pub fn h3SetToVertexGraphEpsilon(
+        h3Set: &[H3Index],
+        epsilon: f64,
+    ) -> Result<VertexGraph, H3Error> {
+        if h3Set.is_empty() {
+            return VertexGraph::with_epsilon(0, Resolution::R0, epsilon);
+        }
+
+        let res = h3Set[0].get_resolution();
+        const minBuckets: usize = 6;
+        // TODO: Better way to calculate/guess?
+        let numBuckets = h3Set.len().max(minBuckets);
+        let mut graph = VertexGraph::with_epsilon(numBuckets, res, epsilon)?;
+
+        // Iterate through every hexagon
+        for h in h3Set.iter() {
+            let vertices = h.h3ToGeoBoundary();
+            // iterate through every edge
+            for j in 0..vertices.numVerts {
+                let fromVtx = &vertices.verts[j];
+                let toVtx = &vertices.verts[(j + 1) % vertices.numVerts];
+                // If we've seen this edge already, it will be reversed
+                match graph.findNodeForEdge(toVtx, Some(fromVtx)).copied() {
+                    // If we've seen it, drop it. No edge is shared by more than 2
+                    // hexagons, so we'll never see it again.
+                    Some(edge) => {
+                        graph.removeVertexNode(&edge);
+                    }
+                    // Add a new node for this edge
+                    None => {
+                        graph.addVertexNode(fromVtx, toVtx);
+                    }
+                }
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /**
+     * Creates the outline of a set of hexagons, the inverse of polyfill: the
+     * loops of cell edges not shared by two cells of the set, grouped into
+     * polygons with their holes.
+     *
+     * Exterior loops wind counterclockwise and holes clockwise, as GeoJSON
+     * wants them, and each hole goes with the smallest exterior around it.
+     * Regions around a pole are not supported.
+     *
+     * @param h3Set Set of hexagons, all at the same resolution
+     * @return The polygons, in no particular order, or CellInvalid for an
+     *         index that is not a cell, ResolutionMismatch for mixed
+     *         resolutions, or DuplicateInput if a cell appears more than once
+     */
+    pub fn h3SetToLinkedGeo(h3Set: &[H3Index]) -> Result<GeoMultiPolygon, H3Error> {
+        let mut seen = HashSet::with_capacity(h3Set.len());
+        for h in h3Set {
+            if !h.is_valid() {
+                return Err(H3Error::CellInvalid);
+            }
+            if h.get_resolution() != h3Set[0].get_resolution() {
+                return Err(H3Error::ResolutionMismatch);
+            }
+            // a repeated cell would cancel its own edges out of the graph
+            if !seen.insert(*h) {
+                return Err(H3Error::DuplicateInput);
+            }
+        }
+
+        // Follow each boundary edge to the one starting at its end, until
+        // the loop closes.
+        let mut graph = Self::h3SetToVertexGraph(h3Set);
+        let mut loops = Vec::new();
+        while let Some(&first) = graph.firstVertexNode() {
+            let mut verts = Vec::new();
+            let mut edge = Some(first);
+            while let Some(e) = edge {
+                verts.push(e.from);
+                graph.removeVertexNode(&e);
+                edge = graph.findNodeForVertex(&e.to).copied();
+            }
+            loops.push(Geofence { verts });
+        }
+
+        Ok(GeoMultiPolygon::from_loops(loops))
+    }
+
+    /// The H3 v4 name for `h3SetToLinkedGeo`.
+    pub fn cells_to_multi_polygon(h3Set: &[H3Index]) -> Result<GeoMultiPolygon, H3Error> {
+        Self::h3SetToLinkedGeo(h3Set)
+    }
+
+    /**
+     * Returns the "hollow" ring of indexes at exactly grid distance k from the
+     * origin index.
+     *
+     * The fast hexRing traversal is tried first, and walks only the 6k cells of
+     * the ring. If it fails because a pentagon or its distortion is in the way,
+     * the ring is taken from kRingDistances instead, which visits every cell
+     * within distance k and so costs O(k^2) rather than O(k). The result is
+     * the same either way.
+     *
+     * @param k k >= 0
+     * @return The indexes at distance k, in no particular order, or
+     *         `H3Error::Domain` if k is past `MAX_KRING_K`
+     */
+    pub fn grid_ring(&self, k: u32) -> Result<Vec<H3Index>, H3Error> {
+        match self.hexRing(k) {
+            Err(H3Error::Pentagon) => Ok(self
+                .kRingDistances(k)
+                .into_iter()
+                .filter(|&(_, d)| d == k)
+                .map(|(h, _)| h)
+                .collect()),
+            ring => ring,
+        }
+    }
+
+    /**
+     * Returns the indexes whose grid distance d from the origin index is
+     * k_inner < d <= k_outer, i.e. the disk of radius k_outer with the disk of
+     * radius k_inner removed.
+     *
+     * The rings making up the collar are traversed with hexRing, so only the
+     * cells in the collar are visited. If a pentagon or its distortion gets in
+     * the way, the collar is taken from kRingDistances(k_outer) instead.
+     *
+     * @param k_inner Radius of the excluded disk
+     * @param k_outer Radius of the outer disk; the collar is empty if it
+     *        equals k_inner
+     * @return The indexes in the collar, in no particular order, or
+     *         `H3Error::Domain` if k_inner is above k_outer or k_outer is
+     *         past `MAX_KRING_K`
+     */
+    pub fn grid_collar(&self, k_inner: u32, k_outer: u32) -> Result<Vec<H3Index>, H3Error> {
+        if k_inner > k_outer || k_outer > H3Index::MAX_KRING_K {
+            return Err(H3Error::Domain);
+        }
+
+        let mut out = Vec::new();
+        for k in k_inner + 1..=k_outer {
+            match self.hexRing(k) {
+                Ok(ring) => out.extend(ring),
+                Err(_) => {
+                    return Ok(self
+                        .kRingDistances(k_outer)
+                        .into_iter()
+                        .filter(|&(_, d)| d > k_inner)
+                        .map(|(h, _)| h)
+                        .collect())
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    /**
+     * Returns the "hollow" ring of indexes at exactly grid distance k from
+     * the origin index, walking only the 6k cells of the ring.
+     *
+     * The walk cannot step around a pentagon: it fails if the origin or any
+     * cell of the ring is a pentagon, or if pentagonal distortion bends the
+     * ring so that it does not close. Callers can then fall back to
+     * `grid_ring`, or filter `kRingDistances`, which handle pentagons at
+     * O(k^2) cost.
+     *
+     * @param k k >= 0
+     * @return The indexes in the ring, in order around the origin,
+     *         `H3Error::Pentagon` if a pentagon or its distortion was
+     *         encountered, or `H3Error::Domain` if k is past `MAX_KRING_K`
+     */
+    pub fn hexRing(&self, k: u32) -> Result<Vec<H3Index>, H3Error> {
+        if k > H3Index::MAX_KRING_K {
+            return Err(H3Error::Domain);
+        }
+
+        // Short-circuit on 'identity' ring
+        if k == 0 {
+            return Ok(vec![*self]);
+        }
+
+        // Number of 60 degree ccw rotations to perform on the direction (based on
+        // which faces have been crossed.)
+        let mut rotations = 0;
+
+        if self.is_pentagon() {
+            // Pentagon was encountered; bail out as user doesn't want this.
+            return Err(H3Error::Pentagon);
+        }
+
+        let mut origin = *self;
+        for _ in 0..k {
+            origin = origin.h3NeighborRotations(NEXT_RING_DIRECTION, &mut rotations);
+            if origin == H3Index::H3_NULL {
+                // Should not be possible because `origin` would have to be a
+                // pentagon
+                return Err(H3Error::Pentagon);
+            }
+
+            if origin.is_pentagon() {
+                return Err(H3Error::Pentagon);
+            }
+        }
+
+        let lastIndex = origin;
+
+        let mut out = Vec::with_capacity(6 * k as usize);
+        out.push(origin);
+
+        for (direction, dir) in DIRECTIONS.iter().enumerate() {
+            for pos in 0..k {
+                origin = origin.h3NeighborRotations(*dir, &mut rotations);
+                if origin == H3Index::H3_NULL {
+                    // Should not be possible because `origin` would have to be a
+                    // pentagon
+                    return Err(H3Error::Pentagon);
+                }
+
+                // Skip the very last index, it was already added. We do
+                // however need to traverse to it because of the pentagonal
+                // distortion check, below.
+                if pos != k - 1 || direction != 5 {
+                    out.push(origin);
+
+                    if origin.is_pentagon() {
+                        return Err(H3Error::Pentagon);
+                    }
+                }
+            }
+        }
+
+        // Check that this matches the expected lastIndex, if it doesn't,
+        // it indicates pentagonal distortion occurred and we should report
+        // failure.
+        if lastIndex != origin {
+            return Err(H3Error::Pentagon);
+        }
+
+        Ok(out)
+    }
+
+    /// The H3 v4 name for `hexRing`, which fails on pentagons and past
+    /// `MAX_KRING_K`.
+    pub fn grid_ring_unsafe(&self, k: u32) -> Result<Vec<H3Index>, H3Error> {
+        self.hexRing(k)
+    }
+
     /**
      * Returns the hexagon index neighboring the origin, in the direction dir.
      *
@@ -359,11 +1003,11 @@ impl H3Index {
         }
 
         let oldBaseCell = out.get_base_cell();
-        if oldBaseCell.0 >= BaseCell::NUM_BASE_CELLS as i32 {
-            // LCOV_EXCL_BR_LINE
-            // Base cells less than zero can not be represented in an index
-            return H3Index::H3_NULL;
-        }
+        // Base cells less than zero can not be represented in an index
+        let oldIndex = match oldBaseCell.index() {
+            Some(i) => i,
+            None => return H3Index::H3_NULL,
+        };
 
         let oldLeadingDigit = out._h3LeadingNonZeroDigit();
 
@@ -373,24 +1017,21 @@ impl H3Index {
         let mut r = out.get_resolution() as i32 - 1;
         loop {
             if r == -1 {
-                out.set_base_cell(baseCellNeighbors[usize::from(oldBaseCell)][dir as usize]);
-                newRotations = baseCellNeighbor60CCWRots[usize::from(oldBaseCell)][dir as usize].0;
-
-                if out.get_base_cell() == BaseCell::INVALID {
+                if let Some(neighbor) = oldBaseCell._getBaseCellNeighbor(&dir) {
+                    out.set_base_cell(neighbor);
+                    newRotations = baseCellNeighbor60CCWRots[oldIndex][dir as usize];
+                } else {
                     // Adjust for the deleted k vertex at the base cell level.
                     // This edge actually borders a different neighbor.
-                    out.set_base_cell(
-                        baseCellNeighbors[usize::from(oldBaseCell)]
-                            [Direction::IK_AXES_DIGIT as usize],
-                    );
-                    newRotations = baseCellNeighbor60CCWRots[usize::from(oldBaseCell)]
-                        [Direction::IK_AXES_DIGIT as usize]
-                        .0;
+                    let neighbor = oldBaseCell._getBaseCellNeighbor(&Direction::IK_AXES_DIGIT);
+                    out.set_base_cell(neighbor.unwrap());
+                    newRotations =
+                        baseCellNeighbor60CCWRots[oldIndex][Direction::IK_AXES_DIGIT as usize];
 
                     // perform the adjustment for the k-subsequence we're skipping over.
                     out = out._h3Rotate60ccw();
                     *rotations += 1;
-                    //*rotations = *rotations + 1;
+                    //*rotations += 1;
                 }
 
                 break;
@@ -434,9 +1075,7 @@ impl H3Index {
                     // We need to rotate out of that case depending on how we got here.
                     // check for a cw/ccw offset face; default is ccw
 
-                    out = if newBaseCell
-                        ._baseCellIsCwOffset(&baseCellData[usize::from(oldBaseCell)].homeFijk)
-                    {
+                    out = if newBaseCell._baseCellIsCwOffset(&baseCellData[oldIndex].homeFijk) {
                         out._h3Rotate60cw()
                     } else {
                         // See cwOffsetPent in testKRing.c for why this is unreachable.
@@ -453,12 +1092,12 @@ impl H3Index {
                         // Rotate out of the deleted k subsequence
                         // We also need an additional change to the direction we're moving in
                         out = out._h3Rotate60ccw();
-                        *rotations = *rotations + 1;
+                        *rotations += 1;
                     } else if oldLeadingDigit == IK_AXES_DIGIT {
                         // Rotate out of the deleted k subsequence
                         // We also need an additional change to the direction we're moving in
                         out = out._h3Rotate60cw();
-                        *rotations = *rotations + 5;
+                        *rotations += 5;
                     } else {
                         // Should never occur
                         return H3Index::H3_NULL; // LCOV_EXCL_LINE
@@ -467,7 +1106,7 @@ impl H3Index {
             }
 
             for _ in 0..newRotations {
-                out = out._h3Rotate60ccw();
+                out = out._h3RotatePent60ccw();
             }
 
             // Account for differing orientation of the base cells (this edge might not follow properties of some other edges.)
@@ -478,13 +1117,13 @@ impl H3Index {
                         && oldBaseCell != 8
                         && out._h3LeadingNonZeroDigit() != JK_AXES_DIGIT
                     {
-                        *rotations = *rotations + 1;
+                        *rotations += 1;
                     }
                 } else if out._h3LeadingNonZeroDigit() == IK_AXES_DIGIT
                     && !alreadyAdjustedKSubsequence
                 {
                     // account for distortion introduced to the 5 neighbor by the deleted k subsequence.
-                    *rotations = *rotations + 1;
+                    *rotations += 1;
                 }
             }
         } else {
@@ -498,3 +1137,717 @@ impl H3Index {
         out
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{h3index::H3Index, GeoCoord};
+
+    fn assertKRingDistances(origin: H3Index, k: u32, expected: &[(u64, u32)]) {
+        let mut result = origin.kRingDistances(k);
+        result.sort_by_key(|&(h, _)| h.0);
+
+        let expected: Vec<(H3Index, u32)> =
+            expected.iter().map(|&(h, d)| (H3Index(h), d)).collect();
+        assert_eq!(result, expected, "got expected k-ring with distances");
+
+        let mut ring = origin.kRing(k);
+        ring.sort_by_key(|h| h.0);
+        let cells: Vec<H3Index> = expected.iter().map(|&(h, _)| h).collect();
+        assert_eq!(ring, cells, "kRing matches kRingDistances");
+    }
+
+    #[test]
+    fn kRing0() {
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let sfHex0 = sf.geoToH3(Resolution::R0);
+
+        assertKRingDistances(
+            sfHex0,
+            1,
+            &[
+                (0x8013fffffffffff, 1),
+                (0x801dfffffffffff, 1),
+                (0x8027fffffffffff, 1),
+                (0x8029fffffffffff, 0),
+                (0x8037fffffffffff, 1),
+                (0x8049fffffffffff, 1),
+                (0x8051fffffffffff, 1),
+            ],
+        );
+    }
+
+    #[test]
+    fn kRing0_PolarPentagon() {
+        let polar = H3Index::setH3Index(Resolution::R0, BaseCell::new_unchecked(4), CENTER_DIGIT);
+
+        assertKRingDistances(
+            polar,
+            2,
+            &[
+                (0x8001fffffffffff, 1),
+                (0x8003fffffffffff, 2),
+                (0x8005fffffffffff, 2),
+                (0x8007fffffffffff, 1),
+                (0x8009fffffffffff, 0),
+                (0x800bfffffffffff, 2),
+                (0x800ffffffffffff, 2),
+                (0x8011fffffffffff, 1),
+                (0x8019fffffffffff, 1),
+                (0x801bfffffffffff, 2),
+                (0x801ffffffffffff, 1),
+                (0x8021fffffffffff, 2),
+                (0x802dfffffffffff, 2),
+                (0x8035fffffffffff, 2),
+                (0x8039fffffffffff, 2),
+                (0x803ffffffffffff, 2),
+            ],
+        );
+    }
+
+    #[test]
+    fn kRing1_PolarPentagon() {
+        let polar = H3Index::setH3Index(Resolution::R1, BaseCell::new_unchecked(4), CENTER_DIGIT);
+
+        assertKRingDistances(
+            polar,
+            2,
+            &[
+                (0x81013ffffffffff, 2),
+                (0x81017ffffffffff, 2),
+                (0x81073ffffffffff, 2),
+                (0x81077ffffffffff, 2),
+                (0x81083ffffffffff, 0),
+                (0x8108bffffffffff, 1),
+                (0x8108fffffffffff, 1),
+                (0x81093ffffffffff, 1),
+                (0x81097ffffffffff, 1),
+                (0x8109bffffffffff, 1),
+                (0x81113ffffffffff, 2),
+                (0x81117ffffffffff, 2),
+                (0x81193ffffffffff, 2),
+                (0x81197ffffffffff, 2),
+                (0x811f3ffffffffff, 2),
+                (0x811f7ffffffffff, 2),
+            ],
+        );
+    }
+
+    #[test]
+    fn kRing_matchesMaxKringSize() {
+        for base in H3Index::getRes0Indexes().iter() {
+            for h in base.h3ToChildren(Resolution::R2) {
+                let ring = h.kRing(2);
+                if !ring.iter().any(|c| c.is_pentagon()) {
+                    assert_eq!(
+                        ring.len(),
+                        maxKringSize(2) as usize,
+                        "hexagon k-ring has maximum size"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gridDisk_pentagonFallback() {
+        let pentagon =
+            H3Index::setH3Index(Resolution::R1, BaseCell::new_unchecked(4), CENTER_DIGIT);
+        let nearPentagon = pentagon.neighbors().as_slice()[2];
+        for &origin in [pentagon, nearPentagon].iter() {
+            let disk = origin.grid_disk(3);
+            assert_eq!(disk, origin.kRing(3), "{} same as kRing", origin);
+            assert!(
+                disk.len() < maxKringSize(3) as usize,
+                "{} lost cells",
+                origin
+            );
+
+            let distances = origin.grid_disk_distances(3);
+            assert_eq!(distances, origin.kRingDistances(3), "{} distances", origin);
+            // where local IJ can unfold the pentagon's distortion
+            for (h, d) in distances {
+                if let Ok(distance) = origin.h3Distance(&h) {
+                    assert_eq!(distance, d as i32, "{} to {}", origin, h);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn maxKringSize_limit() {
+        let k = u64::from(H3Index::MAX_KRING_K);
+        assert_eq!(
+            u64::from(maxKringSize(H3Index::MAX_KRING_K)),
+            3 * k * (k + 1) + 1,
+            "largest k fits"
+        );
+        assert!(
+            3 * (k + 1) * (k + 2) + 1 > u64::from(u32::MAX),
+            "next k does not"
+        );
+    }
+
+    #[test]
+    fn kRingFallback_breadthFirst() {
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let hexagon = sf.geoToH3(Resolution::R9);
+        let mut fast = hexagon.hexRangeDistances(3).unwrap();
+        let mut walked = Vec::new();
+        hexagon.kRingFallback(3, |h, d| walked.push((h, d)));
+        assert!(
+            walked.windows(2).all(|w| w[0].1 <= w[1].1),
+            "by increasing distance"
+        );
+        fast.sort_by_key(|&(h, _)| h);
+        walked.sort_by_key(|&(h, _)| h);
+        assert_eq!(walked, fast, "same as the fast path");
+
+        // Far deeper than a recursive walk could safely go; a pentagon's
+        // ring at distance d has 5d cells while no other pentagon is near.
+        let k = 200;
+        let pentagon = Resolution::R7.getPentagonIndexes()[3];
+        let disk = pentagon.kRingDistances(k);
+        assert_eq!(disk.len() as u32, 1 + 5 * k * (k + 1) / 2, "pentagon disk");
+        for d in 0..=k {
+            let ring = disk.iter().filter(|&&(_, dist)| dist == d).count() as u32;
+            assert_eq!(ring, if d == 0 { 1 } else { 5 * d }, "ring {}", d);
+        }
+
+        let mut none = 0;
+        H3Index::H3_NULL.kRingFallback(2, |_, _| none += 1);
+        assert_eq!(none, 0, "null origin");
+    }
+
+    #[test]
+    fn ringOf() {
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let hexagon = sf.geoToH3(Resolution::R9);
+        let pentagon =
+            H3Index::setH3Index(Resolution::R1, BaseCell::new_unchecked(4), CENTER_DIGIT);
+        let nearPentagon = pentagon.neighbors().as_slice()[2];
+        for &origin in [hexagon, pentagon, nearPentagon].iter() {
+            for (h, d) in origin.kRingDistances(4) {
+                assert_eq!(h.ring_of(origin), Some(d), "{} from {}", h, origin);
+            }
+        }
+
+        // across a pentagon, where local IJ does not reach
+        let across = nearPentagon
+            .kRingDistances(4)
+            .into_iter()
+            .find(|(h, _)| nearPentagon.h3Distance(h).is_err())
+            .unwrap();
+        assert_eq!(across.0.ring_of(nearPentagon), Some(across.1), "fallback");
+
+        assert_eq!(
+            hexagon.ring_of(hexagon.h3ToParent(Resolution::R8).unwrap()),
+            None,
+            "resolution mismatch"
+        );
+        assert_eq!(H3Index::H3_NULL.ring_of(hexagon), None, "invalid");
+        let antipode = GeoCoord::from_rads(-sf.lat, sf.lon - 3.14159).geoToH3(Resolution::R9);
+        assert_eq!(antipode.ring_of(hexagon), None, "too far");
+    }
+
+    #[test]
+    fn hexRangeDistances_failsOnPentagon() {
+        let pentagon =
+            H3Index::setH3Index(Resolution::R1, BaseCell::new_unchecked(4), CENTER_DIGIT);
+        assert_eq!(
+            pentagon.hexRangeDistances(1).err(),
+            Some(HexRangeCode::HEX_RANGE_PENTAGON),
+            "fast path fails on pentagon origin"
+        );
+
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let ring = sf.geoToH3(Resolution::R9).hexRangeDistances(2).unwrap();
+        assert_eq!(
+            ring.len(),
+            maxKringSize(2) as usize,
+            "fast path result size"
+        );
+        assert!(
+            ring.windows(2).all(|w| w[0].1 <= w[1].1),
+            "fast path is ordered by distance"
+        );
+    }
+
+    #[test]
+    fn kRingInto_reusesBuffer() {
+        let pentagon =
+            H3Index::setH3Index(Resolution::R1, BaseCell::new_unchecked(4), CENTER_DIGIT);
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let hexagon = sf.geoToH3(Resolution::R9);
+
+        let mut out = vec![H3Index::H3_NULL; 100];
+        for &origin in [pentagon, hexagon, pentagon].iter() {
+            for k in 0..4 {
+                origin.kRingInto(k, &mut out);
+                let mut expected = origin.kRing(k);
+                out.sort_by_key(|h| h.0);
+                expected.sort_by_key(|h| h.0);
+                assert_eq!(out, expected, "buffer holds only the k-ring");
+            }
+        }
+    }
+
+    #[test]
+    fn h3SetToVertexGraph() {
+        fn graphSize(set: &[u64]) -> usize {
+            let set: Vec<H3Index> = set.iter().map(|&h| H3Index(h)).collect();
+            H3Index::h3SetToVertexGraph(&set).size()
+        }
+
+        assert_eq!(graphSize(&[]), 0, "No edges added to graph");
+        assert_eq!(
+            graphSize(&[0x890dab6220bffff]),
+            6,
+            "All edges of one hex added to graph"
+        );
+        assert_eq!(
+            graphSize(&[0x8928308291bffff, 0x89283082943ffff]),
+            12,
+            "All edges of two non-contiguous hexes added to graph"
+        );
+        assert_eq!(
+            graphSize(&[0x8928308291bffff, 0x89283082957ffff]),
+            10,
+            "All edges except 2 shared added to graph"
+        );
+        assert_eq!(
+            graphSize(&[0x894cc5365afffff, 0x894cc536537ffff]),
+            12,
+            "All edges except 2 shared added to graph"
+        );
+        assert_eq!(
+            graphSize(&[0x8928308288bffff, 0x892830828d7ffff, 0x8928308289bffff]),
+            3 * 4,
+            "All edges except 6 shared added to graph"
+        );
+        assert_eq!(
+            graphSize(&[
+                0x892830828c7ffff,
+                0x892830828d7ffff,
+                0x8928308289bffff,
+                0x89283082813ffff,
+                0x8928308288fffff,
+                0x89283082883ffff
+            ]),
+            (6 * 3) + 6,
+            "All outer edges and inner hole edges added to graph"
+        );
+    }
+
+    #[test]
+    fn h3SetToVertexGraph_loop() {
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let disk = sf.geoToH3(Resolution::R9).kRing(2);
+        let graph = H3Index::h3SetToVertexGraph(&disk);
+        assert_eq!(graph.size(), 30, "outline of a 2-disk");
+
+        // following the edges walks the whole outline once
+        let first = *graph.firstVertexNode().unwrap();
+        let mut node = first;
+        for _ in 1..graph.size() {
+            node = *graph.findNodeForVertex(&node.to).expect("edge continues");
+            assert!(node != first, "loop closes only at the end");
+        }
+        assert_eq!(node.to, first.from, "loop closes");
+    }
+
+    #[test]
+    fn h3SetToVertexGraph_faceCrossing() {
+        // Disks whose cells are on different faces compute their shared
+        // vertexes differently, down to the last bits.
+        let mut disks = 0;
+        for &res in [Resolution::R1, Resolution::R2].iter() {
+            for base in H3Index::getRes0Indexes().iter() {
+                for h in base.h3ToChildren(res) {
+                    let disk = h.kRing(1);
+                    if disk.iter().any(|c| c.is_pentagon())
+                        || disk.iter().all(|c| c.h3GetFaces() == h.h3GetFaces())
+                    {
+                        continue;
+                    }
+                    disks += 1;
+
+                    let graph = H3Index::h3SetToVertexGraph(&disk);
+                    let first = *graph.firstVertexNode().unwrap();
+                    let mut node = first;
+                    let mut walked = 1;
+                    loop {
+                        node = *graph
+                            .findNodeForVertex(&node.to)
+                            .unwrap_or_else(|| panic!("{}: outline continues", h));
+                        if node == first {
+                            break;
+                        }
+                        walked += 1;
+                        assert!(walked <= graph.size(), "{}: loop closes", h);
+                    }
+                    assert_eq!(walked, graph.size(), "{}: one outline", h);
+                }
+            }
+        }
+        assert!(disks > 100, "{} disks across faces", disks);
+
+        let disk = H3Index::getRes0Indexes()[10].kRing(1);
+        assert_eq!(
+            H3Index::h3SetToVertexGraphEpsilon(&disk, 1e-9)
+                .unwrap()
+                .size(),
+            H3Index::h3SetToVertexGraph(&disk).size(),
+            "looser epsilon"
+        );
+        assert_eq!(
+            H3Index::h3SetToVertexGraphEpsilon(&disk, 0.0).map(|g| g.size()),
+            Err(H3Error::Domain),
+            "bad epsilon"
+        );
+    }
+
+    #[test]
+    fn gridRing_matchesKRingDistances() {
+        for base in H3Index::getRes0Indexes().iter() {
+            for h in base.h3ToChildren(Resolution::R2) {
+                for k in 0..4 {
+                    let mut ring = h.grid_ring(k).unwrap();
+                    ring.sort_by_key(|h| h.0);
+
+                    let mut expected: Vec<H3Index> = h
+                        .kRingDistances(k)
+                        .into_iter()
+                        .filter(|&(_, d)| d == k)
+                        .map(|(h, _)| h)
+                        .collect();
+                    expected.sort_by_key(|h| h.0);
+
+                    assert_eq!(ring, expected, "ring {} of {}", k, h);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gridRing_pentagon() {
+        let pentagon =
+            H3Index::setH3Index(Resolution::R1, BaseCell::new_unchecked(4), CENTER_DIGIT);
+        assert!(pentagon.hexRing(1).is_err(), "fast path fails on pentagon");
+
+        let ring = pentagon.grid_ring(1).unwrap();
+        assert_eq!(ring.len(), 5, "pentagon has 5 neighbors");
+        assert!(!ring.contains(&pentagon), "origin is not in its ring");
+
+        let k = H3Index::MAX_KRING_K + 1;
+        assert_eq!(pentagon.grid_ring(k), Err(H3Error::Domain), "no fallback");
+        let hexagon = H3Index::from(0x8928308280fffff);
+        assert_eq!(hexagon.grid_ring(k), Err(H3Error::Domain), "past the cap");
+    }
+
+    #[test]
+    fn gridCollar_matchesKRingDistances() {
+        for base in H3Index::getRes0Indexes().iter() {
+            for h in base.h3ToChildren(Resolution::R1) {
+                for (inner, outer) in [(0, 1), (1, 3), (2, 2)].iter() {
+                    let mut collar = h.grid_collar(*inner, *outer).unwrap();
+                    collar.sort_by_key(|h| h.0);
+
+                    let mut expected: Vec<H3Index> = h
+                        .kRingDistances(*outer)
+                        .into_iter()
+                        .filter(|&(_, d)| d > *inner)
+                        .map(|(h, _)| h)
+                        .collect();
+                    expected.sort_by_key(|h| h.0);
+
+                    assert_eq!(collar, expected, "collar {}..{} of {}", inner, outer, h);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gridCollar_hexagon() {
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+
+        let collar = origin.grid_collar(5, 10).unwrap();
+        assert_eq!(
+            collar.len(),
+            (6..=10).map(|k| 6 * k).sum::<usize>(),
+            "6k cells in each ring"
+        );
+        assert_eq!(origin.grid_collar(0, 0), Ok(vec![]), "empty collar");
+
+        assert_eq!(origin.grid_collar(3, 1), Err(H3Error::Domain), "inside out");
+        let max = H3Index::MAX_KRING_K;
+        assert_eq!(
+            origin.grid_collar(0, max + 1),
+            Err(H3Error::Domain),
+            "past the cap"
+        );
+        let pentagon = Resolution::R9.getPentagonIndexes()[0];
+        assert_eq!(
+            pentagon.grid_collar(max, u32::MAX),
+            Err(H3Error::Domain),
+            "no fallback"
+        );
+    }
+
+    #[test]
+    fn hexRing_hexagon() {
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+
+        assert_eq!(origin.hexRing(0), Ok(vec![origin]), "identity ring");
+        for k in 1..5 {
+            let ring = origin.hexRing(k).unwrap();
+            assert_eq!(ring.len(), 6 * k as usize, "ring has 6k cells");
+            for h in ring.iter() {
+                assert_eq!(origin.h3Distance(h), Ok(k as i32), "cell at distance k");
+            }
+        }
+
+        let max = H3Index::MAX_KRING_K;
+        assert_eq!(
+            origin.hexRing(max + 1),
+            Err(H3Error::Domain),
+            "past the cap"
+        );
+        assert_eq!(
+            origin.hexRing(u32::MAX),
+            Err(H3Error::Domain),
+            "not allocated"
+        );
+        assert_eq!(origin.grid_ring_unsafe(u32::MAX), Err(H3Error::Domain));
+    }
+
+    #[test]
+    fn hexRing_nearPentagon() {
+        let nearPentagon = H3Index::from(0x837405fffffffff);
+        for k in 1..3 {
+            assert_eq!(
+                nearPentagon.hexRing(k),
+                Err(H3Error::Pentagon),
+                "ring {}",
+                k
+            );
+            assert_eq!(nearPentagon.grid_ring_unsafe(k), Err(H3Error::Pentagon));
+
+            // the fallback the error asks for
+            let ring = nearPentagon.grid_ring(k).unwrap();
+            assert!(!ring.is_empty(), "fallback ring {}", k);
+            for (h, d) in nearPentagon.kRingDistances(k) {
+                assert_eq!(ring.contains(&h), d == k, "{} at distance {}", h, d);
+            }
+        }
+
+        let pentagon =
+            H3Index::setH3Index(Resolution::R0, BaseCell::new_unchecked(4), CENTER_DIGIT);
+        assert_eq!(pentagon.hexRing(2), Err(H3Error::Pentagon), "on a pentagon");
+
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+        assert_eq!(sf.grid_ring_unsafe(2), sf.hexRing(2), "same as hexRing");
+    }
+
+    #[test]
+    fn neighborRotations_walkBack() {
+        for &res in &Resolution::RESOLUTIONS[0..3] {
+            for base in H3Index::getRes0Indexes().iter() {
+                for origin in base.h3ToChildren(res) {
+                    for dir in Direction::axes() {
+                        if origin.is_pentagon() && dir == Direction::K_AXES_DIGIT {
+                            continue;
+                        }
+                        let mut rotations = 0;
+                        let neighbor = origin.h3NeighborRotations(dir, &mut rotations);
+                        assert_ne!(neighbor, H3Index::H3_NULL, "{} {:?}", origin, dir);
+
+                        // Opposite digits sum to 7; the rotations carried
+                        // over turn it into the neighbor's frame.
+                        let reverse = Direction::from(7 - dir as usize);
+                        let mut back = rotations;
+                        let walked = neighbor.h3NeighborRotations(reverse, &mut back);
+                        assert_eq!(walked, origin, "{} {:?} and back", origin, dir);
+
+                        // A base cell pentagon's IK neighbor sits across the
+                        // deleted k subsequence, so the way back is one
+                        // rotation short; the polar pentagons are laid out
+                        // so that it is not.
+                        let expected = if res == Resolution::R0
+                            && origin.is_pentagon()
+                            && !origin.get_base_cell()._isBaseCellPolarPentagon()
+                            && dir == Direction::IK_AXES_DIGIT
+                        {
+                            5
+                        } else {
+                            0
+                        };
+                        assert_eq!(
+                            back.rem_euclid(6),
+                            expected,
+                            "{} {:?} orientation",
+                            origin,
+                            dir
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Number of vertexes of each loop of each polygon.
+    fn loopSizes(cells: &[u64]) -> Vec<Vec<usize>> {
+        let cells: Vec<H3Index> = cells.iter().map(|&h| H3Index::from(h)).collect();
+        let mut sizes: Vec<Vec<usize>> = H3Index::h3SetToLinkedGeo(&cells)
+            .unwrap()
+            .polygons
+            .iter()
+            .map(|p| {
+                std::iter::once(p.exterior().len())
+                    .chain(p.holes().map(|hole| hole.len()))
+                    .collect()
+            })
+            .collect();
+        sizes.sort();
+        sizes
+    }
+
+    #[test]
+    fn h3SetToLinkedGeo() {
+        assert!(H3Index::h3SetToLinkedGeo(&[]).unwrap().polygons.is_empty());
+        assert_eq!(loopSizes(&[0x890dab6220bffff]), vec![vec![6]], "single hex");
+        assert_eq!(
+            loopSizes(&[0x8928308291bffff, 0x89283082957ffff]),
+            vec![vec![10]],
+            "contiguous 2"
+        );
+        assert_eq!(
+            loopSizes(&[0x8928308291bffff, 0x89283082943ffff]),
+            vec![vec![6], vec![6]],
+            "non contiguous 2"
+        );
+        assert_eq!(
+            loopSizes(&[0x8928308288bffff, 0x892830828d7ffff, 0x8928308289bffff]),
+            vec![vec![12]],
+            "contiguous 3"
+        );
+        assert_eq!(
+            loopSizes(&[
+                0x892830828c7ffff,
+                0x892830828d7ffff,
+                0x8928308289bffff,
+                0x89283082813ffff,
+                0x8928308288fffff,
+                0x89283082883ffff
+            ]),
+            vec![vec![6 * 3, 6]],
+            "hole"
+        );
+        assert_eq!(loopSizes(&[0x851c0003fffffff]), vec![vec![10]], "pentagon");
+
+        let twoRing = [
+            0x8930062838bffff,
+            0x8930062838fffff,
+            0x89300628383ffff,
+            0x8930062839bffff,
+            0x893006283d7ffff,
+            0x893006283c7ffff,
+            0x89300628313ffff,
+            0x89300628317ffff,
+            0x893006283bbffff,
+            0x89300628387ffff,
+            0x89300628397ffff,
+            0x89300628393ffff,
+            0x89300628067ffff,
+            0x8930062806fffff,
+            0x893006283d3ffff,
+            0x893006283c3ffff,
+            0x893006283cfffff,
+            0x8930062831bffff,
+            0x89300628303ffff,
+        ];
+        assert_eq!(loopSizes(&twoRing), vec![vec![6 * 5]], "2 ring");
+    }
+
+    #[test]
+    fn h3SetToLinkedGeo_nestedDonut() {
+        // a ring of cells inside the hole of a larger ring
+        let cells: Vec<H3Index> = [
+            0x89283082813ffff,
+            0x8928308281bffff,
+            0x8928308280bffff,
+            0x8928308280fffff,
+            0x89283082807ffff,
+            0x89283082817ffff,
+            0x8928308289bffff,
+            0x892830828d7ffff,
+            0x892830828c3ffff,
+            0x892830828cbffff,
+            0x89283082853ffff,
+            0x89283082843ffff,
+            0x8928308284fffff,
+            0x8928308287bffff,
+            0x89283082863ffff,
+            0x89283082867ffff,
+            0x8928308282bffff,
+            0x89283082823ffff,
+            0x89283082837ffff,
+            0x892830828afffff,
+            0x892830828a3ffff,
+            0x892830828b3ffff,
+            0x89283082887ffff,
+            0x89283082883ffff,
+        ]
+        .iter()
+        .map(|&h| H3Index::from(h))
+        .collect();
+        let multi = H3Index::cells_to_multi_polygon(&cells).unwrap();
+        let mut sizes: Vec<(usize, usize)> = multi
+            .polygons
+            .iter()
+            .map(|p| (p.exterior().len(), p.holes().next().map_or(0, |h| h.len())))
+            .collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![(18, 6), (42, 30)], "two donuts");
+
+        for polygon in multi.polygons.iter() {
+            assert!(!polygon.geofence.isClockwise(), "exterior counterclockwise");
+            assert!(
+                polygon.holes.iter().all(|h| h.isClockwise()),
+                "holes clockwise"
+            );
+        }
+        let covered = |h: &H3Index| multi.polygons.iter().any(|p| p.contains(&h.h3ToGeo()));
+        for h in cells.iter() {
+            assert!(covered(h), "{} is covered", h);
+        }
+        // the hole of the inner donut, and the ring between the donuts
+        let inner = H3Index::from(0x89283082803ffff);
+        assert!(!cells.contains(&inner) && !covered(&inner), "inner hole");
+        assert!(inner.neighbors().iter().all(|h| cells.contains(&h)));
+        for h in inner.kRing(3) {
+            assert_eq!(covered(&h), cells.contains(&h), "{}", h);
+        }
+    }
+
+    #[test]
+    fn h3SetToLinkedGeo_errors() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let a = sf.geoToH3(Resolution::R9);
+        let b = sf.geoToH3(Resolution::R8);
+        assert_eq!(
+            H3Index::h3SetToLinkedGeo(&[a, b]).err(),
+            Some(H3Error::ResolutionMismatch)
+        );
+        assert_eq!(
+            H3Index::h3SetToLinkedGeo(&[a, a]).err(),
+            Some(H3Error::DuplicateInput)
+        );
+        assert_eq!(
+            H3Index::h3SetToLinkedGeo(&[a, H3Index::H3_NULL]).err(),
+            Some(H3Error::CellInvalid)
+        );
+    }
+}
@@ -1,7 +1,113 @@
-use crate::{basecell::BaseCell, H3Index};
+use std::convert::TryFrom;
+
+use crate::{basecell::BaseCell, Direction, H3Index, Resolution};
 
 use super::H3Mode;
 
+/// The cells of one base cell at a resolution, in ascending index order,
+/// generated one at a time. Created by `BaseCell::cells_at`.
+#[derive(Clone, Debug)]
+pub struct BaseCellCells {
+    next: Option<H3Index>,
+    remaining: u64,
+}
+
+impl BaseCellCells {
+    /**
+     * Moves on to the cell after `h` in index order: the digits count up like
+     * an odometer, and a pentagon's deleted k subsequence is stepped over.
+     *
+     * @param h A cell of the base cell, at the resolution being generated
+     * @return The next cell, or None after the last
+     */
+    fn successor(mut h: H3Index) -> Option<H3Index> {
+        let res = usize::from(h.get_resolution());
+        let mut r = res;
+        loop {
+            if r == 0 {
+                return None;
+            }
+            let digit = h.get_index_digit(Resolution::from(r)) as u64;
+            if digit < 6 {
+                h.set_index_digit(Resolution::from(r), digit + 1);
+                break;
+            }
+            h.set_index_digit(Resolution::from(r), 0);
+            r -= 1;
+        }
+
+        // Only a leading 1 can have become the leading nonzero digit, with
+        // zeros after it; the next cell then starts with 2.
+        if h.get_base_cell()._isBaseCellPentagon()
+            && h._h3LeadingNonZeroDigit() == Direction::K_AXES_DIGIT
+        {
+            h.set_index_digit(Resolution::from(r), Direction::J_AXES_DIGIT as u64);
+        }
+        Some(h)
+    }
+}
+
+impl Iterator for BaseCellCells {
+    type Item = H3Index;
+
+    fn next(&mut self) -> Option<H3Index> {
+        let h = self.next?;
+        self.next = Self::successor(h);
+        self.remaining -= 1;
+        Some(h)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.remaining) {
+            Ok(n) => (n, Some(n)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+impl std::iter::FusedIterator for BaseCellCells {}
+
+impl From<BaseCell> for H3Index {
+    /// The resolution 0 cell of a base cell.
+    fn from(baseCell: BaseCell) -> Self {
+        let mut h = H3Index::H3_INIT;
+        h.set_mode(H3Mode::H3_HEXAGON_MODE);
+        h.set_base_cell(baseCell);
+        h
+    }
+}
+
+impl BaseCell {
+    /**
+     * Generates every cell of this base cell at a resolution, lazily and in
+     * ascending index order, without building the resolution 0 index and its
+     * children lists first. Whole earth jobs can hand each of the 122 base
+     * cells to a worker of its own.
+     *
+     * @param res The resolution of the cells
+     * @return The cells: 7^res of them, or 1 + 5 * (7^res - 1) / 6 for a
+     *         pentagon, which has no k subsequence
+     */
+    pub fn cells_at(&self, res: Resolution) -> BaseCellCells {
+        let mut first = H3Index::from(*self);
+        first.set_resolution(res);
+        for r in 1..=usize::from(res) {
+            first.set_index_digit(Resolution::from(r), 0);
+        }
+
+        let cells = 7u64.pow(usize::from(res) as u32);
+        let remaining = if self._isBaseCellPentagon() {
+            1 + 5 * (cells - 1) / 6
+        } else {
+            cells
+        };
+        BaseCellCells {
+            next: Some(first),
+            remaining,
+        }
+    }
+}
+
 impl H3Index {
     /**
      * res0IndexCount returns the number of resolution 0 indexes
@@ -20,11 +126,8 @@ impl H3Index {
      */
     pub fn getRes0Indexes() -> [H3Index; BaseCell::NUM_BASE_CELLS] {
         let mut result = [H3Index::H3_INIT; BaseCell::NUM_BASE_CELLS];
-        for bc in 0..BaseCell::NUM_BASE_CELLS {
-            result[bc].set_mode(H3Mode::H3_HEXAGON_MODE);
-
-            let cell = BaseCell::new(bc as i32);
-            result[bc].set_base_cell(cell);
+        for (bc, h) in result.iter_mut().enumerate() {
+            *h = H3Index::from(BaseCell::new_unchecked(bc as i32));
         }
 
         result
@@ -38,6 +141,7 @@ mod tests {
     fn getRes0Indexes() {
         let count = H3Index::res0IndexCount();
         let indexes = H3Index::getRes0Indexes();
+        assert_eq!(indexes.len(), count, "correct number of indexes");
 
         assert_eq!(
             u64::from(indexes[0]),
@@ -50,4 +154,51 @@ mod tests {
             "correct last basecell"
         );
     }
+
+    #[test]
+    fn cellsAt_matchesChildren() {
+        for bc in [0, 4, 20, 121] {
+            let baseCell = BaseCell::new(bc).unwrap();
+            let origin = H3Index::from(baseCell);
+            assert_eq!(origin, H3Index::getRes0Indexes()[bc as usize], "res 0");
+            for &res in &Resolution::RESOLUTIONS[..4] {
+                let cells = baseCell.cells_at(res);
+                let expected = cells.size_hint().0;
+                let cells: Vec<H3Index> = cells.collect();
+                assert_eq!(
+                    cells,
+                    origin.h3ToChildren(res),
+                    "base cell {} res {}",
+                    bc,
+                    res as usize
+                );
+                assert_eq!(cells.len(), expected, "size hint");
+            }
+        }
+    }
+
+    #[test]
+    fn cellsAt_lazy() {
+        let pentagon = BaseCell::new(4).unwrap();
+        let mut cells = pentagon.cells_at(Resolution::R15);
+        let total = 1 + 5 * (7u64.pow(15) - 1) / 6;
+        assert_eq!(cells.size_hint().0 as u64, total, "pentagon count");
+
+        let first: Vec<H3Index> = cells.by_ref().take(10).collect();
+        assert!(first[0].is_pentagon(), "center child first");
+        for h in first.iter() {
+            assert!(h.is_valid(), "{} valid", h);
+            assert_eq!(h.get_base_cell(), pentagon, "{} base cell", h);
+        }
+        assert!(
+            first.windows(2).all(|w| u64::from(w[0]) < u64::from(w[1])),
+            "ascending"
+        );
+        assert_eq!(cells.size_hint().0 as u64, total - 10, "counts down");
+
+        let mut res0 = BaseCell::MAX.cells_at(Resolution::R0);
+        assert_eq!(res0.next(), Some(H3Index::from(BaseCell::MAX)), "itself");
+        assert_eq!(res0.next(), None, "only itself");
+        assert_eq!(res0.next(), None, "fused");
+    }
 }
@@ -0,0 +1,139 @@
+use std::fmt;
+
+use super::{H3Index, H3Mode};
+use crate::Resolution;
+
+/// The fields of an index's 64 bits, as stored, for tools that inspect raw
+/// indexes. Returned by `H3Index::bit_layout`.
+///
+/// From the most significant bit down, an index holds:
+///
+/// | bits  | width | field                                          |
+/// |-------|-------|------------------------------------------------|
+/// | 63    | 1     | high bit, always 0                             |
+/// | 59-62 | 4     | mode, see `H3Mode`                             |
+/// | 56-58 | 3     | reserved; edge direction or vertex number      |
+/// | 52-55 | 4     | resolution, 0-15                               |
+/// | 45-51 | 7     | base cell, 0-121                               |
+/// | 0-44  | 45    | 15 digits of 3 bits, resolution 1 first        |
+///
+/// Digits past the resolution are 7 in a valid index. The fields are copied
+/// as they are, without validation, so any u64 can be taken apart.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BitLayout {
+    /// The highest bit
+    pub highBit: u8,
+    /// The raw mode bits; `H3Index::mode` interprets them
+    pub mode: u8,
+    /// The reserved bits
+    pub reserved: u8,
+    /// The raw resolution bits
+    pub resolution: u8,
+    /// The raw base cell bits, which may be past the last base cell
+    pub baseCell: u8,
+    /// The digits for resolutions 1 through 15
+    pub digits: [u8; Resolution::MAX_H3_RES],
+}
+
+impl fmt::Display for BitLayout {
+    /// Writes the fields in binary, separated by `|`, with the digits grouped
+    /// by resolution.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:01b}|{:04b}|{:03b}|{:04b}|{:07b}|",
+            self.highBit, self.mode, self.reserved, self.resolution, self.baseCell
+        )?;
+        for (i, digit) in self.digits.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{:03b}", digit)?;
+        }
+        Ok(())
+    }
+}
+
+impl H3Index {
+    /// The mode of the index: a cell, a unidirectional edge, and so on.
+    /// Mode values this library does not know are `H3_INVALID_MODE`.
+    pub fn mode(&self) -> H3Mode {
+        self.get_mode()
+    }
+
+    /// The 3 reserved bits. They are 0 for cells, hold the direction for
+    /// unidirectional edges, and the vertex number for vertexes.
+    pub fn reserved_bits(&self) -> u8 {
+        self.get_reserved_bits() as u8
+    }
+
+    /// Splits the index into its bit fields, for debugging and for designing
+    /// storage formats without copying the masks.
+    pub fn bit_layout(&self) -> BitLayout {
+        let mut digits = [0; Resolution::MAX_H3_RES];
+        for (i, digit) in digits.iter_mut().enumerate() {
+            *digit = self.get_index_digit(Resolution::from(i + 1)) as u8;
+        }
+
+        BitLayout {
+            highBit: self.get_high_bit() as u8,
+            mode: ((self.0 & Self::H3_MODE_MASK) >> Self::H3_MODE_OFFSET) as u8,
+            reserved: self.reserved_bits(),
+            resolution: ((self.0 & Self::H3_RES_MASK) >> Self::H3_RES_OFFSET) as u8,
+            baseCell: ((self.0 & Self::H3_BC_MASK) >> Self::H3_BC_OFFSET) as u8,
+            digits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GeoCoord;
+
+    #[test]
+    fn bitLayout_cell() {
+        let h = H3Index::from(0x85283473fffffff);
+        assert_eq!(h.mode(), H3Mode::H3_HEXAGON_MODE, "mode");
+        assert_eq!(h.reserved_bits(), 0, "reserved bits");
+
+        let layout = h.bit_layout();
+        assert_eq!(layout.highBit, 0, "high bit");
+        assert_eq!(layout.mode, 1, "raw mode");
+        assert_eq!(layout.resolution, 5, "resolution");
+        assert_eq!(layout.baseCell, 20, "base cell");
+        assert_eq!(layout.digits[..5], [0, 6, 4, 3, 4], "digits");
+        assert!(layout.digits[5..].iter().all(|&d| d == 7), "unused digits");
+        assert_eq!(
+            layout.to_string(),
+            "0|0001|000|0101|0010100|000 110 100 011 100 111 111 111 111 111 111 111 111 111 111",
+            "display"
+        );
+    }
+
+    #[test]
+    fn bitLayout_edgeAndRaw() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let mut edge = sf.geoToH3(Resolution::R9);
+        edge.set_mode(H3Mode::H3_UNIEDGE_MODE);
+        edge.set_reserved_bits(3);
+        assert_eq!(edge.mode(), H3Mode::H3_UNIEDGE_MODE, "edge mode");
+        assert_eq!(edge.reserved_bits(), 3, "edge direction");
+        assert_eq!(edge.bit_layout().reserved, 3, "layout reserved");
+
+        let raw = H3Index::from(u64::MAX);
+        assert_eq!(raw.mode(), H3Mode::H3_INVALID_MODE, "unknown mode");
+        let layout = raw.bit_layout();
+        assert_eq!(
+            (
+                layout.highBit,
+                layout.mode,
+                layout.resolution,
+                layout.baseCell
+            ),
+            (1, 15, 15, 127),
+            "fields are not validated"
+        );
+        assert_eq!(layout.to_string().len(), 64 + 5 + 14, "every bit shown");
+    }
+}
@@ -0,0 +1,143 @@
+use std::cell::OnceCell;
+
+use crate::{geopolygon::GeoBoundary, GeoCoord, H3Index, Resolution, SphereModel};
+
+/// An H3 cell that remembers its derived properties.
+///
+/// `H3Index` stays the lightweight id to store and pass around; a `Cell` is
+/// for code that asks the same cells for their geometry over and over, such
+/// as the grid of a simulation. Each property is computed on first use and
+/// then returned from the cache.
+#[derive(Clone, Debug)]
+pub struct Cell {
+    index: H3Index,
+    resolution: OnceCell<Resolution>,
+    center: OnceCell<GeoCoord>,
+    boundary: OnceCell<GeoBoundary>,
+    areaRads2: OnceCell<f64>,
+}
+
+impl Cell {
+    /// Wraps an index. Nothing is computed until a property is asked for.
+    pub fn new(index: H3Index) -> Self {
+        Cell {
+            index,
+            resolution: OnceCell::new(),
+            center: OnceCell::new(),
+            boundary: OnceCell::new(),
+            areaRads2: OnceCell::new(),
+        }
+    }
+
+    /// The wrapped index.
+    pub fn index(&self) -> H3Index {
+        self.index
+    }
+
+    /// Resolution of the cell.
+    pub fn resolution(&self) -> Resolution {
+        *self.resolution.get_or_init(|| self.index.get_resolution())
+    }
+
+    /// Center of the cell, as from `h3ToGeo`.
+    pub fn center(&self) -> &GeoCoord {
+        self.center.get_or_init(|| self.index.h3ToGeo())
+    }
+
+    /// Boundary of the cell, as from `h3ToGeoBoundary`.
+    pub fn boundary(&self) -> &GeoBoundary {
+        self.boundary.get_or_init(|| self.index.h3ToGeoBoundary())
+    }
+
+    /// Area of the cell in radians^2. Reuses the cached center and boundary,
+    /// rather than computing them again as `cellAreaRads2` does.
+    pub fn areaRads2(&self) -> f64 {
+        *self.areaRads2.get_or_init(|| {
+            let c = self.center();
+            let gb = self.boundary();
+
+            let mut area = 0.0;
+            for i in 0..gb.numVerts {
+                let j = (i + 1) % gb.numVerts;
+                area += GeoCoord::triangleArea(&gb.verts[i], &gb.verts[j], c);
+            }
+            area
+        })
+    }
+
+    /// Area of the cell in kilometers^2.
+    pub fn areaKm2(&self) -> f64 {
+        SphereModel::EARTH.rads2_to_km2(self.areaRads2())
+    }
+
+    /// Area of the cell in meters^2.
+    pub fn areaM2(&self) -> f64 {
+        self.areaKm2() * 1000. * 1000.
+    }
+}
+
+impl From<H3Index> for Cell {
+    fn from(index: H3Index) -> Self {
+        Cell::new(index)
+    }
+}
+
+impl From<Cell> for H3Index {
+    fn from(cell: Cell) -> Self {
+        cell.index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_matchesIndex() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let pentagon = Resolution::R3.getPentagonIndexes()[0];
+
+        for &h in [sf.geoToH3(Resolution::R9), pentagon].iter() {
+            let cell = Cell::from(h);
+            assert_eq!(cell.index(), h, "index");
+            assert_eq!(cell.resolution(), h.get_resolution(), "resolution");
+
+            let center = h.h3ToGeo();
+            assert_eq!(cell.center().lat, center.lat, "center lat");
+            assert_eq!(cell.center().lon, center.lon, "center lon");
+
+            let gb = h.h3ToGeoBoundary();
+            assert_eq!(cell.boundary().numVerts, gb.numVerts, "vertex count");
+            for (a, b) in cell.boundary().verts[..gb.numVerts]
+                .iter()
+                .zip(gb.verts[..gb.numVerts].iter())
+            {
+                assert_eq!((a.lat, a.lon), (b.lat, b.lon), "boundary vertex");
+            }
+
+            assert_eq!(cell.areaRads2(), h.cellAreaRads2(), "area rads2");
+            assert_eq!(cell.areaKm2(), h.cellAreaKm2(), "area km2");
+            assert_eq!(cell.areaM2(), h.cellAreaM2(), "area m2");
+            assert_eq!(H3Index::from(cell), h, "back to the index");
+        }
+    }
+
+    #[test]
+    fn cell_caches() {
+        let cell = Cell::new(Resolution::R5.getPentagonIndexes()[1]);
+        assert!(cell.center.get().is_none(), "lazy center");
+        assert!(cell.boundary.get().is_none(), "lazy boundary");
+
+        let area = cell.areaKm2();
+        assert!(cell.center.get().is_some(), "area filled the center");
+        assert!(cell.boundary.get().is_some(), "area filled the boundary");
+        assert_eq!(cell.areaKm2(), area, "same area");
+        assert!(
+            std::ptr::eq(cell.boundary(), cell.boundary()),
+            "boundary is not recomputed"
+        );
+
+        let copy = cell.clone();
+        assert!(copy.areaRads2.get().is_some(), "clones keep the cache");
+    }
+}
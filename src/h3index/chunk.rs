@@ -0,0 +1,130 @@
+use std::iter::Peekable;
+
+use crate::{H3Error, H3Index, Resolution};
+
+/// Groups a sorted stream of cells by their ancestor at a coarser
+/// resolution. Created by `H3Index::chunk_by_parent`.
+pub struct ParentChunks<I: Iterator<Item = H3Index>> {
+    cells: Peekable<I>,
+    parentRes: Resolution,
+}
+
+impl<I: Iterator<Item = H3Index>> Iterator for ParentChunks<I> {
+    type Item = Result<(H3Index, Vec<H3Index>), H3Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.cells.next()?;
+        let parent = match first.h3ToParent(self.parentRes) {
+            Ok(parent) => parent,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let parentRes = self.parentRes;
+        let mut children = vec![first];
+        while let Some(cell) = self
+            .cells
+            .next_if(|cell| cell.h3ToParent(parentRes) == Ok(parent))
+        {
+            children.push(cell);
+        }
+
+        Some(Ok((parent, children)))
+    }
+}
+
+impl H3Index {
+    /**
+     * Splits a stream of cells into runs sharing an ancestor, such as one
+     * output file per parent cell when partitioning a table.
+     *
+     * Sorting cells of one resolution by their index value keeps the
+     * descendants of every parent together, so the runs are found by
+     * comparing neighbors without hashing. Unsorted input still works, but a
+     * parent may then be yielded more than once.
+     *
+     * @param cells Cells, sorted by index value
+     * @param parentRes Resolution of the parents to group by
+     * @return An iterator of (parent, children in input order), or
+     *         ResolutionMismatch for a cell coarser than parentRes
+     */
+    pub fn chunk_by_parent<I>(cells: I, parentRes: Resolution) -> ParentChunks<I::IntoIter>
+    where
+        I: IntoIterator<Item = H3Index>,
+    {
+        ParentChunks {
+            cells: cells.into_iter().peekable(),
+            parentRes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GeoCoord;
+
+    #[test]
+    fn chunkByParent_sorted() {
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let mut cells = sf.geoToH3(Resolution::R5).kRing(2);
+        cells.sort_by_key(|&h| u64::from(h));
+        let children: Vec<H3Index> = cells
+            .iter()
+            .flat_map(|h| h.h3ToChildren(Resolution::R7))
+            .collect();
+
+        let chunks: Vec<(H3Index, Vec<H3Index>)> =
+            H3Index::chunk_by_parent(children.iter().copied(), Resolution::R5)
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(chunks.len(), cells.len(), "one chunk per parent");
+        for ((parent, chunk), cell) in chunks.iter().zip(cells.iter()) {
+            assert_eq!(parent, cell, "parents in order");
+            assert_eq!(*chunk, cell.h3ToChildren(Resolution::R7), "all children");
+        }
+    }
+
+    #[test]
+    fn chunkByParent_pentagon() {
+        let pentagon = Resolution::R2.getPentagonIndexes()[0];
+        let chunks: Vec<_> =
+            H3Index::chunk_by_parent(pentagon.h3ToChildren(Resolution::R4), Resolution::R3)
+                .map(|chunk| chunk.unwrap())
+                .collect();
+
+        let parents: Vec<H3Index> = chunks.iter().map(|(parent, _)| *parent).collect();
+        assert_eq!(parents, pentagon.h3ToChildren(Resolution::R3), "parents");
+        assert_eq!(chunks[0].1.len(), 6, "pentagon child has 6 children");
+        assert!(
+            chunks[1..].iter().all(|(_, chunk)| chunk.len() == 7),
+            "hexagon children have 7 children"
+        );
+    }
+
+    #[test]
+    fn chunkByParent_edgeCases() {
+        assert_eq!(
+            H3Index::chunk_by_parent(Vec::new(), Resolution::R3).count(),
+            0,
+            "no cells, no chunks"
+        );
+
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let coarse = sf.geoToH3(Resolution::R2);
+        let fine = sf.geoToH3(Resolution::R4);
+        let chunks: Vec<_> = H3Index::chunk_by_parent(vec![coarse, fine], Resolution::R3).collect();
+        assert_eq!(
+            chunks,
+            vec![
+                Err(H3Error::ResolutionMismatch),
+                Ok((fine.h3ToParent(Resolution::R3).unwrap(), vec![fine]))
+            ],
+            "cells coarser than the parents are errors"
+        );
+
+        let chunks: Vec<_> = H3Index::chunk_by_parent(vec![fine, coarse, fine], Resolution::R3)
+            .map(|chunk| chunk.map(|(_, cells)| cells.len()))
+            .collect();
+        assert_eq!(chunks.len(), 3, "unsorted input repeats parents");
+    }
+}
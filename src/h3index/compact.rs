@@ -0,0 +1,532 @@
+use std::collections::HashMap;
+
+use crate::{budget::Budget, H3Error, H3Index, Resolution};
+
+/// Order of the cells returned by `H3Index::compact_ordered`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum CompactOrder {
+    /// Ascending by index value. This is what `compact` returns, so the same
+    /// set of cells always compacts to the same list, however it was ordered.
+    #[default]
+    Ascending,
+
+    /// In the order their cells first appear in the input: a parent takes the
+    /// place of the first of its children. Ties go to the smaller index value.
+    FirstSeen,
+}
+
+impl H3Index {
+    /**
+     * compact takes a set of hexagons all at the same resolution and compresses
+     * them by pruning full child branches to the parent level. This is also done
+     * for all parents recursively to get the minimum number of hex addresses that
+     * perfectly cover the defined space.
+     *
+     * The output is sorted ascending by index value, so compacting the same set
+     * always gives the same list; see `compact_ordered` for other orders.
+     *
+     * @param h3Set Set of hexagons, all at the same resolution; H3_NULL
+     *        entries are skipped
+     * @return The compacted hexagons, or CellInvalid for an index that is not
+     *         a cell, ResolutionMismatch for mixed resolutions, or
+     *         DuplicateInput if a cell appears more than once
+     */
+    pub fn compact(h3Set: &[H3Index]) -> Result<Vec<H3Index>, H3Error> {
+        Self::compact_ordered(h3Set, CompactOrder::Ascending)
+    }
+
+    /**
+     * Compacts like `compact`, returning the cells in the given order.
+     *
+     * @param h3Set Set of hexagons
+     * @param order Order of the output cells
+     * @return The compacted hexagons, or the errors of `compact`
+     */
+    pub fn compact_ordered(
+        h3Set: &[H3Index],
+        order: CompactOrder,
+    ) -> Result<Vec<H3Index>, H3Error> {
+        let compacted = Self::compactUnordered(h3Set, &mut Budget::unlimited())?;
+        Ok(Self::orderCompacted(compacted, h3Set, order))
+    }
+
+    /**
+     * Compacts like `compact_ordered`, charging the cells examined by each
+     * pass against a budget, so compacting a huge set can be cancelled.
+     *
+     * @param h3Set Set of hexagons
+     * @param order Order of the output cells
+     * @param budget Limit on the cells examined
+     * @return The compacted hexagons, the errors of `compact`, or
+     *         `H3Error::Cancelled` if the budget ran out
+     */
+    pub fn compact_budgeted(
+        h3Set: &[H3Index],
+        order: CompactOrder,
+        budget: &mut Budget,
+    ) -> Result<Vec<H3Index>, H3Error> {
+        let compacted = Self::compactUnordered(h3Set, budget)?;
+        Ok(Self::orderCompacted(compacted, h3Set, order))
+    }
+
+    /**
+     * uncompact takes a compressed set of hexagons and expands back to the
+     * original set of hexagons, each cell replaced by its descendants at the
+     * resolution in the order of `h3ToChildren`. Pentagons expand to their
+     * pentagon and hexagon descendants, without the deleted subsequence.
+     *
+     * @param compactedSet Set of hexagons, at res or coarser; H3_NULL entries
+     *        are skipped
+     * @param res The hexagon resolution to decompress to
+     * @return The hexagons, or CellInvalid for an index that is not a cell,
+     *         or ResolutionMismatch for a cell finer than res
+     */
+    pub fn uncompact(compactedSet: &[H3Index], res: Resolution) -> Result<Vec<H3Index>, H3Error> {
+        Self::uncompact_budgeted(compactedSet, res, &mut Budget::unlimited())
+    }
+
+    /**
+     * Uncompacts like `uncompact`, charging the cells each input cell expands
+     * to against a budget before generating them, so expanding a coarse set
+     * to a fine resolution can be stopped before it fills memory.
+     *
+     * @param compactedSet Set of hexagons, at res or coarser
+     * @param res The hexagon resolution to decompress to
+     * @param budget Limit on the cells generated
+     * @return The hexagons, the errors of `uncompact`, or
+     *         `H3Error::Cancelled` if the budget ran out
+     */
+    pub fn uncompact_budgeted(
+        compactedSet: &[H3Index],
+        res: Resolution,
+        budget: &mut Budget,
+    ) -> Result<Vec<H3Index>, H3Error> {
+        let mut h3Set = Vec::new();
+        for &h in compactedSet {
+            if h == H3Index::H3_NULL {
+                continue;
+            }
+            if !h.is_valid() {
+                return Err(H3Error::CellInvalid);
+            }
+            if !h.get_resolution()._isValidChildRes(&res) {
+                return Err(H3Error::ResolutionMismatch);
+            }
+
+            let count = match h.as_pentagon() {
+                Some(pentagon) => pentagon.children_count(res),
+                None => h.maxH3ToChildrenSize(res),
+            };
+            budget.charge(count as usize)?;
+            h3Set.extend(h.h3ToChildren(res));
+        }
+
+        Ok(h3Set)
+    }
+
+    /// Sorts a compacted set, using the uncompacted input for the order in
+    /// which cells were first seen.
+    fn orderCompacted(
+        mut compacted: Vec<H3Index>,
+        h3Set: &[H3Index],
+        order: CompactOrder,
+    ) -> Vec<H3Index> {
+        match order {
+            CompactOrder::Ascending => compacted.sort(),
+            CompactOrder::FirstSeen => {
+                let mut resolutions: Vec<Resolution> = Vec::new();
+                for h in compacted.iter() {
+                    if !resolutions.contains(&h.get_resolution()) {
+                        resolutions.push(h.get_resolution());
+                    }
+                }
+
+                // Position of the first input cell each output cell covers.
+                let mut firstSeen: HashMap<H3Index, Option<usize>> =
+                    compacted.iter().map(|&h| (h, None)).collect();
+                for (i, h) in h3Set.iter().enumerate() {
+                    for &res in resolutions.iter() {
+                        if let Ok(parent) = h.h3ToParent(res) {
+                            if let Some(seen @ None) = firstSeen.get_mut(&parent) {
+                                *seen = Some(i);
+                            }
+                        }
+                    }
+                }
+
+                compacted.sort_by_key(|h| (firstSeen[h].unwrap_or(usize::MAX), u64::from(*h)));
+            }
+        }
+        compacted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{basecell::BaseCell, Direction, GeoCoord};
+
+    /// A parent's children, a cell on its own, and a second parent's
+    /// children, along with the compacted form of the three.
+    fn setup() -> (Vec<H3Index>, Vec<H3Index>) {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let parents = sf.geoToH3(Resolution::R6).kRing(1);
+        let (a, b) = (parents[1], parents[4]);
+        let lone = parents[2].h3ToChildren(Resolution::R7)[3];
+
+        let mut h3Set = b.h3ToChildren(Resolution::R7);
+        h3Set.reverse();
+        h3Set.insert(2, lone);
+        h3Set.extend(a.h3ToChildren(Resolution::R7));
+
+        (h3Set, vec![a, lone, b])
+    }
+
+    #[test]
+    fn orderCompacted_ascending() {
+        let (h3Set, compacted) = setup();
+        let mut expected = compacted.clone();
+        expected.sort_by_key(|&h| u64::from(h));
+
+        let mut shuffled = compacted.clone();
+        for _ in 0..shuffled.len() {
+            shuffled.rotate_left(1);
+            assert_eq!(
+                H3Index::orderCompacted(shuffled.clone(), &h3Set, CompactOrder::Ascending),
+                expected,
+                "same order whatever the input order"
+            );
+        }
+        assert_eq!(CompactOrder::default(), CompactOrder::Ascending, "default");
+    }
+
+    #[test]
+    fn orderCompacted_firstSeen() {
+        let (h3Set, compacted) = setup();
+        let (a, lone, b) = (compacted[0], compacted[1], compacted[2]);
+
+        assert_eq!(
+            H3Index::orderCompacted(compacted.clone(), &h3Set, CompactOrder::FirstSeen),
+            vec![b, lone, a],
+            "parents take the place of their first child"
+        );
+
+        let mut reversed = h3Set.clone();
+        reversed.reverse();
+        assert_eq!(
+            H3Index::orderCompacted(compacted.clone(), &reversed, CompactOrder::FirstSeen),
+            vec![a, b, lone],
+            "follows the input"
+        );
+
+        assert_eq!(
+            H3Index::orderCompacted(compacted.clone(), &h3Set[..1], CompactOrder::FirstSeen),
+            {
+                let mut rest = [a, lone];
+                rest.sort_by_key(|&h| u64::from(h));
+                vec![b, rest[0], rest[1]]
+            },
+            "unseen cells go last, ascending"
+        );
+    }
+
+    #[test]
+    fn compact_resolution0() {
+        let mut res0 = Resolution::R0.getPentagonIndexes().to_vec();
+        res0.reverse();
+        let mut expected = res0.clone();
+        expected.sort_by_key(|&h| u64::from(h));
+
+        assert_eq!(H3Index::compact(&res0), Ok(expected), "sorted");
+        assert_eq!(
+            H3Index::compact_ordered(&res0, CompactOrder::FirstSeen),
+            Ok(res0.clone()),
+            "input order"
+        );
+        assert_eq!(H3Index::compact(&[]), Ok(vec![]), "empty");
+    }
+
+    /// Every cell of a set at a resolution, as a sorted list.
+    fn uncompacted(compacted: &[H3Index], res: Resolution) -> Vec<H3Index> {
+        let mut cells: Vec<H3Index> = compacted.iter().flat_map(|h| h.h3ToChildren(res)).collect();
+        cells.sort_by_key(|&h| u64::from(h));
+        cells
+    }
+
+    const SUNNYVALE: u64 = 0x89283470c27ffff;
+
+    #[test]
+    fn compact_roundtrip() {
+        let sunnyvale = H3Index::from(SUNNYVALE);
+        let mut disk = sunnyvale.kRing(9);
+        assert_eq!(disk.len(), 271, "k ring size");
+
+        let compacted = H3Index::compact(&disk).unwrap();
+        assert_eq!(compacted.len(), 73, "compacted count");
+        disk.sort_by_key(|&h| u64::from(h));
+        assert_eq!(uncompacted(&compacted, Resolution::R9), disk, "round trip");
+    }
+
+    #[test]
+    fn compact_allChildren() {
+        for &parent in [
+            H3Index::from(SUNNYVALE).h3ToParent(Resolution::R5).unwrap(),
+            Resolution::R2.getPentagonIndexes()[5],
+            H3Index::getRes0Indexes()[8],
+        ]
+        .iter()
+        {
+            let children = parent.h3ToChildren(parent.get_resolution() + 3);
+            assert_eq!(H3Index::compact(&children), Ok(vec![parent]), "{}", parent);
+        }
+
+        let mut res0: Vec<H3Index> = H3Index::getRes0Indexes()
+            .iter()
+            .flat_map(|h| h.h3ToChildren(Resolution::R2))
+            .collect();
+        res0.reverse();
+        assert_eq!(
+            H3Index::compact(&res0),
+            Ok(H3Index::getRes0Indexes().to_vec()),
+            "whole earth"
+        );
+    }
+
+    #[test]
+    fn compact_uncompactable() {
+        let uncompactable = [0x89283470803ffff, 0x8928347081bffff, 0x8928347080bffff]
+            .iter()
+            .map(|&h| H3Index::from(h))
+            .collect::<Vec<_>>();
+        let mut expected = uncompactable.clone();
+        expected.sort_by_key(|&h| u64::from(h));
+        assert_eq!(H3Index::compact(&uncompactable), Ok(expected.clone()));
+
+        let mut withNull = uncompactable.clone();
+        withNull.insert(2, H3Index::H3_NULL);
+        assert_eq!(H3Index::compact(&withNull), Ok(expected), "null skipped");
+
+        // The center child of every base cell: none of them has siblings.
+        let disparate: Vec<H3Index> = H3Index::getRes0Indexes()
+            .iter()
+            .map(|h| h.h3ToChildren(Resolution::R1)[0])
+            .collect();
+        assert_eq!(
+            H3Index::compact(&disparate).map(|c| c.len()),
+            Ok(disparate.len()),
+            "disparate"
+        );
+
+        // A pentagon's children without the center one.
+        let pentagon = Resolution::R3.getPentagonIndexes()[0];
+        let partial = pentagon.h3ToChildren(Resolution::R4)[1..].to_vec();
+        assert_eq!(H3Index::compact(&partial), Ok(partial.clone()), "pentagon");
+    }
+
+    #[test]
+    fn compact_duplicate() {
+        let sunnyvale = H3Index::from(SUNNYVALE);
+        assert_eq!(
+            H3Index::compact(&[sunnyvale; 10]),
+            Err(H3Error::DuplicateInput),
+            "repeated cell"
+        );
+
+        // Seven cells under one parent, one of them twice: the count of
+        // children would make it look complete.
+        let parent =
+            H3Index::from_components(BaseCell::new(0).unwrap(), &[Direction::J_AXES_DIGIT; 10])
+                .unwrap();
+        let mut children = parent.h3ToChildren(Resolution::R11);
+        let last = children.len() - 1;
+        children[last] = children[0];
+        assert_eq!(
+            H3Index::compact(&children),
+            Err(H3Error::DuplicateInput),
+            "duplicate among siblings"
+        );
+
+        let pentagon =
+            H3Index::from_components(BaseCell::new(4).unwrap(), &[Direction::CENTER_DIGIT; 10])
+                .unwrap();
+        let mut children = pentagon.h3ToChildren(Resolution::R11);
+        children.push(children[3]);
+        assert_eq!(
+            H3Index::compact(&children),
+            Err(H3Error::DuplicateInput),
+            "pentagon children"
+        );
+
+        let res0 = H3Index::getRes0Indexes()[3];
+        assert_eq!(
+            H3Index::compact(&[res0, res0]),
+            Err(H3Error::DuplicateInput),
+            "resolution 0"
+        );
+    }
+
+    #[test]
+    fn compact_invalid() {
+        let sunnyvale = H3Index::from(SUNNYVALE);
+        let parent = sunnyvale.h3ToParent(Resolution::R8).unwrap();
+        assert_eq!(
+            H3Index::compact(&[sunnyvale, parent]),
+            Err(H3Error::ResolutionMismatch),
+            "mixed resolutions"
+        );
+
+        let mut reserved = sunnyvale;
+        reserved.set_reserved_bits(1);
+        assert_eq!(
+            H3Index::compact(&[sunnyvale, reserved]),
+            Err(H3Error::CellInvalid),
+            "reserved bits set"
+        );
+        assert_eq!(
+            H3Index::compact(&[H3Index::from(u64::MAX)]),
+            Err(H3Error::CellInvalid),
+            "not a cell"
+        );
+        assert_eq!(H3Index::compact(&[H3Index::H3_NULL]), Ok(vec![]), "null");
+    }
+
+    #[test]
+    fn uncompact_roundtrip() {
+        let sunnyvale = H3Index::from(SUNNYVALE);
+        let disk = sunnyvale.kRing(9);
+        let compacted = H3Index::compact(&disk).unwrap();
+        let mut expanded = H3Index::uncompact(&compacted, Resolution::R9).unwrap();
+        assert_eq!(expanded.len(), 271, "uncompacted count");
+        expanded.sort_by_key(|&h| u64::from(h));
+        assert_eq!(expanded, uncompacted(&compacted, Resolution::R9), "cells");
+
+        let res0 = H3Index::getRes0Indexes();
+        let children = H3Index::uncompact(&res0, Resolution::R1).unwrap();
+        assert_eq!(children.len(), 12 * 6 + 110 * 7, "whole earth at res 1");
+        assert_eq!(H3Index::compact(&children), Ok(res0.to_vec()), "and back");
+
+        // Uncompacting to the same resolution copies the cells.
+        assert_eq!(
+            H3Index::uncompact(&disk, Resolution::R9),
+            Ok(disk.clone()),
+            "same resolution"
+        );
+    }
+
+    #[test]
+    fn uncompact_someHexagonAndPentagon() {
+        let origin =
+            H3Index::from_components(BaseCell::new(5).unwrap(), &[Direction::CENTER_DIGIT; 1])
+                .unwrap();
+        let children = H3Index::uncompact(&[origin], Resolution::R2).unwrap();
+        assert_eq!(children.len(), 7, "hexagon children");
+        assert_eq!(H3Index::compact(&children), Ok(vec![origin]), "hexagon");
+
+        let pentagon =
+            H3Index::from_components(BaseCell::new(4).unwrap(), &[Direction::CENTER_DIGIT; 1])
+                .unwrap();
+        let children = H3Index::uncompact(&[pentagon], Resolution::R2).unwrap();
+        assert_eq!(children.len(), 6, "pentagon children");
+        assert!(children.iter().all(|h| h.is_valid()), "no deleted child");
+        assert_eq!(H3Index::compact(&children), Ok(vec![pentagon]), "pentagon");
+
+        let children = H3Index::uncompact(&[pentagon], Resolution::R4).unwrap();
+        assert_eq!(children.len(), 1 + 5 * (343 - 1) / 6, "three levels down");
+        assert_eq!(H3Index::compact(&children), Ok(vec![pentagon]), "and back");
+    }
+
+    #[test]
+    fn uncompact_nullAndErrors() {
+        let uncompactableWithZero = [0x89283470803ffff, 0x8928347081bffff, 0, 0x8928347080bffff]
+            .iter()
+            .map(|&h| H3Index::from(h))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            H3Index::uncompact(&uncompactableWithZero, Resolution::R10).map(|c| c.len()),
+            Ok(21),
+            "null skipped"
+        );
+        assert_eq!(
+            H3Index::uncompact(&[H3Index::H3_NULL], Resolution::R2),
+            Ok(vec![]),
+            "only null"
+        );
+        assert_eq!(H3Index::uncompact(&[], Resolution::R0), Ok(vec![]), "empty");
+
+        let someHexagons: Vec<H3Index> = (0..3)
+            .map(|bc| {
+                H3Index::from_components(BaseCell::new(bc).unwrap(), &[Direction::CENTER_DIGIT; 5])
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            H3Index::uncompact(&someHexagons, Resolution::R4),
+            Err(H3Error::ResolutionMismatch),
+            "coarser resolution"
+        );
+        assert_eq!(
+            H3Index::uncompact(&[H3Index::from(u64::MAX)], Resolution::R15),
+            Err(H3Error::CellInvalid),
+            "not a cell"
+        );
+    }
+
+    #[test]
+    fn uncompact_budgeted() {
+        let pentagon = Resolution::R1.getPentagonIndexes()[0];
+        let hexagon = H3Index::from(SUNNYVALE).h3ToParent(Resolution::R8).unwrap();
+        let set = [pentagon.h3ToChildren(Resolution::R7)[0], hexagon];
+
+        // 6 + 5 * 7 children of the pentagon at res 9, and 7 of the hexagon.
+        let mut budget = Budget::max_cells(48);
+        let cells = H3Index::uncompact_budgeted(&set, Resolution::R9, &mut budget).unwrap();
+        assert_eq!(cells.len(), 48, "within budget");
+        assert_eq!(budget.spent(), 48, "exact count");
+        assert_eq!(
+            H3Index::uncompact_budgeted(&set, Resolution::R9, &mut Budget::max_cells(47)),
+            Err(H3Error::Cancelled),
+            "over budget"
+        );
+    }
+
+    #[test]
+    fn compact_budgeted() {
+        let res0 = Resolution::R0.getPentagonIndexes();
+        assert_eq!(
+            H3Index::compact_budgeted(&res0, CompactOrder::Ascending, &mut Budget::max_cells(12))
+                .map(|c| c.len()),
+            Ok(12),
+            "within budget"
+        );
+        assert_eq!(
+            H3Index::compact_budgeted(&res0, CompactOrder::Ascending, &mut Budget::max_cells(11)),
+            Err(H3Error::Cancelled),
+            "over budget"
+        );
+
+        // Passes over 49 cells, their 7 parents and the grandparent.
+        let children = H3Index::from(SUNNYVALE)
+            .h3ToParent(Resolution::R7)
+            .unwrap()
+            .h3ToChildren(Resolution::R9);
+        assert_eq!(
+            H3Index::compact_budgeted(
+                &children,
+                CompactOrder::Ascending,
+                &mut Budget::max_cells(57)
+            )
+            .map(|c| c.len()),
+            Ok(1),
+            "every pass within budget"
+        );
+        assert_eq!(
+            H3Index::compact_budgeted(
+                &children,
+                CompactOrder::Ascending,
+                &mut Budget::max_cells(56)
+            ),
+            Err(H3Error::Cancelled),
+            "last pass over budget"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+
+use crate::H3Index;
+
+/// Grows a disk around an origin one ring at a time, for searches that stop
+/// as soon as some ring holds what they look for.
+///
+/// Each `expand` only visits the neighbors of the previous ring, so reaching
+/// distance k costs about as much as one `kRing(k)`, rather than the sum of
+/// `kRing(1)` through `kRing(k)` that recomputing each disk would. The visited
+/// set is kept, so rings stay correct around pentagons.
+#[derive(Clone, Debug)]
+pub struct RingExpander {
+    k: u32,
+    ring: Vec<H3Index>,
+    visited: HashSet<H3Index>,
+    neighbors: Vec<H3Index>,
+}
+
+impl RingExpander {
+    /// Starts at ring 0, which is the origin alone; empty if the origin is not
+    /// a valid cell.
+    pub fn new(origin: H3Index) -> Self {
+        let ring = if origin.is_valid() {
+            vec![origin]
+        } else {
+            Vec::new()
+        };
+        RingExpander {
+            k: 0,
+            visited: ring.iter().copied().collect(),
+            ring,
+            neighbors: Vec::new(),
+        }
+    }
+
+    /// Distance of the current ring from the origin.
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    /// Cells of the current ring, in no particular order.
+    pub fn ring(&self) -> &[H3Index] {
+        &self.ring
+    }
+
+    /// Whether a cell is within distance k of the origin.
+    pub fn contains(&self, h: &H3Index) -> bool {
+        self.visited.contains(h)
+    }
+
+    /// Number of cells within distance k of the origin.
+    pub fn disk_len(&self) -> usize {
+        self.visited.len()
+    }
+
+    /// Moves out to the next ring and returns it.
+    pub fn expand(&mut self) -> &[H3Index] {
+        let mut next = Vec::with_capacity(self.ring.len() + 6);
+        for h in self.ring.iter() {
+            h.kRingInto(1, &mut self.neighbors);
+            for &neighbor in self.neighbors.iter() {
+                if self.visited.insert(neighbor) {
+                    next.push(neighbor);
+                }
+            }
+        }
+
+        self.k += 1;
+        self.ring = next;
+        &self.ring
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeoCoord, Resolution};
+
+    fn assertRings(origin: H3Index, maxK: u32) {
+        let mut expander = RingExpander::new(origin);
+        assert_eq!(expander.ring(), &[origin], "ring 0");
+
+        for k in 1..=maxK {
+            let mut ring: Vec<u64> = expander.expand().iter().map(|&h| h.into()).collect();
+            ring.sort_unstable();
+            let mut expected: Vec<u64> = origin
+                .grid_ring(k)
+                .unwrap()
+                .iter()
+                .map(|&h| h.into())
+                .collect();
+            expected.sort_unstable();
+
+            assert_eq!(expander.k(), k, "k");
+            assert_eq!(ring, expected, "ring {} of {}", k, origin);
+            assert_eq!(expander.disk_len(), origin.kRing(k).len(), "disk {}", k);
+        }
+    }
+
+    #[test]
+    fn ringExpander_hexagon() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        assertRings(sf.geoToH3(Resolution::R9), 6);
+    }
+
+    #[test]
+    fn ringExpander_pentagon() {
+        for pentagon in Resolution::R2.getPentagonIndexes().iter() {
+            assertRings(*pentagon, 4);
+        }
+    }
+
+    #[test]
+    fn ringExpander_search() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+        let target = origin.grid_ring(3).unwrap()[5];
+
+        let mut expander = RingExpander::new(origin);
+        while !expander.contains(&target) {
+            expander.expand();
+        }
+        assert_eq!(expander.k(), 3, "found at its distance");
+        assert!(expander.ring().contains(&target), "in the last ring");
+    }
+
+    #[test]
+    fn ringExpander_invalid() {
+        let mut expander = RingExpander::new(H3Index::H3_NULL);
+        assert!(expander.ring().is_empty(), "no ring 0");
+        assert!(expander.expand().is_empty(), "no ring 1");
+        assert_eq!(expander.disk_len(), 0, "empty disk");
+    }
+}
@@ -0,0 +1,136 @@
+use super::H3Index;
+
+/// Most icosahedron faces a cell can cross: a pentagon always crosses 5.
+const MAX_FACES: usize = 5;
+
+/// The icosahedron faces a cell crosses, from `h3GetFaces`: at most 5,
+/// kept inline in ascending order, so building one never touches the heap.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FaceSet {
+    len: u8,
+    faces: [i32; MAX_FACES],
+}
+
+impl FaceSet {
+    /// Adds a face unless it is already there, keeping the faces sorted.
+    /// Faces past the fifth are dropped; no cell crosses more.
+    pub(crate) fn insert(&mut self, face: i32) {
+        let len = self.len as usize;
+        if let Err(at) = self.faces[..len].binary_search(&face) {
+            if len < MAX_FACES {
+                self.faces[at..=len].rotate_right(1);
+                self.faces[at] = face;
+                self.len += 1;
+            }
+        }
+    }
+
+    /// The faces, in ascending order.
+    pub fn as_slice(&self) -> &[i32] {
+        &self.faces[..self.len as usize]
+    }
+
+    /// Iterates over the faces in ascending order.
+    pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'_, i32>> {
+        self.as_slice().iter().copied()
+    }
+
+    /// Number of faces.
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Whether there are no faces, as for an invalid index.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the cell crosses `face`.
+    pub fn contains(&self, face: &i32) -> bool {
+        self.as_slice().binary_search(face).is_ok()
+    }
+}
+
+impl IntoIterator for FaceSet {
+    type Item = i32;
+    type IntoIter = std::iter::Take<std::array::IntoIter<i32, MAX_FACES>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self.faces).take(self.len as usize)
+    }
+}
+
+impl<'a> IntoIterator for &'a FaceSet {
+    type Item = i32;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, i32>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl H3Index {
+    /**
+     * Returns the most icosahedron faces a cell of this shape may cross, to
+     * size buffers for `h3GetFaces`.
+     *
+     * @return 5 for a pentagon, which always crosses 5, and 2 for a hexagon,
+     * which crosses 1 or 2
+     */
+    pub fn max_face_count(&self) -> usize {
+        if self.is_pentagon() {
+            MAX_FACES
+        } else {
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Resolution;
+
+    #[test]
+    fn faceSet_insert() {
+        let mut faces = FaceSet::default();
+        assert!(faces.is_empty(), "empty");
+        for &face in [7, 3, 7, 19, 0, 3].iter() {
+            faces.insert(face);
+        }
+        assert_eq!(faces.as_slice(), &[0, 3, 7, 19], "sorted, no duplicates");
+        assert!(faces.contains(&19) && !faces.contains(&4), "contains");
+        assert_eq!(faces.into_iter().collect::<Vec<_>>(), vec![0, 3, 7, 19]);
+
+        faces.insert(12);
+        faces.insert(1);
+        assert_eq!(faces.len(), 5, "full");
+        assert_eq!(faces.iter().collect::<Vec<_>>(), vec![0, 3, 7, 12, 19]);
+    }
+
+    #[test]
+    fn maxFaceCount() {
+        for &res in [
+            Resolution::R0,
+            Resolution::R1,
+            Resolution::R2,
+            Resolution::R3,
+        ]
+        .iter()
+        {
+            let cells = H3Index::getRes0Indexes()
+                .iter()
+                .flat_map(|h| h.h3ToChildren(res))
+                .collect::<Vec<_>>();
+            for h in cells {
+                let faces = h.h3GetFaces();
+                assert!(!faces.is_empty(), "{} crosses a face", h);
+                assert!(faces.len() <= h.max_face_count(), "{} within max", h);
+                if h.is_pentagon() {
+                    assert_eq!(faces.len(), 5, "{} pentagon", h);
+                }
+                assert_eq!(h.maxFaceCount() as usize, h.max_face_count(), "C name");
+            }
+        }
+    }
+}
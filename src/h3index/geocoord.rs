@@ -1,8 +1,12 @@
 use crate::{
-    constants::{EARTH_RADIUS_KM, NUM_HEX_VERTS, NUM_PENT_VERTS},
+    basecell::BaseCell,
+    constants::{M_PI, M_PI_2, NUM_HEX_VERTS, NUM_PENT_VERTS},
     faceijk::FaceIJK,
-    geopolygon::GeoBoundary,
-    GeoCoord, H3Index, Resolution,
+    geocoord::{_posAngleRads, radsToDegs},
+    geopolygon::{GeoBoundary, Geofence},
+    vec2d::Overage,
+    vec3d::Vec3d,
+    BBox, Direction, GeoCoord, H3Error, H3Index, Resolution, SphereModel,
 };
 
 impl H3Index {
@@ -34,7 +38,7 @@ impl H3Index {
 
     /// Area of H3 cell in kilometers^2.
     pub fn cellAreaKm2(&self) -> f64 {
-        self.cellAreaRads2() * EARTH_RADIUS_KM * EARTH_RADIUS_KM
+        SphereModel::EARTH.cell_area_km2(*self)
     }
 
     /// Area of H3 cell in meters^2.
@@ -80,70 +84,77 @@ impl H3Index {
     /**
      * Convert an H3Index to a FaceIJK address.
      * @param h The H3Index.
-     * @param fijk The corresponding FaceIJK address.
+     * @return The corresponding FaceIJK address, or CellInvalid if the base
+     *         cell is out of range
      */
-    pub(crate) fn _h3ToFaceIjk(&self /* h */) -> FaceIJK {
+    pub(crate) fn _h3ToFaceIjk(&self /* h */) -> Result<FaceIJK, H3Error> {
+        // Base cells past the last one fit in the index bits, but have no
+        // home face; a default FaceIJK would decode to a plausible but
+        // wrong location.
         let baseCell = self.get_base_cell();
-        todo!()
-        /*
-        if (baseCell < 0 || baseCell >= NUM_BASE_CELLS) {  // LCOV_EXCL_BR_LINE
-            // Base cells less than zero can not be represented in an index
-            // TODO: Indicate an error to the caller
-            // To prevent reading uninitialized memory, we zero the output.
-            fijk->face = 0;
-            fijk->coord.i = fijk->coord.j = fijk->coord.k = 0;
-            return;
-        }
+        let homeFijk = baseCell._baseCellToFaceIjk()?;
+
         // adjust for the pentagonal missing sequence; all of sub-sequence 5 needs
         // to be adjusted (and some of sub-sequence 4 below)
-        if (_isBaseCellPentagon(baseCell) && _h3LeadingNonZeroDigit(h) == 5)
-            h = _h3Rotate60cw(h);
+        let mut h = *self;
+        if baseCell._isBaseCellPentagon() && h._h3LeadingNonZeroDigit() == Direction::IK_AXES_DIGIT
+        {
+            h = h._h3Rotate60cw();
+        }
 
         // start with the "home" face and ijk+ coordinates for the base cell of c
-        *fijk = baseCellData[baseCell].homeFijk;
-        if (!_h3ToFaceIjkWithInitializedFijk(h, fijk))
-            return;  // no overage is possible; h lies on this face
+        let mut fijk = homeFijk;
+        if !h._h3ToFaceIjkWithInitializedFijk(&mut fijk) {
+            // no overage is possible; h lies on this face
+            return Ok(fijk);
+        }
 
         // if we're here we have the potential for an "overage"; i.e., it is
         // possible that c lies on an adjacent face
 
-        CoordIJK origIJK = fijk->coord;
+        let origIJK = fijk.coord;
 
         // if we're in Class III, drop into the next finer Class II grid
-        int res = H3_GET_RESOLUTION(h);
-        if (isResClassIII(res)) {
+        let mut res = h.get_resolution() as usize;
+        if res % 2 == 1 {
             // Class III
-            _downAp7r(&fijk->coord);
-            res++;
+            fijk.coord._downAp7r();
+            res += 1;
         }
 
         // adjust for overage if needed
         // a pentagon base cell with a leading 4 digit requires special handling
-        int pentLeading4 =
-            (_isBaseCellPentagon(baseCell) && _h3LeadingNonZeroDigit(h) == 4);
-        if (_adjustOverageClassII(fijk, res, pentLeading4, 0) != NO_OVERAGE) {
+        let pentLeading4 =
+            baseCell._isBaseCellPentagon() && h._h3LeadingNonZeroDigit() == Direction::I_AXES_DIGIT;
+        if fijk._adjustOverageClassII(res, pentLeading4, false) != Overage::NO_OVERAGE {
             // if the base cell is a pentagon we have the potential for secondary
             // overages
-            if (_isBaseCellPentagon(baseCell)) {
-                while (_adjustOverageClassII(fijk, res, 0, 0) != NO_OVERAGE)
-                    continue;
+            if baseCell._isBaseCellPentagon() {
+                while fijk._adjustOverageClassII(res, false, false) != Overage::NO_OVERAGE {}
             }
 
-            if (res != H3_GET_RESOLUTION(h)) _upAp7r(&fijk->coord);
-        } else if (res != H3_GET_RESOLUTION(h)) {
-            fijk->coord = origIJK;
+            if res != h.get_resolution() as usize {
+                fijk.coord._upAp7r();
+            }
+        } else if res != h.get_resolution() as usize {
+            fijk.coord = origIJK;
         }
-        */
+
+        Ok(fijk)
     }
 
     /**
      * Determines the cell boundary in spherical coordinates for an H3 index.
      *
      * @param h3 The H3 index.
-     * @param gb The boundary of the H3 cell in spherical coordinates.
+     * @return The boundary of the H3 cell in spherical coordinates; empty if
+     *         the base cell is out of range
      */
     pub fn h3ToGeoBoundary(&self) -> GeoBoundary {
-        let fijk: FaceIJK = self._h3ToFaceIjk();
+        let fijk: FaceIJK = match self._h3ToFaceIjk() {
+            Ok(fijk) => fijk,
+            Err(_) => return GeoBoundary::default(),
+        };
         let res = self.get_resolution();
         if self.is_pentagon() {
             fijk._faceIjkPentToGeoBoundary(res, 0, NUM_PENT_VERTS as i32)
@@ -152,6 +163,77 @@ impl H3Index {
         }
     }
 
+    /**
+     * Whether the cell boundary crosses an icosahedron face edge, i.e. has
+     * distortion vertices in addition to the cell's own corners. Only Class
+     * III cells can have these; planar approximations of such cells, which
+     * assume straight edges between corners, are poor.
+     *
+     * @return Whether the boundary has distortion vertices
+     */
+    pub fn crosses_face_edge(&self) -> bool {
+        if !self.h3IsResClassIII() {
+            return false;
+        }
+
+        let corners = if self.is_pentagon() {
+            NUM_PENT_VERTS
+        } else {
+            NUM_HEX_VERTS as usize
+        };
+        self.h3ToGeoBoundary().numVerts > corners
+    }
+
+    /**
+     * Orientation of the cell relative to north: the azimuth from the cell
+     * center to its first boundary vertex, clockwise from north. Cells on the
+     * same icosahedron face share their vertex numbering, so neighbors have
+     * nearly the same orientation, and icons or labels rotated by it line up
+     * with the grid.
+     *
+     * @return Azimuth in degrees, in [0, 360); NaN if the base cell is out of
+     *         range
+     */
+    pub fn orientation_deg(&self) -> f64 {
+        let gb = self.h3ToGeoBoundary();
+        if gb.numVerts == 0 {
+            return f64::NAN;
+        }
+
+        let az = GeoCoord::_geoAzimuthRads(&self.h3ToGeo(), &gb.verts[0]);
+        radsToDegs(_posAngleRads(az)) % 360.0
+    }
+
+    /**
+     * The cell's bounding box, in radians. Cell edges are great circle arcs,
+     * which bow toward the nearer pole, so the box reaches past the vertexes
+     * where an edge does; a cell around a pole spans every longitude up to
+     * the pole.
+     *
+     * @return The box, with east less than west for a cell crossing the
+     *         antimeridian; all zeros if the base cell is out of range
+     */
+    pub fn cell_bbox(&self) -> BBox {
+        let gb = self.h3ToGeoBoundary();
+        let fence = Geofence {
+            verts: gb.verts[..gb.numVerts].to_vec(),
+        };
+        let mut bbox = fence.bboxFromGeofence();
+        for (a, b) in fence.edges() {
+            let (south, north) = arcLatRange(&a._geoToVec3d(), &b._geoToVec3d());
+            bbox.south = bbox.south.min(south);
+            bbox.north = bbox.north.max(north);
+        }
+
+        let res = self.get_resolution();
+        for &pole in [M_PI_2, -M_PI_2].iter() {
+            if gb.numVerts > 0 && GeoCoord::from_rads(pole, 0.0).geoToH3(res) == *self {
+                bbox = BBox::new(bbox.north.max(pole), bbox.south.min(pole), M_PI, -M_PI);
+            }
+        }
+        bbox
+    }
+
     /**
      * Returns the max number of possible icosahedron faces an H3 index
      * may intersect.
@@ -159,11 +241,221 @@ impl H3Index {
      * @return int count of faces
      */
     pub fn maxFaceCount(&self) -> i32 {
-        // a pentagon always intersects 5 faces, a hexagon never intersects more than 2 (but may only intersect 1)
-        if self.is_pentagon() {
-            5
-        } else {
-            3
+        self.max_face_count() as i32
+    }
+}
+
+/**
+ * The range of latitudes along the shorter great arc between two points,
+ * which is wider than that of the endpoints if the arc passes the highest or
+ * lowest point of its great circle.
+ *
+ * @param a One end of the arc, on the unit sphere
+ * @param b The other end
+ * @return (south, north) in radians
+ */
+fn arcLatRange(a: &Vec3d, b: &Vec3d) -> (f64, f64) {
+    let latA = GeoCoord::_vec3dToGeo(a).lat;
+    let latB = GeoCoord::_vec3dToGeo(b).lat;
+    let mut range = (latA.min(latB), latA.max(latB));
+
+    let normal = a.cross(b);
+    let normSq = normal.dot(&normal);
+    if normSq == 0.0 {
+        return range;
+    }
+    // The highest point of the great circle is the pole's projection onto
+    // its plane; it is on the arc if it lies between the ends.
+    let pole = Vec3d::new(0.0, 0.0, 1.0);
+    let along = normal.scale(pole.dot(&normal) / normSq);
+    let top = Vec3d::new(pole.x - along.x, pole.y - along.y, pole.z - along.z);
+    if top.norm() == 0.0 {
+        return range;
+    }
+    let bottom = top.scale(-1.0);
+    for &extreme in [&top, &bottom].iter() {
+        if a.cross(extreme).dot(&normal) >= 0.0 && extreme.cross(b).dot(&normal) >= 0.0 {
+            let lat = GeoCoord::_vec3dToGeo(extreme).lat;
+            range = (range.0.min(lat), range.1.max(lat));
         }
     }
+    range
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn h3ToFaceIjkInvalidBaseCell() {
+        for bc in BaseCell::NUM_BASE_CELLS as i32..128 {
+            let mut h = H3Index::setH3Index(
+                Resolution::R5,
+                BaseCell::new_unchecked(0),
+                Direction::J_AXES_DIGIT,
+            );
+            h.set_base_cell(BaseCell::new_unchecked(bc));
+
+            assert_eq!(
+                h._h3ToFaceIjk().err(),
+                Some(H3Error::CellInvalid),
+                "no home face"
+            );
+
+            let center = h.h3ToGeo();
+            assert!(
+                center.lat.is_nan() && center.lon.is_nan(),
+                "no plausible center"
+            );
+            assert_eq!(h.h3ToGeoBoundary().numVerts, 0, "no boundary");
+            assert!(h.h3GetFaces().is_empty(), "no faces");
+            assert_eq!(h.cellToVertex(0), H3Index::H3_NULL, "no vertexes");
+        }
+    }
+
+    #[test]
+    fn orientationDeg() {
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+
+        for res in Resolution::RESOLUTIONS.iter() {
+            let h = sf.geoToH3(*res);
+            let orientation = h.orientation_deg();
+            assert!((0.0..360.0).contains(&orientation), "orientation in range");
+
+            // coarse cells are distorted by the projection, or span faces
+            if *res < Resolution::R4 {
+                continue;
+            }
+            for neighbor in h.kRing(1) {
+                let diff = (neighbor.orientation_deg() - orientation).abs();
+                let diff = diff.min(360.0 - diff);
+                assert!(diff < 1.0, "neighbors at {:?} are oriented alike", res);
+            }
+        }
+
+        let class2 = sf.geoToH3(Resolution::R8).orientation_deg();
+        let class3 = sf.geoToH3(Resolution::R9).orientation_deg();
+        let diff = ((class3 - class2) % 60.0 + 60.0) % 60.0;
+        assert!(
+            (diff - 19.1).abs() < 1.0 || (diff - 40.9).abs() < 1.0,
+            "class III grids are rotated about 19.1 degrees from class II"
+        );
+
+        let mut invalid = sf.geoToH3(Resolution::R5);
+        invalid.set_base_cell(BaseCell::new_unchecked(127));
+        assert!(invalid.orientation_deg().is_nan(), "invalid base cell");
+    }
+
+    #[test]
+    fn crossesFaceEdgeClassII() {
+        for h in H3Index::getRes0Indexes().iter() {
+            assert!(!h.crosses_face_edge(), "res 0 cells have no distortion");
+
+            for child in h.h3ToChildren(Resolution::R2) {
+                assert!(!child.crosses_face_edge(), "res 2 cells have no distortion");
+            }
+        }
+    }
+
+    #[test]
+    fn crossesFaceEdgeClassIII() {
+        for pentagon in Resolution::R1.getPentagonIndexes().iter() {
+            assert!(
+                pentagon.crosses_face_edge(),
+                "class III pentagons cross every face edge"
+            );
+        }
+
+        let mut crossing = 0;
+        let mut total = 0;
+        for h in H3Index::getRes0Indexes().iter() {
+            for child in h.h3ToChildren(Resolution::R1) {
+                let gb = child.h3ToGeoBoundary();
+                assert_eq!(
+                    child.crosses_face_edge(),
+                    gb.numVerts > NUM_HEX_VERTS as usize,
+                    "matches boundary vertex count"
+                );
+                if child.crosses_face_edge() {
+                    crossing += 1;
+                }
+                total += 1;
+            }
+        }
+        assert!(crossing > 0, "some res 1 cells cross face edges");
+        assert!(crossing < total, "some res 1 cells do not");
+    }
+
+    #[test]
+    fn cellBbox() {
+        let mut cells: Vec<H3Index> = H3Index::getRes0Indexes().to_vec();
+        cells.extend(H3Index::getRes0Indexes()[20].h3ToChildren(Resolution::R2));
+        let mut bowed = 0;
+        for h in cells {
+            let bbox = h.cell_bbox();
+            let inside = |g: &GeoCoord| {
+                let lonInside = if bbox.bboxIsTransmeridian() {
+                    g.lon >= bbox.west - 1e-12 || g.lon <= bbox.east + 1e-12
+                } else {
+                    g.lon >= bbox.west - 1e-12 && g.lon <= bbox.east + 1e-12
+                };
+                lonInside && g.lat >= bbox.south - 1e-12 && g.lat <= bbox.north + 1e-12
+            };
+            assert!(inside(&h.h3ToGeo()), "{} center", h);
+
+            // points along each edge, on the great circle between vertexes
+            let gb = h.h3ToGeoBoundary();
+            let verts = &gb.verts[..gb.numVerts];
+            for (v0, v1) in verts.iter().zip(verts.iter().cycle().skip(1)) {
+                let (a, b) = (v0._geoToVec3d(), v1._geoToVec3d());
+                for i in 0..=32 {
+                    let t = i as f64 / 32.0;
+                    let p = Vec3d::new(
+                        a.x + (b.x - a.x) * t,
+                        a.y + (b.y - a.y) * t,
+                        a.z + (b.z - a.z) * t,
+                    );
+                    let g = GeoCoord::_vec3dToGeo(&p);
+                    assert!(inside(&g), "{} edge point {:?} in {:?}", h, g, bbox);
+                }
+            }
+
+            let vertsNorth = verts.iter().map(|v| v.lat).fold(-M_PI_2, f64::max);
+            if bbox.north > vertsNorth + 1e-6 {
+                bowed += 1;
+            }
+        }
+        assert!(bowed > 0, "edges bow past the vertexes");
+
+        for &pole in [M_PI_2, -M_PI_2].iter() {
+            let h = GeoCoord::from_rads(pole, 0.0).geoToH3(Resolution::R1);
+            let bbox = h.cell_bbox();
+            assert_eq!(
+                (bbox.east, bbox.west),
+                (M_PI, -M_PI),
+                "{} every longitude",
+                h
+            );
+            assert!(
+                bbox.north == pole || bbox.south == pole,
+                "{} reaches the pole",
+                h
+            );
+        }
+
+        let transmeridian = GeoCoord::from_rads(0.0, M_PI).geoToH3(Resolution::R3);
+        assert!(
+            transmeridian.cell_bbox().bboxIsTransmeridian(),
+            "{} crosses the antimeridian",
+            transmeridian
+        );
+
+        let mut invalid = H3Index::getRes0Indexes()[0];
+        invalid.set_base_cell(BaseCell::new_unchecked(122));
+        assert_eq!(
+            invalid.cell_bbox(),
+            BBox::new(0.0, 0.0, 0.0, 0.0),
+            "invalid"
+        );
+    }
 }
@@ -0,0 +1,445 @@
+//! Interop with geohash-indexed data.
+//!
+//! A geohash names a latitude/longitude rectangle by interleaving longitude
+//! and latitude bisections, five bits per base32 character. These helpers map
+//! a geohash to the H3 cell of comparable size at its center, and an H3 cell
+//! to the geohashes whose rectangles overlap it.
+
+use super::H3Index;
+use crate::{
+    bbox::BBox,
+    constants::{EARTH_RADIUS_KM, M_2PI, M_PI, M_PI_2},
+    geocoord::degsToRads,
+    geopolygon::Geofence,
+    GeoCoord, H3Error, Resolution,
+};
+
+/// Characters of the geohash base32 alphabet, indexed by value.
+const GEOHASH_BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Longest geohash handled; at 12 characters a cell is a few centimeters across.
+pub const MAX_GEOHASH_PRECISION: usize = 12;
+
+/// Most geohashes `h3ToGeohashes` tests against a cell, about 1M; a coarse
+/// cell at a long precision spans far more.
+pub const MAX_GEOHASHES: u64 = 1 << 20;
+
+/// Value of a single geohash character, if it is part of the alphabet.
+fn geohashCharValue(c: u8) -> Option<u64> {
+    let c = c.to_ascii_lowercase();
+    GEOHASH_BASE32
+        .iter()
+        .position(|&b| b == c)
+        .map(|v| v as u64)
+}
+
+/// Number of (longitude, latitude) bits encoded by a geohash of the given length.
+fn geohashBits(precision: usize) -> (u32, u32) {
+    let bits = 5 * precision as u32;
+    (bits.div_ceil(2), bits / 2)
+}
+
+/// Width and height, in radians, of a geohash cell of the given length.
+fn geohashCellSize(precision: usize) -> (f64, f64) {
+    let (lonBits, latBits) = geohashBits(precision);
+    (
+        M_2PI / (1u64 << lonBits) as f64,
+        M_PI / (1u64 << latBits) as f64,
+    )
+}
+
+/**
+ * Decodes a geohash into the bounding box it names.
+ *
+ * @param geohash The geohash; case is ignored.
+ * @return The box in radians, or None if the geohash is empty, longer than
+ *         MAX_GEOHASH_PRECISION, or contains a character outside the alphabet.
+ */
+pub(crate) fn geohashToBBox(geohash: &str) -> Option<BBox> {
+    let precision = geohash.len();
+    if precision == 0 || precision > MAX_GEOHASH_PRECISION {
+        return None;
+    }
+
+    // De-interleave the bits; even bits (counting from the most significant)
+    // refine longitude and odd bits refine latitude.
+    let mut lonIdx = 0u64;
+    let mut latIdx = 0u64;
+    let mut isLon = true;
+    for c in geohash.bytes() {
+        let value = geohashCharValue(c)?;
+        for bit in (0..5).rev() {
+            let b = (value >> bit) & 1;
+            if isLon {
+                lonIdx = (lonIdx << 1) | b;
+            } else {
+                latIdx = (latIdx << 1) | b;
+            }
+            isLon = !isLon;
+        }
+    }
+
+    let (width, height) = geohashCellSize(precision);
+    let south = -M_PI_2 + latIdx as f64 * height;
+    let west = -M_PI + lonIdx as f64 * width;
+
+    Some(BBox::new(south + height, south, west + width, west))
+}
+
+/// Encodes the geohash cell at the given row and column of the geohash grid.
+fn geohashFromGrid(latIdx: u64, lonIdx: u64, precision: usize) -> String {
+    let (lonBits, latBits) = geohashBits(precision);
+    let mut lonBit = lonBits;
+    let mut latBit = latBits;
+
+    let mut geohash = String::with_capacity(precision);
+    let mut isLon = true;
+    for _ in 0..precision {
+        let mut value = 0;
+        for _ in 0..5 {
+            let b = if isLon {
+                lonBit -= 1;
+                (lonIdx >> lonBit) & 1
+            } else {
+                latBit -= 1;
+                (latIdx >> latBit) & 1
+            };
+            value = (value << 1) | b as usize;
+            isLon = !isLon;
+        }
+        geohash.push(GEOHASH_BASE32[value] as char);
+    }
+
+    geohash
+}
+
+/// Row and column of the geohash grid cell containing the given coordinate.
+fn geohashGridIndex(coord: &GeoCoord, precision: usize) -> (u64, u64) {
+    let (lonBits, latBits) = geohashBits(precision);
+    let (width, height) = geohashCellSize(precision);
+    let maxLat = (1u64 << latBits) - 1;
+    let maxLon = (1u64 << lonBits) - 1;
+
+    let latIdx = (((coord.lat + M_PI_2) / height).floor().max(0.0) as u64).min(maxLat);
+    let lonIdx = (((coord.lon + M_PI) / width).floor().max(0.0) as u64).min(maxLon);
+
+    (latIdx, lonIdx)
+}
+
+/**
+ * Encodes a coordinate as a geohash.
+ *
+ * @param coord The coordinate, in radians.
+ * @param precision Length of the geohash; clamped to 1..=MAX_GEOHASH_PRECISION.
+ * @return The geohash naming the cell that contains the coordinate.
+ */
+pub fn geohashFromGeo(coord: &GeoCoord, precision: usize) -> String {
+    let precision = precision.clamp(1, MAX_GEOHASH_PRECISION);
+    let (latIdx, lonIdx) = geohashGridIndex(coord, precision);
+    geohashFromGrid(latIdx, lonIdx, precision)
+}
+
+/// Shift a longitude onto the continuous range used for a transmeridian box.
+fn unwrapLon(lon: f64, isTransmeridian: bool) -> f64 {
+    if isTransmeridian && lon < 0.0 {
+        lon + M_2PI
+    } else {
+        lon
+    }
+}
+
+/// Whether two segments in the (lon, lat) plane touch or cross.
+fn segmentsIntersect(a1: (f64, f64), a2: (f64, f64), b1: (f64, f64), b2: (f64, f64)) -> bool {
+    fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    }
+
+    let d1 = cross(b1, b2, a1);
+    let d2 = cross(b1, b2, a2);
+    let d3 = cross(a1, a2, b1);
+    let d4 = cross(a1, a2, b2);
+
+    d1 * d2 <= 0.0 && d3 * d4 <= 0.0
+}
+
+impl H3Index {
+    /**
+     * Finds the H3 cell corresponding to a geohash. The resolution is the one
+     * whose average cell area is closest to the geohash cell's area, and the
+     * returned cell is the one containing the geohash cell's center.
+     *
+     * @param geohash The geohash; case is ignored.
+     * @return The cell, or None if the geohash is not valid.
+     */
+    pub fn geohashToH3(geohash: &str) -> Option<H3Index> {
+        let bbox = geohashToBBox(geohash)?;
+        let center = bbox.center();
+
+        let (width, height) = geohashCellSize(geohash.len());
+        let areaKm2 = width * height * center.lat.cos() * EARTH_RADIUS_KM * EARTH_RADIUS_KM;
+
+        let res = Resolution::RESOLUTIONS
+            .iter()
+            .min_by(|a, b| {
+                let da = (a.areaKm2().ln() - areaKm2.ln()).abs();
+                let db = (b.areaKm2().ln() - areaKm2.ln()).abs();
+                da.partial_cmp(&db).unwrap()
+            })
+            .copied()
+            .unwrap_or(Resolution::R0);
+
+        Some(center.geoToH3(res))
+    }
+
+    /**
+     * Produces the geohashes of the given length whose cells overlap this
+     * H3 cell. Together they cover the cell, and geohash cells that only touch
+     * the cell boundary may be included.
+     *
+     * For the cells containing a pole, every geohash in the latitude band
+     * reaching from the cell's lowest vertex to the pole is returned.
+     *
+     * @param precision Length of the geohashes; clamped to 1..=MAX_GEOHASH_PRECISION.
+     * @return The geohashes, sorted, or Domain if the cell's bounding box
+     *         spans more than MAX_GEOHASHES of them.
+     */
+    pub fn h3ToGeohashes(&self, precision: usize) -> Result<Vec<String>, H3Error> {
+        let precision = precision.clamp(1, MAX_GEOHASH_PRECISION);
+        let (lonBits, _) = geohashBits(precision);
+        let numCols = 1u64 << lonBits;
+        let (width, height) = geohashCellSize(precision);
+
+        let gb = self.h3ToGeoBoundary();
+        let geofence = Geofence {
+            verts: gb.verts[..gb.numVerts].to_vec(),
+        };
+        let res = self.get_resolution();
+
+        let containsPole = |lat: f64| GeoCoord::from_rads(lat, 0.0).geoToH3(res) == *self;
+        let bbox = if containsPole(M_PI_2) {
+            let south = geofence.verts.iter().fold(M_PI_2, |s, v| s.min(v.lat));
+            BBox::new(M_PI_2, south, M_PI, -M_PI)
+        } else if containsPole(-M_PI_2) {
+            let north = geofence.verts.iter().fold(-M_PI_2, |n, v| n.max(v.lat));
+            BBox::new(north, -M_PI_2, M_PI, -M_PI)
+        } else {
+            geofence.bboxFromGeofence()
+        };
+        let isPolar = bbox.east - bbox.west >= M_2PI;
+        let isTransmeridian = bbox.bboxIsTransmeridian();
+
+        // Grid rows and columns spanned by the bounding box; a transmeridian
+        // box wraps past the last column.
+        let (minRow, minCol) =
+            geohashGridIndex(&GeoCoord::from_rads(bbox.south, bbox.west), precision);
+        let (maxRow, mut maxCol) =
+            geohashGridIndex(&GeoCoord::from_rads(bbox.north, bbox.east), precision);
+        if isTransmeridian {
+            maxCol += numCols;
+        }
+        let spanned = (maxRow - minRow + 1).saturating_mul(maxCol - minCol + 1);
+        if spanned > MAX_GEOHASHES {
+            return Err(H3Error::Domain);
+        }
+
+        let verts: Vec<(f64, f64)> = geofence
+            .verts
+            .iter()
+            .map(|v| (unwrapLon(v.lon, isTransmeridian), v.lat))
+            .collect();
+
+        let mut geohashes = Vec::new();
+        for row in minRow..=maxRow {
+            for col in minCol..=maxCol {
+                let south = -M_PI_2 + row as f64 * height;
+                let north = south + height;
+                let west = -M_PI + (col % numCols) as f64 * width;
+                let west = unwrapLon(west, isTransmeridian);
+                let east = west + width;
+
+                if isPolar || Self::boxIntersectsLoop(&verts, north, south, east, west) {
+                    geohashes.push(geohashFromGrid(row, col % numCols, precision));
+                }
+            }
+        }
+
+        geohashes.sort();
+        geohashes.dedup();
+        Ok(geohashes)
+    }
+
+    /// Whether a lat/lon box overlaps a loop of (lon, lat) vertices in the plane.
+    fn boxIntersectsLoop(
+        verts: &[(f64, f64)],
+        north: f64,
+        south: f64,
+        east: f64,
+        west: f64,
+    ) -> bool {
+        // a loop vertex inside the box
+        if verts
+            .iter()
+            .any(|&(lon, lat)| lat >= south && lat <= north && lon >= west && lon <= east)
+        {
+            return true;
+        }
+
+        let corners = [(west, south), (east, south), (east, north), (west, north)];
+
+        // a box corner inside the loop, by ray casting
+        let (cx, cy) = corners[0];
+        let mut inside = false;
+        for (i, &(ax, ay)) in verts.iter().enumerate() {
+            let (bx, by) = verts[(i + 1) % verts.len()];
+            if (ay > cy) != (by > cy) && cx < ax + (cy - ay) * (bx - ax) / (by - ay) {
+                inside = !inside;
+            }
+        }
+        if inside {
+            return true;
+        }
+
+        // an edge of the loop crossing an edge of the box
+        verts.iter().enumerate().any(|(i, &a)| {
+            let b = verts[(i + 1) % verts.len()];
+            (0..4).any(|j| segmentsIntersect(a, b, corners[j], corners[(j + 1) % 4]))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Degrees;
+
+    fn geo(latDegs: f64, lonDegs: f64) -> GeoCoord {
+        GeoCoord::new(Degrees(latDegs), Degrees(lonDegs))
+    }
+
+    #[test]
+    fn geohashRoundTrip() {
+        let coord = geo(57.64911, 10.40744);
+        assert_eq!(geohashFromGeo(&coord, 11), "u4pruydqqvj", "known geohash");
+
+        let bbox = geohashToBBox("u4pruydqqvj").expect("valid geohash");
+        assert!(bbox.bboxContains(&coord), "decoded box contains the point");
+        assert_eq!(
+            geohashToBBox("U4PRUYDQQVJ"),
+            Some(bbox),
+            "geohash case is ignored"
+        );
+    }
+
+    #[test]
+    fn geohashInvalid() {
+        assert_eq!(H3Index::geohashToH3(""), None, "empty geohash");
+        assert_eq!(
+            H3Index::geohashToH3("9q8ya"),
+            None,
+            "a is not in the alphabet"
+        );
+        assert_eq!(
+            H3Index::geohashToH3("9q8yyk8ytpxr9"),
+            None,
+            "longer than the maximum precision"
+        );
+    }
+
+    #[test]
+    fn geohashToH3ComparableResolution() {
+        // precision 5 is about 4.9km by 4.9km at the equator
+        let h = H3Index::geohashToH3("9q8yy").expect("valid geohash");
+        assert_eq!(h.get_resolution(), Resolution::R6, "comparable resolution");
+
+        let center = geohashToBBox("9q8yy").unwrap().center();
+        assert_eq!(
+            h,
+            center.geoToH3(Resolution::R6),
+            "cell contains the center"
+        );
+
+        // each character shrinks the area by 32x, roughly 1.8 H3 resolutions
+        let mut lastRes = 0;
+        for geohash in ["9", "9q", "9q8", "9q8y", "9q8yy", "9q8yyk", "9q8yyk8"].iter() {
+            let res = H3Index::geohashToH3(geohash).unwrap().get_resolution() as i32;
+            assert!(res >= lastRes, "resolution grows with precision");
+            lastRes = res;
+        }
+        assert!(lastRes >= 10, "fine geohashes map to fine cells");
+    }
+
+    #[test]
+    fn h3ToGeohashesCoversCell() {
+        for &(res, precision) in [(5, 5), (7, 6), (9, 7), (3, 4)].iter() {
+            let h = geo(37.7749, -122.4194).geoToH3(res.into());
+            let geohashes = h.h3ToGeohashes(precision).unwrap();
+            assert!(!geohashes.is_empty(), "cell is covered");
+
+            // sample points just inside the boundary, plus the center
+            let center = h.h3ToGeo();
+            let gb = h.h3ToGeoBoundary();
+            let mut samples = vec![center];
+            for v in gb.verts[..gb.numVerts].iter() {
+                samples.push(GeoCoord::from_rads(
+                    center.lat + 0.99 * (v.lat - center.lat),
+                    center.lon + 0.99 * (v.lon - center.lon),
+                ));
+            }
+
+            for p in samples.iter() {
+                let geohash = geohashFromGeo(p, precision);
+                assert!(geohashes.contains(&geohash), "sample is covered");
+            }
+
+            // every geohash returned is near the cell
+            for geohash in geohashes.iter() {
+                let center = geohashToBBox(geohash).unwrap().center();
+                let (width, height) = geohashCellSize(precision);
+                let slack = width.max(height) + 2.0 * h._hexRadiusKm() / EARTH_RADIUS_KM;
+                assert!(
+                    GeoCoord::pointDistRads(&center, &h.h3ToGeo()) < slack,
+                    "geohash overlaps the cell"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn h3ToGeohashesTransmeridian() {
+        let h = geo(0.0, 180.0).geoToH3(Resolution::R4);
+        let geohashes = h.h3ToGeohashes(4).unwrap();
+
+        let east = geohashFromGeo(&geo(0.0, 179.999), 4);
+        let west = geohashFromGeo(&geo(0.0, -179.999), 4);
+        assert!(geohashes.contains(&east), "covers east of the antimeridian");
+        assert!(geohashes.contains(&west), "covers west of the antimeridian");
+        assert!(geohashes.len() < 100, "does not wrap around the globe");
+    }
+
+    #[test]
+    fn h3ToGeohashesPole() {
+        let h = geo(90.0, 0.0).geoToH3(Resolution::R0);
+        let geohashes = h.h3ToGeohashes(1).unwrap();
+
+        for top in ["b", "c", "f", "g", "u", "v", "y", "z"].iter() {
+            assert!(
+                geohashes.contains(&top.to_string()),
+                "polar cell covers the top row"
+            );
+        }
+    }
+
+    #[test]
+    fn h3ToGeohashesLimit() {
+        let h = geo(37.7749, -122.4194).geoToH3(Resolution::R0);
+        let geohashes = h.h3ToGeohashes(4).unwrap();
+        assert!(!geohashes.is_empty(), "coarse cell at a short precision");
+        assert_eq!(
+            h.h3ToGeohashes(MAX_GEOHASH_PRECISION),
+            Err(H3Error::Domain),
+            "trillions of geohashes"
+        );
+        let pole = geo(90.0, 0.0).geoToH3(Resolution::R0);
+        assert_eq!(pole.h3ToGeohashes(8), Err(H3Error::Domain), "polar band");
+    }
+}
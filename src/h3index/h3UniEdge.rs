@@ -10,37 +10,32 @@ impl H3Index {
      */
     pub fn getH3UnidirectionalEdgeBoundary(&self) -> GeoBoundary {
         // Get the origin and neighbor direction from the edge
-        let direction = self.get_reserved_bits();
-        let direction = Direction::from(direction as usize);
-
+        let direction = Direction::from(self.get_reserved_bits());
         let origin = self.getOriginH3IndexFromUnidirectionalEdge();
 
-        /*
-           // Get the start vertex for the edge
-           int startVertex = vertexNumForDirection(origin, direction);
-           if (startVertex == INVALID_VERTEX_NUM) {
-               // This is not actually an edge (i.e. no valid direction),
-               // so return no vertices.
-               gb->numVerts = 0;
-               return;
-           }
-
-           // Get the geo boundary for the appropriate vertexes of the origin. Note
-           // that while there are always 2 topological vertexes per edge, the
-           // resulting edge boundary may have an additional distortion vertex if it
-           // crosses an edge of the icosahedron.
-           FaceIJK fijk;
-           _h3ToFaceIjk(origin, &fijk);
-           int res = H3_GET_RESOLUTION(origin);
-           int isPentagon = H3_EXPORT(h3IsPentagon)(origin);
-
-           if (isPentagon) {
-               _faceIjkPentToGeoBoundary(&fijk, res, startVertex, 2, gb);
-           } else {
-               _faceIjkToGeoBoundary(&fijk, res, startVertex, 2, gb);
-           }
-        */
-        todo!()
+        // Get the start vertex for the edge
+        let startVertex = match origin.vertexNumForDirection(Some(direction)) {
+            Some(v) => v as i32,
+            // This is not actually an edge (i.e. no valid direction),
+            // so return no vertices.
+            None => return GeoBoundary::default(),
+        };
+
+        // Get the geo boundary for the appropriate vertexes of the origin. Note
+        // that while there are always 2 topological vertexes per edge, the
+        // resulting edge boundary may have an additional distortion vertex if it
+        // crosses an edge of the icosahedron.
+        let fijk = match origin._h3ToFaceIjk() {
+            Ok(fijk) => fijk,
+            Err(_) => return GeoBoundary::default(),
+        };
+        let res = origin.get_resolution();
+
+        if origin.is_pentagon() {
+            fijk._faceIjkPentToGeoBoundary(res, startVertex, 2)
+        } else {
+            fijk._faceIjkToGeoBoundary(res, startVertex, 2)
+        }
     }
 
     /**
@@ -91,10 +86,7 @@ impl H3Index {
         if res != Resolution::R0 {
             let parentRes = res - 1;
 
-            let mut origin = *self;
-            let mut dest = destination.clone();
-
-            if origin.h3ToParent(parentRes) == dest.h3ToParent(parentRes) {
+            if self.h3ToParent(parentRes).ok() == destination.h3ToParent(parentRes).ok() {
                 let originResDigit = self.get_index_digit(res);
                 let destinationResDigit = destination.get_index_digit(res);
                 use Direction::*;
@@ -130,26 +122,13 @@ impl H3Index {
         }
 
         // Otherwise, we have to determine the neighbor relationship the "hard" way.
-        todo!();
-        /*
-        let neighborRing = origin.kRing(1);
-        for neighbor in neighborRing {
-            if neighborRing == destination {
-                return true;
-            }
+        // Local IJ coordinates give the grid distance without building a
+        // ring, but cannot be computed across some pentagon distortions; only
+        // then does the ring of neighbors need to be searched.
+        match self.h3Distance(&destination) {
+            Ok(distance) => distance == 1,
+            Err(_) => self.kRing(1).contains(&destination),
         }
-        */
-        /*
-            H3Index neighborRing[7] = {0};
-            H3_EXPORT(kRing)(origin, 1, neighborRing);
-            for (int i = 0; i < 7; i++) {
-                if (neighborRing[i] == destination) {
-                    return true;
-                }
-            }
-        */
-        // Made it here, they definitely aren't neighbors
-        false
     }
 
     /**
@@ -176,3 +155,87 @@ impl H3Index {
         origin.is_valid() //return H3_EXPORT(h3IsValid)(origin);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{basecell::BaseCell, GeoCoord};
+
+    #[test]
+    fn h3IndexesAreNeighbors() {
+        let sfGeo = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let sf = sfGeo.geoToH3(Resolution::R9);
+        let ring = sf.grid_ring(1).unwrap();
+
+        assert!(
+            !sf.h3IndexesAreNeighbors(sf),
+            "an index does not neighbor itself"
+        );
+
+        let neighbors = ring
+            .iter()
+            .filter(|&&h| sf.h3IndexesAreNeighbors(h))
+            .count();
+        assert_eq!(
+            neighbors, 6,
+            "got the expected number of neighbors from a ring of 1"
+        );
+
+        let largerRing = sf.grid_ring(2).unwrap();
+        let neighbors = largerRing
+            .iter()
+            .filter(|&&h| sf.h3IndexesAreNeighbors(h))
+            .count();
+        assert_eq!(
+            neighbors, 0,
+            "got no neighbors, as expected, from a ring of 2"
+        );
+
+        let mut sfBroken = sf;
+        sfBroken.set_mode(H3Mode::H3_UNIEDGE_MODE);
+        assert!(
+            !sf.h3IndexesAreNeighbors(sfBroken),
+            "broken H3Indexes can't be neighbors"
+        );
+        assert!(
+            !sfBroken.h3IndexesAreNeighbors(sf),
+            "broken H3Indexes can't be neighbors (reversed)"
+        );
+
+        let sfBigger = sfGeo.geoToH3(Resolution::R7);
+        assert!(
+            !sf.h3IndexesAreNeighbors(sfBigger),
+            "hexagons of different resolution can't be neighbors"
+        );
+
+        assert!(
+            ring[2].h3IndexesAreNeighbors(ring[1]),
+            "hexagons in a ring are neighbors"
+        );
+    }
+
+    #[test]
+    fn h3IndexesAreNeighbors_matchesKRing() {
+        for &res in [Resolution::R0, Resolution::R1, Resolution::R2].iter() {
+            for bc in 0..BaseCell::NUM_BASE_CELLS as i32 {
+                let base = H3Index::setH3Index(
+                    Resolution::R0,
+                    BaseCell::new_unchecked(bc),
+                    Direction::CENTER_DIGIT,
+                );
+                for origin in base.h3ToChildren(res) {
+                    let neighbors = origin.kRing(1);
+                    for destination in origin.kRing(2) {
+                        assert_eq!(
+                            origin.h3IndexesAreNeighbors(destination),
+                            destination != origin && neighbors.contains(&destination),
+                            "{} and {}",
+                            origin,
+                            destination
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use super::{H3Index, H3Mode};
+use crate::Direction;
+
+/// Most neighbors, edges or vertexes a cell has: a hexagon has 6.
+const MAX_INDEXES: usize = 6;
+
+/// Up to 6 indexes kept inline, for results with a small fixed bound such as
+/// a cell's neighbors, edges and vertexes, so building one never touches the
+/// heap. Pentagons have 5, with no hole left for the missing one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IndexArray {
+    len: u8,
+    indexes: [H3Index; MAX_INDEXES],
+}
+
+impl Default for IndexArray {
+    fn default() -> Self {
+        IndexArray {
+            len: 0,
+            indexes: [H3Index::H3_NULL; MAX_INDEXES],
+        }
+    }
+}
+
+impl IndexArray {
+    /// Adds an index at the end. Indexes past the sixth are dropped; no cell
+    /// has more.
+    pub(crate) fn push(&mut self, h: H3Index) {
+        if (self.len as usize) < MAX_INDEXES {
+            self.indexes[self.len as usize] = h;
+            self.len += 1;
+        }
+    }
+
+    /// The indexes, in order.
+    pub fn as_slice(&self) -> &[H3Index] {
+        &self.indexes[..self.len as usize]
+    }
+
+    /// Iterates over the indexes in order.
+    pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'_, H3Index>> {
+        self.as_slice().iter().copied()
+    }
+
+    /// Number of indexes.
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Whether there are no indexes, as for an invalid cell.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether `h` is one of the indexes.
+    pub fn contains(&self, h: &H3Index) -> bool {
+        self.as_slice().contains(h)
+    }
+}
+
+impl IntoIterator for IndexArray {
+    type Item = H3Index;
+    type IntoIter = std::iter::Take<std::array::IntoIter<H3Index, MAX_INDEXES>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self.indexes).take(self.len as usize)
+    }
+}
+
+impl<'a> IntoIterator for &'a IndexArray {
+    type Item = H3Index;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, H3Index>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl H3Index {
+    /**
+     * The cells sharing an edge with this cell, the same as `kRing(1)`
+     * without the cell itself but without allocating.
+     *
+     * @return The neighbors by the direction of the step to them, from
+     *         K_AXES_DIGIT to IJ_AXES_DIGIT; 5 for a pentagon and none for an
+     *         invalid cell
+     */
+    pub fn neighbors(&self) -> IndexArray {
+        let mut out = IndexArray::default();
+        if !self.is_valid() {
+            return out;
+        }
+        let isPentagon = self.is_pentagon();
+        for dir in Direction::axes() {
+            // a step in the deleted direction lands on another neighbor
+            if isPentagon && dir == Direction::K_AXES_DIGIT {
+                continue;
+            }
+            let mut rotations = 0;
+            let neighbor = self.h3NeighborRotations(dir, &mut rotations);
+            if neighbor != H3Index::H3_NULL {
+                out.push(neighbor);
+            }
+        }
+        out
+    }
+
+    /**
+     * Provides all of the unidirectional edges from the current H3Index.
+     *
+     * @return The edges by direction, from K_AXES_DIGIT to IJ_AXES_DIGIT; 5
+     *         for a pentagon, which has no K_AXES_DIGIT edge, and none for an
+     *         invalid cell
+     */
+    pub fn getH3UnidirectionalEdgesFromHexagon(&self) -> IndexArray {
+        let mut out = IndexArray::default();
+        if !self.is_valid() {
+            return out;
+        }
+        let isPentagon = self.is_pentagon();
+        for dir in Direction::axes() {
+            if isPentagon && dir == Direction::K_AXES_DIGIT {
+                continue;
+            }
+            let mut edge = *self;
+            edge.set_mode(H3Mode::H3_UNIEDGE_MODE);
+            edge.set_reserved_bits(dir as u64);
+            out.push(edge);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeoCoord, Resolution};
+
+    #[test]
+    fn indexArray_push() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+        let mut indexes = IndexArray::default();
+        assert!(indexes.is_empty(), "empty");
+        let ring = sf.kRing(1);
+        for &h in ring.iter() {
+            indexes.push(h);
+        }
+        assert_eq!(indexes.len(), 6, "full");
+        assert_eq!(indexes.as_slice(), &ring[..6], "in order, extra dropped");
+        assert!(indexes.contains(&ring[5]) && !indexes.contains(&ring[6]));
+        assert_eq!(indexes.into_iter().collect::<Vec<_>>(), ring[..6].to_vec());
+        assert_eq!((&indexes).into_iter().count(), 6);
+    }
+
+    #[test]
+    fn neighbors_matchesKRing() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let mut origins: Vec<H3Index> = Resolution::RESOLUTIONS
+            .iter()
+            .map(|&res| sf.geoToH3(res))
+            .collect();
+        for &res in [Resolution::R0, Resolution::R1, Resolution::R5].iter() {
+            for pentagon in res.getPentagonIndexes().iter() {
+                origins.extend(pentagon.kRing(1));
+            }
+        }
+
+        for origin in origins {
+            let neighbors = origin.neighbors();
+            let mut expected = origin.kRing(1);
+            expected.retain(|&h| h != origin);
+            let mut actual = neighbors.as_slice().to_vec();
+            expected.sort_by_key(|&h| u64::from(h));
+            actual.sort_by_key(|&h| u64::from(h));
+            assert_eq!(actual, expected, "{} neighbors", origin);
+            for h in neighbors.iter() {
+                assert!(origin.h3IndexesAreNeighbors(h), "{} and {}", origin, h);
+            }
+        }
+        assert!(H3Index::H3_NULL.neighbors().is_empty(), "invalid");
+    }
+
+    #[test]
+    fn getH3UnidirectionalEdgesFromHexagon() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+        let pentagon = Resolution::R5.getPentagonIndexes()[4];
+
+        for &origin in [sf, pentagon].iter() {
+            let edges = origin.getH3UnidirectionalEdgesFromHexagon();
+            let expected = if origin.is_pentagon() { 5 } else { 6 };
+            assert_eq!(edges.len(), expected, "{} edge count", origin);
+            for edge in edges {
+                assert!(edge.h3UnidirectionalEdgeIsValid(), "{} valid", edge);
+                assert_eq!(
+                    edge.getOriginH3IndexFromUnidirectionalEdge(),
+                    origin,
+                    "{} origin",
+                    edge
+                );
+            }
+        }
+        assert!(
+            H3Index::H3_NULL
+                .getH3UnidirectionalEdgesFromHexagon()
+                .is_empty(),
+            "invalid"
+        );
+    }
+}
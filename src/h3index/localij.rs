@@ -1,12 +1,17 @@
-use std::ops::Add;
+use std::{convert::TryFrom, ops::Add};
 
 use crate::{
     basecell::baseCellNeighbor60CCWRots, coordij::CoordIJ, coordijk::CoordIJK, faceijk::FaceIJK,
-    BaseCell, Direction, Resolution,
+    BaseCell, Direction, H3Error, Resolution,
 };
 
 use super::H3Index;
 
+/// Largest ijk+ component accepted by localIjkToH3. Cells reach about
+/// 3.7 million at res 15, and the aperture 7 steps grow components by up to
+/// 4 times, which stays below the i32 limit.
+const MAX_LOCAL_IJK: i32 = 1 << 26;
+
 /// Origin leading digit -> index leading digit -> rotations 60 cw
 /// Either being 1 (K axis) is invalid.
 /// No good default at 0.
@@ -91,19 +96,43 @@ impl H3Index {
      *
      * @param origin Index to find the distance from.
      * @param index Index to find the distance to.
-     * @return The distance, or a negative number if the library could not
-     * compute the distance.
+     * @return The distance, or ResolutionMismatch if the indexes are at
+     * different resolutions, CellInvalid if either has no base cell, Domain
+     * if they are too far apart, or Pentagon if a pentagon's distortion is
+     * in the way.
      */
-    pub fn h3Distance(&self, h3: &Self) -> Result<i32, ()> {
+    pub fn h3Distance(&self, h3: &Self) -> Result<i32, H3Error> {
         // Currently there are no tests that would cause getting the coordinates
         // for an index the same as the origin to fail.
-        let originIjk = self.h3ToLocalIjk(self).map_err(|_| ())?;
+        let originIjk = self.h3ToLocalIjk(self)?;
 
-        let h3Ijk = self.h3ToLocalIjk(h3).map_err(|_| ())?;
+        let h3Ijk = self.h3ToLocalIjk(h3)?;
 
         Ok(originIjk.ijkDistance(&h3Ijk))
     }
 
+    /**
+     * Estimates the distance in kilometers between the centers of two cells
+     * from their grid distance, as the number of steps times the average
+     * spacing between neighboring hexagon centers at the resolution.
+     *
+     * This is cheaper than `pointDistKm` on the cell centers, but only an
+     * estimate. Paths 30 degrees off the grid axes take 15% more steps than
+     * a straight line would, and cells away from the average size, toward
+     * the icosahedron's vertexes or face centers, are up to about 25% off
+     * the average spacing. For cells a few steps apart or more, the estimate
+     * falls between about 0.78 and 1.42 times the great circle distance
+     * between the centers, at any resolution.
+     *
+     * @param other A cell at the same resolution
+     * @return The estimate, or an error as from h3Distance if the grid
+     * distance cannot be found
+     */
+    pub fn grid_distance_km(&self, other: &Self) -> Result<f64, H3Error> {
+        let steps = self.h3Distance(other)?;
+        Ok(f64::from(steps) * self.get_resolution().edgeLengthKm() * 3f64.sqrt())
+    }
+
     /**
      * Produces ijk+ coordinates for an index anchored by an origin.
      *
@@ -118,30 +147,28 @@ impl H3Index {
      *
      * @param origin An anchoring index for the ijk+ coordinate system.
      * @param index Index to find the coordinates of
-     * @param out ijk+ coordinates of the index will be placed here on success
-     * @return 0 on success, or another value on failure.
+     * @return The ijk+ coordinates, or an error as from h3Distance
      */
-    pub(crate) fn h3ToLocalIjk(&self /*origin*/, h3: &Self) -> Result<CoordIJK, i32> {
+    pub(crate) fn h3ToLocalIjk(&self /*origin*/, h3: &Self) -> Result<CoordIJK, H3Error> {
         let mut h3 = *h3;
 
         let res = self.get_resolution();
 
         if res != h3.get_resolution() {
-            return Err(1);
+            return Err(H3Error::ResolutionMismatch);
         }
 
         let originBaseCell = self.get_base_cell();
         let baseCell = h3.get_base_cell();
 
-        if originBaseCell < 0 || originBaseCell >= BaseCell::NUM_BASE_CELLS {
-            // LCOV_EXCL_BR_LINE
-            // Base cells less than zero can not be represented in an index
-            return Err(1);
-        }
-        if baseCell < 0 || baseCell >= BaseCell::NUM_BASE_CELLS {
-            // LCOV_EXCL_BR_LINE
-            // Base cells less than zero can not be represented in an index
-            return Err(1);
+        // Base cells past the last one can be stored in an index, but have no
+        // table entries
+        let originIndex = match originBaseCell.index() {
+            Some(i) => i,
+            None => return Err(H3Error::CellInvalid),
+        };
+        if baseCell.index().is_none() {
+            return Err(H3Error::CellInvalid);
         }
 
         // Direction from origin base cell to index base cell
@@ -152,7 +179,7 @@ impl H3Index {
             dir = originBaseCell._getBaseCellDirection(baseCell);
             if dir == Direction::INVALID_DIGIT {
                 // Base cells are not neighbors, can't unfold.
-                return Err(2);
+                return Err(H3Error::Domain);
             }
             revDir = baseCell._getBaseCellDirection(originBaseCell);
             assert!(revDir != Direction::INVALID_DIGIT);
@@ -164,10 +191,9 @@ impl H3Index {
         if dir != Direction::CENTER_DIGIT {
             // Rotate index into the orientation of the origin base cell.
             // cw because we are undoing the rotation into that base cell.
-            let baseCellRotations =
-                baseCellNeighbor60CCWRots[originBaseCell.0 as usize][dir as usize];
+            let baseCellRotations = baseCellNeighbor60CCWRots[originIndex][dir as usize];
             if indexOnPent {
-                for _ in 0..baseCellRotations.into() {
+                for _ in 0..baseCellRotations {
                     h3 = h3._h3RotatePent60cw();
 
                     revDir = revDir.rotate60cw();
@@ -176,7 +202,7 @@ impl H3Index {
                     }
                 }
             } else {
-                for _ in 0..baseCellRotations.0 {
+                for _ in 0..baseCellRotations {
                     h3 = h3._h3Rotate60cw();
 
                     revDir = revDir.rotate60cw();
@@ -202,7 +228,7 @@ impl H3Index {
                     // TODO: We may be unfolding the pentagon incorrectly in this
                     // case; return an error code until this is guaranteed to be
                     // correct.
-                    return Err(3);
+                    return Err(H3Error::Pentagon);
                 }
 
                 directionRotations = PENTAGON_ROTATIONS[originLeadingDigit][dir as usize];
@@ -214,7 +240,7 @@ impl H3Index {
                     // TODO: We may be unfolding the pentagon incorrectly in this
                     // case; return an error code until this is guaranteed to be
                     // correct.
-                    return Err(4);
+                    return Err(H3Error::Pentagon);
                 }
 
                 pentagonRotations = PENTAGON_ROTATIONS[revDir as usize][indexLeadingDigit as usize];
@@ -227,20 +253,8 @@ impl H3Index {
                 indexFijk.coord._ijkRotate60cw();
             }
 
-            let mut offset = CoordIJK::default();
-            offset._neighbor(dir);
-
             // Scale offset based on resolution
-            for r in (0..res.into()).rev() {
-                let r: Resolution = (r + 1).into();
-                if r.isResClassIII() {
-                    // rotate ccw
-                    offset._downAp7();
-                } else {
-                    // rotate cw
-                    offset._downAp7r();
-                }
-            }
+            let mut offset = CoordIJK::scaledUnitVec(res, dir);
 
             for _ in 0..directionRotations {
                 offset._ijkRotate60cw();
@@ -261,7 +275,7 @@ impl H3Index {
             if FAILED_DIRECTIONS[originLeadingDigit as usize][indexLeadingDigit as usize] {
                 // TODO: We may be unfolding the pentagon incorrectly in this case;
                 // return an error code until this is guaranteed to be correct.
-                return Err(5);
+                return Err(H3Error::Pentagon);
             }
 
             let withinPentagonRotations =
@@ -277,15 +291,14 @@ impl H3Index {
 
     /**
      * Number of indexes in a line from the start index to the end index,
-     * to be used for allocating memory. Returns a negative number if the
-     * line cannot be computed.
+     * to be used for allocating memory.
      *
      * @param start Start index of the line
      * @param end End index of the line
-     * @return Size of the line, or a negative number if the line cannot
-     * be computed.
+     * @return Size of the line, or an error as from h3Distance if the line
+     * cannot be computed.
      */
-    pub fn h3LineSize(start: &Self, end: &Self) -> Result<i32, ()> {
+    pub fn h3LineSize(start: &Self, end: &Self) -> Result<i32, H3Error> {
         let distance = start.h3Distance(end)?;
         Ok(distance + 1)
     }
@@ -299,20 +312,26 @@ impl H3Index {
      * Failure may occur if the index is too far away from the origin
      * or if the index is on the other side of a pentagon.
      *
+     * Coordinates that can be indexed reach only into the base cells around
+     * the origin's, so |i| and |j| stay below about 2 * 7^(res / 2): 14 at
+     * res 2, 260 at res 5, 33614 at res 10 and 8.9 million at res 15. Larger
+     * coordinates fail rather than overflow, all the way to the i32 limits.
+     *
      * This function is experimental, and its output is not guaranteed
      * to be compatible across different versions of H3.
      *
      * @param origin An anchoring index for the ij coordinate system.
-     * @param out ij coordinates to index.
-     * @param index Index will be placed here on success.
-     * @return 0 on success, or another value on failure.
+     * @param ij ij coordinates to index.
+     * @return The index, or CellInvalid if the origin has no base cell,
+     * Domain if the coordinates are too far from the origin, or Pentagon if
+     * they fall in a pentagon's deleted subsequence.
      */
-    pub fn experimentalLocalIjToH3(&self, ij: &CoordIJ) -> Result<Self, i32> {
+    pub fn experimentalLocalIjToH3(&self, ij: &CoordIJ) -> Result<Self, H3Error> {
         // This function is currently experimental. Once ready to be part of the
         // non-experimental API, this function (with the experimental prefix) will
         // be marked as deprecated and to be removed in the next major version. It
         // will be replaced with a non-prefixed function name.
-        let ijk: CoordIJK = ij.into();
+        let ijk = CoordIJK::try_from(ij).map_err(|_| H3Error::Domain)?;
 
         self.localIjkToH3(&ijk)
     }
@@ -324,15 +343,15 @@ impl H3Index {
     ///
     /// Failure may occur if the coordinates are too far away from the origin
     /// or if the index is on the other side of a pentagon.
-    fn localIjkToH3(&self, ijk: &CoordIJK) -> Result<Self, i32> {
+    fn localIjkToH3(&self, ijk: &CoordIJK) -> Result<Self, H3Error> {
         let res = self.get_resolution();
         let originBaseCell = self.get_base_cell();
 
-        if i32::from(originBaseCell) < 0 || usize::from(originBaseCell) >= BaseCell::NUM_BASE_CELLS
-        {
-            // Base cells less than zero can not be represented in an index
-            return Err(1);
-        }
+        // Base cells less than zero can not be represented in an index
+        let originIndex = match originBaseCell.index() {
+            Some(i) => i,
+            None => return Err(H3Error::CellInvalid),
+        };
 
         let originOnPent = originBaseCell._isBaseCellPentagon();
 
@@ -346,40 +365,47 @@ impl H3Index {
         if res == Resolution::R0 {
             if ijk.i > 1 || ijk.j > 1 || ijk.k > 1 {
                 // out of range input
-                return Err(1);
+                return Err(H3Error::Domain);
             }
 
             let dir: Direction = ijk._unitIjkToDigit();
-            let new_basecell = originBaseCell._getBaseCellNeighbor(&dir);
-            if new_basecell == BaseCell::INVALID {
+            let new_basecell = match originBaseCell._getBaseCellNeighbor(&dir) {
+                Some(bc) => bc,
                 // Moving in an invalid direction off a pentagon.
-                return Err(1);
-            }
+                None => return Err(H3Error::Pentagon),
+            };
             out.set_base_cell(new_basecell);
             return Ok(out);
         }
 
+        // No cell is this far from an origin, and keeping the components well
+        // inside the i32 range lets the aperture 7 steps below skip overflow
+        // checks.
+        if ijk.i > MAX_LOCAL_IJK || ijk.j > MAX_LOCAL_IJK || ijk.k > MAX_LOCAL_IJK {
+            return Err(H3Error::Domain);
+        }
+
         // we need to find the correct base cell offset (if any) for this H3 index;
         // start with the passed in base cell and resolution res ijk coordinates
         // in that base cell's coordinate system
-        let mut ijkCopy = ijk.clone();
+        let mut ijkCopy = *ijk;
 
         // build the H3Index from finest res up
         // adjust r for the fact that the res 0 base cell offsets the indexing
         // digits
         for r in (0..res as u64).rev() {
             let r: Resolution = r.into();
-            let last_ijk = ijkCopy.clone();
+            let last_ijk = ijkCopy;
             let last_center: CoordIJK = if (r + 1).isResClassIII() {
                 // rotate ccw
                 ijkCopy._upAp7();
-                let mut lc = ijkCopy.clone();
+                let mut lc = ijkCopy;
                 lc._downAp7();
                 lc
             } else {
                 // rotate cw
                 ijkCopy._upAp7r();
-                let mut lc = ijkCopy.clone();
+                let mut lc = ijkCopy;
                 lc._downAp7r();
                 lc
             };
@@ -389,7 +415,7 @@ impl H3Index {
 
             let digit: Direction = diff._unitIjkToDigit();
 
-            out.set_index_digit(r.into(), digit.into());
+            out.set_index_digit(r + 1, digit.into());
         }
 
         // ijkCopy should now hold the IJK of the base cell in the
@@ -397,7 +423,7 @@ impl H3Index {
 
         if ijkCopy.i > 1 || ijkCopy.j > 1 || ijkCopy.k > 1 {
             // out of range input
-            return Err(2);
+            return Err(H3Error::Domain);
         }
 
         // lookup the correct base cell
@@ -407,11 +433,7 @@ impl H3Index {
         // If baseCell is invalid, it must be because the origin base cell is a
         // pentagon, and because pentagon base cells do not border each other,
         // baseCell must not be a pentagon.
-        let indexOnPent = if basecell == BaseCell::INVALID {
-            false
-        } else {
-            basecell._isBaseCellPentagon()
-        };
+        let indexOnPent = basecell.is_some_and(|bc| bc._isBaseCellPentagon());
 
         if dir != Direction::CENTER_DIGIT {
             // If the index is in a warped direction, we need to unwarp the base
@@ -419,8 +441,7 @@ impl H3Index {
             let mut pentagon_rotations = 0;
             if originOnPent {
                 let origin_leading_digit: usize = self._h3LeadingNonZeroDigit().into();
-                pentagon_rotations =
-                    PENTAGON_ROTATIONS_REVERSE[origin_leading_digit as usize][dir as usize];
+                pentagon_rotations = PENTAGON_ROTATIONS_REVERSE[origin_leading_digit][dir as usize];
                 for _ in 0..pentagon_rotations {
                     dir = dir.rotate60ccw();
                 }
@@ -429,33 +450,32 @@ impl H3Index {
                 // deleted direction. If it still happens, it means we're moving
                 // into a deleted subsequence, so there is no index here.
                 if dir == Direction::K_AXES_DIGIT {
-                    return Err(3);
+                    return Err(H3Error::Pentagon);
                 }
                 basecell = originBaseCell._getBaseCellNeighbor(&dir);
 
                 // indexOnPent does not need to be checked again since no pentagon
                 // base cells border each other.
-                assert!(basecell != BaseCell::INVALID);
-                assert!(basecell._isBaseCellPentagon());
+                assert!(basecell.is_some_and(|bc| !bc._isBaseCellPentagon()));
             }
 
             // Now we can determine the relation between the origin and target base cell.
-            let base_cell_rotations =
-                baseCellNeighbor60CCWRots[usize::from(originBaseCell)][dir as usize];
-            //assert!(baseCellRotations.into() >= 0);
+            let base_cell_rotations = baseCellNeighbor60CCWRots[originIndex][dir as usize];
+            //assert!(baseCellRotations >= 0);
 
             // Adjust for pentagon warping within the base cell. The base cell
             // should be in the right location, so now we need to rotate the index
             // back. We might not need to check for errors since we would just be
             // double mapping.
             if indexOnPent {
+                let basecell = basecell.ok_or(H3Error::Pentagon)?;
                 let revDir = basecell._getBaseCellDirection(originBaseCell);
                 assert!(revDir != Direction::INVALID_DIGIT);
 
                 // Adjust for the different coordinate space in the two base cells.
                 // This is done first because we need to do the pentagon rotations
                 // based on the leading digit in the pentagon's coordinate system.
-                for _ in 0..base_cell_rotations.into() {
+                for _ in 0..base_cell_rotations {
                     out = out._h3Rotate60ccw();
                 }
 
@@ -477,7 +497,7 @@ impl H3Index {
                 }
 
                 // Adjust for the different coordinate space in the two base cells.
-                for _ in 0..base_cell_rotations.into() {
+                for _ in 0..base_cell_rotations {
                     out = out._h3Rotate60ccw();
                 }
             }
@@ -499,11 +519,11 @@ impl H3Index {
             // accounted for here - instead just fail if the recovered index is
             // invalid.
             if out._h3LeadingNonZeroDigit() == Direction::K_AXES_DIGIT {
-                return Err(4);
+                return Err(H3Error::Pentagon);
             }
         }
 
-        out.set_base_cell(basecell);
+        out.set_base_cell(basecell.ok_or(H3Error::Pentagon)?);
         Ok(out)
     }
 
@@ -518,74 +538,76 @@ impl H3Index {
      *
      *  - The specific output of this function should not be considered stable
      *    across library versions. The only guarantees the library provides are
-     *    that the line length will be `h3Distance(start, end) + 1` and that
-     *    every index in the line will be a neighbor of the preceding index.
+     *    that the line length will be at most `h3Distance(start, end) + 1`,
+     *    that no index is repeated back to back, and that every index in the
+     *    line will be a neighbor of the preceding index.
      *  - Lines are drawn in grid space, and may not correspond exactly to either
      *    Cartesian lines or great arcs.
      *
      * @param start Start index of the line
      * @param end End index of the line
-     * @param out Output array, which must be of size h3LineSize(start, end)
-     * @return 0 on success, or another value on failure.
+     * @return The line, or an error as from h3Distance if it cannot be
+     * computed
      */
-
-    pub fn h3Line(start: Self, end: Self) -> Result<Vec<H3Index>, ()> {
+    pub fn h3Line(start: Self, end: Self) -> Result<Vec<H3Index>, H3Error> {
         // Early exit if we can't calculate the line
         let distance = start.h3Distance(&end)?;
 
         // Get IJK coords for the start and end. We've already confirmed
         // that these can be calculated with the distance check above.
-        let mut startIjk = start.h3ToLocalIjk(&start).unwrap();
-        let mut endIjk = start.h3ToLocalIjk(&end).unwrap();
+        let mut startIjk = start.h3ToLocalIjk(&start)?;
+        let mut endIjk = start.h3ToLocalIjk(&end)?;
 
         // Convert IJK to cube coordinates suitable for linear interpolation
         startIjk.ijkToCube();
         endIjk.ijkToCube();
 
         let iStep = if distance > 0 {
-            (endIjk.i - startIjk.i) as f32 / distance as f32
+            (endIjk.i - startIjk.i) as f64 / distance as f64
         } else {
             0.0
         };
         let jStep = if distance > 0 {
-            (endIjk.j - startIjk.j) as f32 / distance as f32
+            (endIjk.j - startIjk.j) as f64 / distance as f64
         } else {
             0.0
         };
         let kStep = if distance > 0 {
-            (endIjk.k - startIjk.k) as f32 / distance as f32
+            (endIjk.k - startIjk.k) as f64 / distance as f64
         } else {
             0.0
         };
 
-        //let mut currentIjk = startIjk;
-
-        let mut result = Vec::with_capacity(distance as usize + 1);
+        let mut result: Vec<H3Index> = Vec::with_capacity(distance as usize + 1);
 
         for n in 0..=distance {
             let mut currentIjk = Self::cubeRound(
-                startIjk.i as f32 + iStep * n as f32,
-                startIjk.j as f32 + jStep * n as f32,
-                startIjk.k as f32 + kStep * n as f32,
+                startIjk.i as f64 + iStep * n as f64,
+                startIjk.j as f64 + jStep * n as f64,
+                startIjk.k as f64 + kStep * n as f64,
             );
 
             // Convert cube -> ijk -> h3 index
             currentIjk.cubeToIjk();
 
-            result.push(start.localIjkToH3(&currentIjk).unwrap());
+            let h = start.localIjkToH3(&currentIjk)?;
+            // Rounding can land twice in the same cell; keep one of them
+            if result.last() != Some(&h) {
+                result.push(h);
+            }
         }
 
         Ok(result)
     }
 
-    fn cubeRound(i: f32, j: f32, k: f32) -> CoordIJK {
+    fn cubeRound(i: f64, j: f64, k: f64) -> CoordIJK {
         let mut ri = i.round() as i32;
         let mut rj = j.round() as i32;
         let mut rk = k.round() as i32;
 
-        let iDiff = (ri as f32 - i).abs();
-        let jDiff = (rj as f32 - j).abs();
-        let kDiff = (rk as f32 - k).abs();
+        let iDiff = (ri as f64 - i).abs();
+        let jDiff = (rj as f64 - j).abs();
+        let kDiff = (rk as f64 - k).abs();
 
         // Round, maintaining valid cube coords
         if iDiff > jDiff && iDiff > kDiff {
@@ -596,7 +618,7 @@ impl H3Index {
             rk = -ri - rj;
         }
 
-        CoordIJK::new(ri as i32, rj as i32, rk as i32)
+        CoordIJK::new(ri, rj, rk)
     }
 
     /*
@@ -613,27 +635,26 @@ impl H3Index {
      *
      * @param origin An anchoring index for the ijk+ coordinate system.
      * @param index Index to find the coordinates of
-     * @param out ijk+ coordinates of the index will be placed here on success
-     * @return 0 on success, or another value on failure.
+     * @return The ijk+ coordinates, or an error as from h3Distance
      */
     //*
-    fn h3ToLocalIjk__newversion(origin: H3Index, mut h3: H3Index) -> Result<CoordIJK, i32> {
+    fn h3ToLocalIjk__newversion(origin: H3Index, mut h3: H3Index) -> Result<CoordIJK, H3Error> {
         let res = origin.get_resolution();
 
         if res != h3.get_resolution() {
-            return Err(1);
+            return Err(H3Error::ResolutionMismatch);
         }
 
         let originBaseCell = origin.get_base_cell();
         let baseCell = h3.get_base_cell();
 
-        if originBaseCell >= BaseCell::NUM_BASE_CELLS {
-            return Err(1);
-        }
-
-        if baseCell >= BaseCell::NUM_BASE_CELLS {
-            // Base cells less than zero can not be represented in an index
-            return Err(1);
+        // Base cells less than zero can not be represented in an index
+        let originIndex = match originBaseCell.index() {
+            Some(i) => i,
+            None => return Err(H3Error::CellInvalid),
+        };
+        if baseCell.index().is_none() {
+            return Err(H3Error::CellInvalid);
         }
 
         // Direction from origin base cell to index base cell
@@ -644,7 +665,7 @@ impl H3Index {
             dir = originBaseCell._getBaseCellDirection(baseCell);
             if dir == Direction::INVALID_DIGIT {
                 // Base cells are not neighbors, can't unfold.
-                return Err(2);
+                return Err(H3Error::Domain);
             }
             revDir = baseCell._getBaseCellDirection(originBaseCell);
             assert_ne!(revDir, Direction::INVALID_DIGIT);
@@ -656,10 +677,9 @@ impl H3Index {
         if dir != Direction::CENTER_DIGIT {
             // Rotate index into the orientation of the origin base cell.
             // cw because we are undoing the rotation into that base cell.
-            let baseCellRotations =
-                baseCellNeighbor60CCWRots[originBaseCell.0 as usize][dir as usize];
+            let baseCellRotations = baseCellNeighbor60CCWRots[originIndex][dir as usize];
             if indexOnPent {
-                for _ in 0..baseCellRotations.0 {
+                for _ in 0..baseCellRotations {
                     h3 = h3._h3RotatePent60cw();
 
                     revDir = revDir.rotate60cw();
@@ -668,7 +688,7 @@ impl H3Index {
                     }
                 }
             } else {
-                for _ in 0..baseCellRotations.0 {
+                for _ in 0..baseCellRotations {
                     h3 = h3._h3Rotate60cw();
 
                     revDir = revDir.rotate60cw();
@@ -694,7 +714,7 @@ impl H3Index {
                     // TODO: We may be unfolding the pentagon incorrectly in this
                     // case; return an error code until this is guaranteed to be
                     // correct.
-                    return Err(3);
+                    return Err(H3Error::Pentagon);
                 }
 
                 directionRotations = PENTAGON_ROTATIONS[originLeadingDigit as usize][dir as usize];
@@ -706,7 +726,7 @@ impl H3Index {
                     // TODO: We may be unfolding the pentagon incorrectly in this
                     // case; return an error code until this is guaranteed to be
                     // correct.
-                    return Err(4);
+                    return Err(H3Error::Pentagon);
                 }
 
                 pentagonRotations = PENTAGON_ROTATIONS[revDir as usize][indexLeadingDigit as usize];
@@ -719,21 +739,8 @@ impl H3Index {
                 indexFijk.coord._ijkRotate60cw();
             }
 
-            let mut offset = CoordIJK::default();
-            offset._neighbor(dir);
-
             // Scale offset based on resolution
-            for r in (0..res as usize).rev() {
-                let r1: Resolution = (r + 1).into();
-
-                if r1.isResClassIII() {
-                    // rotate ccw
-                    offset._downAp7();
-                } else {
-                    // rotate cw
-                    offset._downAp7r();
-                }
-            }
+            let mut offset = CoordIJK::scaledUnitVec(res, dir);
 
             for _ in 0..directionRotations {
                 offset._ijkRotate60cw();
@@ -756,7 +763,7 @@ impl H3Index {
             if FAILED_DIRECTIONS[originLeadingDigit as usize][indexLeadingDigit as usize] {
                 // TODO: We may be unfolding the pentagon incorrectly in this case;
                 // return an error code until this is guaranteed to be correct.
-                return Err(5);
+                return Err(H3Error::Pentagon);
             }
 
             let withinPentagonRotations =
@@ -787,10 +794,9 @@ impl H3Index {
      *
      * @param origin An anchoring index for the ij coordinate system.
      * @param index Index to find the coordinates of
-     * @param out ij coordinates of the index will be placed here on success
-     * @return 0 on success, or another value on failure.
+     * @return The ij coordinates, or an error as from h3Distance
      */
-    pub fn experimentalH3ToLocalIj(origin: H3Index, h3: H3Index) -> Result<CoordIJ, i32> {
+    pub fn experimentalH3ToLocalIj(origin: H3Index, h3: H3Index) -> Result<CoordIJ, H3Error> {
         // This function is currently experimental. Once ready to be part of the
         // non-experimental API, this function (with the experimental prefix) will
         // be marked as deprecated and to be removed in the next major version. It
@@ -840,14 +846,22 @@ mod tests {
 
         let line = H3Index::h3Line(start, end).expect("no error on line");
 
+        assert!(line.len() <= sz, "line is no longer than the distance");
         assert_eq!(line[0], start, "line starts with start index");
-        assert_eq!(line[sz - 1], end, "line ends with end index");
+        assert_eq!(line[line.len() - 1], end, "line ends with end index");
 
-        for i in 1..sz {
+        for i in 1..line.len() {
             assert!(line[i].is_valid(), "index is valid");
-            //assert!(H3_EXPORT(h3IndexesAreNeighbors)(line[i], line[i - 1]), "index is a neighbor of the previous index");
+            assert_ne!(line[i], line[i - 1], "index is not repeated");
+            assert!(
+                line[i].h3IndexesAreNeighbors(line[i - 1]),
+                "index is a neighbor of the previous index"
+            );
             if i > 1 {
-                //assert!( ! H3_EXPORT(h3IndexesAreNeighbors)(line[i], line[i - 2]), "index is not a neighbor of the index before the previous");
+                assert!(
+                    !line[i].h3IndexesAreNeighbors(line[i - 2]),
+                    "index is not a neighbor of the index before the previous"
+                );
             }
         }
     }
@@ -863,62 +877,84 @@ mod tests {
 
     /// Test for lines from an index to all neighbors within a kRing
     fn h3Line_kRing_assertions(h3: H3Index) {
-        /*
-        int r = H3_GET_RESOLUTION(h3);
-        t_assert(r <= 5, "resolution supported by test function (kRing)");
-        int maxK = MAX_DISTANCES[r];
-
-        int sz = H3_EXPORT(maxKringSize)(maxK);
+        let r = h3.get_resolution() as usize;
+        assert!(r <= 5, "resolution supported by test function (kRing)");
+        let maxK = MAX_DISTANCES[r];
 
-        if (H3_EXPORT(h3IsPentagon)(h3)) {
+        if h3.is_pentagon() {
             return;
         }
 
-        H3Index *neighbors = calloc(sz, sizeof(H3Index));
-        H3_EXPORT(kRing)(h3, maxK, neighbors);
-
-        for (int i = 0; i < sz; i++) {
-            if (neighbors[i] == 0) {
+        for neighbor in h3.kRing(maxK as u32) {
+            if neighbor == H3Index::H3_NULL {
                 continue;
             }
-            int distance = H3_EXPORT(h3Distance)(h3, neighbors[i]);
-            if (distance >= 0) {
-                h3Line_assertions(h3, neighbors[i]);
+            if h3.h3Distance(&neighbor).is_ok() {
+                h3Line_assertions(h3, neighbor);
             } else {
-                h3Line_invalid_assertions(h3, neighbors[i]);
+                h3Line_invalid_assertions(h3, neighbor);
             }
         }
-
-        free(neighbors);
-        */
     }
 
     #[test]
     fn h3Line_kRing() {
-        //iterateAllIndexesAtRes(0, h3Line_kRing_assertions);
-        //iterateAllIndexesAtRes(1, h3Line_kRing_assertions);
-        //iterateAllIndexesAtRes(2, h3Line_kRing_assertions);
-        // Don't iterate all of res 3, to save time
-        //iterateAllIndexesAtResPartial(3, h3Line_kRing_assertions, 6);
-        // Further resolutions aren't tested to save time.
+        iterateAllIndexesAtRes(Resolution::R0, h3Line_kRing_assertions);
+        iterateAllIndexesAtRes(Resolution::R1, h3Line_kRing_assertions);
+        // Don't iterate all of res 2, to save time. Further resolutions
+        // aren't tested to save time; a single res 3 base cell takes seconds.
+        iterateAllIndexesAtResPartial(Resolution::R2, h3Line_kRing_assertions, 12);
+    }
+
+    #[test]
+    fn h3Line_noRepeats() {
+        let sf = crate::GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+        for k in 1..=20 {
+            for end in origin.grid_ring(k).unwrap() {
+                let line = H3Index::h3Line(origin, end).expect("line");
+                assert_eq!(line.len(), k as usize + 1, "one index per step to {}", end);
+                h3Line_assertions(origin, end);
+            }
+        }
     }
 
     fn setup() -> (H3Index, H3Index, H3Index, H3Index) {
-        let bc1 = H3Index::setH3Index(Resolution::R0, BaseCell(15), Direction::CENTER_DIGIT);
-        let bc2 = H3Index::setH3Index(Resolution::R0, BaseCell(8), Direction::CENTER_DIGIT);
-        let bc3 = H3Index::setH3Index(Resolution::R0, BaseCell(31), Direction::CENTER_DIGIT);
-        let pent1 = H3Index::setH3Index(Resolution::R0, BaseCell(4), Direction::CENTER_DIGIT);
+        let bc1 = H3Index::setH3Index(
+            Resolution::R0,
+            BaseCell::new_unchecked(15),
+            Direction::CENTER_DIGIT,
+        );
+        let bc2 = H3Index::setH3Index(
+            Resolution::R0,
+            BaseCell::new_unchecked(8),
+            Direction::CENTER_DIGIT,
+        );
+        let bc3 = H3Index::setH3Index(
+            Resolution::R0,
+            BaseCell::new_unchecked(31),
+            Direction::CENTER_DIGIT,
+        );
+        let pent1 = H3Index::setH3Index(
+            Resolution::R0,
+            BaseCell::new_unchecked(4),
+            Direction::CENTER_DIGIT,
+        );
 
         (bc1, bc2, bc3, pent1)
     }
 
     #[test]
     fn ijkBaseCells1() {
-        let (bc1, bc2, bc3, pent1) = setup();
+        let (bc1, _, _, pent1) = setup();
 
         let ijk = pent1.h3ToLocalIjk(&bc1);
         assert!(ijk.is_ok(), "got ijk for base cells 4 and 15");
-        //assert_eq!(ijk.unwrpa(), &UNIT_VECS[2]) == 1, "neighboring base cell at 0,1,0");
+        assert_eq!(
+            ijk.unwrap(),
+            Direction::J_AXES_DIGIT.to_ijk().unwrap(),
+            "neighboring base cell at 0,1,0"
+        );
     }
 
     #[test]
@@ -956,6 +992,55 @@ mod tests {
         assert!(retrieved.is_err(), "out of range base cell (3)");
     }
 
+    #[test]
+    fn localIj_errors() {
+        let sf = crate::GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R5);
+        assert_eq!(
+            origin.h3Distance(&sf.geoToH3(Resolution::R6)),
+            Err(H3Error::ResolutionMismatch),
+            "resolutions differ"
+        );
+        let farAway = crate::GeoCoord::from_rads(-0.6, 0.4).geoToH3(Resolution::R5);
+        assert_eq!(origin.h3Distance(&farAway), Err(H3Error::Domain), "far");
+        assert_eq!(
+            H3Index::h3Line(origin, farAway),
+            Err(H3Error::Domain),
+            "no line"
+        );
+        let mut noBaseCell = origin;
+        noBaseCell.set_base_cell(BaseCell::new_unchecked(127));
+        assert_eq!(
+            origin.h3Distance(&noBaseCell),
+            Err(H3Error::CellInvalid),
+            "base cell past the last"
+        );
+
+        let pentagon = H3Index::setH3Index(
+            Resolution::R1,
+            BaseCell::new_unchecked(4),
+            Direction::CENTER_DIGIT,
+        );
+        let nearPentagon = pentagon.neighbors().as_slice()[2];
+        let unfolded: Vec<Result<i32, H3Error>> = nearPentagon
+            .kRing(4)
+            .iter()
+            .map(|h| nearPentagon.h3Distance(h))
+            .collect();
+        assert!(unfolded.contains(&Err(H3Error::Pentagon)), "distortion");
+
+        assert_eq!(
+            origin.experimentalLocalIjToH3(&CoordIJ { i: 1 << 30, j: 0 }),
+            Err(H3Error::Domain),
+            "far coordinates"
+        );
+        assert_eq!(
+            pentagon.experimentalLocalIjToH3(&CoordIJ { i: 0, j: 50 }),
+            Err(H3Error::Domain),
+            "beyond the neighboring base cells"
+        );
+    }
+
     #[test]
     fn ijOutOfRange() {
         const numCoords: usize = 7;
@@ -1046,6 +1131,48 @@ mod tests {
         assert!(index.is_err(), "invalid origin for ijToH3");
     }
 
+    #[test]
+    fn experimentalLocalIjToH3Overflow() {
+        let extremes = [
+            i32::MIN,
+            i32::MIN + 1,
+            -MAX_LOCAL_IJK - 1,
+            0,
+            MAX_LOCAL_IJK + 1,
+            i32::MAX,
+        ];
+        for &res in [
+            Resolution::R0,
+            Resolution::R1,
+            Resolution::R9,
+            Resolution::R15,
+        ]
+        .iter()
+        {
+            let origin = crate::GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(res);
+            let pentagon = res.getPentagonIndexes()[0];
+            for &i in extremes.iter() {
+                for &j in extremes.iter() {
+                    let ij = CoordIJ::new(i, j);
+                    if i == 0 && j == 0 {
+                        continue;
+                    }
+                    assert!(
+                        origin.experimentalLocalIjToH3(&ij).is_err(),
+                        "{} is out of range at res {:?}",
+                        ij,
+                        res
+                    );
+                    assert!(
+                        pentagon.experimentalLocalIjToH3(&ij).is_err(),
+                        "{} is out of range of a pentagon",
+                        ij
+                    );
+                }
+            }
+        }
+    }
+
     /**
      * Test that coming from the same direction outside the pentagon is handled
      * the same as coming from the same direction inside the pentagon.
@@ -1055,7 +1182,7 @@ mod tests {
         for bc in 0..BaseCell::NUM_BASE_CELLS {
             for res in 1..=Resolution::MAX_H3_RES {
                 let res: Resolution = res.into();
-                let bc: BaseCell = bc.into();
+                let bc = BaseCell::new_unchecked(bc as i32);
                 // K_AXES_DIGIT is the first internal direction, and it's also
                 // invalid for pentagons, so skip to next.
                 let mut startDir = Direction::K_AXES_DIGIT;
@@ -1063,18 +1190,17 @@ mod tests {
                     startDir += 1;
                 }
 
-                let mut dir = startDir;
-                while dir != Direction::INVALID_DIGIT {
+                for dir in startDir as u64..Direction::INVALID_DIGIT as u64 {
+                    let dir = Direction::from(dir);
                     let internalOrigin = H3Index::setH3Index(res, bc, dir);
                     let externalOrigin = H3Index::setH3Index(
                         res,
-                        bc._getBaseCellNeighbor(&dir),
+                        bc._getBaseCellNeighbor(&dir).unwrap(),
                         Direction::CENTER_DIGIT,
                     );
 
-                    let mut testDir = startDir;
-                    while testDir != Direction::INVALID_DIGIT {
-                        let testIndex = H3Index::setH3Index(res, bc, testDir);
+                    for testDir in startDir as u64..Direction::INVALID_DIGIT as u64 {
+                        let testIndex = H3Index::setH3Index(res, bc, testDir.into());
 
                         let internalIj =
                             H3Index::experimentalH3ToLocalIj(internalOrigin, testIndex);
@@ -1086,38 +1212,32 @@ mod tests {
                             "internal/external failed matches when getting IJ"
                         );
 
-                        if internalIj.is_err() {
-                            continue;
-                        }
+                        let (internalIj, externalIj) = match (internalIj, externalIj) {
+                            (Ok(internalIj), Ok(externalIj)) => (internalIj, externalIj),
+                            _ => continue,
+                        };
 
                         let internalIndex =
-                            H3Index::experimentalLocalIjToH3(&internalOrigin, &internalIj.unwrap());
+                            H3Index::experimentalLocalIjToH3(&internalOrigin, &internalIj);
                         let externalIndex =
-                            H3Index::experimentalLocalIjToH3(&externalOrigin, &externalIj.unwrap());
+                            H3Index::experimentalLocalIjToH3(&externalOrigin, &externalIj);
 
                         assert_eq!(
-                            internalIj.is_err(),
-                            externalIj.is_err(),
+                            internalIndex.is_err(),
+                            externalIndex.is_err(),
                             "internal/external failed matches when getting index"
                         );
 
-                        if internalIj.is_err() {
-                            continue;
+                        if let (Ok(internalIndex), Ok(externalIndex)) =
+                            (internalIndex, externalIndex)
+                        {
+                            assert_eq!(
+                                internalIndex, externalIndex,
+                                "internal/external index matches"
+                            );
                         }
-
-                        assert_eq!(
-                            internalIndex.unwrap(),
-                            externalIndex.unwrap(),
-                            "internal/external index matches"
-                        );
-
-                        testDir += 1;
                     }
-
-                    dir += 1;
                 }
-
-                todo!()
             }
         }
     }
@@ -1157,15 +1277,14 @@ mod tests {
                 "res 1 cell at expected coordinates"
             );
         } else if r == Resolution::R2 {
-            // the C unit test uses an index digit of 1 here, not 2
-            let index = h3.get_index_digit(r);
+            let index = h3.get_index_digit(Resolution::R1);
             let mut expected = CoordIJK::UNIT_VECS[index as usize].0;
 
             expected._downAp7r();
             expected._neighbor(h3.get_index_digit(r));
             assert_eq!(ijk, expected, "res 2 cell at expected coordinates");
         } else {
-            assert!(false, "resolution supported by test function (coordinates)");
+            panic!("resolution supported by test function (coordinates)");
         }
     }
 
@@ -1179,8 +1298,8 @@ mod tests {
 
         let originIjk = origin.unwrap().ijToIjk();
 
-        let mut d = Direction::K_AXES_DIGIT;
-        while d != Direction::INVALID_DIGIT {
+        for d in Direction::K_AXES_DIGIT as u64..Direction::INVALID_DIGIT as u64 {
+            let d = Direction::from(d);
             if d == Direction::K_AXES_DIGIT && h3.is_pentagon() {
                 continue;
             }
@@ -1203,7 +1322,58 @@ mod tests {
 
             assert_eq!(ijk, originIjk, "back to origin");
         }
-        d += 1;
+    }
+
+    /**
+     * Test that every index near an origin that can be unfolded into its
+     * local coordinates comes back from them, both through IJK and through
+     * IJ, and that away from pentagons the coordinates are at the grid
+     * distance.
+     */
+    fn localIjToH3_kRing_assertions(h3: H3Index) {
+        let r = usize::from(h3.get_resolution());
+        assert!(
+            r < MAX_DISTANCES.len(),
+            "resolution supported by test function (kRing)"
+        );
+        // at least 2, past the neighbors at res 0
+        let maxK = MAX_DISTANCES[r].max(2) as u32;
+
+        let disk = h3.kRingDistances(maxK);
+        let nearPentagon = disk.iter().any(|(n, _)| n.is_pentagon());
+        for (neighbor, d) in disk {
+            // Don't consider indexes which we can't unfold in the first place
+            let ijk = match h3.h3ToLocalIjk(&neighbor) {
+                Ok(ijk) => ijk,
+                Err(_) => continue,
+            };
+            assert_eq!(
+                h3.localIjkToH3(&ijk),
+                Ok(neighbor),
+                "round trip of {} through IJK around {}",
+                neighbor,
+                h3
+            );
+            if !nearPentagon {
+                let originIjk = h3.h3ToLocalIjk(&h3).unwrap();
+                assert_eq!(
+                    ijk.ijkDistance(&originIjk),
+                    d as i32,
+                    "{} distance",
+                    neighbor
+                );
+            }
+
+            let ij =
+                H3Index::experimentalH3ToLocalIj(h3, neighbor).expect("unfolds to IJ like IJK");
+            assert_eq!(
+                h3.experimentalLocalIjToH3(&ij),
+                Ok(neighbor),
+                "round trip of {} through IJ around {}",
+                neighbor,
+                h3
+            );
+        }
     }
 
     /// Call the callback for every index at the given resolution.
@@ -1217,40 +1387,90 @@ mod tests {
     fn iterateAllIndexesAtResPartial(res: Resolution, cb: fn(H3Index), baseCells: usize) {
         assert!(baseCells <= BaseCell::NUM_BASE_CELLS);
         for i in 0..baseCells {
-            todo!()
-            //iterateBaseCellIndexesAtRes(res, cb, i);
+            iterateBaseCellIndexesAtRes(res, cb, BaseCell::new_unchecked(i as i32));
         }
     }
 
-    /*
-     /// Call the callback for every index at the given resolution in a specific base cell
-    fn iterateBaseCellIndexesAtRes(res: Resolution, cb: fn(H3Index), baseCell: i32) {
-        let mut bc = H3Index::H3_INIT;
-        bc.set_mode(H3Mode::H3_HEXAGON_MODE);
-        bc.set_resolution(Resolution::R0);
-        bc.set_base_cell(baseCell.into());
-
-        let children = bc.uncompact
-        int childrenSz = H3_EXPORT(maxUncompactSize)(&bc, 1, res);
-        H3Index* children = calloc(childrenSz, sizeof(H3Index));
-        H3_EXPORT(uncompact)(&bc, 1, children, childrenSz, res);
-
-        for (int j = 0; j < childrenSz; j++) {
-            if (children[j] == H3_NULL) {
-                continue;
-            }
+    /// Call the callback for every index at the given resolution in a specific base cell
+    fn iterateBaseCellIndexesAtRes(res: Resolution, cb: fn(H3Index), baseCell: BaseCell) {
+        let bc = H3Index::setH3Index(Resolution::R0, baseCell, Direction::CENTER_DIGIT);
+
+        for child in bc.h3ToChildren(res) {
+            cb(child);
+        }
+    }
+
+    #[test]
+    fn localIjToH3_identity() {
+        iterateAllIndexesAtRes(Resolution::R0, localIjToH3_identity_assertions);
+        iterateAllIndexesAtRes(Resolution::R1, localIjToH3_identity_assertions);
+        iterateAllIndexesAtRes(Resolution::R2, localIjToH3_identity_assertions);
+    }
+
+    #[test]
+    fn localIjToH3_kRing() {
+        iterateAllIndexesAtRes(Resolution::R0, localIjToH3_kRing_assertions);
+        iterateAllIndexesAtRes(Resolution::R1, localIjToH3_kRing_assertions);
+        iterateAllIndexesAtRes(Resolution::R2, localIjToH3_kRing_assertions);
+        // The C suite also covers the first 27 base cells at res 3, out to
+        // k 12, which takes half a minute unoptimized.
+        iterateAllIndexesAtResPartial(Resolution::R3, localIjToH3_kRing_assertions, 2);
+    }
+
+    #[test]
+    fn h3ToLocalIj_coordinates() {
+        iterateAllIndexesAtRes(Resolution::R0, h3ToLocalIj_coordinates_assertions);
+        iterateAllIndexesAtRes(Resolution::R1, h3ToLocalIj_coordinates_assertions);
+        iterateAllIndexesAtRes(Resolution::R2, h3ToLocalIj_coordinates_assertions);
+    }
+
+    #[test]
+    fn h3ToLocalIj_neighbors() {
+        iterateAllIndexesAtRes(Resolution::R0, h3ToLocalIj_neighbors_assertions);
+        iterateAllIndexesAtRes(Resolution::R1, h3ToLocalIj_neighbors_assertions);
+        iterateAllIndexesAtRes(Resolution::R2, h3ToLocalIj_neighbors_assertions);
+    }
+
+    #[test]
+    fn gridDistanceKm() {
+        let sf = crate::GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        for &res in [Resolution::R3, Resolution::R7, Resolution::R11].iter() {
+            let origin = sf.geoToH3(res);
+            assert_eq!(
+                origin.grid_distance_km(&origin),
+                Ok(0.),
+                "{:?} same cell",
+                res
+            );
 
-            (*callback)(children[j]);
+            for h in origin.kRing(8) {
+                let steps = origin.h3Distance(&h).expect("nearby");
+                let estimate = origin.grid_distance_km(&h).expect("nearby");
+                let exact = crate::GeoCoord::pointDistKm(&origin.h3ToGeo(), &h.h3ToGeo());
+                if steps >= 3 {
+                    let ratio = estimate / exact;
+                    assert!(
+                        (0.78..=1.42).contains(&ratio),
+                        "{:?} {} ratio {}",
+                        res,
+                        h,
+                        ratio
+                    );
+                }
+            }
         }
 
-        free(children);
+        let origin = sf.geoToH3(Resolution::R5);
+        assert_eq!(
+            origin.grid_distance_km(&sf.geoToH3(Resolution::R6)),
+            Err(H3Error::ResolutionMismatch),
+            "resolutions differ"
+        );
+        let farAway = crate::GeoCoord::from_rads(-0.6, 0.4).geoToH3(Resolution::R5);
+        assert_eq!(
+            origin.grid_distance_km(&farAway),
+            Err(H3Error::Domain),
+            "too far apart"
+        );
     }
-    */
-
-    //#[test]
-    //fn h3ToLocalIj_neighbors() {
-    //iterateAllIndexesAtRes(0, h3ToLocalIj_neighbors_assertions);
-    //iterateAllIndexesAtRes(1, h3ToLocalIj_neighbors_assertions);
-    //iterateAllIndexesAtRes(2, h3ToLocalIj_neighbors_assertions);
-    //}
 }
@@ -1,25 +1,100 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 mod geocoord;
 pub use geocoord::*;
 
 use crate::{
     basecell::BaseCell,
+    budget::Budget,
     constants::{NUM_HEX_VERTS, NUM_PENT_VERTS},
     faceijk::FaceIJK,
     geopolygon::GeoBoundary,
-    Direction, GeoCoord, Resolution,
+    Degrees, Direction, GeoCoord, H3Error, Resolution,
 };
 
 mod algos;
 mod basecell;
+pub use basecell::BaseCellCells;
+mod bits;
+pub use bits::BitLayout;
+mod cell;
+pub use cell::Cell;
+mod chunk;
+pub use chunk::ParentChunks;
+mod compact;
+pub use compact::CompactOrder;
+mod expander;
+pub use expander::RingExpander;
+mod faces;
+pub use faces::FaceSet;
+mod geohash;
+pub use geohash::{geohashFromGeo, MAX_GEOHASHES, MAX_GEOHASH_PRECISION};
 mod h3UniEdge;
+mod indexarray;
+pub use indexarray::IndexArray;
 mod localij;
+mod packed;
+mod pentagon;
+pub use pentagon::PentagonCell;
+mod rle;
+mod rose;
+pub use rose::DirectionRose;
+#[cfg(feature = "rstar")]
+mod rtree;
+#[cfg(feature = "rand")]
+mod sample;
+mod tagged;
+pub use tagged::TaggedH3Index;
+mod traversal;
+pub use traversal::{PentagonPolicy, TraversalBuilder};
 mod vertex;
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+#[repr(transparent)]
 /// The H3Index fits within a 64-bit unsigned integer
+///
+/// Equality, hashing and ordering compare the raw bits. Cells, edges and
+/// vertexes produced by this library are canonical, so each names one
+/// object; see `normalize` for indexes from elsewhere. Ordered by value,
+/// cells sort by resolution, then base cell, then digits, so the
+/// descendants of a cell at any one resolution are contiguous.
+///
+/// The layout is guaranteed to be that of a `u64`, with the same size,
+/// alignment and ABI, so an index can cross FFI as a C `H3Index`, and slices
+/// convert to and from `&[u64]` without copying through `as_u64_slice` and
+/// `from_u64_slice`, such as to fill an Arrow `UInt64Array` buffer.
 pub struct H3Index(u64);
 
+// The layout guarantee above, checked at compile time.
+const _: () = {
+    assert!(std::mem::size_of::<H3Index>() == std::mem::size_of::<u64>());
+    assert!(std::mem::align_of::<H3Index>() == std::mem::align_of::<u64>());
+};
+
+// `setH3Index` and everything else building an index digit by digit starts
+// from `H3_INIT` and only writes the digits up to the index's resolution, so
+// the digits past it must already read 7, and the mode, resolution, base
+// cell, reserved and high bits 0. Checked at compile time, so an edit to the
+// constant breaks the build rather than the indexes.
+const _: () = {
+    let init = H3Index::H3_INIT.0;
+    let mut r = 1;
+    while r <= Resolution::MAX_H3_RES as u64 {
+        let offset = (Resolution::MAX_H3_RES as u64 - r) * H3Index::H3_PER_DIGIT_OFFSET;
+        assert!(
+            (init >> offset) & H3Index::H3_DIGIT_MASK == Direction::INVALID_DIGIT as u64,
+            "every digit of H3_INIT must be 7"
+        );
+        r += 1;
+    }
+    assert!(
+        init >> H3Index::H3_BC_OFFSET == 0,
+        "H3_INIT must have no bits set above its digits"
+    );
+};
+
 impl H3Index {
     /// Invalid index used to indicate an error from geoToH3 and related functions or missing data in arrays of h3 indices. Analogous to NaN in floating point.
     pub(crate) const H3_NULL: H3Index = H3Index(0);
@@ -88,17 +163,19 @@ impl H3Index {
     /// H3 index with mode 0, res 0, base cell 0, and 7 for all index digits.
     /// Typically used to initialize the creation of an H3 cell index, which
     /// expects all direction digits to be 7 beyond the cell's resolution.
+    /// A compile time check above the `impl` holds it to that pattern.
     pub(crate) const H3_INIT: H3Index = H3Index(35184372088831);
 
     /// Gets the highest bit of the H3 index.
+    #[inline]
     fn get_high_bit(&self) -> u64 {
         self.0 >> Self::H3_MAX_OFFSET
     }
 
     /// Gets the integer mode of h3.
+    #[inline]
     pub(crate) fn get_mode(&self) -> H3Mode {
         let m = (self.0 & Self::H3_MODE_MASK) >> Self::H3_MODE_OFFSET;
-        println!("Getting mode for {:?}: {}", self, m);
         m.into()
     }
 
@@ -109,9 +186,10 @@ impl H3Index {
     }
 
     /// Gets the integer base cell of h3.
+    #[inline]
     pub(crate) fn get_base_cell(&self) -> BaseCell {
         let bc = (self.0 & Self::H3_BC_MASK) >> Self::H3_BC_OFFSET;
-        BaseCell::new(bc as i32)
+        BaseCell::new_unchecked(bc as i32)
     }
 
     /// Sets the integer base cell of h3 to bc.
@@ -121,12 +199,14 @@ impl H3Index {
     }
 
     /// Gets the integer resolution of h3.
+    #[inline]
     pub(crate) fn get_resolution(&self) -> Resolution {
         let r = (self.0 & Self::H3_RES_MASK) >> Self::H3_RES_OFFSET;
         Resolution::from(r)
     }
 
     /// Sets the integer resolution of h3.
+    #[inline]
     pub(crate) fn set_resolution(&mut self, res: Resolution) {
         let i: usize = res.into();
         self.0 = (self.0 & Self::H3_RES_MASK_NEGATIVE) | ((i as u64) << Self::H3_RES_OFFSET);
@@ -138,6 +218,7 @@ impl H3Index {
     }
 
     /// Gets a value in the reserved space. Should always be zero for valid indexes.
+    #[inline]
     pub(crate) fn get_reserved_bits(&self) -> u64 {
         (self.0 & Self::H3_RESERVED_MASK) >> Self::H3_RESERVED_OFFSET
     }
@@ -148,6 +229,7 @@ impl H3Index {
     }
 
     /// Gets the resolution res integer digit (0-7) of h3.
+    #[inline]
     pub(crate) fn get_index_digit(&self, res: Resolution) -> Direction {
         let r = usize::from(res) as u64;
         let d = (self.0 >> ((Resolution::MAX_H3_RES as u64 - r) * Self::H3_PER_DIGIT_OFFSET))
@@ -157,6 +239,7 @@ impl H3Index {
     }
 
     /// Sets the resolution res digit of h3 to the integer digit (0-7)
+    #[inline]
     pub(crate) fn set_index_digit(&mut self, res: Resolution, digit: u64) {
         let r = usize::from(res) as u64;
         self.0 = (self.0
@@ -169,12 +252,17 @@ impl H3Index {
      * Determines the spherical coordinates of the center point of an H3 index.
      *
      * @param h3 The H3 index.
-     * @param g The spherical coordinates of the H3 cell center.
+     * @return The spherical coordinates of the H3 cell center; NaN if the base
+     *         cell is out of range
      */
     pub fn h3ToGeo(&self) -> GeoCoord {
-        let fijk = self._h3ToFaceIjk();
-        let res = self.get_resolution();
-        fijk._faceIjkToGeo(res)
+        match self._h3ToFaceIjk() {
+            Ok(fijk) => fijk._faceIjkToGeo(self.get_resolution()),
+            Err(_) => GeoCoord {
+                lat: f64::NAN,
+                lon: f64::NAN,
+            },
+        }
     }
 
     /**
@@ -183,6 +271,7 @@ impl H3Index {
      * @param h The H3Index to check.
      * @return Returns 1 if it is a pentagon, otherwise 0.
      */
+    #[inline]
     pub fn is_pentagon(&self) -> bool {
         self.get_base_cell()._isBaseCellPentagon()
             && self._h3LeadingNonZeroDigit() == Direction::CENTER_DIGIT
@@ -211,23 +300,51 @@ impl H3Index {
      * @param h H3Index to find parent of
      * @param parentRes The resolution to switch to (parent, grandparent, etc)
      *
-     * @return H3Index of the parent, or H3_NULL if you actually asked for a child
+     * @return H3Index of the parent, or ResolutionMismatch if you actually asked
+     *         for a child
      */
-    pub fn h3ToParent(&mut self, parentRes: Resolution) -> Self {
+    #[inline]
+    pub fn h3ToParent(&self, parentRes: Resolution) -> Result<Self, H3Error> {
         let childRes = self.get_resolution();
         if parentRes > childRes {
-            return Self::H3_NULL;
+            return Err(H3Error::ResolutionMismatch);
         } else if parentRes == childRes {
-            return *self;
+            return Ok(*self);
         }
 
-        self.set_resolution(parentRes);
         let mut parentH = *self;
+        parentH.set_resolution(parentRes);
         for i in parentRes as u64 + 1..=childRes as u64 {
             parentH.set_index_digit(i.into(), Self::H3_DIGIT_MASK);
         }
 
-        parentH
+        Ok(parentH)
+    }
+
+    /**
+     * Whether two cells, at any resolutions, intersect in the hierarchy: they
+     * are the same cell, or one is an ancestor of the other. Distinct cells at
+     * one resolution never do, as they share at most an edge.
+     *
+     * This is the hierarchy's answer, the one query planners need to prune by
+     * index prefix, not the geometric one: a cell's children stick out a little
+     * past its boundary, so a cell can overlap cells of a coarser resolution
+     * other than its ancestor, and this still says false.
+     *
+     * @param other Another cell
+     * @return Whether the cells are equal or one contains the other; false if
+     *         either is not a valid cell
+     */
+    pub fn intersects(&self, other: &H3Index) -> bool {
+        if !self.is_valid() || !other.is_valid() {
+            return false;
+        }
+        let (coarse, fine) = if self.get_resolution() <= other.get_resolution() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        fine.h3ToParent(coarse.get_resolution()) == Ok(*coarse)
     }
 
     /**
@@ -240,6 +357,7 @@ impl H3Index {
      * @return int count of maximum number of children (equal for hexagons, less for
      * pentagons
      */
+    #[inline]
     pub fn maxH3ToChildrenSize(&self, childRes: Resolution) -> u64 {
         let parentRes = self.get_resolution();
         if !parentRes._isValidChildRes(&childRes) {
@@ -262,7 +380,8 @@ impl H3Index {
      * @return H3Index of the center child, or H3_NULL if you actually asked for a
      * parent
      */
-    pub fn h3ToCenterChild(&mut self, childRes: Resolution) -> Self {
+    #[inline]
+    pub fn h3ToCenterChild(&self, childRes: Resolution) -> Self {
         let parentRes = self.get_resolution();
         if !parentRes._isValidChildRes(&childRes) {
             return Self::H3_NULL;
@@ -270,8 +389,8 @@ impl H3Index {
             return *self;
         }
 
-        self.set_resolution(childRes);
         let mut child = *self;
+        child.set_resolution(childRes);
         for i in parentRes as u64 + 1..=childRes as u64 {
             child.set_index_digit(i.into(), 0);
         }
@@ -287,6 +406,7 @@ impl H3Index {
      * @param h The H3Index to check.
      * @return Returns 1 if the hexagon is class III, otherwise 0.
      */
+    #[inline]
     pub fn h3IsResClassIII(&self) -> bool {
         self.get_resolution() as u64 % 2 == 1
     }
@@ -304,7 +424,7 @@ impl H3Index {
             h.set_index_digit(r.into(), old_digit);
         }
 
-        *self
+        h
     }
 
     /**
@@ -320,7 +440,7 @@ impl H3Index {
             h.set_index_digit(r.into(), old_digit);
         }
 
-        *self
+        h
     }
 
     /// Rotate an H3Index 60 degrees counter-clockwise about a pentagonal center.
@@ -333,7 +453,7 @@ impl H3Index {
             // rotate this digit
             let r = r.into();
             let digit = h.get_index_digit(r).rotate60ccw();
-            h.set_index_digit(r.into(), digit.into());
+            h.set_index_digit(r, digit.into());
 
             // look for the first non-zero digit so we can adjust for deleted k-axes sequence if necessary
             if !foundFirstNonZeroDigit && h.get_index_digit(r) != Direction::CENTER_DIGIT {
@@ -359,7 +479,7 @@ impl H3Index {
             // rotate this digit
             let r = r.into();
             let digit = h.get_index_digit(r).rotate60cw();
-            h.set_index_digit(r.into(), digit.into());
+            h.set_index_digit(r, digit.into());
 
             // look for the first non-zero digit so we can adjust for deleted k-axes sequence if necessary
             if !foundFirstNonZeroDigit && h.get_index_digit(r) != Direction::CENTER_DIGIT {
@@ -386,7 +506,7 @@ impl H3Index {
         let res = self.get_resolution();
 
         // center base cell hierarchy is entirely on this face
-        let mut possibleOverage = false;
+        let mut possibleOverage = true;
 
         if !self.get_base_cell()._isBaseCellPentagon()
             && (res == Resolution::R0
@@ -413,7 +533,7 @@ impl H3Index {
 
     /// The number of pentagons (same at any resolution)
     pub fn pentagonIndexCount() -> i32 {
-        crate::constants::NUM_PENTAGONS
+        crate::constants::NUM_PENTAGONS as i32
     }
 
     /**
@@ -422,30 +542,26 @@ impl H3Index {
      * @param res The resolution to produce pentagons at.
      * @param out Output array. Must be of size pentagonIndexCount().
      */
-    pub fn getPentagonIndexes(res: Resolution) -> [Self; BaseCell::NUM_BASE_CELLS] {
-        let mut result = [H3Index::H3_NULL; BaseCell::NUM_BASE_CELLS];
+    pub fn getPentagonIndexes(res: Resolution) -> [Self; crate::constants::NUM_PENTAGONS] {
+        res.getPentagonIndexes()
+    }
 
-        for bc in 0..BaseCell::NUM_BASE_CELLS as i32 {
-            let basecell = BaseCell::new(bc);
-            if basecell._isBaseCellPentagon() {
-                let pentagon = Self::setH3Index(res, basecell, Direction::CENTER_DIGIT);
-                result[bc as usize] = pentagon;
-            }
-        }
+    /// Views indexes as their raw values, without copying.
+    pub fn as_u64_slice(indexes: &[H3Index]) -> &[u64] {
+        // SAFETY: H3Index is repr(transparent) over u64, so the slices have
+        // the same layout, and the borrow keeps the lifetime.
+        unsafe { std::slice::from_raw_parts(indexes.as_ptr().cast::<u64>(), indexes.len()) }
+    }
 
-        /*
-        for bc in 0..BaseCell::NUM_BASE_CELLS {
-            let basecell = BaseCell::new(bc as i32);
-            if basecell._isBaseCellPentagon() {
-                let pentagon = Self::setH3Index(res, basecell, Direction::CENTER_DIGIT);
-                result[bc] = pentagon;
-            }
-        }
-        */
-        result
+    /// Views raw values as indexes, without copying. Like `From<u64>`, the
+    /// values are not validated.
+    pub fn from_u64_slice(values: &[u64]) -> &[H3Index] {
+        // SAFETY: as for `as_u64_slice`; every u64 is a possible H3Index.
+        unsafe { std::slice::from_raw_parts(values.as_ptr().cast::<H3Index>(), values.len()) }
     }
 
     /// Returns whether or not an H3 index is a valid cell (hexagon or pentagon).
+    #[inline]
     pub fn is_valid(&self) -> bool {
         if self.get_high_bit() != 0 {
             return false;
@@ -460,7 +576,7 @@ impl H3Index {
         }
 
         let baseCell = self.get_base_cell();
-        if baseCell.0 < 0 || baseCell.0 as usize >= BaseCell::NUM_BASE_CELLS {
+        if baseCell.index().is_none() {
             // LCOV_EXCL_BR_LINE
             // Base cells less than zero can not be represented in an index
             return false;
@@ -468,7 +584,7 @@ impl H3Index {
 
         let res = self.get_resolution();
 
-        if res as usize >= Resolution::MAX_H3_RES {
+        if res as usize > Resolution::MAX_H3_RES {
             // Resolutions less than zero can not be represented in an index
             return false;
         }
@@ -499,6 +615,119 @@ impl H3Index {
         true
     }
 
+    /**
+     * Canonical form of a cell, unidirectional edge or vertex index, so that
+     * indexes naming the same object compare and hash equal.
+     *
+     * A cell has a single representation, as does an edge: its origin cell
+     * and the direction to the destination. A vertex is shared by up to three
+     * cells and belongs to the one with the lowest index, its owner; a vertex
+     * index built from any other cell is rewritten to the owner's vertex
+     * number. Indexes returned by this library are already canonical.
+     *
+     * @return The canonical index, or None if this is not a valid cell, edge
+     *         or vertex
+     */
+    pub fn normalize(&self) -> Option<H3Index> {
+        match self.get_mode() {
+            H3Mode::H3_HEXAGON_MODE if self.is_valid() => Some(*self),
+            H3Mode::H3_UNIEDGE_MODE if self.h3UnidirectionalEdgeIsValid() => Some(*self),
+            H3Mode::H3_VERTEX_MODE if self.get_high_bit() == 0 => {
+                let vertexNum = self.get_reserved_bits() as usize;
+                let mut cell = *self;
+                cell.set_mode(H3Mode::H3_HEXAGON_MODE);
+                cell.set_reserved_bits(0);
+
+                if !cell.is_valid() {
+                    return None;
+                }
+                match cell.cellToVertex(vertexNum) {
+                    H3Index::H3_NULL => None,
+                    vertex => Some(vertex),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /**
+     * Recovers an index written by a producer that mishandles the bits a
+     * valid index leaves unused, instead of rejecting it: digits past the
+     * resolution are set back to 7, and for a cell the reserved bits are
+     * cleared. Everything else must be valid as is: the mode, high bit, base
+     * cell and the digits up to the resolution are not guessed at.
+     *
+     * Edges and vertexes keep their reserved bits, which hold the direction or
+     * vertex number, and come back canonical, as from `normalize`.
+     *
+     * @param raw The id as stored
+     * @return The repaired index, or None if it is not a cell, edge or vertex
+     *         once repaired
+     */
+    pub fn sanitize(raw: u64) -> Option<H3Index> {
+        let mut h = H3Index(raw);
+        for r in usize::from(h.get_resolution()) + 1..=Resolution::MAX_H3_RES {
+            h.set_index_digit(r.into(), Self::H3_DIGIT_MASK);
+        }
+        if h.get_mode() == H3Mode::H3_HEXAGON_MODE {
+            h.set_reserved_bits(0);
+        }
+        h.normalize()
+    }
+
+    /**
+     * The cell at a resolution containing a point given in degrees; the
+     * checked form of `GeoCoord::geoToH3`, which returns H3_NULL for bad
+     * input.
+     *
+     * @param lat Latitude, -90 to 90 degrees
+     * @param lng Longitude, -180 to 180 degrees
+     * @param res The resolution of the cell
+     * @return The cell, or Domain if a coordinate is not finite or is out of
+     *         range
+     */
+    pub fn from_point(lat: Degrees, lng: Degrees, res: Resolution) -> Result<Self, H3Error> {
+        if !(-90.0..=90.0).contains(&lat.0) || !(-180.0..=180.0).contains(&lng.0) {
+            return Err(H3Error::Domain);
+        }
+        match GeoCoord::new(lat, lng).geoToH3(res) {
+            H3Index::H3_NULL => Err(H3Error::Domain),
+            h => Ok(h),
+        }
+    }
+
+    /**
+     * The cell with a base cell and a digit for each resolution, instead of
+     * setting the bits of an index one by one; the resolution is the number
+     * of digits.
+     *
+     * @param base_cell The resolution 0 ancestor
+     * @param digits The digits from resolution 1 down
+     * @return The cell; Domain if there are more than 15 digits or one is
+     *         INVALID_DIGIT, or CellInvalid if the first digit other than
+     *         CENTER_DIGIT under a pentagon is the deleted K_AXES_DIGIT
+     */
+    pub fn from_components(base_cell: BaseCell, digits: &[Direction]) -> Result<Self, H3Error> {
+        if digits.len() > Resolution::MAX_H3_RES
+            || base_cell.index().is_none()
+            || digits.contains(&Direction::INVALID_DIGIT)
+        {
+            return Err(H3Error::Domain);
+        }
+        let mut h = Self::setH3Index(
+            Resolution::from(digits.len()),
+            base_cell,
+            Direction::CENTER_DIGIT,
+        );
+        for (r, &digit) in (1..).zip(digits.iter()) {
+            h.set_index_digit(Resolution::from(r), digit as u64);
+        }
+        if !h.is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        Ok(h)
+    }
+
     /**
      * Initializes an H3 index.
      * @param hp The H3 index to initialize.
@@ -520,242 +749,97 @@ impl H3Index {
     }
 
     /**
-     * compact takes a set of hexagons all at the same resolution and compresses
-     * them by pruning full child branches to the parent level. This is also done
-     * for all parents recursively to get the minimum number of hex addresses that
-     * perfectly cover the defined space.
-     * @param h3Set Set of hexagons
-     * @param compactedSet The output array of compressed hexagons (preallocated)
-     * @param numHexes The size of the input and output arrays (possible that no
-     * contiguous regions exist in the set at all and no compression possible)
-     * @return an error code on bad input data
+     * Prunes full child branches of a set of hexagons to their parents, in no
+     * particular order; `compact` and `compact_ordered` order the result.
+     * Each pass charges the hexagons it examines against the budget.
+     *
+     * Each pass groups the remaining hexagons by parent, recording which of
+     * the 7 children are present as bits of a mask. A parent with every child
+     * present, 6 for a pentagon, goes on to the next pass, one resolution
+     * coarser; the children of any other parent are final.
+     *
+     * @param h3Set Set of hexagons, all at the same resolution; H3_NULL
+     *        entries are skipped
+     * @param budget Limit on the hexagons examined
+     * @return The compacted hexagons, or CellInvalid for an index that is not
+     *         a cell, ResolutionMismatch if the cells are not all at one
+     *         resolution, DuplicateInput if a cell appears twice, or Cancelled
+     *         if the budget ran out
      */
-    pub fn compact(h3Set: &[H3Index]) -> Result<Vec<H3Index>, i32> {
-        if h3Set.is_empty() {
-            return Ok(h3Set.iter().cloned().collect());
+    fn compactUnordered(h3Set: &[H3Index], budget: &mut Budget) -> Result<Vec<H3Index>, H3Error> {
+        let mut remaining: Vec<H3Index> = h3Set
+            .iter()
+            .copied()
+            .filter(|&h| h != H3Index::H3_NULL)
+            .collect();
+        let mut res = match remaining.first() {
+            Some(h) => h.get_resolution(),
+            None => return Ok(remaining),
+        };
+        budget.charge(remaining.len())?;
+        for h in remaining.iter() {
+            if !h.is_valid() {
+                return Err(H3Error::CellInvalid);
+            }
+            if h.get_resolution() != res {
+                return Err(H3Error::ResolutionMismatch);
+            }
         }
 
-        let res = h3Set[0].get_resolution();
-
         if res == Resolution::R0 {
-            // No compaction possible, just copy the set to output
-            return Ok(h3Set.iter().cloned().collect());
-        }
-
-        /*
-        H3Index* remainingHexes = H3_MEMORY(malloc)(numHexes * sizeof(H3Index));
-        if (!remainingHexes) {
-            return COMPACT_ALLOC_FAILED;
-        }
-        memcpy(remainingHexes, h3Set, numHexes * sizeof(H3Index));
-        H3Index* hashSetArray = H3_MEMORY(calloc)(numHexes, sizeof(H3Index));
-        if (!hashSetArray) {
-            H3_MEMORY(free)(remainingHexes);
-            return COMPACT_ALLOC_FAILED;
-        }
-        H3Index* compactedSetOffset = compactedSet;
-        int numRemainingHexes = numHexes;
-        while (numRemainingHexes) {
-            res = H3_GET_RESOLUTION(remainingHexes[0]);
-            int parentRes = res - 1;
-            // Put the parents of the hexagons into the temp array
-            // via a hashing mechanism, and use the reserved bits
-            // to track how many times a parent is duplicated
-            for (int i = 0; i < numRemainingHexes; i++) {
-                H3Index currIndex = remainingHexes[i];
-                if (currIndex != 0) {
-                    H3Index parent = H3_EXPORT(h3ToParent)(currIndex, parentRes);
-                    // Modulus hash the parent into the temp array
-                    int loc = (int)(parent % numRemainingHexes);
-                    int loopCount = 0;
-                    while (hashSetArray[loc] != 0) {
-                        if (loopCount > numRemainingHexes) {  // LCOV_EXCL_BR_LINE
-                            // LCOV_EXCL_START
-                            // This case should not be possible because at most one
-                            // index is placed into hashSetArray per
-                            // numRemainingHexes.
-                            H3_MEMORY(free)(remainingHexes);
-                            H3_MEMORY(free)(hashSetArray);
-                            return COMPACT_LOOP_EXCEEDED;
-                            // LCOV_EXCL_STOP
-                        }
-                        H3Index tempIndex =
-                            hashSetArray[loc] & H3_RESERVED_MASK_NEGATIVE;
-                        if (tempIndex == parent) {
-                            int count = H3_GET_RESERVED_BITS(hashSetArray[loc]) + 1;
-                            int limitCount = 7;
-                            if (H3_EXPORT(h3IsPentagon)(
-                                    tempIndex & H3_RESERVED_MASK_NEGATIVE)) {
-                                limitCount--;
-                            }
-                            // One is added to count for this check to match one
-                            // being added to count later in this function when
-                            // checking for all children being present.
-                            if (count + 1 > limitCount) {
-                                // Only possible on duplicate input
-                                H3_MEMORY(free)(remainingHexes);
-                                H3_MEMORY(free)(hashSetArray);
-                                return COMPACT_DUPLICATE;
-                            }
-                            H3_SET_RESERVED_BITS(parent, count);
-                            hashSetArray[loc] = H3_NULL;
-                        } else {
-                            loc = (loc + 1) % numRemainingHexes;
-                        }
-                        loopCount++;
-                    }
-                    hashSetArray[loc] = parent;
-                }
-            }
-            // Determine which parent hexagons have a complete set
-            // of children and put them in the compactableHexes array
-            int compactableCount = 0;
-            int maxCompactableCount =
-                numRemainingHexes / 6;  // Somehow all pentagons; conservative
-            if (maxCompactableCount == 0) {
-                memcpy(compactedSetOffset, remainingHexes,
-                       numRemainingHexes * sizeof(remainingHexes[0]));
-                break;
+            // No compaction possible, but repeats are still an error
+            let mut seen = HashSet::with_capacity(remaining.len());
+            if !remaining.iter().all(|h| seen.insert(*h)) {
+                return Err(H3Error::DuplicateInput);
             }
-            H3Index* compactableHexes =
-                H3_MEMORY(calloc)(maxCompactableCount, sizeof(H3Index));
-            if (!compactableHexes) {
-                H3_MEMORY(free)(remainingHexes);
-                H3_MEMORY(free)(hashSetArray);
-                return COMPACT_ALLOC_FAILED;
-            }
-            for (int i = 0; i < numRemainingHexes; i++) {
-                if (hashSetArray[i] == 0) continue;
-                int count = H3_GET_RESERVED_BITS(hashSetArray[i]) + 1;
-                // Include the deleted direction for pentagons as implicitly "there"
-                if (H3_EXPORT(h3IsPentagon)(hashSetArray[i] &
-                                            H3_RESERVED_MASK_NEGATIVE)) {
-                    // We need this later on, no need to recalculate
-                    H3_SET_RESERVED_BITS(hashSetArray[i], count);
-                    // Increment count after setting the reserved bits,
-                    // since count is already incremented above, so it
-                    // will be the expected value for a complete hexagon.
-                    count++;
-                }
-                if (count == 7) {
-                    // Bingo! Full set!
-                    compactableHexes[compactableCount] =
-                        hashSetArray[i] & H3_RESERVED_MASK_NEGATIVE;
-                    compactableCount++;
-                }
-            }
-            // Uncompactable hexes are immediately copied into the
-            // output compactedSetOffset
-            int uncompactableCount = 0;
-            for (int i = 0; i < numRemainingHexes; i++) {
-                H3Index currIndex = remainingHexes[i];
-                if (currIndex != H3_NULL) {
-                    H3Index parent = H3_EXPORT(h3ToParent)(currIndex, parentRes);
-                    // Modulus hash the parent into the temp array
-                    // to determine if this index was included in
-                    // the compactableHexes array
-                    int loc = (int)(parent % numRemainingHexes);
-                    int loopCount = 0;
-                    bool isUncompactable = true;
-                    do {
-                        if (loopCount > numRemainingHexes) {  // LCOV_EXCL_BR_LINE
-                            // LCOV_EXCL_START
-                            // This case should not be possible because at most one
-                            // index is placed into hashSetArray per input hexagon.
-                            H3_MEMORY(free)(compactableHexes);
-                            H3_MEMORY(free)(remainingHexes);
-                            H3_MEMORY(free)(hashSetArray);
-                            return COMPACT_LOOP_EXCEEDED;
-                            // LCOV_EXCL_STOP
-                        }
-                        H3Index tempIndex =
-                            hashSetArray[loc] & H3_RESERVED_MASK_NEGATIVE;
-                        if (tempIndex == parent) {
-                            int count = H3_GET_RESERVED_BITS(hashSetArray[loc]) + 1;
-                            if (count == 7) {
-                                isUncompactable = false;
-                            }
-                            break;
-                        } else {
-                            loc = (loc + 1) % numRemainingHexes;
-                        }
-                        loopCount++;
-                    } while (hashSetArray[loc] != parent);
-                    if (isUncompactable) {
-                        compactedSetOffset[uncompactableCount] = remainingHexes[i];
-                        uncompactableCount++;
-                    }
-                }
-            }
-            // Set up for the next loop
-            memset(hashSetArray, 0, numHexes * sizeof(H3Index));
-            compactedSetOffset += uncompactableCount;
-            memcpy(remainingHexes, compactableHexes,
-                   compactableCount * sizeof(H3Index));
-            numRemainingHexes = compactableCount;
-            H3_MEMORY(free)(compactableHexes);
+            return Ok(remaining);
         }
-        H3_MEMORY(free)(remainingHexes);
-        H3_MEMORY(free)(hashSetArray);
-        return COMPACT_SUCCESS;
-        */
-        todo!()
-    }
-
-    /**
-     * uncompact takes a compressed set of hexagons and expands back to the
-     * original set of hexagons.
-     * @param compactedSet Set of hexagons
-     * @param numHexes The number of hexes in the input set
-     * @param h3Set Output array of decompressed hexagons (preallocated)
-     * @param maxHexes The size of the output array to bound check against
-     * @param res The hexagon resolution to decompress to
-     * @return An error code if output array is too small or any hexagon is
-     * smaller than the output resolution.
-     */
-    pub fn uncompact(
-        compactedSet: Vec<H3Index>,
-        res: Resolution,
-        maxHexes: usize,
-    ) -> Result<Vec<H3Index>, i32> {
-        let numHexes = compactedSet.len();
 
-        let mut h3Set = Vec::new();
+        // Child masks of complete parents: every digit but k for pentagons.
+        const ALL_CHILDREN: u8 = 0b111_1111;
+        const PENTAGON_CHILDREN: u8 = ALL_CHILDREN & !(1 << Direction::K_AXES_DIGIT as u8);
 
-        for i in 0..numHexes {
-            if compactedSet[i] == H3Index::H3_NULL {
-                continue;
-            }
-
-            if h3Set.len() > maxHexes {
-                // We went too far, abort!
-                return Err(-1);
+        let mut compacted = Vec::new();
+        let mut first = true;
+        while res > Resolution::R0 && !remaining.is_empty() {
+            if !first {
+                budget.charge(remaining.len())?;
             }
-
-            let currentRes = compactedSet[i].get_resolution();
-            if !currentRes._isValidChildRes(&res) {
-                // Nonsensical. Abort.
-                return Err(-2);
+            first = false;
+
+            let parentRes = res - 1;
+            let mut children: HashMap<H3Index, u8> = HashMap::with_capacity(remaining.len());
+            for h in remaining.drain(..) {
+                let parent = h.h3ToParent(parentRes)?;
+                let bit = 1 << h.get_index_digit(res) as u8;
+                let mask = children.entry(parent).or_insert(0);
+                if *mask & bit != 0 {
+                    return Err(H3Error::DuplicateInput);
+                }
+                *mask |= bit;
             }
 
-            if currentRes == res {
-                // Just copy and move along
-                h3Set.push(compactedSet[i]);
-            } else {
-                // Bigger hexagon to reduce in size
-                let numHexesToGen = compactedSet[i].maxH3ToChildrenSize(res);
-
-                if h3Set.len() + numHexesToGen as usize > maxHexes {
-                    // We're about to go too far, abort!
-                    return Err(-1);
+            for (parent, mask) in children {
+                let complete = if parent.is_pentagon() {
+                    PENTAGON_CHILDREN
+                } else {
+                    ALL_CHILDREN
+                };
+                if mask == complete {
+                    remaining.push(parent);
+                } else {
+                    compacted.extend(
+                        (0..7)
+                            .filter(|digit| mask & (1 << digit) != 0)
+                            .map(|digit| parent.makeDirectChild(digit)),
+                    );
                 }
-
-                todo!()
-                //H3_EXPORT(h3ToChildren)(compactedSet[i], res, h3Set + outOffset);
             }
+            res = parentRes;
         }
+        compacted.extend(remaining);
 
-        Ok(h3Set)
+        Ok(compacted)
     }
 
     /**
@@ -763,6 +847,8 @@ impl H3Index {
      * at the specified resolution storing them into the provided memory pointer.
      * It's assumed that maxH3ToChildrenSize was used to determine the allocation.
      *
+     * The children are in ascending index order.
+     *
      * @param h H3Index to find the children of
      * @param childRes int the child level to produce
      * @param children H3Index* the memory to store the resulting addresses in
@@ -779,8 +865,6 @@ impl H3Index {
             return results;
         }
 
-        let bufferSize = self.maxH3ToChildrenSize(childRes);
-        let bufferChildStep = bufferSize / 7;
         let isAPentagon = self.is_pentagon();
 
         for i in 0..7 {
@@ -803,6 +887,70 @@ impl H3Index {
         results
     }
 
+    /**
+     * Finds the child that `h3ToChildren` would return at position n, without
+     * generating the others, for random access into the children of a cell.
+     *
+     * The position is read as a base 7 number with a digit per resolution
+     * below the cell's, most significant first. Below a pentagon, the deleted
+     * k subsequence is skipped, so positions stay contiguous.
+     *
+     * @param childRes The resolution of the descendants
+     * @param n Position of the descendant, from 0
+     * @return The descendant, ResolutionMismatch if childRes is coarser than
+     *         the cell, or Domain if the cell has no more than n descendants
+     */
+    pub fn nth_descendant(&self, childRes: Resolution, n: u64) -> Result<H3Index, H3Error> {
+        let parentRes = self.get_resolution();
+        if !parentRes._isValidChildRes(&childRes) {
+            return Err(H3Error::ResolutionMismatch);
+        }
+
+        // Number of descendants of a hexagon and of a pentagon, `depth`
+        // resolutions down.
+        let hexagonCount = |depth: u64| 7u64.pow(depth as u32);
+        let pentagonCount = |depth: u64| 1 + 5 * (hexagonCount(depth) - 1) / 6;
+
+        let depth = childRes as u64 - parentRes as u64;
+        let mut onPentagon = self.is_pentagon();
+        let count = if onPentagon {
+            pentagonCount(depth)
+        } else {
+            hexagonCount(depth)
+        };
+        if n >= count {
+            return Err(H3Error::Domain);
+        }
+
+        let mut out = *self;
+        out.set_resolution(childRes);
+        let mut n = n;
+        for r in parentRes as u64 + 1..=childRes as u64 {
+            let below = childRes as u64 - r;
+            let digit = if onPentagon {
+                // The center child is the pentagon, and comes first; the
+                // hexagon children follow from the j axis on.
+                let centerCount = pentagonCount(below);
+                if n < centerCount {
+                    0
+                } else {
+                    n -= centerCount;
+                    onPentagon = false;
+                    let digit = n / hexagonCount(below) + Direction::J_AXES_DIGIT as u64;
+                    n %= hexagonCount(below);
+                    digit
+                }
+            } else {
+                let digit = n / hexagonCount(below);
+                n %= hexagonCount(below);
+                digit
+            };
+            out.set_index_digit(r.into(), digit);
+        }
+
+        Ok(out)
+    }
+
     /**
      * makeDirectChild takes an index and immediately returns the immediate child
      * index based on the specified cell number. Bit operations only, could generate
@@ -823,61 +971,18 @@ impl H3Index {
     }
 
     /**
-     * uncompact takes a compressed set of hexagons and expands back to the
-     * original set of hexagons.
-     * @param compactedSet Set of hexagons
-     * @param numHexes The number of hexes in the input set
-     * @param h3Set Output array of decompressed hexagons (preallocated)
-     * @param maxHexes The size of the output array to bound check against
-     * @param res The hexagon resolution to decompress to
-     * @return An error code if output array is too small or any hexagon is smaller than the output resolution.
-     */
-    pub fn uncompact_x(compactedSet: Vec<H3Index>, res: Resolution) -> Result<Vec<H3Index>, i32> {
-        let mut results = Vec::new();
-
-        for h in compactedSet {
-            if h == H3Index::H3_NULL {
-                continue;
-            }
-
-            let currentRes = h.get_resolution();
-            if !currentRes._isValidChildRes(&res) {
-                // Nonsensical. Abort.
-                return Err(-2);
-            }
-
-            if currentRes == res {
-                // Just copy and move along
-                results.push(h);
-            } else {
-                // Bigger hexagon to reduce in size
-                todo!()
-                /*
-                let numHexesToGen = H3_EXPORT(maxH3ToChildrenSize)(compactedSet[i], res);
-                if (outOffset + numHexesToGen > maxHexes) {
-                    // We're about to go too far, abort!
-                    return Err(-1);
-                }
-                H3_EXPORT(h3ToChildren)(compactedSet[i], res, h3Set + outOffset);
-                outOffset += numHexesToGen;
-                */
-            }
-        }
-
-        Ok(results)
-    }
-
-    /**
-     * Find all icosahedron faces intersected by a given H2 index, represented
-     * as integers from -1-19. The array is sparse; since 0 is a valid value,
-     * invalid array values are represented as -2. It is the responsibility of
-     * the caller to filter out invalid values.
+     * Find all icosahedron faces intersected by a given H3 index, represented
+     * as integers from 0-19.
+     *
+     * The faces are kept inline, up to `max_face_count` of them, so this
+     * does not allocate. They iterate in ascending order, the same on every
+     * run.
      *
-     * @param h2 The H3 index
-     * @param out Output array. Must be of size maxFaceCount(h2).
+     * @param h3 The H3 index
+     * @return The faces, empty for an invalid index
      */
-    pub fn h3GetFaces(&self) -> HashSet<i32> {
-        let mut res = self.get_resolution();
+    pub fn h3GetFaces(&self) -> FaceSet {
+        let res = self.get_resolution();
         let isPentagon = self.is_pentagon();
 
         // We can't use the vertex-based approach here for class II pentagons,
@@ -891,10 +996,14 @@ impl H3Index {
             return out;
         }
 
-        let mut out = HashSet::new();
+        let mut out = FaceSet::default();
 
         // convert to FaceIJK
-        let mut fijk = self._h3ToFaceIjk();
+        let mut fijk = match self._h3ToFaceIjk() {
+            Ok(fijk) => fijk,
+            Err(_) => return out,
+        };
+        let mut res = res as usize;
 
         // Get all vertices as FaceIJK addresses. For simplicity, always
         // initialize the array with 5 verts, ignoring the last one for pentagons
@@ -939,28 +1048,39 @@ impl From<H3Index> for u64 {
     }
 }
 
-impl ToString for H3Index {
-    fn to_string(&self) -> String {
-        format!("{:x}", self.0)
+impl From<u64> for H3Index {
+    fn from(h3: u64) -> Self {
+        H3Index(h3)
+    }
+}
+
+impl std::fmt::Display for H3Index {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:x}", self.0)
     }
 }
 
 impl FromStr for H3Index {
-    type Err = ();
+    type Err = H3Error;
 
+    /// Reads an index written in hexadecimal, as `Display` writes it,
+    /// without checking that it is valid; Domain if `s` is not hexadecimal
+    /// or is more than 64 bits.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let n: u64 = u64::from_str_radix(s, 16).map_err(|_| ())?;
+        let n: u64 = u64::from_str_radix(s, 16).map_err(|_| H3Error::Domain)?;
         Ok(H3Index(n))
     }
 }
 
 /// H3 index modes
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub(crate) enum H3Mode {
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum H3Mode {
     H3_HEXAGON_MODE = 1,
     H3_UNIEDGE_MODE = 2,
     H3_EDGE_MODE = 3,
     H3_VERTEX_MODE = 4,
+    /// Any other mode value; such indexes are never valid
+    H3_INVALID_MODE = 0,
 }
 
 impl From<u64> for H3Mode {
@@ -970,7 +1090,7 @@ impl From<u64> for H3Mode {
             2 => H3Mode::H3_UNIEDGE_MODE,
             3 => H3Mode::H3_EDGE_MODE,
             4 => H3Mode::H3_VERTEX_MODE,
-            _ => panic!("Unexpected value {} for H3Mode", v),
+            _ => H3Mode::H3_INVALID_MODE,
         }
     }
 }
@@ -980,6 +1100,140 @@ mod test {
     use super::*;
     const PADDED_COUNT: usize = 16;
 
+    #[test]
+    fn fromPoint() {
+        let origin = H3Index::from_point(Degrees(37.779265), Degrees(-122.419277), Resolution::R9);
+        assert_eq!(
+            origin,
+            Ok(GeoCoord::new(Degrees(37.779265), Degrees(-122.419277)).geoToH3(Resolution::R9)),
+            "same as geoToH3"
+        );
+        assert!(origin.unwrap().is_valid());
+        assert!(
+            H3Index::from_point(Degrees(-90.0), Degrees(180.0), Resolution::R15).is_ok(),
+            "bounds included"
+        );
+
+        for &(lat, lng) in [
+            (90.5, 0.0),
+            (-91.0, 0.0),
+            (0.0, 180.5),
+            (0.0, -360.0),
+            (f64::NAN, 0.0),
+            (0.0, f64::INFINITY),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                H3Index::from_point(Degrees(lat), Degrees(lng), Resolution::R5),
+                Err(H3Error::Domain),
+                "{}, {}",
+                lat,
+                lng
+            );
+        }
+    }
+
+    #[test]
+    fn fromComponents() {
+        let bc = |n| BaseCell::new(n).unwrap();
+        let origin =
+            H3Index::from_point(Degrees(37.779265), Degrees(-122.419277), Resolution::R9).unwrap();
+        let digits: Vec<Direction> = (1..=9)
+            .map(|r| origin.get_index_digit(Resolution::from(r)))
+            .collect();
+        assert_eq!(
+            H3Index::from_components(origin.get_base_cell(), &digits),
+            Ok(origin),
+            "round trip"
+        );
+        assert_eq!(
+            H3Index::from_components(bc(4), &[]),
+            Ok(Resolution::R0.getPentagonIndexes()[0]),
+            "res 0"
+        );
+        assert_eq!(
+            H3Index::from_components(bc(4), &[Direction::CENTER_DIGIT; 15]),
+            Ok(Resolution::R15.getPentagonIndexes()[0]),
+            "res 15"
+        );
+        assert!(
+            H3Index::from_components(bc(0), &[Direction::K_AXES_DIGIT; 3]).is_ok(),
+            "K under a hexagon"
+        );
+
+        assert_eq!(
+            H3Index::from_components(bc(0), &[Direction::CENTER_DIGIT; 16]),
+            Err(H3Error::Domain),
+            "too many digits"
+        );
+        assert_eq!(
+            H3Index::from_components(bc(0), &[Direction::J_AXES_DIGIT, Direction::INVALID_DIGIT]),
+            Err(H3Error::Domain),
+            "invalid digit"
+        );
+        assert_eq!(
+            H3Index::from_components(bc(4), &[Direction::CENTER_DIGIT, Direction::K_AXES_DIGIT]),
+            Err(H3Error::CellInvalid),
+            "deleted pentagon subsequence"
+        );
+        assert!(
+            H3Index::from_components(bc(4), &[Direction::J_AXES_DIGIT, Direction::K_AXES_DIGIT])
+                .is_ok(),
+            "K after leaving the pentagon"
+        );
+    }
+
+    #[test]
+    fn h3GetFaces_ascending() {
+        let pentagon = Resolution::R0.getPentagonIndexes()[0];
+        let faces: Vec<i32> = pentagon.h3GetFaces().into_iter().collect();
+        assert_eq!(faces.len(), 5, "class II pentagon touches 5 faces");
+        assert!(faces.windows(2).all(|w| w[0] < w[1]), "ascending");
+        assert_eq!(
+            pentagon.h3GetFaces().into_iter().collect::<Vec<_>>(),
+            faces,
+            "same on every call"
+        );
+        assert!(faces.iter().all(|f| (0..20).contains(f)), "face numbers");
+    }
+
+    #[test]
+    fn ord_rawBits() {
+        use std::collections::{BTreeMap, BTreeSet};
+
+        let origin = crate::GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let mut cells = origin.geoToH3(Resolution::R6).kRing(2);
+        cells.extend(origin.geoToH3(Resolution::R5).kRing(1));
+        cells.push(Resolution::R6.getPentagonIndexes()[0]);
+
+        let mut byValue = cells.clone();
+        byValue.sort_by_key(|&h| u64::from(h));
+        cells.sort();
+        assert_eq!(cells, byValue, "sorted by the raw u64");
+
+        let set: BTreeSet<H3Index> = cells.iter().rev().copied().collect();
+        assert!(set.iter().copied().eq(cells.iter().copied()), "btree order");
+
+        let counts: BTreeMap<H3Index, usize> = cells.iter().map(|&h| (h, 1)).collect();
+        assert_eq!(counts.len(), cells.len(), "keys");
+
+        // Descendants of one cell are contiguous at each resolution.
+        let parent = origin.geoToH3(Resolution::R4);
+        let mut grandchildren = Vec::new();
+        for h in parent.kRing(1) {
+            grandchildren.extend(h.h3ToChildren(Resolution::R6));
+        }
+        grandchildren.sort();
+        let isDescendant: Vec<bool> = grandchildren
+            .iter()
+            .map(|h| h.h3ToParent(Resolution::R4) == Ok(parent))
+            .collect();
+        let runs = isDescendant.windows(2).filter(|w| w[0] != w[1]).count();
+        assert!(runs <= 2, "one run of descendants");
+        assert_eq!(isDescendant.iter().filter(|&&d| d).count(), 49);
+    }
+
     #[test]
     fn pentagon_indexes_property_tests() {
         let expectedCount = H3Index::pentagonIndexCount();
@@ -990,7 +1244,7 @@ mod test {
 
             let mut numFound = 0;
 
-            for i in 0..PADDED_COUNT {
+            for i in 0..h3Indexes.len() {
                 let h3Index = h3Indexes[i];
 
                 if h3Index != H3Index::H3_NULL {
@@ -1006,11 +1260,10 @@ mod test {
                     );
 
                     // verify uniqueness
-                    for j in i + 1..PADDED_COUNT {
-                        if h3Indexes[j] == h3Index {
-                            assert!(false, "index should be seen only once");
-                        }
-                    }
+                    assert!(
+                        !h3Indexes[i + 1..].contains(&h3Index),
+                        "index should be seen only once"
+                    );
                 }
             }
 
@@ -1047,8 +1300,8 @@ mod test {
         h.set_resolution(Resolution::R13);
         assert_eq!(h.get_resolution(), Resolution::R13);
 
-        h.set_base_cell(BaseCell::from(123));
-        assert_eq!(h.get_base_cell(), BaseCell::from(123));
+        h.set_base_cell(BaseCell::new_unchecked(123));
+        assert_eq!(h.get_base_cell(), BaseCell::new_unchecked(123));
 
         let digit = Direction::JK_AXES_DIGIT;
         h.set_index_digit(Resolution::R0, digit.into());
@@ -1067,7 +1320,7 @@ mod test {
         }
     }
 
-    fn verifyCountAndUniqueness(children: &Vec<H3Index>, paddedCount: usize, expectedCount: usize) {
+    fn verifyCountAndUniqueness(children: &[H3Index], paddedCount: usize, expectedCount: usize) {
         let mut numFound = 0;
         for i in 0..paddedCount {
             let currIndex = children[i];
@@ -1088,19 +1341,19 @@ mod test {
         assert_eq!(numFound, expectedCount, "got expected number of children");
     }
 
-    const sf: GeoCoord = GeoCoord::new(0.659966917655, 2. * 3.14159 - 2.1364398519396);
+    const sf: GeoCoord = GeoCoord::from_rads(0.659966917655, 2. * 3.14159 - 2.1364398519396);
     //let sfHex8 : H3Index = sf.geoToH3(8);
 
     #[test]
     fn geoToH3ExtremeCoordinates() {
         // Check that none of these cause crashes.
-        let g = GeoCoord::new(0., 1e45);
+        let g = GeoCoord::from_rads(0., 1e45);
         let _h3 = g.geoToH3(Resolution::R14);
 
-        let g2 = GeoCoord::new(1e46, 1e45);
+        let g2 = GeoCoord::from_rads(1e46, 1e45);
         let _h3 = g2.geoToH3(Resolution::R15);
 
-        let g4 = GeoCoord::new(2., -3e39);
+        let g4 = GeoCoord::from_rads(2., -3e39);
         let _h3 = g4.geoToH3(Resolution::R0);
     }
 
@@ -1130,11 +1383,11 @@ mod test {
         for i in 0..BaseCell::NUM_BASE_CELLS {
             let mut h = H3Index::H3_INIT;
             h.set_mode(H3Mode::H3_HEXAGON_MODE);
-            h.set_base_cell(i.into());
+            h.set_base_cell(BaseCell::new(i as i32).unwrap());
 
             assert!(h.is_valid(), "h3IsValid failed on base cell {}", i);
 
-            let recovered = h.get_base_cell().0;
+            let recovered = i32::from(h.get_base_cell());
             assert_eq!(recovered, i as i32, "failed to recover base cell");
         }
     }
@@ -1143,7 +1396,7 @@ mod test {
     fn h3IsValidBaseCellInvalid() {
         let mut hWrongBaseCell = H3Index::H3_INIT;
         hWrongBaseCell.set_mode(H3Mode::H3_HEXAGON_MODE);
-        hWrongBaseCell.set_base_cell(BaseCell::NUM_BASE_CELLS.into());
+        hWrongBaseCell.set_base_cell(BaseCell::new_unchecked(BaseCell::NUM_BASE_CELLS as i32));
         assert!(
             !hWrongBaseCell.is_valid(),
             "h3IsValid failed on invalid base cell"
@@ -1169,6 +1422,12 @@ mod test {
                 assert!(!h.is_valid(), "h3IsValid failed on mode {:?}", mode);
             }
         }
+
+        // mode values without a variant
+        for i in (0..1).chain(5..=0xf) {
+            let h = H3Index(H3Index::H3_INIT.0 | (i << H3Index::H3_MODE_OFFSET));
+            assert!(!h.is_valid(), "h3IsValid failed on mode {}", i);
+        }
     }
 
     #[test]
@@ -1192,7 +1451,11 @@ mod test {
 
     #[test]
     fn h3DeletedSubsequenceInvalid() {
-        let h = H3Index::setH3Index(Resolution::R1, BaseCell::new(4), Direction::K_AXES_DIGIT);
+        let h = H3Index::setH3Index(
+            Resolution::R1,
+            BaseCell::new_unchecked(4),
+            Direction::K_AXES_DIGIT,
+        );
 
         // Create an index located in a deleted subsequence of a pentagon.
         assert!(!h.is_valid(), "h3IsValid failed on deleted subsequence");
@@ -1200,10 +1463,14 @@ mod test {
 
     #[test]
     fn setH3Index() {
-        let h = H3Index::setH3Index(Resolution::R5, BaseCell::new(12), Direction::K_AXES_DIGIT);
+        let h = H3Index::setH3Index(
+            Resolution::R5,
+            BaseCell::new_unchecked(12),
+            Direction::K_AXES_DIGIT,
+        );
 
         assert_eq!(h.get_resolution(), Resolution::R5, "resolution as expected");
-        assert_eq!(h.get_base_cell().0, 12, "base cell as expected");
+        assert_eq!(h.get_base_cell(), 12, "base cell as expected");
         assert_eq!(h.get_mode(), H3Mode::H3_HEXAGON_MODE, "mode as expected");
 
         for i in 1..=5 {
@@ -1225,14 +1492,104 @@ mod test {
         assert_eq!(h.0, 0x85184927fffffff, "index matches expected");
     }
 
-    //#[test]
+    #[test]
+    fn intersects() {
+        let cell = sf.geoToH3(Resolution::R7);
+        let parent = cell.h3ToParent(Resolution::R3).unwrap();
+        let child = cell.h3ToCenterChild(Resolution::R12);
+        let neighbor = cell.kRing(1).into_iter().find(|&h| h != cell).unwrap();
+        let cousin = cell.h3ToParent(Resolution::R6).unwrap().kRing(2)[10];
+
+        assert!(cell.intersects(&cell), "itself");
+        assert!(
+            cell.intersects(&parent) && parent.intersects(&cell),
+            "ancestor"
+        );
+        assert!(
+            cell.intersects(&child) && child.intersects(&cell),
+            "descendant"
+        );
+        assert!(parent.intersects(&child), "skipping resolutions");
+        assert!(!cell.intersects(&neighbor), "same resolution");
+        assert!(
+            !cell.intersects(&cousin) && !cousin.intersects(&child),
+            "other branch"
+        );
+        assert!(!cell.intersects(&H3Index::H3_NULL), "invalid");
+
+        let pentagon = Resolution::R2.getPentagonIndexes()[0];
+        for h in pentagon.h3ToChildren(Resolution::R4) {
+            assert!(pentagon.intersects(&h), "{} under pentagon", h);
+        }
+    }
+
+    #[test]
+    fn u64Slices() {
+        assert_eq!(std::mem::size_of::<H3Index>(), 8, "size");
+        assert_eq!(std::mem::align_of::<H3Index>(), 8, "alignment");
+        assert_eq!(std::mem::size_of::<[H3Index; 7]>(), 56, "no padding");
+
+        let cells = sf.geoToH3(Resolution::R9).kRing(2);
+        let values = H3Index::as_u64_slice(&cells);
+        assert_eq!(values.as_ptr() as usize, cells.as_ptr() as usize, "no copy");
+        let expected: Vec<u64> = cells.iter().map(|&h| u64::from(h)).collect();
+        assert_eq!(values, &expected[..], "values");
+        assert_eq!(H3Index::from_u64_slice(values), &cells[..], "and back");
+        assert!(H3Index::as_u64_slice(&[]).is_empty(), "empty");
+
+        let raw = [u64::MAX, 0];
+        assert_eq!(
+            H3Index::from_u64_slice(&raw),
+            &[H3Index::from(u64::MAX), H3Index::H3_NULL],
+            "not validated"
+        );
+    }
+
+    #[test]
+    fn setH3Index_roundTrip() {
+        for &res in Resolution::RESOLUTIONS.iter() {
+            for bc in 0..BaseCell::NUM_BASE_CELLS as i32 {
+                for d in 0..=usize::from(Direction::INVALID_DIGIT) {
+                    let digit = Direction::from(d);
+                    let h = H3Index::setH3Index(res, BaseCell::new_unchecked(bc), digit);
+                    let name = format!("res {:?} base cell {} digit {}", res, bc, d);
+
+                    assert_eq!(h.get_mode(), H3Mode::H3_HEXAGON_MODE, "{}: mode", name);
+                    assert_eq!(h.get_resolution(), res, "{}: resolution", name);
+                    assert_eq!(h.get_base_cell(), bc, "{}: base cell", name);
+                    assert_eq!(h.get_reserved_bits(), 0, "{}: reserved bits", name);
+                    assert_eq!(h.get_high_bit(), 0, "{}: high bit", name);
+                    for r in 1..=Resolution::MAX_H3_RES {
+                        let expected = if r <= usize::from(res) {
+                            digit
+                        } else {
+                            Direction::INVALID_DIGIT
+                        };
+                        assert_eq!(
+                            h.get_index_digit(r.into()),
+                            expected,
+                            "{}: digit {}",
+                            name,
+                            r
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
     fn h3IsResClassIII() {
         let coord = GeoCoord::default();
 
         for i in Resolution::RESOLUTIONS.iter() {
             let h = coord.geoToH3(*i);
 
-            //t_assert(H3_EXPORT(h3IsResClassIII)(h) == isResClassIII(i), "matches existing definition");
+            assert_eq!(
+                h.h3IsResClassIII(),
+                i.isResClassIII(),
+                "matches existing definition"
+            );
         }
     }
 
@@ -1251,6 +1608,307 @@ mod test {
         }
     }
 
+    #[test]
+    fn maxResolutionCells() {
+        let maxRes = Resolution::from(Resolution::MAX_H3_RES);
+        assert_eq!(maxRes, Resolution::R15, "finest resolution");
+        assert_eq!(
+            Resolution::RESOLUTIONS.len(),
+            Resolution::MAX_H3_RES + 1,
+            "resolutions 0 through MAX_H3_RES"
+        );
+
+        for bc in 0..BaseCell::NUM_BASE_CELLS as i32 {
+            let bc = BaseCell::new_unchecked(bc);
+            for digit in 0..Direction::INVALID_DIGIT as u64 {
+                let digit = Direction::from(digit);
+                let h = H3Index::setH3Index(maxRes, bc, digit);
+
+                if bc._isBaseCellPentagon() && digit == Direction::K_AXES_DIGIT {
+                    assert!(!h.is_valid(), "deleted subsequence at res 15");
+                    continue;
+                }
+                assert!(h.is_valid(), "res 15 cell {} is valid", h);
+                assert_eq!(h.get_resolution(), maxRes, "resolution round trips");
+
+                assert_eq!(h.h3ToParent(maxRes), Ok(h), "own parent");
+                assert_eq!(h.h3ToCenterChild(maxRes), h, "own center child");
+                assert_eq!(h.maxH3ToChildrenSize(maxRes), 1, "one child");
+                assert_eq!(h.h3ToChildren(maxRes), vec![h], "only child");
+                assert_eq!(h.h3ToGeo().geoToH3(maxRes), h, "center round trips");
+
+                for res in Resolution::RESOLUTIONS.iter() {
+                    let parent = h.h3ToParent(*res).unwrap();
+                    assert!(parent.is_valid(), "parent at {:?} is valid", res);
+                    assert!(
+                        parent.h3ToCenterChild(maxRes).is_valid(),
+                        "center child at res 15 is valid"
+                    );
+                    assert_eq!(
+                        parent.maxH3ToChildrenSize(maxRes),
+                        7u64.pow((Resolution::MAX_H3_RES - *res as usize) as u32),
+                        "children at res 15"
+                    );
+                }
+
+                for neighbor in h.kRing(1) {
+                    if let Ok(ij) = H3Index::experimentalH3ToLocalIj(h, neighbor) {
+                        assert_eq!(
+                            h.experimentalLocalIjToH3(&ij),
+                            Ok(neighbor),
+                            "local ij round trips at res 15"
+                        );
+                    }
+                }
+
+                let mut bad = h;
+                bad.set_index_digit(maxRes, Direction::INVALID_DIGIT as u64);
+                assert!(!bad.is_valid(), "invalid res 15 digit");
+            }
+        }
+    }
+
+    #[test]
+    fn normalize() {
+        let cell = H3Index::setH3Index(
+            Resolution::R1,
+            BaseCell::new_unchecked(16),
+            Direction::J_AXES_DIGIT,
+        );
+        assert_eq!(cell.normalize(), Some(cell), "cells are canonical");
+
+        let mut edge = cell;
+        edge.set_mode(H3Mode::H3_UNIEDGE_MODE);
+        edge.set_reserved_bits(Direction::K_AXES_DIGIT as u64);
+        assert_eq!(edge.normalize(), Some(edge), "edges are canonical");
+
+        edge.set_reserved_bits(Direction::CENTER_DIGIT as u64);
+        assert_eq!(edge.normalize(), None, "not an edge");
+
+        let mut nonCanonical = 0;
+        for vertexNum in 0..NUM_HEX_VERTS as u64 {
+            let vertex = cell.cellToVertex(vertexNum as usize);
+            assert_eq!(vertex.normalize(), Some(vertex), "vertexes are canonical");
+
+            let mut fromCell = cell;
+            fromCell.set_mode(H3Mode::H3_VERTEX_MODE);
+            fromCell.set_reserved_bits(vertexNum);
+            assert_eq!(fromCell.normalize(), Some(vertex), "rewritten to the owner");
+            if fromCell != vertex {
+                nonCanonical += 1;
+            }
+        }
+        assert!(nonCanonical > 0, "some vertexes are owned by a neighbor");
+
+        let mut vertex = cell;
+        vertex.set_mode(H3Mode::H3_VERTEX_MODE);
+        vertex.set_reserved_bits(NUM_HEX_VERTS as u64);
+        assert_eq!(vertex.normalize(), None, "vertex number out of range");
+        assert_eq!(H3Index::H3_NULL.normalize(), None, "null index");
+    }
+
+    #[test]
+    fn sanitize() {
+        let mut state = 1238;
+        let mut cells: Vec<H3Index> = (0..500).map(|_| randomCell(&mut state)).collect();
+        cells.extend_from_slice(&Resolution::R5.getPentagonIndexes());
+
+        for h in cells {
+            assert_eq!(H3Index::sanitize(h.0), Some(h), "{} valid as is", h);
+
+            // Zero-filled unused digits and stray reserved bits, as written by
+            // producers that build indexes by hand.
+            let mut broken = h;
+            for r in usize::from(h.get_resolution()) + 1..=Resolution::MAX_H3_RES {
+                broken.set_index_digit(r.into(), nextRandom(&mut state) % 8);
+            }
+            broken.set_reserved_bits(nextRandom(&mut state) % 8);
+            assert_eq!(H3Index::sanitize(broken.0), Some(h), "{} repaired", h);
+        }
+
+        let cell = H3Index::setH3Index(
+            Resolution::R3,
+            BaseCell::new_unchecked(16),
+            Direction::J_AXES_DIGIT,
+        );
+        let mut edge = cell;
+        edge.set_mode(H3Mode::H3_UNIEDGE_MODE);
+        edge.set_reserved_bits(Direction::K_AXES_DIGIT as u64);
+        let mut zeroFilled = edge;
+        zeroFilled.set_index_digit(Resolution::R4, 0);
+        assert_eq!(
+            H3Index::sanitize(zeroFilled.0),
+            Some(edge),
+            "edge keeps its direction"
+        );
+
+        let mut badDigit = cell;
+        badDigit.set_index_digit(Resolution::R2, 7);
+        let mut highBit = cell;
+        highBit.set_high_bit(1);
+        let pentagonK = H3Index::setH3Index(
+            Resolution::R2,
+            BaseCell::new_unchecked(4),
+            Direction::K_AXES_DIGIT,
+        );
+        let mut noMode = cell;
+        noMode.set_mode(H3Mode::H3_INVALID_MODE);
+        for &bad in [badDigit, highBit, pentagonK, noMode, H3Index::H3_NULL].iter() {
+            assert_eq!(H3Index::sanitize(bad.0), None, "{} not repaired", bad);
+        }
+    }
+
+    /// SplitMix64, to draw reproducible arbitrary cells without a dependency.
+    fn nextRandom(state: &mut u64) -> u64 {
+        *state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A valid cell with random base cell, resolution and digits.
+    fn randomCell(state: &mut u64) -> H3Index {
+        loop {
+            let bc = nextRandom(state) % BaseCell::NUM_BASE_CELLS as u64;
+            let res = Resolution::from((nextRandom(state) % 16) as usize);
+            let mut h = H3Index::setH3Index(
+                res,
+                BaseCell::new_unchecked(bc as i32),
+                Direction::CENTER_DIGIT,
+            );
+            for &r in Resolution::RESOLUTIONS[1..=usize::from(res)].iter() {
+                h.set_index_digit(r, nextRandom(state) % 7);
+            }
+            // rejects the deleted k subsequence of pentagons
+            if h.is_valid() {
+                return h;
+            }
+        }
+    }
+
+    #[test]
+    fn hierarchyFuzz() {
+        let mut state = 1199;
+        let mut cells: Vec<H3Index> = (0..2000).map(|_| randomCell(&mut state)).collect();
+        // arbitrary cells are rarely pentagons
+        for &res in Resolution::RESOLUTIONS.iter() {
+            cells.extend_from_slice(&res.getPentagonIndexes());
+        }
+
+        for h in cells {
+            let res = h.get_resolution();
+
+            for &r in Resolution::RESOLUTIONS.iter() {
+                let parent = h.h3ToParent(r);
+                let centerChild = h.h3ToCenterChild(r);
+                if r <= res {
+                    let parent = parent.unwrap();
+                    assert!(parent.is_valid(), "parent of {} at {:?} is valid", h, r);
+                    assert_eq!(parent.get_resolution(), r, "parent resolution");
+                    assert_eq!(
+                        h.h3ToParent(r).and_then(|p| p.h3ToParent(Resolution::R0)),
+                        h.h3ToParent(Resolution::R0),
+                        "ancestors agree"
+                    );
+                } else {
+                    assert_eq!(parent, Err(H3Error::ResolutionMismatch), "no parent");
+                }
+
+                if r >= res {
+                    assert!(centerChild.is_valid(), "center child of {} is valid", h);
+                    assert_eq!(centerChild.h3ToParent(res), Ok(h), "center child's parent");
+                    assert_eq!(centerChild.is_pentagon(), h.is_pentagon(), "pentagons stay");
+                } else {
+                    assert_eq!(centerChild, H3Index::H3_NULL, "no center child");
+                }
+            }
+
+            let finest = (usize::from(res) + 2).min(Resolution::MAX_H3_RES);
+            for &r in Resolution::RESOLUTIONS[usize::from(res)..=finest].iter() {
+                let children = h.h3ToChildren(r);
+                let levels = usize::from(r) - usize::from(res);
+                let expectedCount = if h.is_pentagon() {
+                    1 + 5 * (7usize.pow(levels as u32) - 1) / 6
+                } else {
+                    7usize.pow(levels as u32)
+                };
+                assert_eq!(children.len(), expectedCount, "children of {}", h);
+
+                let unique: HashSet<H3Index> = children.iter().copied().collect();
+                assert_eq!(unique.len(), children.len(), "children are unique");
+                for child in children.iter() {
+                    assert!(child.is_valid(), "child {} of {} is valid", child, h);
+                    assert_eq!(child.h3ToParent(res), Ok(h), "child's parent");
+                }
+
+                for (n, child) in children.iter().enumerate() {
+                    assert_eq!(h.nth_descendant(r, n as u64), Ok(*child), "child {}", n);
+                }
+                assert_eq!(
+                    h.nth_descendant(r, children.len() as u64),
+                    Err(H3Error::Domain),
+                    "past the last child of {}",
+                    h
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn nthDescendant() {
+        let pentagon = Resolution::R0.getPentagonIndexes()[0];
+        let hexagon = H3Index::setH3Index(
+            Resolution::R0,
+            BaseCell::new_unchecked(0),
+            Direction::CENTER_DIGIT,
+        );
+        let depth = Resolution::MAX_H3_RES as u32;
+
+        assert_eq!(
+            hexagon.nth_descendant(Resolution::R15, 0),
+            Ok(hexagon.h3ToCenterChild(Resolution::R15)),
+            "first descendant is the center child"
+        );
+        assert_eq!(
+            pentagon.nth_descendant(Resolution::R15, 0),
+            Ok(pentagon.h3ToCenterChild(Resolution::R15)),
+            "pentagon's first descendant is its center child"
+        );
+
+        let last = hexagon.nth_descendant(Resolution::R15, 7u64.pow(depth) - 1);
+        let mut expected = hexagon;
+        expected.set_resolution(Resolution::R15);
+        for &r in Resolution::RESOLUTIONS[1..].iter() {
+            expected.set_index_digit(r, Direction::IJ_AXES_DIGIT as u64);
+        }
+        assert_eq!(last, Ok(expected), "last descendant is all 6s");
+
+        let pentagonCount = 1 + 5 * (7u64.pow(depth) - 1) / 6;
+        let last = pentagon
+            .nth_descendant(Resolution::R15, pentagonCount - 1)
+            .unwrap();
+        assert!(last.is_valid(), "last pentagon descendant is valid");
+        assert_eq!(
+            last.get_index_digit(Resolution::R1),
+            Direction::IJ_AXES_DIGIT,
+            "in the last hexagon child"
+        );
+        assert_eq!(
+            pentagon.nth_descendant(Resolution::R15, pentagonCount),
+            Err(H3Error::Domain),
+            "pentagons have fewer descendants"
+        );
+
+        let child = hexagon.h3ToCenterChild(Resolution::R3);
+        assert_eq!(
+            child.nth_descendant(Resolution::R2, 0),
+            Err(H3Error::ResolutionMismatch),
+            "no descendants at coarser resolutions"
+        );
+        assert_eq!(child.nth_descendant(Resolution::R3, 0), Ok(child), "itself");
+    }
+
     #[test]
     fn h3ToString() {
         let h = H3Index(0xcafe);
@@ -1288,31 +1946,64 @@ mod test {
 
             for res in Resolution::RESOLUTIONS.iter().skip(1) {
                 for step in 0..*res as i32 {
-                    let mut child = sf.geoToH3(*res);
+                    let child = sf.geoToH3(*res);
                     let parent = child.h3ToParent(*res - step);
 
                     let comparison_parent = sf.geoToH3(*res - step);
-                    assert_eq!(parent, comparison_parent, "Got expected parent");
+                    assert_eq!(parent, Ok(comparison_parent), "Got expected parent");
                 }
             }
         }
 
         #[test]
         fn h3ToParent_invalidInputs() {
-            let mut child = sf.geoToH3(Resolution::R5);
+            let child = sf.geoToH3(Resolution::R5);
 
             assert_eq!(
                 child.h3ToParent(Resolution::R6),
-                H3Index::H3_NULL,
+                Err(H3Error::ResolutionMismatch),
                 "Higher resolution fails"
             );
-            //assert_eq!(child.h3ToParent(-1), 0, "Invalid resolution fails");
             assert_eq!(
                 child.h3ToParent(Resolution::R15),
-                H3Index::H3_NULL,
-                "Invalid resolution fails"
+                Err(H3Error::ResolutionMismatch),
+                "Finest resolution fails"
             );
-            //assert_eq!( child.h3ToParent(16), 0, "Invalid resolution fails");
+            // resolutions below 0 or above MAX_H3_RES cannot be expressed as a
+            // `Resolution`
+        }
+
+        #[test]
+        fn h3ToParent_res15() {
+            let child = sf.geoToH3(Resolution::R15);
+            let parent = child.h3ToParent(Resolution::R14).unwrap();
+
+            assert_eq!(parent, sf.geoToH3(Resolution::R14), "res 14 parent");
+            assert!(
+                parent.h3ToChildren(Resolution::R15).contains(&child),
+                "child of its parent"
+            );
+        }
+
+        #[test]
+        fn h3ToParent_everyResolution() {
+            for childRes in Resolution::RESOLUTIONS.iter() {
+                let child = sf.geoToH3(*childRes);
+                for parentRes in Resolution::RESOLUTIONS.iter() {
+                    let parent = child.h3ToParent(*parentRes);
+                    if parentRes > childRes {
+                        assert_eq!(parent, Err(H3Error::ResolutionMismatch), "finer fails");
+                    } else {
+                        assert_eq!(
+                            parent,
+                            Ok(sf.geoToH3(*parentRes)),
+                            "parent of {:?} at {:?}",
+                            childRes,
+                            parentRes
+                        );
+                    }
+                }
+            }
         }
     }
 
@@ -1320,12 +2011,14 @@ mod test {
         use super::*;
 
         fn verifyCountAndUniqueness(
-            children: &Vec<H3Index>,
+            children: &[H3Index],
             paddedCount: usize,
             expectedCount: usize,
         ) {
+            assert!(children.len() <= paddedCount, "children fit in the buffer");
+
             let mut num_found = 0;
-            for i in 0..paddedCount {
+            for i in 0..children.len() {
                 let currIndex = children[i];
 
                 if currIndex == H3Index::H3_NULL {
@@ -1336,7 +2029,7 @@ mod test {
 
                 // verify uniqueness
                 let mut indexSeen = 0;
-                for j in (i + 1)..paddedCount {
+                for j in (i + 1)..children.len() {
                     if children[j] == currIndex {
                         indexSeen += 1;
                     }
@@ -1358,6 +2051,24 @@ mod test {
             verifyCountAndUniqueness(&children, PADDED_COUNT, EXPECTED_COUNT);
         }
 
+        #[test]
+        fn ascending() {
+            let hexagon = sf.geoToH3(Resolution::R5);
+            let pentagon = Resolution::R5.getPentagonIndexes()[4];
+            for h in [hexagon, pentagon].iter() {
+                let children: Vec<u64> = h
+                    .h3ToChildren(Resolution::R8)
+                    .into_iter()
+                    .map(u64::from)
+                    .collect();
+                assert!(
+                    children.windows(2).all(|w| w[0] < w[1]),
+                    "children of {} ascending",
+                    h
+                );
+            }
+        }
+
         #[test]
         fn sameRes() {
             const EXPECTED_COUNT: usize = 1;
@@ -1380,34 +2091,23 @@ mod test {
             verifyCountAndUniqueness(&children, PADDED_COUNT, EXPECTED_COUNT);
         }
 
-        //#[test]
-        fn childResTooFine() {
-            const EXPECTED_COUNT: usize = 0;
-            const PADDED_COUNT: usize = 7;
-
-            let sfHex8 = sf.geoToH3(Resolution::R8);
-            let children = sfHex8.h3ToChildren(Resolution::R15);
-
-            let sfHexMax = sf.geoToH3(Resolution::R15);
+        // childResTooFine is not ported: a child resolution past MAX_H3_RES
+        // cannot be expressed as a `Resolution`.
 
-            //H3_EXPORT(h3ToChildren)(sfHexMax, MAX_H3_RES + 1, children);
-            //verifyCountAndUniqueness(&children, PADDED_COUNT, EXPECTED_COUNT);
-        }
-
-        //#[test]
+        #[test]
         fn pentagonChildren() {
-            let pentagon = H3Index::setH3Index(Resolution::R1, 4.into(), Direction::CENTER_DIGIT);
-            todo!()
+            let pentagon = H3Index::setH3Index(
+                Resolution::R1,
+                BaseCell::new_unchecked(4),
+                Direction::CENTER_DIGIT,
+            );
 
-            //const expectedCount : usize = (5 * 7) + 6;
-            //const paddedCount : usize = pentagon H3_EXPORT(maxH3ToChildrenSize)(pentagon, 3);
+            let expectedCount: usize = (5 * 7) + 6;
+            let paddedCount = pentagon.maxH3ToChildrenSize(Resolution::R3) as usize;
 
-            //H3Index* children = calloc(paddedCount, sizeof(H3Index));
-            //H3_EXPORT(h3ToChildren)(sfHex8, 10, children);
-            //H3_EXPORT(h3ToChildren)(pentagon, 3, children);
+            let children = pentagon.h3ToChildren(Resolution::R3);
 
-            //verifyCountAndUniqueness(children, paddedCount, expectedCount);
-            //free(children);
+            verifyCountAndUniqueness(&children, paddedCount, expectedCount);
         }
     }
 }
@@ -0,0 +1,175 @@
+//! Packed cells: the base cell and the digits of a cell at a resolution known
+//! to the application, without the bits that are the same for every such
+//! cell.
+//!
+//! A cell index spends 12 of its 64 bits on the high bit, mode, reserved bits
+//! and resolution, and 3 bits on each digit past its resolution, all fixed
+//! once the resolution is known. Packing keeps the rest, `7 + 3 * res` bits:
+//! 49 bits at resolution 14 and 52 at resolution 15, enough to store billions
+//! of cells in 7 bytes instead of 8, or to share a word with other fields.
+//!
+//! A packed value does not record its resolution, so it can only be unpacked
+//! at the resolution it was packed at; unpacking at another gives a different
+//! cell or an error. Edges and vertexes, which use the reserved bits, do not
+//! pack. Packing preserves order: packed values of cells at one resolution
+//! sort the same way their indexes do.
+
+use super::{H3Index, H3Mode};
+use crate::{H3Error, Resolution};
+
+impl H3Index {
+    /// Number of bits of a cell packed at a resolution: 7 for the base cell
+    /// and 3 for each digit.
+    pub fn packed_bits(res: Resolution) -> u32 {
+        7 + 3 * usize::from(res) as u32
+    }
+
+    /// Shift from a packed value to its bits in an index: the digits past the
+    /// resolution.
+    fn packedShift(res: Resolution) -> u32 {
+        3 * (Resolution::MAX_H3_RES - usize::from(res)) as u32
+    }
+
+    /**
+     * Packs a cell into the low `packed_bits(res)` bits of an integer,
+     * keeping its base cell and digits and dropping the fields that are the
+     * same for every cell at `res`. Packed values sort like the indexes.
+     *
+     * The resolution is not kept, so unpacking at any other resolution gives
+     * another cell or an error; edges and vertexes do not pack.
+     *
+     * @param res The resolution of the cell, which unpacking needs again
+     * @return The packed cell, or CellInvalid if this is not a valid cell, or
+     *         ResolutionMismatch if the cell is at another resolution
+     */
+    pub fn pack(&self, res: Resolution) -> Result<u64, H3Error> {
+        if !self.is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        if self.get_resolution() != res {
+            return Err(H3Error::ResolutionMismatch);
+        }
+        let bits = (self.0 & !(Self::H3_RES_MASK | Self::H3_MODE_MASK)) >> Self::packedShift(res);
+        Ok(bits)
+    }
+
+    /**
+     * Restores a cell packed by `pack`.
+     *
+     * @param packed The packed cell
+     * @param res The resolution the cell was packed at
+     * @return The cell, or CellInvalid if the value has bits past
+     *         `packed_bits(res)` or does not unpack to a valid cell, such as
+     *         one with a base cell past 121 or a deleted pentagon digit
+     */
+    pub fn unpack(packed: u64, res: Resolution) -> Result<H3Index, H3Error> {
+        let packedMask = (1u64 << Self::packed_bits(res)) - 1;
+        if packed & !packedMask != 0 {
+            return Err(H3Error::CellInvalid);
+        }
+
+        let shift = Self::packedShift(res);
+        let mut h = H3Index::H3_INIT;
+        h.set_mode(H3Mode::H3_HEXAGON_MODE);
+        h.set_resolution(res);
+        h.0 = (h.0 & !(packedMask << shift)) | (packed << shift);
+        if h.is_valid() {
+            Ok(h)
+        } else {
+            Err(H3Error::CellInvalid)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GeoCoord;
+
+    #[test]
+    fn pack_roundTrip() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        for &res in Resolution::RESOLUTIONS.iter() {
+            let h = sf.geoToH3(res);
+            let packed = h.pack(res).unwrap();
+            assert!(
+                packed < 1 << H3Index::packed_bits(res),
+                "{} fits in {} bits",
+                h,
+                H3Index::packed_bits(res)
+            );
+            assert_eq!(H3Index::unpack(packed, res), Ok(h), "{} round trip", h);
+        }
+        assert_eq!(H3Index::packed_bits(Resolution::R14), 49, "res 14");
+        assert_eq!(H3Index::packed_bits(Resolution::R15), 52, "res 15");
+
+        let h = H3Index::from(0x85283473fffffff);
+        assert_eq!(
+            h.pack(Resolution::R5),
+            Ok((20 << 15) | 0o06434),
+            "base cell then digits"
+        );
+
+        for &res in [Resolution::R0, Resolution::R2].iter() {
+            for pentagon in res.getPentagonIndexes() {
+                for cell in pentagon.kRing(2) {
+                    let packed = cell.pack(res).unwrap();
+                    assert_eq!(H3Index::unpack(packed, res), Ok(cell), "{}", cell);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pack_order() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R8);
+        let mut cells = sf.kRing(3);
+        cells.sort_by_key(|&h| u64::from(h));
+        let packed: Vec<u64> = cells
+            .iter()
+            .map(|h| h.pack(Resolution::R8).unwrap())
+            .collect();
+        assert!(packed.windows(2).all(|w| w[0] < w[1]), "same order");
+    }
+
+    #[test]
+    fn pack_invalid() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+        assert_eq!(
+            sf.pack(Resolution::R8),
+            Err(H3Error::ResolutionMismatch),
+            "other resolution"
+        );
+        assert_eq!(
+            H3Index::H3_NULL.pack(Resolution::R0),
+            Err(H3Error::CellInvalid),
+            "null"
+        );
+        let edge = sf.getH3UnidirectionalEdgesFromHexagon().as_slice()[0];
+        assert_eq!(edge.pack(Resolution::R9), Err(H3Error::CellInvalid), "edge");
+
+        let packed = sf.pack(Resolution::R9).unwrap();
+        assert_eq!(
+            H3Index::unpack(packed, Resolution::R8),
+            Err(H3Error::CellInvalid),
+            "too many bits for the resolution"
+        );
+        assert_eq!(
+            H3Index::unpack(122 << 27, Resolution::R9),
+            Err(H3Error::CellInvalid),
+            "no such base cell"
+        );
+        let pentagon = Resolution::R1.getPentagonIndexes()[0];
+        let deleted = pentagon.pack(Resolution::R1).unwrap() | 1;
+        assert_eq!(
+            H3Index::unpack(deleted, Resolution::R1),
+            Err(H3Error::CellInvalid),
+            "deleted pentagon subsequence"
+        );
+        assert_eq!(
+            H3Index::unpack(u64::MAX, Resolution::R15),
+            Err(H3Error::CellInvalid),
+            "all ones"
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::convert::TryInto;
+
+use super::{H3Index, IndexArray};
+use crate::{basecell::BaseCell, constants::NUM_PENTAGONS, Direction, Resolution};
+
+/// Number of neighbors, edges and vertexes of a pentagon.
+const PENTAGON_SIDES: usize = 5;
+
+/// One of the 12 pentagons of a resolution, from `H3Index::as_pentagon`.
+///
+/// Pentagons have 5 neighbors, edges and vertexes where hexagons have 6, and
+/// no descendants in the k direction: the k subsequence is deleted. Code that
+/// treats them apart can branch once on `as_pentagon` and then use these
+/// methods, whose results are sized for a pentagon.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PentagonCell(H3Index);
+
+/// The five indexes of a pentagon's neighbors, edges or vertexes.
+fn sides(indexes: IndexArray) -> [H3Index; PENTAGON_SIDES] {
+    indexes
+        .as_slice()
+        .try_into()
+        .expect("a pentagon has 5 sides")
+}
+
+impl PentagonCell {
+    /// The 12 pentagons of a resolution, in base cell order.
+    pub fn all(res: Resolution) -> [PentagonCell; NUM_PENTAGONS] {
+        let mut out = [PentagonCell(H3Index::H3_NULL); NUM_PENTAGONS];
+        for (p, h) in out.iter_mut().zip(res.getPentagonIndexes().iter()) {
+            *p = PentagonCell(*h);
+        }
+        out
+    }
+
+    /// The pentagon's index.
+    pub fn index(&self) -> H3Index {
+        self.0
+    }
+
+    /// Resolution of the pentagon.
+    pub fn resolution(&self) -> Resolution {
+        self.0.get_resolution()
+    }
+
+    /// The base cell, one of the 12 pentagonal base cells.
+    pub fn base_cell(&self) -> BaseCell {
+        self.0.get_base_cell()
+    }
+
+    /// The direction of the deleted subsequence: no descendant of the
+    /// pentagon has it as its first nonzero digit, and there is no neighbor,
+    /// edge or vertex that way.
+    pub fn deleted_direction(&self) -> Direction {
+        Direction::K_AXES_DIGIT
+    }
+
+    /// The 5 neighbors, in the order of `H3Index::neighbors`.
+    pub fn neighbors(&self) -> [H3Index; PENTAGON_SIDES] {
+        sides(self.0.neighbors())
+    }
+
+    /// The 5 unidirectional edges out of the pentagon, by direction from
+    /// J_AXES_DIGIT.
+    pub fn edges(&self) -> [H3Index; PENTAGON_SIDES] {
+        sides(self.0.getH3UnidirectionalEdgesFromHexagon())
+    }
+
+    /// The 5 vertexes, in the order of `H3Index::cellToVertexes`.
+    pub fn vertexes(&self) -> [H3Index; PENTAGON_SIDES] {
+        sides(self.0.cellToVertexes())
+    }
+
+    /// The parent pentagon, which is the parent of the pentagon's index; none
+    /// at resolution 0.
+    pub fn parent(&self) -> Option<PentagonCell> {
+        let res = self.resolution();
+        if res == Resolution::R0 {
+            return None;
+        }
+        self.0.h3ToParent(res - 1).ok().map(PentagonCell)
+    }
+
+    /// The center child, the one child that is a pentagon; none at
+    /// resolution 15.
+    pub fn center_child(&self) -> Option<PentagonCell> {
+        let res = self.resolution();
+        if res == Resolution::R15 {
+            return None;
+        }
+        Some(PentagonCell(self.0.h3ToCenterChild(res + 1)))
+    }
+
+    /**
+     * Number of descendants at a resolution: the center child's descendants
+     * and those of the 5 hexagon children, without the deleted subsequence.
+     *
+     * @param childRes Resolution of the descendants
+     * @return 1 + 5 * (7^d - 1) / 6 for d resolutions down, or 0 if childRes
+     *         is coarser than the pentagon
+     */
+    pub fn children_count(&self, childRes: Resolution) -> u64 {
+        let parentRes = self.resolution();
+        if !parentRes._isValidChildRes(&childRes) {
+            return 0;
+        }
+        let depth = (childRes as u64 - parentRes as u64) as u32;
+        1 + 5 * (7u64.pow(depth) - 1) / 6
+    }
+
+    /// The descendants at a resolution, as from `h3ToChildren`, which never
+    /// includes the deleted subsequence.
+    pub fn children(&self, childRes: Resolution) -> Vec<H3Index> {
+        self.0.h3ToChildren(childRes)
+    }
+}
+
+impl From<PentagonCell> for H3Index {
+    fn from(pentagon: PentagonCell) -> Self {
+        pentagon.0
+    }
+}
+
+impl H3Index {
+    /// The cell as a `PentagonCell`, if it is a valid pentagon.
+    pub fn as_pentagon(&self) -> Option<PentagonCell> {
+        if self.is_valid() && self.is_pentagon() {
+            Some(PentagonCell(*self))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeoCoord, H3Mode};
+
+    #[test]
+    fn asPentagon() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        for &res in Resolution::RESOLUTIONS.iter() {
+            assert!(sf.geoToH3(res).as_pentagon().is_none(), "hexagon");
+
+            let all = PentagonCell::all(res);
+            for (p, &h) in all.iter().zip(res.getPentagonIndexes().iter()) {
+                assert_eq!(h.as_pentagon(), Some(*p), "{}", h);
+                assert_eq!(H3Index::from(*p), h, "index");
+                assert_eq!(p.resolution(), res, "resolution");
+                assert!(p.base_cell()._isBaseCellPentagon(), "base cell");
+            }
+        }
+        assert!(H3Index::H3_NULL.as_pentagon().is_none(), "null");
+
+        // A pentagon's index with the mode of an edge is not a cell.
+        let mut edge = Resolution::R3.getPentagonIndexes()[0];
+        edge.set_mode(H3Mode::H3_UNIEDGE_MODE);
+        edge.set_reserved_bits(2);
+        assert!(edge.as_pentagon().is_none(), "edge");
+    }
+
+    #[test]
+    fn pentagonCell_sides() {
+        for &res in [Resolution::R0, Resolution::R1, Resolution::R6].iter() {
+            for p in PentagonCell::all(res).iter() {
+                let h = p.index();
+                let neighbors = p.neighbors();
+                assert_eq!(&neighbors[..], h.neighbors().as_slice(), "{}", h);
+                for n in neighbors.iter() {
+                    assert!(h.h3IndexesAreNeighbors(*n), "{} and {}", h, n);
+                }
+
+                for edge in p.edges().iter() {
+                    assert!(edge.h3UnidirectionalEdgeIsValid(), "{} valid", edge);
+                    assert_eq!(edge.getOriginH3IndexFromUnidirectionalEdge(), h);
+                }
+
+                let vertexes = p.vertexes();
+                assert_eq!(&vertexes[..], h.cellToVertexes().as_slice(), "{}", h);
+                assert!(vertexes.iter().all(|v| v.isValidVertex()), "{}", h);
+                assert_eq!(p.deleted_direction(), Direction::K_AXES_DIGIT);
+            }
+        }
+    }
+
+    #[test]
+    fn pentagonCell_hierarchy() {
+        let p = PentagonCell::all(Resolution::R2)[7];
+        assert_eq!(
+            p.parent(),
+            Some(PentagonCell::all(Resolution::R1)[7]),
+            "parent"
+        );
+        assert_eq!(
+            p.center_child(),
+            Some(PentagonCell::all(Resolution::R3)[7]),
+            "center child"
+        );
+        assert_eq!(PentagonCell::all(Resolution::R0)[0].parent(), None);
+        assert_eq!(PentagonCell::all(Resolution::R15)[0].center_child(), None);
+
+        for &childRes in &Resolution::RESOLUTIONS[2..6] {
+            let children = p.children(childRes);
+            assert_eq!(children.len() as u64, p.children_count(childRes));
+            assert_eq!(
+                children.iter().filter(|h| h.is_pentagon()).count(),
+                1,
+                "one pentagon child"
+            );
+            for h in children {
+                assert!(h.is_valid(), "{} valid", h);
+            }
+        }
+        assert_eq!(p.children_count(Resolution::R1), 0, "coarser");
+        assert_eq!(p.children_count(Resolution::R3), 6, "direct children");
+    }
+}
@@ -0,0 +1,239 @@
+//! Run-length digit notation for reading indexes in logs.
+//!
+//! `BC12:0^5 3 5 7*` is the resolution 8 cell on base cell 12 whose digits are
+//! five 0s followed by 3, 5 and 7. A run of `n` repeats of digit `d` is written
+//! `d^n`, and `*` ends the resolution's digits when every unused digit is 7.
+//! Otherwise the unused digits follow a `|`. Fields that differ from a cell
+//! index are appended: ` m<mode>`, ` r<reserved bits>` and ` h` for the high bit.
+
+use super::{H3Index, H3Mode};
+use crate::{basecell::BaseCell, H3Error, Resolution};
+use std::fmt::Write;
+
+/// Appends the digits as space separated runs.
+fn writeRuns(out: &mut String, digits: &[u64]) {
+    let mut i = 0;
+    while i < digits.len() {
+        let run = digits[i..].iter().take_while(|&&d| d == digits[i]).count();
+        if i > 0 {
+            out.push(' ');
+        }
+        if run > 1 {
+            write!(out, "{}^{}", digits[i], run).unwrap();
+        } else {
+            write!(out, "{}", digits[i]).unwrap();
+        }
+        i += run;
+    }
+}
+
+/// Expands one `d` or `d^n` run onto the digits.
+fn parseRun(token: &str, digits: &mut Vec<u64>) -> Result<(), H3Error> {
+    let (digit, run) = match token.split_once('^') {
+        Some((digit, run)) => (digit, run.parse::<usize>().map_err(|_| H3Error::Domain)?),
+        None => (token, 1),
+    };
+    let digit = digit.parse::<u64>().map_err(|_| H3Error::Domain)?;
+    if digit > 7 || run == 0 || run > Resolution::MAX_H3_RES - digits.len() {
+        return Err(H3Error::Domain);
+    }
+    digits.extend(std::iter::repeat_n(digit, run));
+    Ok(())
+}
+
+impl H3Index {
+    /**
+     * Formats the index with its digits run-length encoded, so that fine
+     * resolution indexes can be compared at a glance.
+     *
+     * @return The notation described in this module, which from_digit_rle
+     *         reads back to the same index
+     */
+    pub fn to_digit_rle(&self) -> String {
+        let res = usize::from(self.get_resolution());
+        let digits: Vec<u64> = Resolution::RESOLUTIONS[1..]
+            .iter()
+            .map(|&r| self.get_index_digit(r) as u64)
+            .collect();
+        let (used, unused) = digits.split_at(res);
+
+        let mut out = String::new();
+        write!(out, "BC{}:", i32::from(self.get_base_cell())).unwrap();
+        writeRuns(&mut out, used);
+        if unused.iter().all(|&d| d == 7) {
+            out.push('*');
+        } else {
+            out.push_str(" | ");
+            writeRuns(&mut out, unused);
+        }
+
+        let mode = (self.0 & Self::H3_MODE_MASK) >> Self::H3_MODE_OFFSET;
+        if mode != H3Mode::H3_HEXAGON_MODE as u64 {
+            write!(out, " m{}", mode).unwrap();
+        }
+        if self.get_reserved_bits() != 0 {
+            write!(out, " r{}", self.get_reserved_bits()).unwrap();
+        }
+        if self.get_high_bit() != 0 {
+            out.push_str(" h");
+        }
+        out
+    }
+
+    /**
+     * Parses the run-length notation written by to_digit_rle.
+     *
+     * @param s The notation, such as `BC12:0^5 3 5 7*`
+     * @return The index, or Domain if `s` is malformed or spells more than
+     *         15 digits
+     */
+    pub fn from_digit_rle(s: &str) -> Result<H3Index, H3Error> {
+        let (bc, rest) = s
+            .strip_prefix("BC")
+            .ok_or(H3Error::Domain)?
+            .split_once(':')
+            .ok_or(H3Error::Domain)?;
+        let bc = bc.parse::<i32>().map_err(|_| H3Error::Domain)?;
+        if !(0..128).contains(&bc) {
+            return Err(H3Error::Domain);
+        }
+
+        let rest = rest.replace('*', " * ").replace('|', " | ");
+        let mut tokens = rest.split_whitespace().peekable();
+
+        let mut digits = Vec::with_capacity(Resolution::MAX_H3_RES);
+        while let Some(token) = tokens.next_if(|t| t.starts_with(|c: char| c.is_ascii_digit())) {
+            parseRun(token, &mut digits)?;
+        }
+        let res = digits.len();
+        match tokens.next() {
+            Some("*") => digits.resize(Resolution::MAX_H3_RES, 7),
+            Some("|") => {
+                while let Some(token) =
+                    tokens.next_if(|t| t.starts_with(|c: char| c.is_ascii_digit()))
+                {
+                    parseRun(token, &mut digits)?;
+                }
+                if digits.len() != Resolution::MAX_H3_RES {
+                    return Err(H3Error::Domain);
+                }
+            }
+            _ => return Err(H3Error::Domain),
+        }
+
+        let mut h = H3Index(0);
+        h.set_mode(H3Mode::H3_HEXAGON_MODE);
+        h.set_base_cell(BaseCell::new_unchecked(bc));
+        h.set_resolution(Resolution::from(res));
+        for (&r, &digit) in Resolution::RESOLUTIONS[1..].iter().zip(digits.iter()) {
+            h.set_index_digit(r, digit);
+        }
+
+        for token in tokens {
+            let field = |max: u64| match token[1..].parse::<u64>() {
+                Ok(v) if v <= max => Ok(v),
+                _ => Err(H3Error::Domain),
+            };
+            match token.as_bytes()[0] {
+                // set_mode only takes the modes H3Mode names
+                b'm' => {
+                    let mode = field(15)?;
+                    h.0 = (h.0 & Self::H3_MODE_MASK_NEGATIVE) | (mode << Self::H3_MODE_OFFSET);
+                }
+                b'r' => h.set_reserved_bits(field(7)?),
+                b'h' if token.len() == 1 => h.set_high_bit(1),
+                _ => return Err(H3Error::Domain),
+            }
+        }
+        Ok(h)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, GeoCoord};
+
+    #[test]
+    fn digitRle_format() {
+        let mut h = H3Index::setH3Index(
+            Resolution::R8,
+            BaseCell::new_unchecked(12),
+            Direction::CENTER_DIGIT,
+        );
+        h.set_index_digit(Resolution::R6, 3);
+        h.set_index_digit(Resolution::R7, 5);
+        h.set_index_digit(Resolution::R8, 7);
+        assert_eq!(
+            h.to_digit_rle(),
+            "BC12:0^5 3 5 7*",
+            "runs and unused digits"
+        );
+
+        let baseCell = H3Index::setH3Index(
+            Resolution::R0,
+            BaseCell::new_unchecked(4),
+            Direction::CENTER_DIGIT,
+        );
+        assert_eq!(baseCell.to_digit_rle(), "BC4:*", "no digits at res 0");
+
+        assert_eq!(
+            H3Index::H3_NULL.to_digit_rle(),
+            "BC0: | 0^15 m0",
+            "unused digits and mode are spelled out"
+        );
+    }
+
+    #[test]
+    fn digitRle_roundtrip() {
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        for &res in Resolution::RESOLUTIONS.iter() {
+            let cell = sf.geoToH3(res);
+            let s = cell.to_digit_rle();
+            assert_eq!(H3Index::from_digit_rle(&s), Ok(cell), "cell {}", s);
+
+            for dir in 1..=6 {
+                let mut edge = cell;
+                edge.set_mode(H3Mode::H3_UNIEDGE_MODE);
+                edge.set_reserved_bits(dir);
+                let s = edge.to_digit_rle();
+                assert_eq!(H3Index::from_digit_rle(&s), Ok(edge), "edge {}", s);
+            }
+        }
+
+        for &bits in [0, u64::MAX, 0x8123456789abcdef].iter() {
+            let h = H3Index(bits);
+            let s = h.to_digit_rle();
+            assert_eq!(H3Index::from_digit_rle(&s), Ok(h), "raw bits {}", s);
+        }
+    }
+
+    #[test]
+    fn digitRle_invalid() {
+        for s in [
+            "",
+            "12:0*",
+            "BC:0*",
+            "BC128:*",
+            "BC12:0^5 3",
+            "BC12:8*",
+            "BC12:0^0*",
+            "BC12:0^16*",
+            "BC12:0 0^18446744073709551615*",
+            "BC12:0 | 7",
+            "BC12:0* x",
+            "BC12:0* m16",
+            "BC12:0* r8",
+            "BC12:0* h1",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                H3Index::from_digit_rle(s),
+                Err(H3Error::Domain),
+                "rejects {:?}",
+                s
+            );
+        }
+    }
+}
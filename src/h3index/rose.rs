@@ -0,0 +1,122 @@
+use crate::{Direction, H3Index};
+
+/// Number of neighbor directions around a hexagon.
+const NUM_ROSE_BINS: usize = 6;
+
+/// Histogram of destinations by the direction of their first step away from an
+/// origin cell, for drawing flow roses on the hex grid.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DirectionRose {
+    /// Counts by direction, indexed by digit minus one: K_AXES_DIGIT first,
+    /// IJ_AXES_DIGIT last. The K bin stays empty for pentagon origins.
+    pub counts: [usize; NUM_ROSE_BINS],
+
+    /// Destinations that were the origin itself, or that no line could be
+    /// drawn to (too far, across a pentagon, or at another resolution).
+    pub unbinned: usize,
+}
+
+impl DirectionRose {
+    /// Count of destinations whose first step was in the given direction.
+    pub fn count(&self, dir: Direction) -> usize {
+        match dir {
+            Direction::CENTER_DIGIT | Direction::INVALID_DIGIT => 0,
+            _ => self.counts[dir as usize - 1],
+        }
+    }
+
+    /// Total number of destinations that were binned.
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+impl H3Index {
+    /**
+     * Bins destination cells by the direction of the first step of the
+     * h3Line from this origin to each of them.
+     *
+     * @param destinations Cells at the origin's resolution
+     * @return The 6-bin histogram, and the number of destinations that could not
+     *         be binned
+     */
+    pub fn direction_rose(&self, destinations: &[H3Index]) -> DirectionRose {
+        let mut rose = DirectionRose::default();
+
+        for destination in destinations.iter() {
+            match self.firstStepDirection(*destination) {
+                Some(dir) => rose.counts[dir as usize - 1] += 1,
+                None => rose.unbinned += 1,
+            }
+        }
+
+        rose
+    }
+
+    /// Direction of the neighbor that the line to `destination` passes through first.
+    fn firstStepDirection(&self, destination: H3Index) -> Option<Direction> {
+        if destination == *self {
+            return None;
+        }
+
+        let line = H3Index::h3Line(*self, destination).ok()?;
+        H3Index::directionForNeighbor(*self, *line.get(1)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BaseCell, GeoCoord, Resolution};
+
+    #[test]
+    fn directionRose_neighbors() {
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+
+        // each neighbor, and each cell two steps straight out, lands in its own bin
+        let mut destinations = vec![origin];
+        for dir in Direction::VALID_DIRECTIONS.iter().skip(1) {
+            let mut rotations = 0;
+            let neighbor = origin.h3NeighborRotations(*dir, &mut rotations);
+            destinations.push(neighbor);
+            destinations.push(neighbor.h3NeighborRotations(*dir, &mut rotations));
+        }
+
+        let rose = origin.direction_rose(&destinations);
+        assert_eq!(rose.counts, [2; 6], "two destinations per direction");
+        assert_eq!(rose.unbinned, 1, "origin is not binned");
+        assert_eq!(rose.total(), 12, "all others binned");
+        assert_eq!(rose.count(Direction::I_AXES_DIGIT), 2, "count by direction");
+        assert_eq!(rose.count(Direction::CENTER_DIGIT), 0, "no center bin");
+    }
+
+    #[test]
+    fn directionRose_unreachable() {
+        let sf = GeoCoord::from_rads(0.659966917655, 2.0 * 3.14159 - 2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+
+        let otherRes = sf.geoToH3(Resolution::R8);
+        let farAway = GeoCoord::from_rads(-0.5, 0.5).geoToH3(Resolution::R9);
+
+        let rose = origin.direction_rose(&[otherRes, farAway]);
+        assert_eq!(rose.total(), 0, "nothing binned");
+        assert_eq!(rose.unbinned, 2, "both unbinned");
+    }
+
+    #[test]
+    fn directionRose_pentagon() {
+        let pentagon =
+            H3Index::from_components(BaseCell::new(4).unwrap(), &[Direction::CENTER_DIGIT; 2])
+                .unwrap();
+
+        let ring = pentagon.grid_ring(2).unwrap();
+        let rose = pentagon.direction_rose(&ring);
+        assert_eq!(rose.count(Direction::K_AXES_DIGIT), 0, "deleted direction");
+        assert_eq!(
+            rose.total() + rose.unbinned,
+            ring.len(),
+            "every destination accounted for"
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Cells as objects of an [rstar](https://docs.rs/rstar) R-tree, so that one
+//! spatial index can hold cells together with other geometries.
+//!
+//! Envelopes are `[lng, lat]` in degrees, the axis order and unit of
+//! geo-types, GeoJSON and most other geometry crates, so a cell's envelope
+//! can be compared with theirs directly. A tree mixing cells with other
+//! shapes stores an enum over them whose envelope delegates to each variant.
+
+use rstar::{RTreeObject, AABB};
+
+use crate::{geocoord::radsToDegs, H3Index};
+
+impl RTreeObject for H3Index {
+    type Envelope = AABB<[f64; 2]>;
+
+    /// The cell's `cell_bbox` as `[lng, lat]` corners in degrees. An
+    /// envelope cannot wrap around the antimeridian, so that of a cell
+    /// crossing it spans every longitude.
+    fn envelope(&self) -> Self::Envelope {
+        let bbox = self.cell_bbox();
+        let (west, east) = if bbox.bboxIsTransmeridian() {
+            (-180.0, 180.0)
+        } else {
+            (radsToDegs(bbox.west), radsToDegs(bbox.east))
+        };
+        AABB::from_corners(
+            [west, radsToDegs(bbox.south)],
+            [east, radsToDegs(bbox.north)],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::M_PI, GeoCoord, Resolution};
+    use rstar::{Envelope, RTree};
+
+    /// A shape indexed alongside cells.
+    enum Shape {
+        Cell(H3Index),
+        Point([f64; 2]),
+    }
+
+    impl RTreeObject for Shape {
+        type Envelope = AABB<[f64; 2]>;
+
+        fn envelope(&self) -> Self::Envelope {
+            match self {
+                Shape::Cell(h) => h.envelope(),
+                Shape::Point(p) => AABB::from_point(*p),
+            }
+        }
+    }
+
+    #[test]
+    fn envelope_containsCell() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R7);
+        let transmeridian = GeoCoord::from_rads(0.0, M_PI).geoToH3(Resolution::R3);
+        let mut cells = sf.kRing(2);
+        cells.push(transmeridian);
+
+        for h in cells.iter() {
+            let envelope = h.envelope();
+            let gb = h.h3ToGeoBoundary();
+            for v in gb.verts[..gb.numVerts].iter().chain(Some(&h.h3ToGeo())) {
+                let (lng, lat) = v.lng_lat();
+                assert!(envelope.contains_point(&[lng.0, lat.0]), "{} {}", h, v);
+            }
+        }
+        let wide = transmeridian.envelope();
+        assert_eq!(
+            (wide.lower()[0], wide.upper()[0]),
+            (-180.0, 180.0),
+            "every longitude"
+        );
+    }
+
+    #[test]
+    fn rtree_mixedShapes() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let cells = sf.geoToH3(Resolution::R8).kRing(1);
+        let (lng, lat) = sf.lng_lat();
+
+        let mut shapes: Vec<Shape> = cells.iter().map(|&h| Shape::Cell(h)).collect();
+        shapes.push(Shape::Point([lng.0, lat.0]));
+        shapes.push(Shape::Point([0.0, 0.0]));
+        let tree = RTree::bulk_load(shapes);
+
+        let found: Vec<&Shape> = tree
+            .locate_in_envelope_intersecting(&AABB::from_point([lng.0, lat.0]))
+            .collect();
+        assert!(
+            found
+                .iter()
+                .any(|s| matches!(s, Shape::Cell(h) if *h == sf.geoToH3(Resolution::R8))),
+            "the cell holding the point"
+        );
+        assert!(
+            found.iter().any(|s| matches!(s, Shape::Point(_))),
+            "the point itself"
+        );
+        assert!(found.len() < tree.size() - 1, "not everything");
+    }
+}
@@ -0,0 +1,104 @@
+use rand::Rng;
+
+use crate::{constants::M_2PI, geocoord::_geoAzDistanceRads, GeoCoord, H3Index};
+
+impl H3Index {
+    /**
+     * Generates approximately uniform random points inside the cell.
+     *
+     * Points are drawn uniformly from a spherical cap around the cell center
+     * that covers the boundary, and kept if they index back to this cell. The
+     * cell fills most of the cap, so few points are rejected.
+     *
+     * @param n The number of points to generate
+     * @param rng Source of randomness
+     * @return The points, in radians; empty if this is not a valid cell
+     */
+    pub fn sample_points<R: Rng + ?Sized>(&self, n: usize, rng: &mut R) -> Vec<GeoCoord> {
+        if !self.is_valid() {
+            return Vec::new();
+        }
+
+        let res = self.get_resolution();
+        let center = self.h3ToGeo();
+        let gb = self.h3ToGeoBoundary();
+
+        // the farthest point of the cell is one of its vertices; pad the cap
+        // a little so rounding cannot cut off a corner
+        let radius = gb.verts[..gb.numVerts]
+            .iter()
+            .map(|v| GeoCoord::pointDistRads(&center, v))
+            .fold(0.0, f64::max)
+            * 1.01;
+        let minCos = radius.cos();
+
+        let mut out = Vec::with_capacity(n);
+        while out.len() < n {
+            // uniform in area: the cosine of the distance from the center is
+            // uniform over the cap
+            let dist = (1.0 - rng.gen::<f64>() * (1.0 - minCos)).acos();
+            let az = rng.gen::<f64>() * M_2PI;
+            let point = _geoAzDistanceRads(&center, az, dist);
+
+            if point.geoToH3(res) == *self {
+                out.push(point);
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{basecell::BaseCell, Direction, Resolution};
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
+
+    #[test]
+    fn samplePoints_inside() {
+        let mut rng = Pcg64::seed_from_u64(1191);
+        let hexagon =
+            H3Index::from_components(BaseCell::new(20).unwrap(), &[Direction::J_AXES_DIGIT; 5])
+                .unwrap();
+        let pentagon = Resolution::R3.getPentagonIndexes()[0];
+
+        for h in [hexagon, pentagon].iter() {
+            let points = h.sample_points(500, &mut rng);
+            assert_eq!(points.len(), 500, "all points generated");
+            for p in points.iter() {
+                assert_eq!(p.geoToH3(h.get_resolution()), *h, "point inside cell");
+            }
+        }
+
+        assert!(
+            H3Index::H3_NULL.sample_points(10, &mut rng).is_empty(),
+            "no points for an invalid cell"
+        );
+    }
+
+    #[test]
+    fn samplePoints_uniform() {
+        let mut rng = Pcg64::seed_from_u64(1191);
+        let h = H3Index::from_components(BaseCell::new(20).unwrap(), &[Direction::J_AXES_DIGIT; 4])
+            .unwrap();
+        let centerChild = h.h3ToCenterChild(Resolution::R5);
+
+        // the center child lies wholly inside the cell, covering a seventh of it
+        let n = 7000;
+        let expected = n as f64 * centerChild.cellAreaRads2() / h.cellAreaRads2();
+        let count = h
+            .sample_points(n, &mut rng)
+            .iter()
+            .filter(|p| p.geoToH3(Resolution::R5) == centerChild)
+            .count() as f64;
+
+        assert!(
+            (count - expected).abs() < 4.0 * expected.sqrt(),
+            "{} points in the center child, expected about {}",
+            count,
+            expected
+        );
+    }
+}
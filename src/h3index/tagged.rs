@@ -0,0 +1,194 @@
+use std::convert::TryFrom;
+
+use super::H3Index;
+use crate::H3Error;
+
+/// A cell index with up to 3 bits of user flags stored in its reserved bits,
+/// for marking cells (visited, on the border, ...) in memory without a
+/// second array. This is the trick the C library's compact plays to count
+/// children, made explicit.
+///
+/// Valid cells always have their reserved bits at 0, so a tagged value is
+/// not a valid H3 index whenever its tag is not 0: do not store it, send it,
+/// or pass its raw value to anything expecting an index. Take the cell back
+/// out with `cell`, which clears the tag.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TaggedH3Index(u64);
+
+impl TaggedH3Index {
+    /// The largest tag, with all 3 flags set.
+    pub const MAX_TAG: u8 = 7;
+
+    /**
+     * Tags a cell.
+     *
+     * @param cell A valid cell; edges and vertexes use the reserved bits
+     * themselves
+     * @param tag The flags, 0 to `MAX_TAG`
+     * @return The tagged cell, `CellInvalid` if the cell is not valid, or
+     * `Domain` if the tag does not fit in 3 bits
+     */
+    pub fn new(cell: H3Index, tag: u8) -> Result<Self, H3Error> {
+        if !cell.is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        TaggedH3Index(cell.0).with_tag(tag)
+    }
+
+    /// The cell, with the tag cleared: a valid index again.
+    pub fn cell(&self) -> H3Index {
+        let mut h = H3Index(self.0);
+        h.set_reserved_bits(0);
+        h
+    }
+
+    /// All 3 flags, as a number from 0 to `MAX_TAG`.
+    pub fn tag(&self) -> u8 {
+        H3Index(self.0).get_reserved_bits() as u8
+    }
+
+    /// The same cell with a different tag, or `Domain` if it does not fit.
+    pub fn with_tag(self, tag: u8) -> Result<Self, H3Error> {
+        if tag > Self::MAX_TAG {
+            return Err(H3Error::Domain);
+        }
+        let mut h = H3Index(self.0);
+        h.set_reserved_bits(tag as u64);
+        Ok(TaggedH3Index(h.0))
+    }
+
+    /// Whether flag `bit`, 0 to 2, is set.
+    pub fn flag(&self, bit: u8) -> bool {
+        bit < 3 && self.tag() & (1 << bit) != 0
+    }
+
+    /// Sets or clears flag `bit`, 0 to 2; other bits are ignored.
+    pub fn set_flag(&mut self, bit: u8, on: bool) {
+        if bit >= 3 {
+            return;
+        }
+        let tag = if on {
+            self.tag() | (1 << bit)
+        } else {
+            self.tag() & !(1 << bit)
+        };
+        *self = self.with_tag(tag).unwrap_or(*self);
+    }
+
+    /// The raw bits, tag included, for keeping in a `u64` column of scratch
+    /// data. Not a valid index unless the tag is 0.
+    pub fn to_raw(&self) -> u64 {
+        self.0
+    }
+}
+
+impl TryFrom<u64> for TaggedH3Index {
+    type Error = H3Error;
+
+    /// Reads back `to_raw` bits, failing with `CellInvalid` if they are not a
+    /// valid cell once the tag is cleared.
+    fn try_from(raw: u64) -> Result<Self, H3Error> {
+        let tagged = TaggedH3Index(raw);
+        if !tagged.cell().is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        Ok(tagged)
+    }
+}
+
+impl From<H3Index> for TaggedH3Index {
+    /// An untagged copy of the index. Like `new`, but without validation:
+    /// an invalid index stays invalid.
+    fn from(h: H3Index) -> Self {
+        TaggedH3Index(h.0)
+    }
+}
+
+impl From<TaggedH3Index> for H3Index {
+    /// The cell, with the tag cleared.
+    fn from(tagged: TaggedH3Index) -> Self {
+        tagged.cell()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeoCoord, Resolution};
+
+    #[test]
+    fn taggedH3Index_roundTrip() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let pentagon = Resolution::R15.getPentagonIndexes()[11];
+        for &cell in [
+            sf.geoToH3(Resolution::R0),
+            sf.geoToH3(Resolution::R15),
+            pentagon,
+        ]
+        .iter()
+        {
+            for tag in 0..=TaggedH3Index::MAX_TAG {
+                let tagged = TaggedH3Index::new(cell, tag).expect("tag fits");
+                assert_eq!(tagged.tag(), tag, "tag kept");
+                assert_eq!(tagged.cell(), cell, "cell recovered");
+                assert_eq!(H3Index::from(tagged), cell, "converts back");
+                assert_eq!(
+                    H3Index(tagged.to_raw()).is_valid(),
+                    tag == 0,
+                    "tagged raw value is not an index"
+                );
+                assert_eq!(
+                    TaggedH3Index::try_from(tagged.to_raw()),
+                    Ok(tagged),
+                    "raw round trip"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn taggedH3Index_flags() {
+        let cell = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+        let mut tagged = TaggedH3Index::from(cell);
+        assert_eq!(tagged.tag(), 0, "untagged");
+
+        tagged.set_flag(0, true);
+        tagged.set_flag(2, true);
+        assert_eq!(tagged.tag(), 0b101, "flags 0 and 2");
+        assert!(tagged.flag(0) && !tagged.flag(1) && tagged.flag(2), "flags");
+
+        tagged.set_flag(0, false);
+        tagged.set_flag(3, true);
+        assert_eq!(tagged.tag(), 0b100, "flag 0 cleared, flag 3 ignored");
+        assert!(!tagged.flag(3), "no flag 3");
+        assert_eq!(tagged.cell(), cell, "cell untouched");
+    }
+
+    #[test]
+    fn taggedH3Index_invalid() {
+        let cell = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+        assert_eq!(
+            TaggedH3Index::new(cell, 8),
+            Err(H3Error::Domain),
+            "tag too big"
+        );
+        assert_eq!(
+            TaggedH3Index::new(H3Index::H3_NULL, 1),
+            Err(H3Error::CellInvalid),
+            "not a cell"
+        );
+        let mut edge = cell;
+        edge.set_mode(crate::H3Mode::H3_EDGE_MODE);
+        edge.set_reserved_bits(1);
+        assert_eq!(
+            TaggedH3Index::new(edge, 0),
+            Err(H3Error::CellInvalid),
+            "edge"
+        );
+        assert_eq!(
+            TaggedH3Index::try_from(edge.0),
+            Err(H3Error::CellInvalid),
+            "edge bits"
+        );
+    }
+}
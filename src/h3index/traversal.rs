@@ -0,0 +1,341 @@
+use super::H3Index;
+use crate::H3Error;
+
+/// What a traversal does when a pentagon or its distortion is in the way of
+/// the fast ring walk.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PentagonPolicy {
+    /// Fall back to the slower traversal that handles pentagons, as `kRing`
+    /// does
+    #[default]
+    Fallback,
+
+    /// Fail with `H3Error::Pentagon`, as the C library's `hexRange` does
+    Error,
+}
+
+/**
+ * Configures a traversal of the cells around an origin: which distances to
+ * include, what to do about pentagons and whether to order the cells by
+ * distance. Then one of `cells`, `distances`, their `_into` variants or
+ * `visit` runs it, so each combination does not need a function of its own.
+ *
+ * A disk, a ring and a collar are the distances from `min_k` to `max_k`,
+ * from 0, at one k, and from past an inner radius. Rings and collars walk
+ * only their own cells, like `grid_ring` and `grid_collar`. Distances go up
+ * to `H3Index::MAX_KRING_K`; a traversal reaching past it fails with
+ * `H3Error::Domain` rather than panicking as `kRing` does.
+ *
+ * ```
+ * use h3rs::{prelude::*, PentagonPolicy};
+ *
+ * let origin = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277)).geoToH3(Resolution::R9);
+ * let collar = origin
+ *     .traversal()
+ *     .collar(1, 3)
+ *     .pentagons(PentagonPolicy::Error)
+ *     .ordered(true)
+ *     .distances()
+ *     .unwrap();
+ * assert_eq!(collar.len(), 12 + 18);
+ * assert_eq!(collar[0].1, 2);
+ * ```
+ */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TraversalBuilder {
+    origin: H3Index,
+    minK: u32,
+    maxK: u32,
+    pentagons: PentagonPolicy,
+    ordered: bool,
+}
+
+impl TraversalBuilder {
+    /// A traversal of the origin alone, with pentagons handled by falling
+    /// back and cells in no particular order.
+    pub fn new(origin: H3Index) -> Self {
+        TraversalBuilder {
+            origin,
+            minK: 0,
+            maxK: 0,
+            pentagons: PentagonPolicy::default(),
+            ordered: false,
+        }
+    }
+
+    /// The cells within distance k of the origin, as from `kRing`.
+    pub fn disk(self, k: u32) -> Self {
+        self.distances_between(0, k)
+    }
+
+    /// The cells at exactly distance k, as from `grid_ring`.
+    pub fn ring(self, k: u32) -> Self {
+        self.distances_between(k, k)
+    }
+
+    /// The cells past distance `k_inner` and up to `k_outer`, as from
+    /// `grid_collar`; none unless `k_outer` is above `k_inner`.
+    pub fn collar(self, k_inner: u32, k_outer: u32) -> Self {
+        match k_inner.checked_add(1) {
+            Some(minK) => self.distances_between(minK, k_outer),
+            None => self.distances_between(1, 0),
+        }
+    }
+
+    /// The cells at distances `min_k` through `max_k`; none if `min_k` is
+    /// above `max_k`.
+    pub fn distances_between(mut self, min_k: u32, max_k: u32) -> Self {
+        self.minK = min_k;
+        self.maxK = max_k;
+        self
+    }
+
+    /// What to do when a pentagon is in the way.
+    pub fn pentagons(mut self, policy: PentagonPolicy) -> Self {
+        self.pentagons = policy;
+        self
+    }
+
+    /// Whether to return cells by increasing distance from the origin, rather
+    /// than in whatever order the traversal finds them.
+    pub fn ordered(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
+
+    /// Runs the traversal, returning the cells.
+    pub fn cells(&self) -> Result<Vec<H3Index>, H3Error> {
+        Ok(self.run()?.into_iter().map(|(h, _)| h).collect())
+    }
+
+    /// Runs the traversal, returning each cell with its distance from the
+    /// origin.
+    pub fn distances(&self) -> Result<Vec<(H3Index, u32)>, H3Error> {
+        self.run()
+    }
+
+    /// Runs the traversal, adding the cells to a container, such as a
+    /// `HashSet` or a reused `Vec`. Nothing is added on failure.
+    pub fn cells_into<C: Extend<H3Index>>(&self, out: &mut C) -> Result<(), H3Error> {
+        out.extend(self.run()?.into_iter().map(|(h, _)| h));
+        Ok(())
+    }
+
+    /// Runs the traversal, adding each cell with its distance to a container,
+    /// such as a `HashMap`. Nothing is added on failure.
+    pub fn distances_into<C: Extend<(H3Index, u32)>>(&self, out: &mut C) -> Result<(), H3Error> {
+        out.extend(self.run()?);
+        Ok(())
+    }
+
+    /// Runs the traversal, passing each cell and its distance to `visit`.
+    /// Nothing is visited on failure.
+    pub fn visit(&self, mut visit: impl FnMut(H3Index, u32)) -> Result<(), H3Error> {
+        for (h, d) in self.run()? {
+            visit(h, d);
+        }
+        Ok(())
+    }
+
+    /**
+     * Walks the rings, or the whole disk when it starts at the origin, falling
+     * back to the pentagon-safe traversal of the disk if allowed.
+     *
+     * @return The cells and their distances, or CellInvalid for an invalid
+     *         origin, Domain for a max k above `H3Index::MAX_KRING_K`, or
+     *         Pentagon
+     */
+    fn run(&self) -> Result<Vec<(H3Index, u32)>, H3Error> {
+        if !self.origin.is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        let mut out = Vec::new();
+        if self.minK > self.maxK {
+            return Ok(out);
+        }
+        if self.maxK > H3Index::MAX_KRING_K {
+            return Err(H3Error::Domain);
+        }
+
+        // Both walks produce cells by increasing distance.
+        let walked = if self.minK == 0 {
+            self.origin
+                .hexRangeVisit(self.maxK, |h, d| out.push((h, d)))
+                .is_ok()
+        } else {
+            (self.minK..=self.maxK).all(|k| match self.origin.hexRing(k) {
+                Ok(ring) => {
+                    out.extend(ring.into_iter().map(|h| (h, k)));
+                    true
+                }
+                Err(_) => false,
+            })
+        };
+        if walked {
+            return Ok(out);
+        }
+
+        if self.pentagons == PentagonPolicy::Error {
+            return Err(H3Error::Pentagon);
+        }
+        out.clear();
+        let minK = self.minK;
+        self.origin.kRingFallback(self.maxK, |h, d| {
+            if d >= minK {
+                out.push((h, d));
+            }
+        });
+        if self.ordered {
+            out.sort_by_key(|&(_, d)| d);
+        }
+        Ok(out)
+    }
+}
+
+impl H3Index {
+    /// Starts configuring a traversal around this cell; see
+    /// `TraversalBuilder`.
+    pub fn traversal(&self) -> TraversalBuilder {
+        TraversalBuilder::new(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeoCoord, Resolution};
+    use std::collections::{HashMap, HashSet};
+
+    fn sorted(mut cells: Vec<H3Index>) -> Vec<H3Index> {
+        cells.sort_by_key(|&h| u64::from(h));
+        cells
+    }
+
+    #[test]
+    fn traversalBuilder_matchesFunctions() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+        let pentagon = Resolution::R4.getPentagonIndexes()[2];
+        let nearPentagon = pentagon.grid_ring(2).unwrap()[0];
+
+        for &origin in [sf, pentagon, nearPentagon].iter() {
+            let t = origin.traversal();
+            for k in 0..4 {
+                assert_eq!(
+                    sorted(t.disk(k).cells().unwrap()),
+                    sorted(origin.kRing(k)),
+                    "{} disk {}",
+                    origin,
+                    k
+                );
+                assert_eq!(
+                    sorted(t.ring(k).cells().unwrap()),
+                    sorted(origin.grid_ring(k).unwrap()),
+                    "{} ring {}",
+                    origin,
+                    k
+                );
+                assert_eq!(
+                    sorted(t.collar(1, k).cells().unwrap()),
+                    sorted(origin.grid_collar(1, k).unwrap_or_default()),
+                    "{} collar {}",
+                    origin,
+                    k
+                );
+
+                let expected: HashMap<H3Index, u32> =
+                    origin.kRingDistances(k).into_iter().collect();
+                let mut distances = HashMap::new();
+                t.disk(k).distances_into(&mut distances).unwrap();
+                assert_eq!(distances, expected, "{} distances {}", origin, k);
+            }
+        }
+    }
+
+    #[test]
+    fn traversalBuilder_options() {
+        let pentagon = Resolution::R4.getPentagonIndexes()[2];
+        let nearPentagon = pentagon.grid_ring(2).unwrap()[0];
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+
+        for &origin in [sf, pentagon, nearPentagon].iter() {
+            let ordered = origin
+                .traversal()
+                .disk(4)
+                .ordered(true)
+                .distances()
+                .unwrap();
+            assert!(
+                ordered.windows(2).all(|w| w[0].1 <= w[1].1),
+                "{} by distance",
+                origin
+            );
+            let collar = origin
+                .traversal()
+                .collar(1, 4)
+                .ordered(true)
+                .distances()
+                .unwrap();
+            assert!(
+                collar.windows(2).all(|w| w[0].1 <= w[1].1) && collar[0].1 == 2,
+                "{} collar by distance",
+                origin
+            );
+        }
+
+        for &origin in [pentagon, nearPentagon].iter() {
+            let strict = origin.traversal().disk(3).pentagons(PentagonPolicy::Error);
+            assert_eq!(strict.cells(), Err(H3Error::Pentagon), "{} disk", origin);
+            let mut visited = 0;
+            assert_eq!(
+                strict.visit(|_, _| visited += 1),
+                Err(H3Error::Pentagon),
+                "{} visit",
+                origin
+            );
+            assert_eq!(visited, 0, "nothing visited on failure");
+        }
+        assert!(
+            sf.traversal()
+                .disk(3)
+                .pentagons(PentagonPolicy::Error)
+                .cells()
+                .is_ok(),
+            "no pentagon nearby"
+        );
+
+        let mut set = HashSet::new();
+        sf.traversal().ring(2).cells_into(&mut set).unwrap();
+        assert_eq!(set.len(), 12, "ring into a set");
+        assert_eq!(sf.traversal().cells(), Ok(vec![sf]), "origin alone");
+        assert_eq!(
+            sf.traversal().collar(3, 3).cells(),
+            Ok(vec![]),
+            "empty collar"
+        );
+        assert_eq!(
+            sf.traversal().collar(u32::MAX, 3).cells(),
+            Ok(vec![]),
+            "no overflow"
+        );
+        assert_eq!(
+            H3Index::H3_NULL.traversal().disk(1).cells(),
+            Err(H3Error::CellInvalid),
+            "invalid origin"
+        );
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+        for builder in [
+            sf.traversal().disk(40_000),
+            sf.traversal().ring(H3Index::MAX_KRING_K + 1),
+            sf.traversal().collar(10, u32::MAX),
+        ]
+        .iter()
+        {
+            assert_eq!(builder.cells(), Err(H3Error::Domain), "k past the limit");
+        }
+        assert_eq!(
+            sf.traversal().distances_between(50_000, 40_000).cells(),
+            Ok(vec![]),
+            "empty range"
+        );
+    }
+}
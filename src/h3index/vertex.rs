@@ -2,10 +2,13 @@ use crate::{
     basecell::BaseCell,
     constants::{NUM_HEX_VERTS, NUM_PENTAGONS, NUM_PENT_VERTS},
     direction::DIRECTION_NUM_DIGITS,
-    Direction, Resolution,
+    Direction, H3Error, Resolution,
 };
 
-use super::{H3Index, H3Mode};
+use super::{H3Index, H3Mode, IndexArray};
+
+/// Offset of the first direction (J) in the direction-to-face tables
+const DIRECTION_INDEX_OFFSET: usize = 2;
 
 /// The faces in each axial direction of a given pentagon base cell
 pub(crate) struct PentagonDirectionFaces {
@@ -17,7 +20,7 @@ pub(crate) struct PentagonDirectionFaces {
 
 impl PentagonDirectionFaces {
     const fn new(bc: i32, faces: [i32; NUM_PENT_VERTS]) -> Self {
-        let baseCell = BaseCell::new(bc);
+        let baseCell = BaseCell::new_unchecked(bc);
         Self { baseCell, faces }
     }
 }
@@ -43,8 +46,50 @@ const pentagonDirectionFaces: [PentagonDirectionFaces; NUM_PENTAGONS] = [
 ];
 
 impl H3Index {
-    fn vertexRotations(&self /*cell*/) -> usize {
-        todo!()
+    /**
+     * Get the number of CCW rotations of the cell's vertex numbers
+     * compared to the directional layout of its neighbors.
+     * @return Number of CCW rotations for the cell, or CellInvalid if the base
+     *         cell is out of range
+     */
+    fn vertexRotations(&self /*cell*/) -> Result<usize, H3Error> {
+        // Get the face and other info for the origin
+        let fijk = self._h3ToFaceIjk()?;
+        let baseCell = self.get_base_cell();
+        let cellLeadingDigit = self._h3LeadingNonZeroDigit();
+
+        // get the base cell face
+        let baseFijk = baseCell._baseCellToFaceIjk()?;
+
+        let mut ccwRot60 = baseCell._baseCellToCCWrot60(fijk.face as usize) as usize;
+
+        if baseCell._isBaseCellPentagon() {
+            // Find the appropriate direction-to-face mapping
+            let dirFaces = pentagonDirectionFaces
+                .iter()
+                .find(|p| p.baseCell == baseCell)
+                .expect("every pentagon base cell has direction faces");
+            let ikFace = dirFaces.faces[Direction::IK_AXES_DIGIT as usize - DIRECTION_INDEX_OFFSET];
+            let jkFace = dirFaces.faces[Direction::JK_AXES_DIGIT as usize - DIRECTION_INDEX_OFFSET];
+
+            // additional CCW rotation for polar neighbors or IK neighbors
+            if fijk.face != baseFijk.face
+                && (baseCell._isBaseCellPolarPentagon() || fijk.face == ikFace)
+            {
+                ccwRot60 = (ccwRot60 + 1) % 6;
+            }
+
+            // Check whether the cell crosses a deleted pentagon subsequence
+            if cellLeadingDigit == Direction::JK_AXES_DIGIT && fijk.face == ikFace {
+                // Crosses from JK to IK: Rotate CW
+                ccwRot60 = (ccwRot60 + 5) % 6;
+            } else if cellLeadingDigit == Direction::IK_AXES_DIGIT && fijk.face == jkFace {
+                // Crosses from IK to JK: Rotate CCW
+                ccwRot60 = (ccwRot60 + 1) % 6;
+            }
+        }
+
+        Ok(ccwRot60)
     }
 
     /**
@@ -54,7 +99,7 @@ impl H3Index {
      * @returns The number for the first topological vertex, or INVALID_VERTEX_NUM
      *          if the direction is not valid for this cell
      */
-    fn vertexNumForDirection(&self, direction: Option<Direction>) -> Option<usize> {
+    pub(crate) fn vertexNumForDirection(&self, direction: Option<Direction>) -> Option<usize> {
         /// Hexagon direction to vertex number relationships (same face). Note that we don't use direction 0 (center).
         const directionToVertexNumHex: [usize; DIRECTION_NUM_DIGITS] =
             [7 /* invalid digit */, 3, 1, 2, 5, 4, 0];
@@ -73,7 +118,7 @@ impl H3Index {
         };
 
         // Determine the vertex rotations for this cell
-        let rotations = self.vertexRotations();
+        let rotations = self.vertexRotations().ok()?;
 
         // Find the appropriate vertex, rotating CCW if necessary
         let result = if isPentagon {
@@ -120,7 +165,10 @@ impl H3Index {
         //return INVALID_DIGIT;
 
         // Determine the vertex rotations for this cell
-        let rotations = self.vertexRotations();
+        let rotations = match self.vertexRotations() {
+            Ok(rotations) => rotations,
+            Err(_) => return INVALID_DIGIT,
+        };
 
         // Find the appropriate direction, rotating CW if necessary
         if isPentagon {
@@ -142,7 +190,7 @@ impl H3Index {
      * TODO: This is currently a brute-force algorithm, but as it's O(6) that's
      * probably acceptible.
      */
-    fn directionForNeighbor(origin: H3Index, destination: H3Index) -> Option<Direction> {
+    pub(crate) fn directionForNeighbor(origin: H3Index, destination: H3Index) -> Option<Direction> {
         let isPentagon = origin.is_pentagon();
 
         // Checks each neighbor, in order, to determine which direction the
@@ -190,12 +238,12 @@ impl H3Index {
         // Reverse direction from neighbor in each direction, given as an index into DIRECTIONS to facilitate rotation
         const revNeighborDirectionsHex: [Option<Direction>; 7] = [
             None, //INVALID_DIGIT,
-            Some(Direction::from(5u64)),
-            Some(Direction::from(3u64)),
-            Some(Direction::from(4u64)),
-            Some(Direction::from(1u64)),
-            Some(Direction::from(0u64)),
-            Some(Direction::from(2u64)),
+            Some(Direction::IK_AXES_DIGIT),
+            Some(Direction::JK_AXES_DIGIT),
+            Some(Direction::I_AXES_DIGIT),
+            Some(Direction::K_AXES_DIGIT),
+            Some(Direction::CENTER_DIGIT),
+            Some(Direction::J_AXES_DIGIT),
         ];
 
         let cellNumVerts = if self.is_pentagon() {
@@ -206,7 +254,7 @@ impl H3Index {
         let res = self.get_resolution();
 
         // Check for invalid vertexes
-        if vertexNum < 0 || vertexNum > cellNumVerts - 1 {
+        if vertexNum > cellNumVerts - 1 {
             return H3Index::H3_NULL;
         }
 
@@ -223,10 +271,11 @@ impl H3Index {
             // Get the left neighbor of the vertex, with its rotations
             let left = self.directionForVertexNum(vertexNum);
 
-            // This case should be unreachable; invalid verts fail earlier
-            //if left == INVALID_DIGIT {
-            //    return H3Index::H3_NULL; // LCOV_EXCL_LINE
-            //}
+            // Invalid verts fail earlier, so this only happens for an
+            // invalid base cell
+            if left == Direction::INVALID_DIGIT {
+                return H3Index::H3_NULL;
+            }
 
             let mut lRotations = 0;
             let leftNeighbor = self.h3NeighborRotations(left, &mut lRotations);
@@ -241,7 +290,7 @@ impl H3Index {
                 // Get the right neighbor of the vertex, with its rotations
                 // Note that vertex - 1 is the right side, as vertex numbers are CCW
                 let right =
-                    self.directionForVertexNum((vertexNum - 1 + cellNumVerts) % cellNumVerts);
+                    self.directionForVertexNum((vertexNum + cellNumVerts - 1) % cellNumVerts);
 
                 // This case should be unreachable; invalid verts fail earlier
                 if right == Direction::INVALID_DIGIT {
@@ -261,32 +310,43 @@ impl H3Index {
                         let index = right as usize;
                         let rev_dir = revNeighborDirectionsHex[index]
                             .expect("Invalid direction indexing into revNeighborDirectionsHex");
-                        Some(DIRECTIONS[(rev_dir + rRotations) as usize % NUM_HEX_VERTS as usize])
+                        Some(
+                            DIRECTIONS
+                                [(rev_dir as usize + rRotations as usize) % NUM_HEX_VERTS as usize],
+                        )
                     };
 
-                    //ownerVertexNum = owner.vertexNumForDirection(dir);
+                    if let Some(vertexNum) = owner.vertexNumForDirection(dir) {
+                        ownerVertexNum = vertexNum;
+                    }
                 }
             }
 
-            /*
             // Determine the vertex number for the left neighbor
-            if (owner == leftNeighbor) {
-                int ownerIsPentagon = H3_EXPORT(h3IsPentagon)(owner);
-                Direction dir =
-                    ownerIsPentagon
-                        ? directionForNeighbor(owner, cell)
-                        : DIRECTIONS[(revNeighborDirectionsHex[left] + lRotations) %
-                                     NUM_HEX_VERTS];
+            if owner == leftNeighbor {
+                let ownerIsPentagon = owner.is_pentagon();
+                let dir = if ownerIsPentagon {
+                    H3Index::directionForNeighbor(owner, *self)
+                } else {
+                    let rev_dir = revNeighborDirectionsHex[left as usize]
+                        .expect("Invalid direction indexing into revNeighborDirectionsHex");
+                    Some(
+                        DIRECTIONS
+                            [(rev_dir as usize + lRotations as usize) % NUM_HEX_VERTS as usize],
+                    )
+                };
 
                 // For the left neighbor, we need the second vertex of the
                 // edge, which may involve looping around the vertex nums
-                ownerVertexNum = vertexNumForDirection(owner, dir) + 1;
-                if (ownerVertexNum == NUM_HEX_VERTS ||
-                    (ownerIsPentagon && ownerVertexNum == NUM_PENT_VERTS)) {
-                    ownerVertexNum = 0;
+                if let Some(vertexNum) = owner.vertexNumForDirection(dir) {
+                    let ownerNumVerts = if ownerIsPentagon {
+                        NUM_PENT_VERTS
+                    } else {
+                        NUM_HEX_VERTS as usize
+                    };
+                    ownerVertexNum = (vertexNum + 1) % ownerNumVerts;
                 }
             }
-            */
         }
 
         // Create the vertex index
@@ -300,13 +360,18 @@ impl H3Index {
     /**
      * Get all vertexes for the given cell
      * @param cell      Cell to get the vertexes for
-     * @param vertexes  Array to hold vertex output. Must have length >= 6.
+     * @return The vertexes in vertex number order; 5 for a pentagon, where the
+     *         C library leaves H3_NULL in the sixth slot
      */
-    pub fn cellToVertexes(&self) -> Vec<H3Index> {
-        // Get all vertexes. If the cell is a pentagon, will fill the final slot with H3_NULL.
-        (0..NUM_HEX_VERTS)
-            .map(|i| self.cellToVertex(i as usize))
-            .collect()
+    pub fn cellToVertexes(&self) -> IndexArray {
+        let mut vertexes = IndexArray::default();
+        for i in 0..NUM_HEX_VERTS {
+            let vertex = self.cellToVertex(i as usize);
+            if vertex != H3Index::H3_NULL {
+                vertexes.push(vertex);
+            }
+        }
+        vertexes
     }
 
     /**
@@ -335,3 +400,41 @@ impl H3Index {
         *self == canonical
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Every vertex is shared by three cells, of which exactly one owns it, so
+    /// by Euler's formula a grid of F cells has 2F - 4 distinct vertexes.
+    fn assertVertexesCanonical(cells: &[H3Index]) {
+        let mut vertexes = HashSet::new();
+        for cell in cells.iter() {
+            let cellVertexes = cell.cellToVertexes();
+            let expected = if cell.is_pentagon() { 5 } else { 6 };
+            assert_eq!(
+                cellVertexes.len(),
+                expected,
+                "only pentagons lack a sixth vertex"
+            );
+            for vertex in cellVertexes {
+                assert!(vertex.isValidVertex(), "vertex is valid");
+                vertexes.insert(vertex);
+            }
+        }
+        assert_eq!(vertexes.len(), 2 * cells.len() - 4, "distinct vertexes");
+    }
+
+    #[test]
+    fn cellToVertex_sharedVertexesEqual() {
+        let res0 = H3Index::getRes0Indexes();
+        assertVertexesCanonical(&res0);
+
+        let res1: Vec<H3Index> = res0
+            .iter()
+            .flat_map(|h| h.h3ToChildren(Resolution::R1))
+            .collect();
+        assertVertexesCanonical(&res1);
+    }
+}
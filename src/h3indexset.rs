@@ -0,0 +1,361 @@
+//! A set of cells at a coarse resolution, stored as one bit per cell.
+
+use crate::{basecell::BaseCell, Direction, H3Error, H3Index, Resolution};
+
+/// Bits in a word of the bitmap.
+const WORD_BITS: usize = 64;
+
+/**
+ * A set of cells at one resolution, up to `MAX_RES`, backed by a bitmap with
+ * a bit for every cell of that resolution.
+ *
+ * The globe only has about 290,000 cells at res 4, so the bitmap takes 36 KB
+ * at most, and membership, insertion and removal are a bit operation with
+ * no hashing. Unions and intersections work a word at a time. This beats a
+ * `HashSet` by a wide margin for global analyses at coarse resolutions, where
+ * sets hold a sizable share of all cells; use a hash set for finer cells,
+ * where a bitmap of the whole globe would not fit.
+ *
+ * A cell's bit is at its base cell number times 7^res, plus its digits read
+ * as a base 7 number, so iteration is in ascending index order.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct H3IndexSet {
+    res: Resolution,
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl H3IndexSet {
+    /// The finest resolution a set may hold.
+    pub const MAX_RES: Resolution = Resolution::R4;
+
+    /**
+     * Creates an empty set.
+     *
+     * @param res The resolution of the cells, up to `MAX_RES`
+     * @return The set, or Domain if the resolution is finer than `MAX_RES`
+     */
+    pub fn new(res: Resolution) -> Result<Self, H3Error> {
+        if res > Self::MAX_RES {
+            return Err(H3Error::Domain);
+        }
+        let bits = BaseCell::NUM_BASE_CELLS * 7usize.pow(usize::from(res) as u32);
+        Ok(H3IndexSet {
+            res,
+            words: vec![0; bits.div_ceil(WORD_BITS)],
+            len: 0,
+        })
+    }
+
+    /**
+     * Creates a set holding some cells.
+     *
+     * @param res The resolution of the cells, up to `MAX_RES`
+     * @param cells The cells
+     * @return The set, or Domain, CellInvalid or ResolutionMismatch
+     */
+    pub fn from_cells(
+        res: Resolution,
+        cells: impl IntoIterator<Item = H3Index>,
+    ) -> Result<Self, H3Error> {
+        let mut set = Self::new(res)?;
+        for h in cells {
+            set.insert(h)?;
+        }
+        Ok(set)
+    }
+
+    /// The resolution of the cells.
+    pub fn resolution(&self) -> Resolution {
+        self.res
+    }
+
+    /// Number of cells in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the set holds no cells.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes every cell.
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|w| *w = 0);
+        self.len = 0;
+    }
+
+    /// Bit of a cell at the set's resolution, assumed valid.
+    fn slot(&self, h: H3Index) -> usize {
+        let mut slot = i32::from(h.get_base_cell()) as usize;
+        for r in 1..=usize::from(self.res) {
+            slot = slot * 7 + h.get_index_digit(r.into()) as usize;
+        }
+        slot
+    }
+
+    /// Cell of a bit; the inverse of `slot`.
+    fn cell(&self, mut slot: usize) -> H3Index {
+        let mut h = H3Index::setH3Index(
+            self.res,
+            BaseCell::new_unchecked(0),
+            Direction::CENTER_DIGIT,
+        );
+        for r in (1..=usize::from(self.res)).rev() {
+            h.set_index_digit(r.into(), (slot % 7) as u64);
+            slot /= 7;
+        }
+        h.set_base_cell(BaseCell::new_unchecked(slot as i32));
+        h
+    }
+
+    /**
+     * Adds a cell.
+     *
+     * @param h The cell, at the set's resolution
+     * @return Whether the cell was not in the set yet, or CellInvalid or
+     *         ResolutionMismatch
+     */
+    pub fn insert(&mut self, h: H3Index) -> Result<bool, H3Error> {
+        if !h.is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        if h.get_resolution() != self.res {
+            return Err(H3Error::ResolutionMismatch);
+        }
+        let slot = self.slot(h);
+        let word = &mut self.words[slot / WORD_BITS];
+        let bit = 1 << (slot % WORD_BITS);
+        let added = *word & bit == 0;
+        *word |= bit;
+        self.len += added as usize;
+        Ok(added)
+    }
+
+    /// Removes a cell, returning whether it was in the set.
+    pub fn remove(&mut self, h: H3Index) -> bool {
+        if !self.contains(h) {
+            return false;
+        }
+        let slot = self.slot(h);
+        self.words[slot / WORD_BITS] &= !(1 << (slot % WORD_BITS));
+        self.len -= 1;
+        true
+    }
+
+    /// Whether a cell is in the set; false for anything that is not a cell at
+    /// the set's resolution.
+    pub fn contains(&self, h: H3Index) -> bool {
+        if h.get_resolution() != self.res {
+            return false;
+        }
+        // Only valid cells are inserted, so anything that maps to a set bit
+        // and back to itself is one of them; cheaper than is_valid.
+        let slot = self.slot(h);
+        slot / WORD_BITS < self.words.len()
+            && self.words[slot / WORD_BITS] & (1 << (slot % WORD_BITS)) != 0
+            && self.cell(slot) == h
+    }
+
+    /// The cells, in ascending index order.
+    pub fn iter(&self) -> impl Iterator<Item = H3Index> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(self.cell(i * WORD_BITS + bit))
+            })
+        })
+    }
+
+    /// Combines another set into this one a word at a time, recounting.
+    fn combine(&mut self, other: &Self, op: impl Fn(u64, u64) -> u64) -> Result<(), H3Error> {
+        if self.res != other.res {
+            return Err(H3Error::ResolutionMismatch);
+        }
+        let mut len = 0;
+        for (a, &b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a = op(*a, b);
+            len += a.count_ones() as usize;
+        }
+        self.len = len;
+        Ok(())
+    }
+
+    /// Adds the cells of another set, or fails with ResolutionMismatch.
+    pub fn union_with(&mut self, other: &Self) -> Result<(), H3Error> {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Keeps only the cells also in another set, or fails with
+    /// ResolutionMismatch.
+    pub fn intersect_with(&mut self, other: &Self) -> Result<(), H3Error> {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// Removes the cells of another set, or fails with ResolutionMismatch.
+    pub fn difference_with(&mut self, other: &Self) -> Result<(), H3Error> {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    /// Whether every cell of this set is in another one at the same
+    /// resolution.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.res == other.res
+            && self
+                .words
+                .iter()
+                .zip(other.words.iter())
+                .all(|(&a, &b)| a & !b == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GeoCoord;
+    use std::collections::HashSet;
+
+    fn allCells(res: Resolution) -> Vec<H3Index> {
+        H3Index::getRes0Indexes()
+            .iter()
+            .flat_map(|h| h.h3ToChildren(res))
+            .collect()
+    }
+
+    #[test]
+    fn h3IndexSet_everyCell() {
+        for &res in Resolution::RESOLUTIONS[..=4].iter() {
+            let cells = allCells(res);
+            let set = H3IndexSet::from_cells(res, cells.iter().copied()).expect("coarse cells");
+            assert_eq!(set.len(), cells.len(), "{:?} every cell", res);
+            assert_eq!(set.len(), res.numHexagons(), "{:?} count", res);
+
+            let mut sorted = cells;
+            sorted.sort_by_key(|&h| u64::from(h));
+            assert!(set.iter().eq(sorted.iter().copied()), "{:?} ascending", res);
+        }
+    }
+
+    #[test]
+    fn h3IndexSet_membership() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let pentagon = Resolution::R3.getPentagonIndexes()[7];
+        let mut set = H3IndexSet::new(Resolution::R3).expect("coarse");
+        assert!(set.is_empty(), "empty");
+
+        let a = sf.geoToH3(Resolution::R3);
+        assert_eq!(set.insert(a), Ok(true), "added");
+        assert_eq!(set.insert(a), Ok(false), "already there");
+        assert_eq!(set.insert(pentagon), Ok(true), "pentagon");
+        assert_eq!(set.len(), 2, "two cells");
+        assert!(set.contains(a) && set.contains(pentagon), "contains");
+        assert!(
+            !set.contains(sf.geoToH3(Resolution::R2)),
+            "other resolution"
+        );
+        assert!(!set.contains(H3Index::H3_NULL), "not a cell");
+        let mut edge = a;
+        edge.set_mode(crate::H3Mode::H3_EDGE_MODE);
+        edge.set_reserved_bits(1);
+        assert!(!set.contains(edge), "edge of a member");
+        let mut deep = a;
+        deep.set_index_digit(Resolution::R4, 0);
+        assert!(!set.contains(deep), "stray digit past the resolution");
+
+        assert!(set.remove(a), "removed");
+        assert!(!set.remove(a), "already removed");
+        assert!(!set.contains(a), "gone");
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![pentagon], "iter");
+
+        set.clear();
+        assert!(set.is_empty() && set.iter().next().is_none(), "cleared");
+    }
+
+    #[test]
+    fn h3IndexSet_setOperations() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R4);
+        let a: HashSet<H3Index> = origin.kRing(4).into_iter().collect();
+        let b: HashSet<H3Index> = origin
+            .kRing(2)
+            .into_iter()
+            .chain(Resolution::R4.getPentagonIndexes().to_vec())
+            .collect();
+
+        let setA = H3IndexSet::from_cells(Resolution::R4, a.iter().copied()).unwrap();
+        let setB = H3IndexSet::from_cells(Resolution::R4, b.iter().copied()).unwrap();
+
+        let sorted = |cells: HashSet<H3Index>| {
+            let mut v: Vec<H3Index> = cells.into_iter().collect();
+            v.sort_by_key(|&h| u64::from(h));
+            v
+        };
+
+        let mut union = setA.clone();
+        union.union_with(&setB).unwrap();
+        assert_eq!(union.iter().collect::<Vec<_>>(), sorted(&a | &b), "union");
+        assert_eq!(union.len(), (&a | &b).len(), "union len");
+
+        let mut intersection = setA.clone();
+        intersection.intersect_with(&setB).unwrap();
+        assert_eq!(
+            intersection.iter().collect::<Vec<_>>(),
+            sorted(&a & &b),
+            "intersection"
+        );
+        assert_eq!(intersection.len(), 19, "kRing(2)");
+
+        let mut difference = setA.clone();
+        difference.difference_with(&setB).unwrap();
+        assert_eq!(
+            difference.iter().collect::<Vec<_>>(),
+            sorted(&a - &b),
+            "difference"
+        );
+
+        assert!(
+            intersection.is_subset(&setA) && intersection.is_subset(&setB),
+            "subset"
+        );
+        assert!(!setA.is_subset(&setB), "not a subset");
+    }
+
+    #[test]
+    fn h3IndexSet_errors() {
+        assert_eq!(
+            H3IndexSet::new(Resolution::R5),
+            Err(H3Error::Domain),
+            "too fine"
+        );
+
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let mut set = H3IndexSet::new(Resolution::R2).unwrap();
+        assert_eq!(
+            set.insert(H3Index::H3_NULL),
+            Err(H3Error::CellInvalid),
+            "invalid"
+        );
+        assert_eq!(
+            set.insert(sf.geoToH3(Resolution::R1)),
+            Err(H3Error::ResolutionMismatch),
+            "other resolution"
+        );
+
+        let mut other = H3IndexSet::new(Resolution::R1).unwrap();
+        other.insert(sf.geoToH3(Resolution::R1)).unwrap();
+        assert_eq!(
+            set.union_with(&other),
+            Err(H3Error::ResolutionMismatch),
+            "union"
+        );
+        assert!(!set.is_subset(&other), "different resolutions");
+        assert!(set.is_empty(), "unchanged");
+    }
+}
@@ -0,0 +1,274 @@
+//! Operations on values keyed by cell, such as counts or measurements
+//! gathered into a grid.
+
+use std::collections::HashMap;
+
+use crate::{H3Error, H3Index, Resolution};
+
+/**
+ * Smooths values over the grid: each cell's value becomes the weighted
+ * average of the values of the cells within k steps of it, weighted by a
+ * kernel of the grid distance.
+ *
+ * Only cells with a value take part: a neighbor missing from the map is left
+ * out of the average rather than counted as 0, so the edges of the data are
+ * not pulled down. A cell whose neighbors all weigh 0 keeps its value.
+ *
+ * ```
+ * use std::collections::HashMap;
+ * use h3rs::{h3map, prelude::*};
+ *
+ * let origin = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277)).geoToH3(Resolution::R8);
+ * let values: HashMap<H3Index, f64> = origin
+ *     .kRingDistances(3)
+ *     .into_iter()
+ *     .map(|(h, d)| (h, if d == 0 { 100.0 } else { 0.0 }))
+ *     .collect();
+ *
+ * // Halve the weight with every step.
+ * let smoothed = h3map::smooth(&values, 1, |d| 0.5f64.powi(d as i32)).unwrap();
+ * assert_eq!(smoothed[&origin], 100.0 / 4.0);
+ * ```
+ *
+ * @param values Values by cell, at any resolutions
+ * @param k Steps from a cell to the farthest cell averaged into it
+ * @param kernel Weight of a cell at a grid distance, from 0 to k
+ * @return The smoothed values, for the same cells, or Domain if k is past
+ *         `H3Index::MAX_KRING_K`
+ */
+pub fn smooth(
+    values: &HashMap<H3Index, f64>,
+    k: u32,
+    kernel: impl Fn(u32) -> f64,
+) -> Result<HashMap<H3Index, f64>, H3Error> {
+    if k > H3Index::MAX_KRING_K {
+        return Err(H3Error::Domain);
+    }
+    let weights: Vec<f64> = (0..=k).map(kernel).collect();
+    let mut disk = Vec::new();
+
+    let smoothed = values
+        .iter()
+        .map(|(&h, &value)| {
+            h.kRingDistancesInto(k, &mut disk);
+            let (mut sum, mut weight) = (0.0, 0.0);
+            for &(n, d) in disk.iter() {
+                if let Some(&v) = values.get(&n) {
+                    sum += weights[d as usize] * v;
+                    weight += weights[d as usize];
+                }
+            }
+            (h, if weight == 0.0 { value } else { sum / weight })
+        })
+        .collect();
+    Ok(smoothed)
+}
+
+/**
+ * Rolls values up to a coarser resolution: each parent's value becomes the
+ * weighted average of the values of its descendants in the map.
+ *
+ * Missing descendants are left out of the average, as in `smooth`, so a
+ * parent only partly covered takes the average of what is there.
+ *
+ * @param values Values by cell, at resolutions at or finer than res
+ * @param res The resolution of the parents; cells coarser than it are left
+ *        out
+ * @param weight Weight of a cell's value in its parent's average
+ * @return The averages by parent; a parent whose cells all weigh 0 has none
+ */
+pub fn rollup(
+    values: &HashMap<H3Index, f64>,
+    res: Resolution,
+    weight: impl Fn(H3Index) -> f64,
+) -> HashMap<H3Index, f64> {
+    let mut sums: HashMap<H3Index, (f64, f64)> = HashMap::new();
+    for (&h, &value) in values.iter() {
+        if let Ok(parent) = h.h3ToParent(res) {
+            let w = weight(h);
+            let sum = sums.entry(parent).or_insert((0.0, 0.0));
+            sum.0 += w * value;
+            sum.1 += w;
+        }
+    }
+    sums.into_iter()
+        .filter(|&(_, (_, w))| w != 0.0)
+        .map(|(parent, (sum, w))| (parent, sum / w))
+        .collect()
+}
+
+/**
+ * Rolls values up to a coarser resolution weighted by the exact area of each
+ * cell, so that the parent's value is the average over its area rather than
+ * over its cells.
+ *
+ * Cells at one resolution differ in area, pentagons and the cells near them
+ * most of all, so a plain average of a density or a fraction counts small
+ * cells for more than their share of the ground. Sums of counts need no
+ * weights; roll those up by adding.
+ *
+ * ```
+ * use std::collections::HashMap;
+ * use h3rs::{h3map, prelude::*};
+ *
+ * // 1 on a pentagon, 0 on the rest of its parent's grandchildren
+ * let pentagon = Resolution::R2.getPentagonIndexes()[0];
+ * let values: HashMap<H3Index, f64> = pentagon
+ *     .h3ToParent(Resolution::R1)
+ *     .unwrap()
+ *     .h3ToChildren(Resolution::R2)
+ *     .into_iter()
+ *     .map(|h| (h, if h == pentagon { 1.0 } else { 0.0 }))
+ *     .collect();
+ *
+ * // The pentagon is smaller than its siblings, so it covers less of the
+ * // parent than one cell in six.
+ * let naive = h3map::rollup(&values, Resolution::R1, |_| 1.0);
+ * let weighted = h3map::rollup_area_weighted(&values, Resolution::R1);
+ * assert!(weighted.values().all(|&w| w < 1.0 / 6.0));
+ * assert!(naive.values().all(|&n| n == 1.0 / 6.0));
+ * ```
+ *
+ * @param values Values by cell, at resolutions at or finer than res
+ * @param res The resolution of the parents
+ * @return The area-weighted averages by parent
+ */
+pub fn rollup_area_weighted(
+    values: &HashMap<H3Index, f64>,
+    res: Resolution,
+) -> HashMap<H3Index, f64> {
+    rollup(values, res, |h| h.cellAreaRads2())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeoCoord, Resolution};
+
+    #[test]
+    fn smooth_constant() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let pentagon = Resolution::R4.getPentagonIndexes()[3];
+        let values: HashMap<H3Index, f64> = sf
+            .geoToH3(Resolution::R9)
+            .kRing(5)
+            .into_iter()
+            .chain(pentagon.kRing(3))
+            .map(|h| (h, 7.5))
+            .collect();
+
+        let smoothed = smooth(&values, 2, |d| 1.0 / (1.0 + d as f64)).unwrap();
+        assert_eq!(smoothed.len(), values.len(), "same cells");
+        for (h, v) in smoothed.iter() {
+            assert!((v - 7.5).abs() < 1e-12, "{} stays flat: {}", h, v);
+        }
+        assert_eq!(smooth(&values, 0, |_| 1.0), Ok(values), "k 0");
+        assert_eq!(
+            smooth(&HashMap::new(), 3, |_| 1.0),
+            Ok(HashMap::new()),
+            "empty"
+        );
+        assert_eq!(
+            smooth(&HashMap::new(), u32::MAX, |_| 1.0),
+            Err(H3Error::Domain),
+            "k past MAX_KRING_K, before allocating weights"
+        );
+        assert_eq!(
+            smooth(&HashMap::new(), H3Index::MAX_KRING_K + 1, |_| 1.0),
+            Err(H3Error::Domain),
+            "just past"
+        );
+    }
+
+    #[test]
+    fn smooth_naive() {
+        let origin = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R7);
+        // A ramp with holes, so that some neighbors are missing.
+        let values: HashMap<H3Index, f64> = origin
+            .kRingDistances(6)
+            .into_iter()
+            .filter(|&(h, _)| u64::from(h) % 5 != 0)
+            .map(|(h, d)| (h, (d * d) as f64 + (u64::from(h) % 3) as f64))
+            .collect();
+        let kernel = |d: u32| (3 - d) as f64;
+
+        let smoothed = smooth(&values, 2, kernel).unwrap();
+        for (&h, &v) in smoothed.iter() {
+            let (mut sum, mut weight) = (0.0, 0.0);
+            for n in h.kRing(2) {
+                if let Some(&nv) = values.get(&n) {
+                    let d = h.h3Distance(&n).expect("nearby") as u32;
+                    sum += kernel(d) * nv;
+                    weight += kernel(d);
+                }
+            }
+            assert!((v - sum / weight).abs() < 1e-12, "{}", h);
+        }
+
+        let zero = smooth(&values, 2, |_| 0.0);
+        assert_eq!(zero, Ok(values), "no weight keeps the values");
+    }
+
+    #[test]
+    fn rollup_pentagonBias() {
+        let pentagon = Resolution::R3.getPentagonIndexes()[5];
+        let parent = pentagon.h3ToParent(Resolution::R2).unwrap();
+        let cells = parent.h3ToChildren(Resolution::R5);
+        let areas: HashMap<H3Index, f64> = cells.iter().map(|&h| (h, h.cellAreaRads2())).collect();
+        let total: f64 = areas.values().sum();
+
+        // 1 on the pentagon's descendants, so the true average is the share
+        // of the parent's area the pentagon covers.
+        let inPentagon: HashMap<H3Index, f64> = cells
+            .iter()
+            .map(|&h| {
+                let inside = h.h3ToParent(Resolution::R3) == Ok(pentagon);
+                (h, if inside { 1.0 } else { 0.0 })
+            })
+            .collect();
+        let share: f64 = inPentagon.iter().map(|(h, v)| v * areas[h]).sum::<f64>() / total;
+
+        let weighted = rollup_area_weighted(&inPentagon, Resolution::R2);
+        assert_eq!(weighted.len(), 1, "one parent");
+        assert!((weighted[&parent] - share).abs() < 1e-12, "area share");
+
+        // Counting cells overstates the pentagon, whose cells are smaller.
+        let naive = rollup(&inPentagon, Resolution::R2, |_| 1.0);
+        let counted = inPentagon.values().sum::<f64>() / cells.len() as f64;
+        assert!((naive[&parent] - counted).abs() < 1e-12, "cell share");
+        assert!(
+            naive[&parent] > share * 1.01,
+            "biased: {} {}",
+            naive[&parent],
+            share
+        );
+    }
+
+    #[test]
+    fn rollup_partial() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let values: HashMap<H3Index, f64> = sf
+            .geoToH3(Resolution::R9)
+            .kRing(4)
+            .into_iter()
+            .map(|h| (h, 2.5))
+            .chain(std::iter::once((sf.geoToH3(Resolution::R5), 9.0)))
+            .collect();
+
+        let weighted = rollup_area_weighted(&values, Resolution::R7);
+        assert!(weighted.len() > 1, "several parents");
+        for (h, v) in weighted.iter() {
+            assert_eq!(h.get_resolution(), Resolution::R7);
+            assert!((v - 2.5).abs() < 1e-12, "{} flat, coarser cell left out", h);
+        }
+        assert_eq!(
+            rollup(&values, Resolution::R9, |_| 1.0).len(),
+            values.len() - 1,
+            "same resolution"
+        );
+        assert!(
+            rollup(&values, Resolution::R7, |_| 0.0).is_empty(),
+            "no weight"
+        );
+    }
+}
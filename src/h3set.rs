@@ -0,0 +1,1171 @@
+//! Operations on sets of cells, such as the coverings produced by polyfill.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::{
+    budget::Budget, constants::M_SQRT3_2, vec3d::Vec3d, BBox, Direction, GeoCoord, GeoPolygon,
+    Geofence, H3Error, H3Index, Resolution, SphereModel,
+};
+
+/// How well one set of cells covers another, measured at the finest
+/// resolution found in either set. Returned by `coverage_metrics`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CoverageMetrics {
+    /// Resolution both sets were expanded to before comparing
+    pub resolution: Resolution,
+
+    /// Number of distinct cells in the reference set, at `resolution`
+    pub countA: usize,
+    /// Number of distinct cells in the candidate set, at `resolution`
+    pub countB: usize,
+    /// Number of cells in both sets, at `resolution`
+    pub countIntersection: usize,
+    /// Number of cells in either set, at `resolution`
+    pub countUnion: usize,
+
+    /// Area of the reference set in kilometers^2
+    pub areaAKm2: f64,
+    /// Area of the candidate set in kilometers^2
+    pub areaBKm2: f64,
+    /// Area covered by both sets in kilometers^2
+    pub intersectionKm2: f64,
+    /// Area covered by either set in kilometers^2
+    pub unionKm2: f64,
+}
+
+impl CoverageMetrics {
+    /// Fraction of the candidate's area that lies in the reference, or None
+    /// if the candidate is empty.
+    pub fn precision(&self) -> Option<f64> {
+        ratio(self.intersectionKm2, self.areaBKm2)
+    }
+
+    /// Fraction of the reference's area that the candidate covers, or None if
+    /// the reference is empty.
+    pub fn recall(&self) -> Option<f64> {
+        ratio(self.intersectionKm2, self.areaAKm2)
+    }
+
+    /// Area of the intersection over the area of the union (Jaccard index),
+    /// or None if both sets are empty.
+    pub fn iou(&self) -> Option<f64> {
+        ratio(self.intersectionKm2, self.unionKm2)
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> Option<f64> {
+    if denominator > 0.0 {
+        Some(numerator / denominator)
+    } else {
+        None
+    }
+}
+
+/// Expands cells to their descendants at res, dropping duplicates, charging
+/// each cell's descendants against a budget before generating them.
+fn expand(
+    cells: &[H3Index],
+    res: Resolution,
+    budget: &mut Budget,
+) -> Result<HashSet<H3Index>, H3Error> {
+    let mut out = HashSet::new();
+    for h in cells {
+        let count = match h.as_pentagon() {
+            Some(pentagon) => pentagon.children_count(res),
+            None => h.maxH3ToChildrenSize(res),
+        };
+        budget.charge(count as usize)?;
+        out.extend(h.h3ToChildren(res));
+    }
+    Ok(out)
+}
+
+/// Total area of cells in kilometers^2.
+fn areaKm2<'a>(cells: impl Iterator<Item = &'a H3Index>) -> f64 {
+    cells.map(|h| h.cellAreaKm2()).sum()
+}
+
+/**
+ * Compares a candidate covering against a reference, such as a polyfill of a
+ * geofence against the cells known to be inside it.
+ *
+ * The sets may mix resolutions, and may be compacted. Every cell is expanded
+ * to the finest resolution present in either set, so that a coarse cell only
+ * partly covered by the other set counts the right area. Keep the resolutions
+ * close: expanding a cell by r levels yields about 7^r cells; see
+ * `coverage_metrics_budgeted` to cap it.
+ *
+ * @param a The reference cells
+ * @param b The candidate cells
+ * @return The overlap counts and areas, or CellInvalid if either set holds
+ *         something other than a valid cell
+ */
+pub fn coverage_metrics(a: &[H3Index], b: &[H3Index]) -> Result<CoverageMetrics, H3Error> {
+    coverage_metrics_budgeted(a, b, &mut Budget::unlimited())
+}
+
+/**
+ * Compares coverings like `coverage_metrics`, charging the cells each input
+ * cell expands to against a budget before generating them, so a coarse cell
+ * compared against a fine one can be stopped before it fills memory.
+ *
+ * @param a The reference cells
+ * @param b The candidate cells
+ * @param budget Limit on the cells generated
+ * @return The overlap counts and areas, the errors of `coverage_metrics`, or
+ *         `H3Error::Cancelled` if the budget ran out
+ */
+pub fn coverage_metrics_budgeted(
+    a: &[H3Index],
+    b: &[H3Index],
+    budget: &mut Budget,
+) -> Result<CoverageMetrics, H3Error> {
+    let mut res = Resolution::R0;
+    for h in a.iter().chain(b.iter()) {
+        if !h.is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        if h.get_resolution() > res {
+            res = h.get_resolution();
+        }
+    }
+
+    let setA = expand(a, res, budget)?;
+    let setB = expand(b, res, budget)?;
+
+    let areaAKm2 = areaKm2(setA.iter());
+    let areaBKm2 = areaKm2(setB.iter());
+    let intersectionKm2 = areaKm2(setA.intersection(&setB));
+
+    let countIntersection = setA.intersection(&setB).count();
+    Ok(CoverageMetrics {
+        resolution: res,
+        countA: setA.len(),
+        countB: setB.len(),
+        countIntersection,
+        countUnion: setA.len() + setB.len() - countIntersection,
+        areaAKm2,
+        areaBKm2,
+        intersectionKm2,
+        unionKm2: areaAKm2 + areaBKm2 - intersectionKm2,
+    })
+}
+
+/// Samples along each side of the triangles a cell is split into when
+/// measuring how much of it lies inside a polygon; each triangle is tested at
+/// the square of this many points.
+const COVERING_SAMPLES: usize = 6;
+
+/**
+ * Estimates the area of a cell inside a region by testing points spread
+ * evenly over the cell. The cell is split into triangles from its center to
+ * each edge, as for its area, and each of those into COVERING_SAMPLES^2
+ * smaller triangles whose centroids are tested.
+ *
+ * @param h The cell
+ * @param inside Whether a point is inside the region
+ * @return The area inside, in radians^2: exact when the whole cell is in or
+ *         out, to within the area of the small triangles the boundary
+ *         crosses otherwise
+ */
+fn cellAreaInsideRads2(h: H3Index, inside: impl Fn(&GeoCoord) -> bool) -> f64 {
+    let n = COVERING_SAMPLES as f64;
+    let center = h.h3ToGeo();
+    let c = center._geoToVec3d();
+    let gb = h.h3ToGeoBoundary();
+
+    let mut area = 0.0;
+    for i in 0..gb.numVerts {
+        let (v0, v1) = (&gb.verts[i], &gb.verts[(i + 1) % gb.numVerts]);
+        let (a, b) = (v0._geoToVec3d(), v1._geoToVec3d());
+        // Centroids of the small triangles, as barycentric weights of the
+        // two boundary vertexes: those pointing up, then those pointing down.
+        let up = (0..COVERING_SAMPLES)
+            .flat_map(|i| (0..COVERING_SAMPLES - i).map(move |j| (i, j, 1.0 / 3.0)));
+        let down = (0..COVERING_SAMPLES)
+            .flat_map(|i| (0..COVERING_SAMPLES - i).map(move |j| (i, j, 2.0 / 3.0)))
+            .filter(|&(i, j, _)| i + j + 2 <= COVERING_SAMPLES);
+
+        let mut hits = 0;
+        for (i, j, offset) in up.chain(down) {
+            let u = (i as f64 + offset) / n;
+            let w = (j as f64 + offset) / n;
+            let t = 1.0 - u - w;
+            let p = Vec3d::new(
+                t * c.x + u * a.x + w * b.x,
+                t * c.y + u * a.y + w * b.y,
+                t * c.z + u * a.z + w * b.z,
+            );
+            if inside(&GeoCoord::_vec3dToGeo(&p)) {
+                hits += 1;
+            }
+        }
+        area += GeoCoord::triangleArea(v0, v1, &center) * hits as f64 / (n * n);
+    }
+    area
+}
+
+/**
+ * Measures how far a covering, such as the output of polyfill, is from the
+ * polygon it approximates: the area of the cells outside the polygon, and
+ * the area of the polygon left uncovered.
+ *
+ * Each cell is tested at COVERING_SAMPLES^2 points per side, so the areas are
+ * estimates for cells the polygon's boundary crosses, good to a few percent
+ * of those cells' area, and exact for the others. The polygon's edges are
+ * straight lines in latitude and longitude, as for `GeoPolygon::contains`.
+ *
+ * @param polygon The polygon
+ * @param cells The covering, at any resolutions; cells are taken not to
+ *        overlap, as in a polyfill or a compacted set, and repeats are counted
+ *        once
+ * @return (covered area outside the polygon, polygon area not covered), both
+ *         in kilometers^2, or CellInvalid if a cell is not valid
+ */
+pub fn covering_error_km2(polygon: &GeoPolygon, cells: &[H3Index]) -> Result<(f64, f64), H3Error> {
+    let fences: Vec<(&Geofence, BBox)> = std::iter::once(&polygon.geofence)
+        .chain(polygon.holes.iter())
+        .map(|fence| (fence, fence.bboxFromGeofence()))
+        .collect();
+    let inside = |g: &GeoCoord| {
+        let (exterior, bbox) = &fences[0];
+        exterior.pointInsideGeofence(bbox, g)
+            && !fences[1..]
+                .iter()
+                .any(|(hole, bbox)| hole.pointInsideGeofence(bbox, g))
+    };
+
+    let mut seen = HashSet::with_capacity(cells.len());
+    let mut outsideRads2 = 0.0;
+    let mut insideRads2 = 0.0;
+    for &h in cells {
+        if !h.is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        if !seen.insert(h) {
+            continue;
+        }
+        let cellInside = cellAreaInsideRads2(h, inside);
+        outsideRads2 += (h.cellAreaRads2() - cellInside).max(0.0);
+        insideRads2 += cellInside;
+    }
+
+    let sphere = SphereModel::EARTH;
+    let uncoveredKm2 = (polygon.area_km2() - sphere.rads2_to_km2(insideRads2)).max(0.0);
+    Ok((sphere.rads2_to_km2(outsideRads2), uncoveredKm2))
+}
+
+/**
+ * Grows a region outward from seed cells, through neighbors for which the
+ * predicate holds, such as cells below an elevation threshold when tracing a
+ * catchment.
+ *
+ * The predicate is called at most once per cell. Seeds are subject to it too,
+ * so a seed that fails it is left out, and so are invalid seeds. Each cell is
+ * expanded into its own neighbors, so mixing resolutions in the seeds grows
+ * one region per resolution.
+ *
+ * @param seeds Cells to start from
+ * @param predicate Whether a cell belongs in the region
+ * @return The filled region, in breadth first order from the seeds
+ */
+pub fn flood_fill<I, F>(seeds: I, predicate: F) -> Vec<H3Index>
+where
+    I: IntoIterator<Item = H3Index>,
+    F: FnMut(H3Index) -> bool,
+{
+    match flood_fill_budgeted(seeds, predicate, &mut Budget::unlimited()) {
+        Ok(region) => region,
+        Err(_) => unreachable!("an unlimited budget is never exhausted"),
+    }
+}
+
+/**
+ * Grows a region like `flood_fill`, charging each cell added to it against a
+ * budget, for predicates that might let the region cover far more than
+ * expected.
+ *
+ * @param seeds Cells to start from
+ * @param predicate Whether a cell belongs in the region
+ * @param budget Limit on the size of the region
+ * @return The filled region, or `H3Error::Cancelled` if the budget ran out
+ */
+pub fn flood_fill_budgeted<I, F>(
+    seeds: I,
+    mut predicate: F,
+    budget: &mut Budget,
+) -> Result<Vec<H3Index>, H3Error>
+where
+    I: IntoIterator<Item = H3Index>,
+    F: FnMut(H3Index) -> bool,
+{
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    let mut region = Vec::new();
+
+    let mut visit = |h: H3Index, queue: &mut VecDeque<H3Index>| -> Result<(), H3Error> {
+        if seen.insert(h) && predicate(h) {
+            budget.charge(1)?;
+            region.push(h);
+            queue.push_back(h);
+        }
+        Ok(())
+    };
+
+    for seed in seeds {
+        if seed.is_valid() {
+            visit(seed, &mut queue)?;
+        }
+    }
+
+    let mut neighbors = Vec::new();
+    while let Some(h) = queue.pop_front() {
+        h.kRingInto(1, &mut neighbors);
+        for &neighbor in neighbors.iter() {
+            visit(neighbor, &mut queue)?;
+        }
+    }
+
+    Ok(region)
+}
+
+/// Number of rings of cells at res spanning about distanceKm. Neighboring
+/// centers are sqrt(3) average edge lengths apart. Saturates at `u32::MAX`.
+fn ringsForKm(res: Resolution, distanceKm: f64) -> u32 {
+    (distanceKm / (2. * M_SQRT3_2 * res.edgeLengthKm())).ceil() as u32
+}
+
+/**
+ * Grows a set of cells by about a distance, for quick "within X km of the
+ * region" queries without buffering the geometry.
+ *
+ * The distance is turned into a number of rings from the average spacing of
+ * cell centers at the set's resolution, rounding up, and every cell within
+ * that many rings of the set is added. The buffer is approximate: rounding
+ * up and the corners of the rings overshoot the distance by up to about a
+ * ring, and cells vary in size, shrinking to about half the average area near
+ * pentagons, so the buffer falls a little short where cells are larger than
+ * average. Buffer at a finer resolution to tighten it.
+ *
+ * @param cells Cells of the region, all at the same resolution
+ * @param distanceKm Distance to grow by, in kilometers
+ * @return The region and the cells added around it, input cells first, or
+ *         CellInvalid, ResolutionMismatch, or Domain for a negative or
+ *         non-finite distance or one spanning more than
+ *         `H3Index::MAX_KRING_K` rings
+ */
+pub fn buffer_km(cells: &[H3Index], distanceKm: f64) -> Result<Vec<H3Index>, H3Error> {
+    if !distanceKm.is_finite() || distanceKm < 0.0 {
+        return Err(H3Error::Domain);
+    }
+
+    let mut res = None;
+    for h in cells {
+        if !h.is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        if *res.get_or_insert(h.get_resolution()) != h.get_resolution() {
+            return Err(H3Error::ResolutionMismatch);
+        }
+    }
+    let k = match res {
+        Some(res) => ringsForKm(res, distanceKm),
+        None => return Ok(Vec::new()),
+    };
+    if k > H3Index::MAX_KRING_K {
+        return Err(H3Error::Domain);
+    }
+
+    let mut seen: HashSet<H3Index> = HashSet::with_capacity(cells.len());
+    let mut out: Vec<H3Index> = cells.iter().copied().filter(|&h| seen.insert(h)).collect();
+    let inside: HashSet<H3Index> = seen.clone();
+
+    let mut disk = Vec::new();
+    for h in cells {
+        // only cells on the edge of the region reach past it
+        h.kRingInto(1, &mut disk);
+        if k == 0 || disk.iter().all(|n| inside.contains(n)) {
+            continue;
+        }
+
+        h.kRingInto(k, &mut disk);
+        out.extend(disk.iter().copied().filter(|&n| seen.insert(n)));
+    }
+    Ok(out)
+}
+
+/// The change from one covering to another, as returned by `diff`: applying
+/// it to the old covering with `CoveringDiff::apply` gives the new one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoveringDiff {
+    /// Area covered by the new covering and not the old one, compacted and
+    /// sorted ascending by index value
+    pub added: Vec<H3Index>,
+    /// Area covered by the old covering and not the new one, compacted and
+    /// sorted ascending by index value
+    pub removed: Vec<H3Index>,
+}
+
+impl CoveringDiff {
+    /// Whether the coverings cover the same area.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /**
+     * Applies the change to the old covering, for the side of a sync that
+     * only receives the diff.
+     *
+     * @param old The covering the diff was taken from, in any form that
+     *        covers the same area
+     * @return The new covering, compacted and sorted, or CellInvalid if a
+     *         set holds something other than a valid cell
+     */
+    pub fn apply(&self, old: &[H3Index]) -> Result<Vec<H3Index>, H3Error> {
+        let old = coveringCells(old)?;
+        let removed = coveringCells(&self.removed)?;
+        let mut cells = subtract(&old, &removed);
+        cells.extend(coveringCells(&self.added)?);
+        Ok(compactMixed(&coveringCells(&cells)?))
+    }
+}
+
+/// The cells of a covering with duplicates and cells inside another of its
+/// cells dropped, so that no two overlap.
+fn coveringCells(cells: &[H3Index]) -> Result<HashSet<H3Index>, H3Error> {
+    let mut set = HashSet::with_capacity(cells.len());
+    for &h in cells {
+        if !h.is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        set.insert(h);
+    }
+    let nested: Vec<H3Index> = set
+        .iter()
+        .copied()
+        .filter(|&h| strictAncestors(h).any(|parent| set.contains(&parent)))
+        .collect();
+    for h in nested {
+        set.remove(&h);
+    }
+    Ok(set)
+}
+
+/// The ancestors of a cell, from its parent up to resolution 0.
+fn strictAncestors(h: H3Index) -> impl Iterator<Item = H3Index> {
+    Resolution::RESOLUTIONS[..usize::from(h.get_resolution())]
+        .iter()
+        .rev()
+        .filter_map(move |&res| h.h3ToParent(res).ok())
+}
+
+/**
+ * The part of one covering not covered by another, as cells of the first or
+ * their descendants: a cell of `a` is kept whole if no cell of `b` overlaps
+ * it, and split into its children only where one does.
+ *
+ * @param a Non-overlapping cells, from `coveringCells`
+ * @param b Non-overlapping cells, from `coveringCells`
+ * @return Non-overlapping cells covering the area of `a` outside `b`
+ */
+fn subtract(a: &HashSet<H3Index>, b: &HashSet<H3Index>) -> Vec<H3Index> {
+    let containsB: HashSet<H3Index> = b.iter().flat_map(|&h| strictAncestors(h)).collect();
+
+    let mut out = Vec::new();
+    let mut stack: Vec<H3Index> = a.iter().copied().collect();
+    while let Some(h) = stack.pop() {
+        if b.contains(&h) || strictAncestors(h).any(|parent| b.contains(&parent)) {
+            continue;
+        }
+        if containsB.contains(&h) {
+            stack.extend(h.h3ToChildren(h.get_resolution() + 1));
+        } else {
+            out.push(h);
+        }
+    }
+    out
+}
+
+/// Compacts non-overlapping cells of mixed resolutions, finest first, so
+/// that parents completed by compacting one resolution join the cells
+/// already at theirs. Sorted ascending by index value.
+fn compactMixed(cells: &HashSet<H3Index>) -> Vec<H3Index> {
+    let mut byRes: Vec<Vec<H3Index>> = vec![Vec::new(); Resolution::RESOLUTIONS.len()];
+    for &h in cells {
+        byRes[usize::from(h.get_resolution())].push(h);
+    }
+
+    let mut out = Vec::with_capacity(cells.len());
+    for res in (0..byRes.len()).rev() {
+        let level = std::mem::take(&mut byRes[res]);
+        let compacted = H3Index::compact(&level).expect("disjoint valid cells compact");
+        for h in compacted {
+            let hRes = usize::from(h.get_resolution());
+            if hRes == res {
+                out.push(h);
+            } else {
+                byRes[hRes].push(h);
+            }
+        }
+    }
+    out.sort_unstable();
+    out
+}
+
+/**
+ * Finds the change between two coverings of a region, such as two versions
+ * of a geofence's polyfill, as the fewest cells: a service syncing geofence
+ * updates can ship the diff instead of the whole new covering.
+ *
+ * The coverings may mix resolutions and be compacted or not; only the area
+ * they cover matters. A cell of one covering that the other covers only in
+ * part is split down to where they differ, never expanded further, and both
+ * sides of the result are compacted across resolutions.
+ *
+ * @param old The covering before the change
+ * @param new The covering after the change
+ * @return The cells added and removed, or CellInvalid if either set holds
+ *         something other than a valid cell
+ */
+pub fn diff(old: &[H3Index], new: &[H3Index]) -> Result<CoveringDiff, H3Error> {
+    let old = coveringCells(old)?;
+    let new = coveringCells(new)?;
+    Ok(CoveringDiff {
+        added: compactMixed(&subtract(&new, &old).into_iter().collect()),
+        removed: compactMixed(&subtract(&old, &new).into_iter().collect()),
+    })
+}
+
+/// Checks that cells are valid cells at one resolution, in strictly
+/// ascending order.
+fn checkSortedCells(cells: &[H3Index]) -> Result<(), H3Error> {
+    for h in cells {
+        if !h.is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        if h.get_resolution() != cells[0].get_resolution() {
+            return Err(H3Error::ResolutionMismatch);
+        }
+    }
+    if cells.windows(2).any(|w| u64::from(w[0]) >= u64::from(w[1])) {
+        return Err(H3Error::Domain);
+    }
+    Ok(())
+}
+
+/// The neighbors of a cell, paired with the index of their direction in
+/// `Direction::axes`. Pentagons have no neighbor in the K direction.
+fn axisNeighbors(h: H3Index) -> impl Iterator<Item = (usize, H3Index)> {
+    let isPentagon = h.is_pentagon();
+    Direction::axes()
+        .enumerate()
+        .filter(move |&(_, dir)| !(isPentagon && dir == Direction::K_AXES_DIGIT))
+        .map(move |(bit, dir)| (bit, h.h3NeighborRotations(dir, &mut 0)))
+        .filter(|&(_, n)| n != H3Index::H3_NULL)
+}
+
+/**
+ * Finds every pair of neighboring cells in a set, for building the graph of
+ * a region over millions of cells without a kRing and a hash lookup per cell.
+ *
+ * The neighbors of each cell that sort after it are gathered, sorted, and
+ * merged against the cells in a single pass, so each pair is found once.
+ *
+ * @param cells Cells at the same resolution, sorted in strictly ascending
+ *        index order
+ * @return The pairs, each in ascending order and sorted, or CellInvalid,
+ *         ResolutionMismatch or Domain if the cells are not sorted or hold a
+ *         duplicate
+ */
+pub fn adjacent_pairs(cells: &[H3Index]) -> Result<Vec<(H3Index, H3Index)>, H3Error> {
+    checkSortedCells(cells)?;
+
+    let mut candidates: Vec<(u64, usize)> = Vec::with_capacity(3 * cells.len());
+    for (i, &h) in cells.iter().enumerate() {
+        candidates.extend(
+            axisNeighbors(h)
+                .map(|(_, n)| u64::from(n))
+                .filter(|&n| n > u64::from(h))
+                .map(|n| (n, i)),
+        );
+    }
+    candidates.sort_unstable();
+
+    let mut pairs = Vec::new();
+    let mut j = 0;
+    for (n, i) in candidates {
+        while j < cells.len() && u64::from(cells[j]) < n {
+            j += 1;
+        }
+        if j == cells.len() {
+            break;
+        }
+        if u64::from(cells[j]) == n {
+            pairs.push((cells[i], cells[j]));
+        }
+    }
+    pairs.sort_unstable_by_key(|&(a, b)| (u64::from(a), u64::from(b)));
+    pairs.dedup();
+    Ok(pairs)
+}
+
+/**
+ * Records which neighbors of each cell are in the set, as one byte per cell,
+ * for graph algorithms that walk a fixed neighborhood.
+ *
+ * Bit `i` of a cell's byte is set if its neighbor in the `i`th direction of
+ * `Direction::axes`, from K_AXES_DIGIT at bit 0 to IJ_AXES_DIGIT at bit 5, is
+ * one of the cells. Pentagons have no neighbor in the K direction, so their
+ * bit 0 is never set.
+ *
+ * @param cells Cells at the same resolution, sorted in strictly ascending
+ *        index order
+ * @return The bitmaps, in the order of the cells, or CellInvalid,
+ *         ResolutionMismatch or Domain if the cells are not sorted or hold a
+ *         duplicate
+ */
+pub fn adjacency_bitmaps(cells: &[H3Index]) -> Result<Vec<u8>, H3Error> {
+    checkSortedCells(cells)?;
+
+    Ok(cells
+        .iter()
+        .map(|&h| {
+            axisNeighbors(h)
+                .filter(|&(_, n)| {
+                    cells
+                        .binary_search_by_key(&u64::from(n), |&c| u64::from(c))
+                        .is_ok()
+                })
+                .fold(0, |bits, (bit, _)| bits | 1 << bit)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Degrees;
+
+    fn assertClose(a: f64, b: f64, msg: &str) {
+        assert!(
+            (a - b).abs() <= 1e-9 * b.abs().max(1.0),
+            "{}: {} vs {}",
+            msg,
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn coverageMetrics_identical() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let cells = sf.geoToH3(Resolution::R7).kRing(2);
+
+        let m = coverage_metrics(&cells, &cells).unwrap();
+        assert_eq!(m.resolution, Resolution::R7, "resolution");
+        assert_eq!(m.countA, 19, "count a");
+        assert_eq!(m.countIntersection, 19, "count intersection");
+        assert_eq!(m.countUnion, 19, "count union");
+        assertClose(m.intersectionKm2, m.unionKm2, "areas");
+        assertClose(m.precision().unwrap(), 1.0, "precision");
+        assertClose(m.recall().unwrap(), 1.0, "recall");
+        assertClose(m.iou().unwrap(), 1.0, "iou");
+    }
+
+    #[test]
+    fn coverageMetrics_mixedResolutions() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let parent = sf.geoToH3(Resolution::R6);
+        let children = parent.h3ToChildren(Resolution::R7);
+
+        // The reference is one child; the candidate is the whole parent.
+        let m = coverage_metrics(&children[..1], &[parent]).unwrap();
+        assert_eq!(m.resolution, Resolution::R7, "finest resolution");
+        assert_eq!(m.countA, 1, "count a");
+        assert_eq!(m.countB, 7, "parent expanded");
+        assert_eq!(m.countIntersection, 1, "count intersection");
+        assert_eq!(m.countUnion, 7, "count union");
+        assertClose(m.recall().unwrap(), 1.0, "recall");
+        assertClose(
+            m.precision().unwrap(),
+            children[0].cellAreaKm2() / m.areaBKm2,
+            "precision",
+        );
+        assertClose(m.iou().unwrap(), m.precision().unwrap(), "iou");
+
+        let duplicated = [parent, children[3], parent];
+        let m = coverage_metrics(&duplicated, &children).unwrap();
+        assert_eq!(m.countA, 7, "duplicates are dropped");
+        assertClose(m.iou().unwrap(), 1.0, "compacted and uncompacted match");
+    }
+
+    #[test]
+    fn coverageMetrics_edgeCases() {
+        let m = coverage_metrics(&[], &[]).unwrap();
+        assert_eq!(m.countUnion, 0, "nothing to compare");
+        assert_eq!(m.precision(), None, "no precision");
+        assert_eq!(m.recall(), None, "no recall");
+        assert_eq!(m.iou(), None, "no iou");
+
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let cell = sf.geoToH3(Resolution::R5);
+        let m = coverage_metrics(&[cell], &[]).unwrap();
+        assert_eq!(m.recall(), Some(0.0), "nothing recalled");
+        assert_eq!(m.precision(), None, "empty candidate");
+
+        let far = GeoCoord::from_rads(-0.5, 1.0).geoToH3(Resolution::R5);
+        let m = coverage_metrics(&[cell], &[far]).unwrap();
+        assert_eq!(m.countIntersection, 0, "disjoint");
+        assert_eq!(m.iou(), Some(0.0), "disjoint iou");
+
+        assert_eq!(
+            coverage_metrics(&[cell], &[H3Index::H3_NULL]),
+            Err(H3Error::CellInvalid),
+            "invalid cell"
+        );
+    }
+
+    #[test]
+    fn coverageMetrics_budgeted() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let parent = sf.geoToH3(Resolution::R5);
+        let children = parent.h3ToChildren(Resolution::R7);
+        let mut budget = Budget::max_cells(100);
+        let m = coverage_metrics_budgeted(&children, &[parent], &mut budget).unwrap();
+        assert_eq!(m.countIntersection, 49, "within budget");
+        assert!((m.iou().unwrap() - 1.0).abs() < 1e-12, "same cells");
+        assert_eq!(budget.spent(), 98, "49 children each");
+
+        // 7^15 cells, cancelled before any are generated
+        let coarse = sf.geoToH3(Resolution::R0);
+        let fine = sf.geoToH3(Resolution::R15);
+        assert_eq!(
+            coverage_metrics_budgeted(&[coarse], &[fine], &mut Budget::max_cells(1_000_000)),
+            Err(H3Error::Cancelled)
+        );
+        let pentagon = Resolution::R0.getPentagonIndexes()[0];
+        assert_eq!(
+            coverage_metrics_budgeted(&[fine], &[pentagon], &mut Budget::max_cells(1_000_000)),
+            Err(H3Error::Cancelled),
+            "pentagon"
+        );
+    }
+
+    #[test]
+    fn coveringError_bbox() {
+        let deg = |d: f64| d.to_radians();
+        let bbox = BBox::new(deg(0.5), deg(-0.5), deg(0.5), deg(-0.5));
+        let polygon = GeoPolygon::from_bbox(&bbox).unwrap();
+        let res = Resolution::R6;
+
+        // The cells whose centers are inside, as polyfill would give.
+        let seed = GeoCoord::from_rads(0.0, 0.0).geoToH3(res);
+        let cells = flood_fill(Some(seed), |h| polygon.contains(&h.h3ToGeo()));
+        let (outside, uncovered) = covering_error_km2(&polygon, &cells).unwrap();
+
+        // Both errors come from the cells along the boundary, about half in
+        // and half out, and roughly cancel.
+        let covered: f64 = cells.iter().map(|h| h.cellAreaKm2()).sum();
+        let band = 4.0 * 111.2 * 2.0 * res.edgeLengthKm();
+        assert!(outside > 0.0 && outside < band / 2.0, "outside {}", outside);
+        assert!(
+            uncovered > 0.0 && uncovered < band / 2.0,
+            "uncovered {}",
+            uncovered
+        );
+        assert!(
+            (covered - outside + uncovered - polygon.area_km2()).abs() < 1e-6,
+            "covered inside plus uncovered is the polygon"
+        );
+        assert!(
+            (outside - uncovered).abs() < 0.25 * (outside + uncovered),
+            "centers inside: {} out, {} uncovered",
+            outside,
+            uncovered
+        );
+
+        // Adding the ring around the covering leaves nothing uncovered, up to
+        // the sampling of the cells the boundary crosses.
+        let mut grown: Vec<H3Index> = cells.iter().flat_map(|h| h.kRing(1)).collect();
+        grown.sort_by_key(|&h| u64::from(h));
+        grown.dedup();
+        let (_, grownUncovered) = covering_error_km2(&polygon, &grown).unwrap();
+        assert!(
+            grownUncovered < 0.01 * uncovered,
+            "fully covered: {}",
+            grownUncovered
+        );
+    }
+
+    #[test]
+    fn coveringError_exact() {
+        let deg = |d: f64| d.to_radians();
+        let bbox = BBox::new(deg(38.0), deg(37.0), deg(-122.0), deg(-123.0));
+        let polygon = GeoPolygon::from_bbox(&bbox).unwrap();
+        let area = polygon.area_km2();
+
+        let inside = GeoCoord::new(Degrees(37.5), Degrees(-122.5)).geoToH3(Resolution::R7);
+        let (outside, uncovered) = covering_error_km2(&polygon, &[inside, inside]).unwrap();
+        assert_eq!(outside, 0.0, "cell inside");
+        assertClose(uncovered, area - inside.cellAreaKm2(), "rest uncovered");
+
+        let far = GeoCoord::new(Degrees(-20.0), Degrees(30.0)).geoToH3(Resolution::R7);
+        let (outside, uncovered) = covering_error_km2(&polygon, &[far]).unwrap();
+        assertClose(outside, far.cellAreaKm2(), "cell outside");
+        assertClose(uncovered, area, "nothing covered");
+
+        assert_eq!(covering_error_km2(&polygon, &[]), Ok((0.0, area)), "empty");
+        assert_eq!(
+            covering_error_km2(&polygon, &[H3Index::H3_NULL]),
+            Err(H3Error::CellInvalid),
+            "invalid"
+        );
+
+        // A hole around the cell puts it outside the polygon.
+        let hole = inside.h3ToParent(Resolution::R5).unwrap().h3ToGeoBoundary();
+        let holed = GeoPolygon::new(
+            polygon.exterior().to_vec(),
+            vec![hole.verts[..hole.numVerts].to_vec()],
+        )
+        .unwrap();
+        let (outside, _) = covering_error_km2(&holed, &[inside]).unwrap();
+        assertClose(outside, inside.cellAreaKm2(), "cell in the hole");
+    }
+
+    #[test]
+    fn floodFill_bounded() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+        let disk = origin.kRing(3);
+
+        let mut calls = 0;
+        let region = flood_fill(vec![origin], |h| {
+            calls += 1;
+            disk.contains(&h)
+        });
+        assert_eq!(region[0], origin, "seed first");
+        assert_eq!(region.len(), disk.len(), "fills the disk");
+        assert!(region.iter().all(|h| disk.contains(h)), "only the disk");
+        assert_eq!(
+            calls,
+            origin.kRing(4).len(),
+            "predicate called once per cell tested"
+        );
+    }
+
+    #[test]
+    fn floodFill_budgeted() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+        let disk = origin.kRing(3);
+
+        let mut budget = Budget::max_cells(disk.len());
+        let region = flood_fill_budgeted(vec![origin], |h| disk.contains(&h), &mut budget);
+        assert_eq!(region.map(|r| r.len()), Ok(disk.len()), "fits the budget");
+        assert_eq!(budget.spent(), disk.len(), "one charge per cell");
+
+        let mut calls = 0;
+        let mut budget = Budget::max_cells(100);
+        let region = flood_fill_budgeted(
+            vec![origin],
+            |_| {
+                calls += 1;
+                true
+            },
+            &mut budget,
+        );
+        assert_eq!(region, Err(H3Error::Cancelled), "runaway region");
+        assert!(calls < 200, "stopped soon after the budget ran out");
+    }
+
+    #[test]
+    fn bufferKm_distance() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+        let center = origin.h3ToGeo();
+        let spacing = 2. * M_SQRT3_2 * Resolution::R9.edgeLengthKm();
+
+        for &km in [0.1, 1.0, 2.5].iter() {
+            let buffer = buffer_km(&[origin], km).unwrap();
+            assert_eq!(buffer[0], origin, "input first");
+
+            let k = ringsForKm(Resolution::R9, km);
+            assert_eq!(
+                buffer.len(),
+                origin.kRing(k).len(),
+                "{} km is {} rings",
+                km,
+                k
+            );
+
+            for h in origin.kRing(k + 2) {
+                let d = GeoCoord::pointDistKm(&center, &h.h3ToGeo());
+                if d <= km {
+                    assert!(buffer.contains(&h), "{} km away, within {} km", d, km);
+                }
+                if buffer.contains(&h) {
+                    assert!(d <= 1.25 * (km + spacing), "{} km away, past {} km", d, km);
+                }
+            }
+        }
+
+        assert_eq!(buffer_km(&[origin], 0.0), Ok(vec![origin]), "no distance");
+        assert_eq!(buffer_km(&[], 1.0), Ok(vec![]), "empty");
+    }
+
+    #[test]
+    fn bufferKm_region() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+        let km = 1.0;
+        let k = ringsForKm(Resolution::R9, km);
+
+        let mut region = origin.kRing(5);
+        region.push(region[3]);
+        let mut buffer: Vec<u64> = buffer_km(&region, km)
+            .unwrap()
+            .into_iter()
+            .map(u64::from)
+            .collect();
+        buffer.sort_unstable();
+        let mut expected: Vec<u64> = origin.kRing(5 + k).into_iter().map(u64::from).collect();
+        expected.sort_unstable();
+        assert_eq!(
+            buffer, expected,
+            "a disk grows into a bigger disk, once per cell"
+        );
+
+        let pentagon = Resolution::R9.getPentagonIndexes()[2];
+        let buffer = buffer_km(&[pentagon], km).unwrap();
+        assert_eq!(buffer.len(), pentagon.kRing(k).len(), "around a pentagon");
+    }
+
+    #[test]
+    fn bufferKm_errors() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let cell = sf.geoToH3(Resolution::R9);
+        assert_eq!(buffer_km(&[cell], -1.0), Err(H3Error::Domain), "negative");
+        assert_eq!(buffer_km(&[cell], f64::NAN), Err(H3Error::Domain), "nan");
+        assert_eq!(
+            buffer_km(&[sf.geoToH3(Resolution::R15)], 1e6),
+            Err(H3Error::Domain),
+            "past MAX_KRING_K rings"
+        );
+        assert_eq!(buffer_km(&[], 1e6), Ok(Vec::new()), "nothing to grow");
+        assert_eq!(
+            buffer_km(&[cell, H3Index::H3_NULL], 1.0),
+            Err(H3Error::CellInvalid),
+            "invalid cell"
+        );
+        assert_eq!(
+            buffer_km(&[cell, sf.geoToH3(Resolution::R8)], 1.0),
+            Err(H3Error::ResolutionMismatch),
+            "mixed resolutions"
+        );
+    }
+
+    fn sortedCells(mut cells: Vec<H3Index>) -> Vec<H3Index> {
+        cells.sort_unstable_by_key(|&h| u64::from(h));
+        cells.dedup();
+        cells
+    }
+
+    #[test]
+    fn adjacentPairs_naive() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let pentagon = Resolution::R9.getPentagonIndexes()[4];
+        let mut cells = sf.geoToH3(Resolution::R9).kRing(6);
+        cells.extend(pentagon.kRing(3));
+        // holes, so some neighbors are missing
+        cells.retain(|h| u64::from(*h) % 5 != 0);
+        let cells = sortedCells(cells);
+
+        let mut expected = Vec::new();
+        for &a in cells.iter() {
+            for &b in cells.iter() {
+                if u64::from(a) < u64::from(b) && a.h3IndexesAreNeighbors(b) {
+                    expected.push((a, b));
+                }
+            }
+        }
+        assert!(!expected.is_empty(), "has pairs");
+        assert_eq!(adjacent_pairs(&cells), Ok(expected.clone()), "same pairs");
+
+        let bitmaps = adjacency_bitmaps(&cells).expect("sorted cells");
+        assert_eq!(bitmaps.len(), cells.len(), "one per cell");
+        let ones: u32 = bitmaps.iter().map(|b| b.count_ones()).sum();
+        assert_eq!(ones as usize, 2 * expected.len(), "each pair seen twice");
+        for (h, bits) in cells.iter().zip(bitmaps) {
+            assert!(bits < 1 << 6, "six directions");
+            if h.is_pentagon() {
+                assert_eq!(bits & 1, 0, "no K neighbor");
+            }
+            for (bit, dir) in Direction::axes().enumerate() {
+                if bits & 1 << bit != 0 {
+                    let n = h.h3NeighborRotations(dir, &mut 0);
+                    assert!(cells.contains(&n), "{} neighbor in the set", h);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn adjacentPairs_errors() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let cells = sortedCells(sf.geoToH3(Resolution::R9).kRing(1));
+
+        assert_eq!(adjacent_pairs(&[]), Ok(Vec::new()), "empty");
+        assert_eq!(adjacent_pairs(&cells[..1]), Ok(Vec::new()), "single");
+        assert_eq!(adjacency_bitmaps(&cells[..1]), Ok(vec![0]), "single");
+
+        let mut reversed = cells.clone();
+        reversed.reverse();
+        assert_eq!(adjacent_pairs(&reversed), Err(H3Error::Domain), "unsorted");
+        let duplicated = [cells[0], cells[0]];
+        assert_eq!(
+            adjacency_bitmaps(&duplicated),
+            Err(H3Error::Domain),
+            "duplicate"
+        );
+        assert_eq!(
+            adjacent_pairs(&[H3Index::H3_NULL]),
+            Err(H3Error::CellInvalid),
+            "invalid cell"
+        );
+        let mut mixed = cells.clone();
+        mixed.push(sf.geoToH3(Resolution::R10));
+        assert_eq!(
+            adjacent_pairs(&sortedCells(mixed)),
+            Err(H3Error::ResolutionMismatch),
+            "mixed resolutions"
+        );
+    }
+
+    #[test]
+    fn floodFill_disconnected() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+        let inner = origin.kRing(1);
+        let ring = origin.grid_ring(3).unwrap();
+
+        // A moat at distance 2 separates the center from the outer ring.
+        let allowed = |h: H3Index| inner.contains(&h) || ring.contains(&h);
+        let region = flood_fill(vec![origin], allowed);
+        assert_eq!(region.len(), 7, "stops at the moat");
+
+        let region = flood_fill(vec![origin, ring[0]], allowed);
+        assert_eq!(region.len(), 7 + 18, "second seed fills the ring");
+    }
+
+    #[test]
+    fn floodFill_pentagon() {
+        let pentagon = Resolution::R4.getPentagonIndexes()[2];
+        let disk = pentagon.kRing(2);
+        let region = flood_fill(vec![pentagon], |h| disk.contains(&h));
+        assert_eq!(region.len(), disk.len(), "fills around the pentagon");
+    }
+
+    #[test]
+    fn floodFill_edgeCases() {
+        assert!(flood_fill(Vec::new(), |_| true).is_empty(), "no seeds");
+
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let origin = sf.geoToH3(Resolution::R9);
+        assert!(
+            flood_fill(vec![origin], |_| false).is_empty(),
+            "seed fails the predicate"
+        );
+        assert!(
+            flood_fill(vec![H3Index::H3_NULL], |_| true).is_empty(),
+            "invalid seed"
+        );
+        assert_eq!(
+            flood_fill(vec![origin, origin], |h| h == origin),
+            vec![origin],
+            "duplicate seeds"
+        );
+    }
+
+    #[test]
+    fn diff_regions() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R8);
+        let moved = sf.neighbors().as_slice()[2].neighbors().as_slice()[2];
+        let oldCells = sf.kRing(4);
+        let newCells = moved.kRing(3);
+        let old = H3Index::compact(&oldCells).unwrap();
+
+        let d = diff(&old, &newCells).unwrap();
+        let oldSet: HashSet<H3Index> = oldCells.iter().copied().collect();
+        let newSet: HashSet<H3Index> = newCells.iter().copied().collect();
+        let added: HashSet<H3Index> = H3Index::uncompact(&d.added, Resolution::R8)
+            .unwrap()
+            .into_iter()
+            .collect();
+        let removed: HashSet<H3Index> = H3Index::uncompact(&d.removed, Resolution::R8)
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(added, &newSet - &oldSet, "added");
+        assert_eq!(removed, &oldSet - &newSet, "removed");
+
+        assert!(d
+            .added
+            .windows(2)
+            .all(|w| u64::from(w[0]) < u64::from(w[1])));
+        assert!(d
+            .removed
+            .windows(2)
+            .all(|w| u64::from(w[0]) < u64::from(w[1])));
+        assert_eq!(d.apply(&old), H3Index::compact(&newCells), "apply");
+        assert_eq!(d.apply(&oldCells), H3Index::compact(&newCells), "any form");
+
+        assert!(diff(&old, &oldCells).unwrap().is_empty(), "same area");
+        assert_eq!(
+            diff(&[], &old).unwrap(),
+            CoveringDiff {
+                added: old.clone(),
+                removed: Vec::new()
+            },
+            "from nothing"
+        );
+    }
+
+    #[test]
+    fn diff_hierarchical() {
+        let parent = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R6);
+        let children = parent.h3ToChildren(Resolution::R7);
+        let grandchildren = parent.h3ToChildren(Resolution::R8);
+
+        // A cell dropped from deep inside a coarse cell is all that changes.
+        let d = diff(&[parent], &grandchildren[1..]).unwrap();
+        assert_eq!(d.removed, vec![grandchildren[0]], "one cell removed");
+        assert!(d.added.is_empty(), "nothing added");
+        assert_eq!(
+            d.apply(&[parent]).unwrap().len(),
+            6 + 6,
+            "six children and six grandchildren"
+        );
+
+        // Removed cells from different inputs compact together.
+        let d = diff(&children, &[]).unwrap();
+        assert_eq!(d.removed, vec![parent], "compacted across inputs");
+        let d = diff(
+            &[children[1], grandchildren[8], children[1]],
+            &grandchildren[7..14],
+        )
+        .unwrap();
+        assert!(d.is_empty(), "duplicates and overlaps");
+
+        let pentagon = Resolution::R2.getPentagonIndexes()[3];
+        let d = diff(&[pentagon], &pentagon.h3ToChildren(Resolution::R4)).unwrap();
+        assert!(d.is_empty(), "pentagon children");
+        let d = diff(&pentagon.h3ToChildren(Resolution::R3), &[]).unwrap();
+        assert_eq!(d.removed, vec![pentagon], "pentagon compacted");
+
+        assert_eq!(
+            diff(&[parent], &[H3Index::H3_NULL]),
+            Err(H3Error::CellInvalid),
+            "invalid cell"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Dense ids for the cells of a covering, for graph algorithms that store
+//! ids rather than indexes.
+
+use std::{collections::HashMap, convert::TryFrom, iter::FromIterator};
+
+use crate::H3Index;
+
+/**
+ * Maps indexes to dense `u32` ids and back: the first index interned gets 0,
+ * the next new one 1, and so on, and an index keeps its id for the life of
+ * the interner.
+ *
+ * Edge lists and adjacency arrays over tens of millions of cells take half
+ * the memory with `u32` ids instead of 64 bit indexes, and ids index
+ * straight into `Vec`s of per-cell data. Indexes are not validated, so
+ * cells, edges and vertexes can all be interned.
+ *
+ * ```
+ * use h3rs::{prelude::*, H3Interner};
+ *
+ * let origin = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277)).geoToH3(Resolution::R9);
+ * let interner: H3Interner = origin.kRing(1).into_iter().collect();
+ *
+ * // A hexagon and its ring share 6 edges, and the ring has 6 more.
+ * let edges = interner.neighbor_edges();
+ * assert_eq!(edges.len(), 12);
+ * assert_eq!(interner.cell(edges[0].0), Some(origin));
+ * ```
+ */
+#[derive(Clone, Debug, Default)]
+pub struct H3Interner {
+    ids: HashMap<H3Index, u32>,
+    cells: Vec<H3Index>,
+}
+
+impl H3Interner {
+    /// Creates an empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty interner with room for `capacity` indexes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        H3Interner {
+            ids: HashMap::with_capacity(capacity),
+            cells: Vec::with_capacity(capacity),
+        }
+    }
+
+    /**
+     * The id of an index, giving it the next id if it has none yet.
+     *
+     * # Panics
+     *
+     * If the index is new and every `u32` id is taken.
+     *
+     * @param h The index
+     * @return Its id
+     */
+    pub fn intern(&mut self, h: H3Index) -> u32 {
+        if let Some(&id) = self.ids.get(&h) {
+            return id;
+        }
+        let id = u32::try_from(self.cells.len()).expect("more than u32::MAX indexes interned");
+        self.ids.insert(h, id);
+        self.cells.push(h);
+        id
+    }
+
+    /// The id of an index, if it has been interned.
+    pub fn id(&self, h: H3Index) -> Option<u32> {
+        self.ids.get(&h).copied()
+    }
+
+    /// The index with an id, if the id has been given out.
+    pub fn cell(&self, id: u32) -> Option<H3Index> {
+        self.cells.get(id as usize).copied()
+    }
+
+    /// The interned indexes, in id order: the index at position i has id i.
+    pub fn cells(&self) -> &[H3Index] {
+        &self.cells
+    }
+
+    /// Number of interned indexes, and so the next id.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether no index has been interned.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /**
+     * The edges of the graph of interned cells where neighboring cells are
+     * joined, each once.
+     *
+     * @return Pairs of ids of neighboring interned cells, the smaller id
+     *         first, in order; indexes that are not cells have no edges
+     */
+    pub fn neighbor_edges(&self) -> Vec<(u32, u32)> {
+        let mut edges = Vec::new();
+        for (a, h) in self.cells.iter().enumerate() {
+            let a = a as u32;
+            let start = edges.len();
+            for n in h.neighbors() {
+                match self.id(n) {
+                    Some(b) if b > a => edges.push((a, b)),
+                    _ => {}
+                }
+            }
+            edges[start..].sort_unstable();
+        }
+        edges
+    }
+}
+
+impl Extend<H3Index> for H3Interner {
+    /// Interns each index in turn.
+    fn extend<I: IntoIterator<Item = H3Index>>(&mut self, iter: I) {
+        for h in iter {
+            self.intern(h);
+        }
+    }
+}
+
+impl FromIterator<H3Index> for H3Interner {
+    /// Interns the indexes in order, so the first distinct index gets id 0.
+    fn from_iter<I: IntoIterator<Item = H3Index>>(iter: I) -> Self {
+        let mut interner = Self::new();
+        interner.extend(iter);
+        interner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeoCoord, Resolution};
+
+    #[test]
+    fn intern_dense() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+        let cells = sf.kRing(2);
+        let mut interner = H3Interner::with_capacity(cells.len());
+        for (i, &h) in cells.iter().enumerate() {
+            assert_eq!(interner.intern(h), i as u32, "next id");
+        }
+        for (i, &h) in cells.iter().enumerate().rev() {
+            assert_eq!(interner.intern(h), i as u32, "same id again");
+            assert_eq!(interner.id(h), Some(i as u32));
+            assert_eq!(interner.cell(i as u32), Some(h), "back");
+        }
+        assert_eq!(interner.len(), cells.len(), "no new ids");
+        assert_eq!(interner.cells(), &cells[..], "in id order");
+        assert_eq!(interner.cell(cells.len() as u32), None, "not given out");
+        assert_eq!(interner.id(sf.h3ToParent(Resolution::R8).unwrap()), None);
+
+        let edge = sf.getH3UnidirectionalEdgesFromHexagon().as_slice()[0];
+        interner.intern(edge);
+        assert_eq!(interner.cell(cells.len() as u32), Some(edge), "any index");
+        assert!(H3Interner::new().is_empty());
+    }
+
+    #[test]
+    fn neighborEdges() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396).geoToH3(Resolution::R9);
+        let pentagon = Resolution::R9.getPentagonIndexes()[0];
+        for &origin in [sf, pentagon].iter() {
+            let mut interner: H3Interner = origin.kRing(2).into_iter().collect();
+            interner.intern(origin.getH3UnidirectionalEdgesFromHexagon().as_slice()[0]);
+            let edges = interner.neighbor_edges();
+            assert!(
+                edges.windows(2).all(|w| w[0] < w[1]),
+                "{} sorted, once each",
+                origin
+            );
+
+            // every pair of neighbors among the cells, once
+            let cells = interner.cells();
+            let mut expected = 0;
+            for (i, a) in cells.iter().enumerate() {
+                for b in cells[i + 1..].iter() {
+                    if a.is_valid() && b.is_valid() && a.h3IndexesAreNeighbors(*b) {
+                        expected += 1;
+                    }
+                }
+            }
+            assert_eq!(edges.len(), expected, "{} all neighbors", origin);
+            for &(a, b) in edges.iter() {
+                assert!(a < b, "smaller id first");
+                let (a, b) = (interner.cell(a).unwrap(), interner.cell(b).unwrap());
+                assert!(a.h3IndexesAreNeighbors(b), "{} and {}", a, b);
+            }
+        }
+    }
+}
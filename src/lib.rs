@@ -1,6 +1,88 @@
-#![allow(dead_code, unused_imports, non_camel_case_types, non_snake_case)]
+//! A Rust port of the [H3](https://h3geo.org) hexagonal hierarchical
+//! geospatial indexing system.
+//!
+//! # Allocation
+//!
+//! Operations on a single index never touch the heap, so they are safe to
+//! call per row from database UDFs and other hot loops. This includes
+//! `is_valid`, `is_pentagon`, `h3ToParent`, `h3ToCenterChild`,
+//! `maxH3ToChildrenSize`, `h3Distance`, `h3ToGeo`, `h3ToGeoBoundary`,
+//! `h3GetFaces`, `GeoCoord::geoToH3` and the cell area and edge length
+//! functions. Results with a small fixed bound come back inline rather than
+//! in a `Vec`: `h3GetFaces` as a `FaceSet`, and `neighbors`,
+//! `getH3UnidirectionalEdgesFromHexagon` and `cellToVertexes` as an
+//! `IndexArray`.
+//!
+//! Functions producing a variable number of results allocate their output:
+//! `h3ToChildren`, `compact`, `uncompact`, `kRing`, `kRingDistances`,
+//! `grid_ring`, `grid_collar`, `h3Line`, `direction_rose`, `h3ToGeohashes`,
+//! `geohashFromGeo`, `h3SetToVertexGraph`, `h3SetToLinkedGeo`,
+//! `chunk_by_parent`, `flood_fill`, `buffer_km`, `RingExpander`,
+//! `to_lng_lat_ring`, `Topology::from_regions`, `Tile::cells`,
+//! `tiles_for_cells`, `GeoPolygon::new`, `GeoPolygon::polyfill` and
+//! `GeoMultiPolygon::polyfill`.
+//! `kRingInto` and `kRingDistancesInto` fill a caller's buffer instead, and
+//! stop allocating once it has grown to fit.
+//!
+//! # Ordering
+//!
+//! No function returns its results in hash table order, so the same input
+//! always gives the same output, run to run. Where the order means something,
+//! it is documented: `h3GetFaces` returns a `FaceSet` of ascending faces,
+//! `h3ToChildren`, `compact` and `polyfill` return ascending indexes,
+//! `h3ToGeohashes` is sorted, `hexRange` goes out by distance, `h3Line`
+//! runs from start to end and `flood_fill` is breadth first from its seeds. Functions documented as
+//! returning cells in no particular order, such as `kRing` and `grid_ring`,
+//! are still deterministic, but their order may change between versions;
+//! sort the output before comparing it to a snapshot.
+//!
+//! # Coordinate order
+//!
+//! Like the C library, this crate puts latitude first: `GeoCoord::new` takes
+//! `(lat, lon)`, and `GeoCoord` stores them in that order. Formats that store
+//! x before y, such as GeoJSON, WKB, FlatGeobuf and GeoParquet, want
+//! longitude first instead. Convert at the boundary with
+//! `GeoCoord::from_lng_lat`, `GeoCoord::lng_lat` and
+//! `GeoBoundary::to_lng_lat_ring`, whose names spell out the order, rather
+//! than swapping tuples by hand.
+//!
+//! # Features
+//!
+//! No feature is on by default, and the default build has no dependencies.
+//! Each feature adds one capability, pulls in only what that capability
+//! needs, and builds alone or with any of the others:
+//!
+//! | Feature   | Adds                                              | Dependencies |
+//! |-----------|---------------------------------------------------|--------------|
+//! | `serde`   | Serialize and Deserialize for `H3Index`, `GeoCoord`, `GeoBoundary`, `GeoPolygon` and `CoordIJ` | serde |
+//! | `geojson` | `GeoMultiPolygon::from_geojson` and `polyfill_geojson` | serde_json |
+//! | `wkb`     | `GeoMultiPolygon::from_wkb` and `polyfill_wkb`    | none         |
+//! | `rand`    | `H3Index::sample_points`                          | rand         |
+//! | `rstar`   | `RTreeObject` for `H3Index`                       | rstar        |
+//! | `f32`     | `GeoBoundaryF32`                                  | none         |
+//! | `async`   | the `chunked` module, on any runtime              | none         |
+//! | `arrow`   | the `udf` module of Arrow kernels                 | arrow-array, arrow-schema |
+//!
+//! `tests/features.rs` uses each feature's public API under its own cfg, so
+//! `cargo test --features <feature>` checks that the feature still builds
+//! and is reachable, without a CI matrix.
+
+#![allow(
+    dead_code,
+    unused_imports,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals
+)]
+// lookup tables and constants are carried over from the C library verbatim
+#![allow(
+    clippy::excessive_precision,
+    clippy::approx_constant,
+    clippy::needless_range_loop
+)]
 
 mod bbox;
+pub use bbox::BBox;
 use bbox::*;
 
 mod constants;
@@ -9,6 +91,9 @@ use constants::*;
 mod direction;
 pub use direction::Direction;
 
+mod error;
+pub use error::H3Error;
+
 mod resolution;
 pub use resolution::*;
 
@@ -19,16 +104,24 @@ mod vec3d;
 use vec3d::*;
 
 mod coordij;
-use coordij::*;
+pub use coordij::CoordIJ;
 
 mod coordijk;
+pub use coordijk::CoordIJK;
 use coordijk::*;
 
 mod geocoord;
 pub use geocoord::*;
 
+pub mod units;
+pub use units::{Degrees, Radians};
+
+mod sphere;
+pub use sphere::SphereModel;
+
 mod basecell;
-use basecell::{BaseCell, BaseCellData};
+pub use basecell::BaseCell;
+use basecell::BaseCellData;
 
 mod basecellrotation;
 use basecellrotation::BaseCellRotation;
@@ -37,7 +130,166 @@ mod faceijk;
 use faceijk::{FaceIJK, FaceOrientIJK};
 
 mod geopolygon;
-use geopolygon::{GeoBoundary, GeoMultiPolygon, GeoPolygon, Geofence};
+pub use geopolygon::{
+    GeoBoundary, GeoMultiPolygon, GeoPolygon, Geofence, ParseGeometryError, PolygonError,
+};
+
+mod polyfill;
+
+#[cfg(feature = "serde")]
+mod serialize;
+
+#[cfg(feature = "geojson")]
+mod geojson;
+
+#[cfg(feature = "wkb")]
+mod wkb;
+
+#[cfg(feature = "f32")]
+mod geoboundary32;
+#[cfg(feature = "f32")]
+pub use geoboundary32::{GeoBoundaryF32, F32_BOUNDARY_MAX_ERROR_RADS};
+
+mod vertexgraph;
+pub use vertexgraph::{VertexGraph, VertexNode};
 
 mod h3index;
-pub use h3index::H3Index;
+pub use h3index::{
+    geohashFromGeo, BaseCellCells, BitLayout, Cell, CompactOrder, DirectionRose, FaceSet, H3Index,
+    H3Mode, IndexArray, ParentChunks, PentagonPolicy, RingExpander, TaggedH3Index,
+    TraversalBuilder, MAX_GEOHASHES, MAX_GEOHASH_PRECISION,
+};
+
+pub mod budget;
+
+pub mod h3set;
+pub use h3set::{flood_fill, flood_fill_budgeted};
+
+pub mod h3map;
+
+#[cfg(feature = "async")]
+pub mod chunked;
+
+mod h3indexset;
+pub use h3indexset::H3IndexSet;
+
+mod h3cellset;
+pub use h3cellset::H3CellSet;
+
+mod interner;
+pub use interner::H3Interner;
+
+mod audit;
+pub use audit::{audit_ids, AuditReport};
+
+mod tile;
+pub use tile::{tiles_for_cells, tiles_for_cells_budgeted, Tile};
+
+pub mod topology;
+
+pub mod prelude;
+
+#[cfg(feature = "arrow")]
+pub mod udf;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+    };
+
+    /// Counts allocations made by the current thread, so tests running in
+    /// parallel do not see each other's allocations.
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.with(|a| a.set(a.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    fn assertNoAllocations<T>(name: &str, f: impl FnOnce() -> T) -> T {
+        let before = ALLOCATIONS.with(|a| a.get());
+        let result = f();
+        let after = ALLOCATIONS.with(|a| a.get());
+        assert_eq!(before, after, "{} allocated", name);
+        result
+    }
+
+    #[test]
+    fn coreOpsDoNotAllocate() {
+        let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        let pentagon = Resolution::R5.getPentagonIndexes()[0];
+
+        for &res in Resolution::RESOLUTIONS.iter() {
+            let h = assertNoAllocations("geoToH3", || sf.geoToH3(res));
+
+            assertNoAllocations("is_valid", || h.is_valid());
+            assertNoAllocations("is_pentagon", || h.is_pentagon());
+            assertNoAllocations("h3ToParent", || h.h3ToParent(Resolution::R0)).unwrap();
+            assertNoAllocations("h3ToCenterChild", || h.h3ToCenterChild(Resolution::R15));
+            assertNoAllocations("maxH3ToChildrenSize", || {
+                h.maxH3ToChildrenSize(Resolution::R15)
+            });
+            assert_eq!(
+                assertNoAllocations("h3Distance", || h.h3Distance(&h)),
+                Ok(0)
+            );
+            assertNoAllocations("h3ToGeo", || h.h3ToGeo());
+            assertNoAllocations("h3ToGeoBoundary", || h.h3ToGeoBoundary());
+            assertNoAllocations("cellAreaKm2", || h.cellAreaKm2());
+            assertNoAllocations("h3GetFaces", || h.h3GetFaces());
+            assertNoAllocations("neighbors", || h.neighbors());
+            assertNoAllocations("getH3UnidirectionalEdgesFromHexagon", || {
+                h.getH3UnidirectionalEdgesFromHexagon()
+            });
+            assertNoAllocations("cellToVertexes", || h.cellToVertexes());
+        }
+
+        let neighbor = pentagon.h3NeighborRotations(Direction::J_AXES_DIGIT, &mut 0);
+        assert_eq!(
+            assertNoAllocations("h3Distance", || pentagon.h3Distance(&neighbor)),
+            Ok(1)
+        );
+        assertNoAllocations("is_valid", || pentagon.is_valid());
+        assertNoAllocations("h3GetFaces", || pentagon.h3GetFaces());
+        assertNoAllocations("neighbors", || pentagon.neighbors());
+        assertNoAllocations("cellToVertexes", || pentagon.cellToVertexes());
+    }
+
+    #[test]
+    fn reusedBuffersDoNotAllocate() {
+        let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        let hexagon = sf.geoToH3(Resolution::R9);
+        let pentagon = Resolution::R9.getPentagonIndexes()[0];
+
+        let mut cells = Vec::new();
+        let mut distances = Vec::new();
+        for &origin in [hexagon, pentagon].iter() {
+            // the first calls grow the buffers and the thread's scratch space
+            origin.kRingInto(3, &mut cells);
+            origin.kRingDistancesInto(3, &mut distances);
+
+            for k in 0..=3 {
+                assertNoAllocations("kRingInto", || origin.kRingInto(k, &mut cells));
+                assertNoAllocations("kRingDistancesInto", || {
+                    origin.kRingDistancesInto(k, &mut distances)
+                });
+            }
+        }
+    }
+}
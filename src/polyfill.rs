@@ -0,0 +1,329 @@
+//! Polyfill: the cells of a resolution whose centers lie inside a polygon.
+//!
+//! As in the C library, only a cell's center is tested, so two polygons that
+//! share an edge without overlapping never share a cell, and every cell of a
+//! tiling of polygons goes to exactly one of them.
+
+use std::collections::HashSet;
+
+use crate::{
+    geopolygon::normalizeLon, BBox, GeoCoord, GeoMultiPolygon, GeoPolygon, Geofence, H3Index,
+    Resolution,
+};
+
+/// Cells added to the estimate of `maxPolyfillSize`: tracing a very small
+/// polygon near an icosahedron edge at a Class III resolution can find more
+/// cells than the bounding box estimate allows for.
+const POLYFILL_BUFFER: usize = 12;
+
+impl Geofence {
+    /**
+     * Traces a loop with cells, sampling each edge at about the spacing of
+     * the cells at the resolution. The cells are where polyfill starts its
+     * search; their centers may be on either side of the loop.
+     *
+     * @param bbox The bbox of the loop, to tell whether edges cross the
+     *        antimeridian
+     * @param res The resolution of the cells
+     * @param traced Cells already traced, which are not added again
+     * @param out The traced cells, in order along the loop
+     */
+    fn traceEdges(
+        &self,
+        bbox: &BBox,
+        res: Resolution,
+        traced: &mut HashSet<H3Index>,
+        out: &mut Vec<H3Index>,
+    ) {
+        let isTransmeridian = bbox.bboxIsTransmeridian();
+        for (origin, destination) in self.edges() {
+            let numCells = GeoCoord::lineHexEstimate(origin, destination, res);
+            let originLon = normalizeLon(origin.lon, isTransmeridian);
+            let destinationLon = normalizeLon(destination.lon, isTransmeridian);
+
+            for j in 0..numCells {
+                let t = j as f64 / numCells as f64;
+                let point = GeoCoord::from_rads(
+                    origin.lat + (destination.lat - origin.lat) * t,
+                    GeoCoord::constrainLng(originLon + (destinationLon - originLon) * t),
+                );
+                let h = point.geoToH3(res);
+                if traced.insert(h) {
+                    out.push(h);
+                }
+            }
+        }
+    }
+}
+
+impl GeoPolygon {
+    /// Whether a point is inside the polygon, given the bboxes of its
+    /// exterior loop and holes in that order.
+    fn pointInsidePolygon(&self, bboxes: &[BBox], coord: &GeoCoord) -> bool {
+        self.geofence.pointInsideGeofence(&bboxes[0], coord)
+            && !self
+                .holes
+                .iter()
+                .zip(&bboxes[1..])
+                .any(|(hole, bbox)| hole.pointInsideGeofence(bbox, coord))
+    }
+
+    /**
+     * maxPolyfillSize returns an upper bound on the number of cells polyfill
+     * returns, for sizing storage ahead of time.
+     *
+     * The size is the number of cells estimated to fit in the bounding box of
+     * the exterior loop, or the number of vertexes of all loops if that is
+     * larger, plus a small buffer.
+     *
+     * @param res The resolution of the cells
+     * @return The most cells `polyfill(res)` is expected to return
+     */
+    pub fn maxPolyfillSize(&self, res: Resolution) -> usize {
+        let bbox = self.geofence.bboxFromGeofence();
+        let numCells = bbox.bboxHexEstimate(res) as usize;
+
+        // The number of vertexes is usually less than the number of cells,
+        // but not for small, detailed polygons.
+        let totalVerts =
+            self.geofence.len() + self.holes.iter().map(|hole| hole.len()).sum::<usize>();
+        numCells.max(totalVerts) + POLYFILL_BUFFER
+    }
+
+    /**
+     * polyfill returns the cells at a resolution whose centers are inside the
+     * polygon and outside its holes.
+     *
+     * The loops are traced with cells, and from those the search spreads to
+     * neighboring cells as long as their centers are inside. Edges are
+     * straight lines in latitude and longitude, as for `contains`, and
+     * polygons around a pole are not supported.
+     *
+     * @param res The resolution of the cells
+     * @return The cells, sorted ascending by index value; none if the polygon
+     *         is too small to hold a cell center
+     */
+    pub fn polyfill(&self, res: Resolution) -> Vec<H3Index> {
+        let bboxes: Vec<BBox> = std::iter::once(&self.geofence)
+            .chain(self.holes.iter())
+            .map(|geofence| geofence.bboxFromGeofence())
+            .collect();
+
+        // Trace the exterior and the holes: a cell just inside a hole's edge
+        // may only be reachable from it.
+        let mut search = Vec::new();
+        let mut traced = HashSet::new();
+        for (geofence, bbox) in std::iter::once(&self.geofence)
+            .chain(self.holes.iter())
+            .zip(bboxes.iter())
+        {
+            geofence.traceEdges(bbox, res, &mut traced, &mut search);
+        }
+
+        // Spread from the traced cells through cells whose centers are
+        // inside; every cell's center is tested once.
+        let mut tested = HashSet::new();
+        let mut out = Vec::new();
+        while !search.is_empty() {
+            let mut next = Vec::new();
+            for h in search {
+                for candidate in std::iter::once(h).chain(h.neighbors()) {
+                    if !tested.insert(candidate) {
+                        continue;
+                    }
+                    if self.pointInsidePolygon(&bboxes, &candidate.h3ToGeo()) {
+                        out.push(candidate);
+                        next.push(candidate);
+                    }
+                }
+            }
+            search = next;
+        }
+
+        out.sort_unstable();
+        out
+    }
+}
+
+impl GeoMultiPolygon {
+    /**
+     * polyfill returns the cells at a resolution whose centers are inside
+     * any of the polygons, as `GeoPolygon::polyfill` finds them.
+     *
+     * @param res The resolution of the cells
+     * @return The cells, sorted ascending by index value, each once even
+     *         where polygons overlap
+     */
+    pub fn polyfill(&self, res: Resolution) -> Vec<H3Index> {
+        let mut out: Vec<H3Index> = self
+            .polygons
+            .iter()
+            .flat_map(|polygon| polygon.polyfill(res))
+            .collect();
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::M_PI, BaseCell, Direction};
+
+    fn sfVerts() -> Vec<GeoCoord> {
+        [
+            (0.659966917655, -2.1364398519396),
+            (0.6595011102219, -2.1359434279405),
+            (0.6583348114025, -2.1354884206045),
+            (0.6581220034068, -2.1382437718946),
+            (0.6594479998527, -2.1384597563896),
+            (0.6599990002976, -2.1376771158464),
+        ]
+        .iter()
+        .map(|&(lat, lon)| GeoCoord::from_rads(lat, lon))
+        .collect()
+    }
+
+    fn holeVerts() -> Vec<GeoCoord> {
+        [
+            (0.6595072188743, -2.1371053983433),
+            (0.6591482046471, -2.1373141048153),
+            (0.6592295020837, -2.1365222838402),
+        ]
+        .iter()
+        .map(|&(lat, lon)| GeoCoord::from_rads(lat, lon))
+        .collect()
+    }
+
+    fn rectangle(north: f64, south: f64, east: f64, west: f64) -> Vec<GeoCoord> {
+        vec![
+            GeoCoord::from_rads(north, west),
+            GeoCoord::from_rads(north, east),
+            GeoCoord::from_rads(south, east),
+            GeoCoord::from_rads(south, west),
+        ]
+    }
+
+    #[test]
+    fn maxPolyfillSize() {
+        let sf = GeoPolygon::new(sfVerts(), Vec::new()).unwrap();
+        assert_eq!(sf.maxPolyfillSize(Resolution::R9), 5613, "sf");
+        let hole = GeoPolygon::new(sfVerts(), vec![holeVerts()]).unwrap();
+        assert_eq!(hole.maxPolyfillSize(Resolution::R9), 5613, "with hole");
+    }
+
+    #[test]
+    fn polyfill() {
+        let sf = GeoPolygon::new(sfVerts(), Vec::new()).unwrap();
+        let cells = sf.polyfill(Resolution::R9);
+        assert_eq!(cells.len(), 1253, "sf");
+        assert!(cells.len() <= sf.maxPolyfillSize(Resolution::R9), "fits");
+        assert!(
+            cells.windows(2).all(|w| u64::from(w[0]) < u64::from(w[1])),
+            "sorted"
+        );
+        for h in cells.iter() {
+            assert!(sf.contains(&h.h3ToGeo()), "{} center inside", h);
+        }
+
+        let hole = GeoPolygon::new(sfVerts(), vec![holeVerts()]).unwrap();
+        let holeCells = hole.polyfill(Resolution::R9);
+        assert_eq!(holeCells.len(), 1214, "with hole");
+        assert!(holeCells.iter().all(|h| cells.contains(h)), "subset");
+    }
+
+    #[test]
+    fn polyfillExact() {
+        let origin = GeoCoord::from_rads(1.0, 2.0).geoToH3(Resolution::R9);
+        let boundary = origin.h3ToGeoBoundary();
+        let hexagon =
+            GeoPolygon::new(boundary.verts[..boundary.numVerts].to_vec(), Vec::new()).unwrap();
+        assert_eq!(hexagon.polyfill(Resolution::R9), vec![origin], "one cell");
+
+        // a cell's own boundary fills to its children, pentagons included
+        let pentagon =
+            H3Index::from_components(BaseCell::new(24).unwrap(), &[Direction::CENTER_DIGIT; 9])
+                .unwrap();
+        for &h in [origin, pentagon].iter() {
+            let boundary = h.h3ToGeoBoundary();
+            let cell =
+                GeoPolygon::new(boundary.verts[..boundary.numVerts].to_vec(), Vec::new()).unwrap();
+            let mut children = h.h3ToChildren(Resolution::R10);
+            children.sort_by_key(|&c| u64::from(c));
+            assert_eq!(cell.polyfill(Resolution::R10), children, "{} children", h);
+        }
+    }
+
+    #[test]
+    fn polyfillTransmeridian() {
+        let primeMeridian =
+            GeoPolygon::new(rectangle(0.01, -0.01, 0.01, -0.01), Vec::new()).unwrap();
+        assert_eq!(
+            primeMeridian.polyfill(Resolution::R7).len(),
+            4228,
+            "prime meridian"
+        );
+
+        // Not quite the prime meridian count: the cells are a little
+        // different in size and offset.
+        let outer = rectangle(0.01, -0.01, -M_PI + 0.01, M_PI - 0.01);
+        let inner = rectangle(0.005, -0.005, -M_PI + 0.005, M_PI - 0.005);
+        let transmeridian = GeoPolygon::new(outer.clone(), Vec::new()).unwrap();
+        assert_eq!(
+            transmeridian.polyfill(Resolution::R7).len(),
+            4238,
+            "transmeridian"
+        );
+
+        let filledHole = GeoPolygon::new(inner.clone(), Vec::new()).unwrap();
+        let withHole = GeoPolygon::new(outer, vec![inner]).unwrap();
+        assert_eq!(
+            withHole.polyfill(Resolution::R7).len(),
+            4238 - filledHole.polyfill(Resolution::R7).len(),
+            "transmeridian hole"
+        );
+
+        let complex = GeoPolygon::new(
+            [
+                (0.1, -M_PI + 0.00001),
+                (0.1, M_PI - 0.00001),
+                (0.05, M_PI - 0.2),
+                (-0.1, M_PI - 0.00001),
+                (-0.1, -M_PI + 0.00001),
+                (-0.05, -M_PI + 0.2),
+            ]
+            .iter()
+            .map(|&(lat, lon)| GeoCoord::from_rads(lat, lon))
+            .collect(),
+            Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(complex.polyfill(Resolution::R4).len(), 1204, "complex");
+    }
+
+    #[test]
+    fn polyfillSmall() {
+        // Smaller than a cell and away from any center.
+        let center = GeoCoord::from_rads(0.659966917655, -2.1364398519396)
+            .geoToH3(Resolution::R5)
+            .h3ToGeo();
+        let offset = 0.001;
+        let tiny = GeoPolygon::new(
+            rectangle(
+                center.lat + offset + 1e-6,
+                center.lat + offset,
+                center.lon + 1e-6,
+                center.lon,
+            ),
+            Vec::new(),
+        )
+        .unwrap();
+        assert!(tiny.polyfill(Resolution::R5).is_empty(), "no center inside");
+        assert_eq!(
+            tiny.maxPolyfillSize(Resolution::R5),
+            4 + 12,
+            "vertexes and buffer"
+        );
+    }
+}
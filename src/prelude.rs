@@ -0,0 +1,14 @@
+//! The commonly used types, for glob import.
+//!
+//! ```
+//! use h3rs::prelude::*;
+//!
+//! let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+//! let cell: H3Index = sf.geoToH3(Resolution::R9);
+//! assert!(cell.is_valid());
+//! ```
+
+pub use crate::{
+    CoordIJ, CoordIJK, Degrees, Direction, GeoCoord, GeoPolygon, Geofence, H3Error, H3Index,
+    PolygonError, Radians, Resolution,
+};
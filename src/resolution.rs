@@ -1,4 +1,16 @@
-use crate::{basecell::BaseCell, constants::NUM_PENTAGONS, Direction, H3Index};
+use std::sync::OnceLock;
+
+use crate::{basecell::BaseCell, constants::NUM_PENTAGONS, Direction, H3CellSet, H3Error, H3Index};
+
+/// Number of resolution and k pairs `Resolution::pentagon_neighborhood`
+/// keeps cells for.
+const NUM_PENTAGON_NEIGHBORHOODS: usize =
+    (Resolution::MAX_H3_RES + 1) * (Resolution::MAX_PENTAGON_NEIGHBORHOOD_K as usize + 1);
+
+/// The cells near the pentagons for each resolution and k, built on first
+/// use.
+static PENTAGON_NEIGHBORHOODS: [OnceLock<H3CellSet>; NUM_PENTAGON_NEIGHBORHOODS] =
+    [const { OnceLock::new() }; NUM_PENTAGON_NEIGHBORHOODS];
 
 #[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
 pub enum Resolution {
@@ -156,81 +168,167 @@ impl Resolution {
     pub(crate) fn _isValidChildRes(&self, childRes: &Self) -> bool {
         let childRes = usize::from(*childRes);
         let parentRes = usize::from(*self);
-        if childRes < parentRes || childRes > Resolution::MAX_H3_RES {
-            false
-        } else {
-            true
-        }
+        (parentRes..=Resolution::MAX_H3_RES).contains(&childRes)
     }
 
-    /** @brief overage distance table */
+    /// Maximum ijk+ coordinate sum of a cell at this resolution; see `maxDimByCIIres`.
     pub(crate) fn maxDimByCIIres(&self) -> i32 {
-        match self {
-            Resolution::R0 => 2,        // res  0
-            Resolution::R1 => -1,       // res  1
-            Resolution::R2 => 14,       // res  2
-            Resolution::R3 => -1,       // res  3
-            Resolution::R4 => 98,       // res  4
-            Resolution::R5 => -1,       // res  5
-            Resolution::R6 => 686,      // res  6
-            Resolution::R7 => -1,       // res  7
-            Resolution::R8 => 4802,     // res  8
-            Resolution::R9 => -1,       // res  9
-            Resolution::R10 => 33614,   // res 10
-            Resolution::R11 => -1,      // res 11
-            Resolution::R12 => 235298,  // res 12
-            Resolution::R13 => -1,      // res 13
-            Resolution::R14 => 1647086, // res 14
-            Resolution::R15 => -1,      // res 15
-                                         //Resolution::R16 => 11529602  // res 16
-        }
+        maxDimByCIIres[*self as usize]
     }
 
-    /// unit scale distance table
+    /// Unit scale distance at this resolution; see `unitScaleByCIIres`.
     pub(crate) fn unitScaleByCIIres(&self) -> i32 {
-        match self {
-            Resolution::R0 => 1,       // res  0
-            Resolution::R1 => -1,      // res  1
-            Resolution::R2 => 7,       // res  2
-            Resolution::R3 => -1,      // res  3
-            Resolution::R4 => 49,      // res  4
-            Resolution::R5 => -1,      // res  5
-            Resolution::R6 => 343,     // res  6
-            Resolution::R7 => -1,      // res  7
-            Resolution::R8 => 2401,    // res  8
-            Resolution::R9 => -1,      // res  9
-            Resolution::R10 => 16807,  // res 10
-            Resolution::R11 => -1,     // res 11
-            Resolution::R12 => 117649, // res 12
-            Resolution::R13 => -1,     // res 13
-            Resolution::R14 => 823543, // res 14
-            Resolution::R15 => -1,     // res 15
-                                        //Resolution::R16 => 5764801  // res 16
+        unitScaleByCIIres[*self as usize]
+    }
+
+    /**
+     * Steps a number of resolutions finer, or coarser for a negative step.
+     *
+     * @param step Number of resolutions to add
+     * @return The resolution, or None if it would leave 0 to `MAX_H3_RES`
+     */
+    pub fn checked_add(self, step: i32) -> Option<Self> {
+        let res = i32::from(self).checked_add(step)?;
+        if (0..=Self::MAX_H3_RES as i32).contains(&res) {
+            Some(res.into())
+        } else {
+            None
         }
     }
 
+    /**
+     * Steps a number of resolutions coarser, or finer for a negative step.
+     *
+     * @param step Number of resolutions to subtract
+     * @return The resolution, or None if it would leave 0 to `MAX_H3_RES`
+     */
+    pub fn checked_sub(self, step: i32) -> Option<Self> {
+        self.checked_add(step.checked_neg()?)
+    }
+
+    /// Steps a number of resolutions finer, stopping at R0 or R15.
+    pub fn saturating_add(self, step: i32) -> Self {
+        i32::from(self)
+            .saturating_add(step)
+            .clamp(0, Self::MAX_H3_RES as i32)
+            .into()
+    }
+
+    /// Steps a number of resolutions coarser, stopping at R0 or R15.
+    pub fn saturating_sub(self, step: i32) -> Self {
+        self.saturating_add(step.saturating_neg())
+    }
+
     /**
      * Generates all pentagons at the specified resolution
      *
      * @param res The resolution to produce pentagons at.
      * @param out Output array. Must be of size pentagonIndexCount().
      */
-    pub fn getPentagonIndexes(&self) -> [H3Index; NUM_PENTAGONS as usize] {
-        let mut result = [H3Index::H3_NULL; NUM_PENTAGONS as usize];
+    pub fn getPentagonIndexes(&self) -> [H3Index; NUM_PENTAGONS] {
+        let mut result = [H3Index::H3_NULL; NUM_PENTAGONS];
 
-        for i in -1..BaseCell::NUM_BASE_CELLS as i32 {
-            let bc = BaseCell::new(i);
+        let mut i = 0;
+        for bc in 0..BaseCell::NUM_BASE_CELLS as i32 {
+            let bc = BaseCell::new_unchecked(bc);
             if bc._isBaseCellPentagon() {
-                let pentagon = H3Index::setH3Index(*self, bc, Direction::CENTER_DIGIT);
-
-                result[i as usize] = pentagon;
+                result[i] = H3Index::setH3Index(*self, bc, Direction::CENTER_DIGIT);
+                i += 1;
             }
         }
 
         result
     }
+
+    /// Largest k that `pentagon_neighborhood` keeps.
+    pub const MAX_PENTAGON_NEIGHBORHOOD_K: u32 = 4;
+
+    /**
+     * The cells within k steps of any pentagon at this resolution, to route
+     * the cells that need the slow, pentagon-aware code paths away from the
+     * fast ones.
+     *
+     * The cells are found once per resolution and k, on first use, and kept
+     * for the life of the process: 12 disks of at most 61 cells each.
+     *
+     * ```
+     * use h3rs::prelude::*;
+     *
+     * let nearPentagons = Resolution::R7.pentagon_neighborhood(2).unwrap();
+     * let cell = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277)).geoToH3(Resolution::R7);
+     * assert!(!nearPentagons.contains_cell(cell));
+     * assert!(nearPentagons.contains_cell(Resolution::R7.getPentagonIndexes()[0]));
+     * ```
+     *
+     * @param k Steps from a pentagon, up to `MAX_PENTAGON_NEIGHBORHOOD_K`
+     * @return The cells, or Domain if k is too large
+     */
+    pub fn pentagon_neighborhood(&self, k: u32) -> Result<&'static H3CellSet, H3Error> {
+        if k > Self::MAX_PENTAGON_NEIGHBORHOOD_K {
+            return Err(H3Error::Domain);
+        }
+        let slot =
+            usize::from(*self) * (Self::MAX_PENTAGON_NEIGHBORHOOD_K as usize + 1) + k as usize;
+        Ok(PENTAGON_NEIGHBORHOODS[slot].get_or_init(|| {
+            let cells = self
+                .getPentagonIndexes()
+                .iter()
+                .flat_map(|p| p.kRing(k))
+                .collect::<Vec<_>>();
+            H3CellSet::from_cells(cells).expect("kRing cells are valid")
+        }))
+    }
 }
 
+/// Number of Class II resolutions addressed by the substrate grid tables.
+///
+/// A Class III cell is drawn on the next finer Class II grid, so a cell at
+/// `MAX_H3_RES` reaches one resolution past it. Code working in that grid
+/// carries the resolution as a plain `usize` rather than a `Resolution`.
+const NUM_CII_RES: usize = Resolution::MAX_H3_RES + 2;
+
+/** @brief overage distance table */
+pub(crate) const maxDimByCIIres: [i32; NUM_CII_RES] = [
+    2,        // res  0
+    -1,       // res  1
+    14,       // res  2
+    -1,       // res  3
+    98,       // res  4
+    -1,       // res  5
+    686,      // res  6
+    -1,       // res  7
+    4802,     // res  8
+    -1,       // res  9
+    33614,    // res 10
+    -1,       // res 11
+    235298,   // res 12
+    -1,       // res 13
+    1647086,  // res 14
+    -1,       // res 15
+    11529602, // res 16
+];
+
+/** @brief unit scale distance table */
+pub(crate) const unitScaleByCIIres: [i32; NUM_CII_RES] = [
+    1,       // res  0
+    -1,      // res  1
+    7,       // res  2
+    -1,      // res  3
+    49,      // res  4
+    -1,      // res  5
+    343,     // res  6
+    -1,      // res  7
+    2401,    // res  8
+    -1,      // res  9
+    16807,   // res 10
+    -1,      // res 11
+    117649,  // res 12
+    -1,      // res 13
+    823543,  // res 14
+    -1,      // res 15
+    5764801, // res 16
+];
+
 macro_rules! from_res {
     ($t : ty) => {
         impl From<$t> for Resolution {
@@ -288,6 +386,8 @@ from_res!(usize);
 from_res!(u32);
 from_res!(i32);
 
+/// Steps finer by a number of resolutions, panicking past R15 or below R0;
+/// see `checked_add` and `saturating_add` to handle those.
 impl std::ops::Add<i32> for Resolution {
     type Output = Self;
 
@@ -296,6 +396,8 @@ impl std::ops::Add<i32> for Resolution {
     }
 }
 
+/// Steps coarser by a number of resolutions, panicking below R0 or past
+/// R15; see `checked_sub` and `saturating_sub` to handle those.
 impl std::ops::Sub<i32> for Resolution {
     type Output = Self;
 
@@ -313,3 +415,117 @@ impl Ord for Resolution {
     }
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pentagonNeighborhood() {
+        for &res in [Resolution::R0, Resolution::R2, Resolution::R9].iter() {
+            let pentagons = res.getPentagonIndexes();
+            for k in 0..=Resolution::MAX_PENTAGON_NEIGHBORHOOD_K {
+                let near = res.pentagon_neighborhood(k).expect("small k");
+                assert!(
+                    std::ptr::eq(near, res.pentagon_neighborhood(k).unwrap()),
+                    "kept"
+                );
+                assert!(
+                    near.as_slice().iter().all(|h| h.get_resolution() == res),
+                    "{:?} resolution",
+                    res
+                );
+                if res == Resolution::R9 {
+                    // Far enough apart that the disks do not overlap.
+                    let disk = 1 + 5 * (k * (k + 1) / 2) as usize;
+                    assert_eq!(near.len(), 12 * disk, "{:?} k {}", res, k);
+                }
+
+                // A cell is near a pentagon exactly when a pentagon is near it;
+                // check the cells just inside and just outside the disks.
+                for h in pentagons.iter().flat_map(|p| p.kRing(k + 1)) {
+                    assert_eq!(
+                        near.contains_cell(h),
+                        h.kRing(k).iter().any(|n| n.is_pentagon()),
+                        "{:?} k {} {}",
+                        res,
+                        k,
+                        h
+                    );
+                }
+            }
+        }
+        assert_eq!(
+            Resolution::R5
+                .pentagon_neighborhood(Resolution::MAX_PENTAGON_NEIGHBORHOOD_K + 1)
+                .err(),
+            Some(H3Error::Domain),
+            "k too large"
+        );
+    }
+
+    #[test]
+    fn checkedArithmetic() {
+        assert_eq!(
+            Resolution::R9.checked_add(2),
+            Some(Resolution::R11),
+            "finer"
+        );
+        assert_eq!(
+            Resolution::R9.checked_sub(2),
+            Some(Resolution::R7),
+            "coarser"
+        );
+        assert_eq!(
+            Resolution::R9.checked_add(-9),
+            Some(Resolution::R0),
+            "negative step"
+        );
+        assert_eq!(Resolution::R15.checked_add(1), None, "past R15");
+        assert_eq!(Resolution::R0.checked_sub(1), None, "below R0");
+        assert_eq!(Resolution::R0.checked_add(i32::MAX), None, "overflow");
+        assert_eq!(
+            Resolution::R0.checked_sub(i32::MIN),
+            None,
+            "negation overflow"
+        );
+
+        for &res in Resolution::RESOLUTIONS.iter() {
+            for step in -16..=16 {
+                if let Some(sum) = res.checked_add(step) {
+                    assert_eq!(res + step, sum, "same as +");
+                    assert_eq!(res.checked_sub(-step), Some(sum), "sub of negative");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn saturatingArithmetic() {
+        assert_eq!(
+            Resolution::R9.saturating_add(3),
+            Resolution::R12,
+            "in range"
+        );
+        assert_eq!(
+            Resolution::R14.saturating_add(3),
+            Resolution::R15,
+            "clamped to R15"
+        );
+        assert_eq!(
+            Resolution::R2.saturating_sub(3),
+            Resolution::R0,
+            "clamped to R0"
+        );
+        assert_eq!(
+            Resolution::R2.saturating_add(i32::MIN),
+            Resolution::R0,
+            "MIN"
+        );
+        assert_eq!(
+            Resolution::R2.saturating_sub(i32::MIN),
+            Resolution::R15,
+            "-MIN"
+        );
+    }
+}
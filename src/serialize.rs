@@ -0,0 +1,226 @@
+//! Serialize and Deserialize, behind the `serde` feature.
+//!
+//! An `H3Index` is its hex string, as `Display` writes it, in human readable
+//! formats such as JSON, and its `u64` in binary formats such as bincode.
+//! Either way any 64 bits round trip; deserializing does not check that the
+//! index is valid. A `GeoCoord` is its `lat` and `lon` in radians. A
+//! `GeoBoundary` is the sequence of its vertices, and a `GeoPolygon` its
+//! `exterior` vertices and `holes`, each without a closing vertex;
+//! deserializing a polygon checks its loops as `GeoPolygon::new` does.
+
+use std::fmt;
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{geopolygon::MAX_CELL_BNDRY_VERTS, GeoBoundary, GeoCoord, GeoPolygon, H3Index};
+
+impl Serialize for H3Index {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u64(u64::from(*self))
+        }
+    }
+}
+
+/// Reads an index from its hex string or its integer.
+struct H3IndexVisitor;
+
+impl<'de> Visitor<'de> for H3IndexVisitor {
+    type Value = H3Index;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "an H3 index as a hex string or an integer")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<H3Index, E> {
+        v.parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<H3Index, E> {
+        Ok(H3Index::from(v))
+    }
+}
+
+impl<'de> Deserialize<'de> for H3Index {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            // Integers are accepted too, as written by other tools.
+            deserializer.deserialize_any(H3IndexVisitor)
+        } else {
+            deserializer.deserialize_u64(H3IndexVisitor)
+        }
+    }
+}
+
+impl Serialize for GeoBoundary {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.numVerts))?;
+        for v in self.verts[..self.numVerts].iter() {
+            seq.serialize_element(v)?;
+        }
+        seq.end()
+    }
+}
+
+/// Reads a boundary from a sequence of at most MAX_CELL_BNDRY_VERTS
+/// vertices.
+struct GeoBoundaryVisitor;
+
+impl<'de> Visitor<'de> for GeoBoundaryVisitor {
+    type Value = GeoBoundary;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at most {} vertices", MAX_CELL_BNDRY_VERTS)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<GeoBoundary, A::Error> {
+        let mut gb = GeoBoundary::default();
+        while let Some(v) = seq.next_element()? {
+            if gb.numVerts == MAX_CELL_BNDRY_VERTS {
+                return Err(de::Error::invalid_length(gb.numVerts + 1, &self));
+            }
+            gb.verts[gb.numVerts] = v;
+            gb.numVerts += 1;
+        }
+        Ok(gb)
+    }
+}
+
+impl<'de> Deserialize<'de> for GeoBoundary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(GeoBoundaryVisitor)
+    }
+}
+
+/// A polygon's loops, borrowed for serializing.
+#[derive(Serialize)]
+struct PolygonLoops<'a> {
+    exterior: &'a [GeoCoord],
+    holes: Vec<&'a [GeoCoord]>,
+}
+
+/// A polygon's loops, to check with `GeoPolygon::new` once deserialized.
+#[derive(Deserialize)]
+struct OwnedPolygonLoops {
+    exterior: Vec<GeoCoord>,
+    #[serde(default)]
+    holes: Vec<Vec<GeoCoord>>,
+}
+
+impl Serialize for GeoPolygon {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PolygonLoops {
+            exterior: self.exterior(),
+            holes: self.holes().collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GeoPolygon {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let loops = OwnedPolygonLoops::deserialize(deserializer)?;
+        GeoPolygon::new(loops.exterior, loops.holes).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Resolution;
+
+    fn sf() -> GeoCoord {
+        GeoCoord::from_rads(0.659966917655, -2.1364398519396)
+    }
+
+    #[test]
+    fn h3Index_formats() {
+        let h = sf().geoToH3(Resolution::R9);
+        let json = serde_json::to_string(&h).unwrap();
+        assert_eq!(json, format!("\"{}\"", h), "hex string");
+        assert_eq!(json.len(), 15 + 2, "15 characters");
+        assert_eq!(serde_json::from_str::<H3Index>(&json).unwrap(), h);
+        assert_eq!(
+            serde_json::from_str::<H3Index>(&u64::from(h).to_string()).unwrap(),
+            h,
+            "integer in JSON"
+        );
+        assert!(serde_json::from_str::<H3Index>("\"not hex\"").is_err());
+
+        let binary = bincode::serialize(&h).unwrap();
+        assert_eq!(binary, u64::from(h).to_le_bytes(), "u64");
+        assert_eq!(bincode::deserialize::<H3Index>(&binary).unwrap(), h);
+
+        let cells = sf().geoToH3(Resolution::R5).kRing(1);
+        let json = serde_json::to_string(&cells).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<H3Index>>(&json).unwrap(), cells);
+    }
+
+    #[test]
+    fn geometry_roundTrip() {
+        let coord = sf();
+        let json = serde_json::to_string(&coord).unwrap();
+        assert_eq!(json, r#"{"lat":0.659966917655,"lon":-2.1364398519396}"#);
+        let back: GeoCoord = serde_json::from_str(&json).unwrap();
+        assert_eq!((back.lat, back.lon), (coord.lat, coord.lon), "coord");
+
+        let pentagon = Resolution::R3.getPentagonIndexes()[0].h3ToGeoBoundary();
+        for gb in [coord.geoToH3(Resolution::R7).h3ToGeoBoundary(), pentagon].iter() {
+            let json = serde_json::to_value(gb).unwrap();
+            assert_eq!(json.as_array().unwrap().len(), gb.numVerts, "vertices");
+            let back: GeoBoundary = serde_json::from_value(json).unwrap();
+            assert_eq!(back.numVerts, gb.numVerts);
+            for (a, b) in back.verts.iter().zip(gb.verts.iter()) {
+                assert_eq!((a.lat, a.lon), (b.lat, b.lon), "vertex");
+            }
+            let binary = bincode::serialize(gb).unwrap();
+            let back: GeoBoundary = bincode::deserialize(&binary).unwrap();
+            assert_eq!(back.numVerts, gb.numVerts, "binary");
+        }
+        let tooMany = vec![coord; MAX_CELL_BNDRY_VERTS + 1];
+        assert!(serde_json::from_value::<GeoBoundary>(serde_json::json!(tooMany)).is_err());
+    }
+
+    #[test]
+    fn geoPolygon_roundTrip() {
+        let square = |d: f64| {
+            vec![
+                GeoCoord::from_rads(d, d),
+                GeoCoord::from_rads(-d, d),
+                GeoCoord::from_rads(-d, -d),
+                GeoCoord::from_rads(d, -d),
+            ]
+        };
+        let polygon = GeoPolygon::new(square(0.1), vec![square(0.01)]).unwrap();
+        let json = serde_json::to_value(&polygon).unwrap();
+        assert_eq!(json["exterior"].as_array().unwrap().len(), 4, "exterior");
+        assert_eq!(json["holes"].as_array().unwrap().len(), 1, "holes");
+
+        let back: GeoPolygon = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            back.polyfill(Resolution::R4),
+            polygon.polyfill(Resolution::R4),
+            "same cells"
+        );
+        let binary = bincode::serialize(&polygon).unwrap();
+        let back: GeoPolygon = bincode::deserialize(&binary).unwrap();
+        assert_eq!(back.holes().count(), 1, "binary");
+
+        let noHoles = serde_json::json!({ "exterior": square(0.1) });
+        let back: GeoPolygon = serde_json::from_value(noHoles).unwrap();
+        assert_eq!(back.holes().count(), 0, "holes default to none");
+
+        let degenerate = serde_json::json!({ "exterior": &square(0.1)[..2] });
+        assert!(
+            serde_json::from_value::<GeoPolygon>(degenerate).is_err(),
+            "checked as by new"
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! The sphere that angular measurements are scaled to, for kilometers and
+//! meters on the Earth or on other bodies.
+
+use crate::{constants::EARTH_RADIUS_KM, GeoCoord, H3Error, H3Index, Resolution};
+
+/**
+ * A sphere of a given radius, to turn the angles H3 works in into lengths
+ * and areas.
+ *
+ * The `*Km` and `*M` functions elsewhere in the crate measure on `EARTH`,
+ * the sphere with the same area as the WGS84 ellipsoid, as the C library
+ * does. Measure on another sphere, such as `EARTH_MEAN` or a different body,
+ * with the methods here.
+ *
+ * ```
+ * use h3rs::{prelude::*, SphereModel};
+ *
+ * let mars = SphereModel::new(3389.5).unwrap();
+ * let olympus = GeoCoord::new(Degrees(18.65), Degrees(-133.8));
+ * let cell = olympus.geoToH3(Resolution::R3);
+ *
+ * let ratio = mars.cell_area_km2(cell) / cell.cellAreaKm2();
+ * assert!((ratio - (3389.5f64 / 6371.007180918475).powi(2)).abs() < 1e-12);
+ * ```
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SphereModel {
+    radiusKm: f64,
+}
+
+impl SphereModel {
+    /// The Earth, as a sphere with the area of the WGS84 ellipsoid (its
+    /// authalic radius); what the rest of the crate measures on.
+    pub const EARTH: SphereModel = SphereModel {
+        radiusKm: EARTH_RADIUS_KM,
+    };
+
+    /// The Earth, as a sphere with the IUGG mean radius of the WGS84
+    /// ellipsoid, which some datasets are measured on.
+    pub const EARTH_MEAN: SphereModel = SphereModel {
+        radiusKm: 6371.0088,
+    };
+
+    /**
+     * A sphere of a given radius.
+     *
+     * @param radiusKm The radius, in kilometers
+     * @return The sphere, or Domain if the radius is not positive and finite
+     */
+    pub fn new(radiusKm: f64) -> Result<Self, H3Error> {
+        if radiusKm.is_finite() && radiusKm > 0.0 {
+            Ok(SphereModel { radiusKm })
+        } else {
+            Err(H3Error::Domain)
+        }
+    }
+
+    /// The radius, in kilometers.
+    pub fn radius_km(&self) -> f64 {
+        self.radiusKm
+    }
+
+    /// Length in kilometers of an arc of `rads` radians.
+    pub fn rads_to_km(&self, rads: f64) -> f64 {
+        rads * self.radiusKm
+    }
+
+    /// Area in kilometers^2 of a region of `rads2` steradians.
+    pub fn rads2_to_km2(&self, rads2: f64) -> f64 {
+        rads2 * self.radiusKm * self.radiusKm
+    }
+
+    /// The great circle distance in kilometers between two points.
+    pub fn point_dist_km(&self, a: &GeoCoord, b: &GeoCoord) -> f64 {
+        self.rads_to_km(GeoCoord::pointDistRads(a, b))
+    }
+
+    /// The great circle distance in meters between two points.
+    pub fn point_dist_m(&self, a: &GeoCoord, b: &GeoCoord) -> f64 {
+        self.point_dist_km(a, b) * 1000.
+    }
+
+    /// Area of a cell in kilometers^2.
+    pub fn cell_area_km2(&self, h: H3Index) -> f64 {
+        self.rads2_to_km2(h.cellAreaRads2())
+    }
+
+    /// Area of a cell in meters^2.
+    pub fn cell_area_m2(&self, h: H3Index) -> f64 {
+        self.cell_area_km2(h) * 1000. * 1000.
+    }
+
+    /// Length of a unidirectional edge in kilometers.
+    pub fn edge_length_km(&self, edge: H3Index) -> f64 {
+        self.rads_to_km(edge.exactEdgeLengthRads())
+    }
+
+    /// Average area of a hexagon at a resolution in kilometers^2; see
+    /// `Resolution::areaKm2`.
+    pub fn average_area_km2(&self, res: Resolution) -> f64 {
+        res.areaKm2() * (self.radiusKm / EARTH_RADIUS_KM).powi(2)
+    }
+
+    /// Average edge length of a hexagon at a resolution in kilometers; see
+    /// `Resolution::edgeLengthKm`.
+    pub fn average_edge_length_km(&self, res: Resolution) -> f64 {
+        res.edgeLengthKm() * self.radiusKm / EARTH_RADIUS_KM
+    }
+}
+
+impl Default for SphereModel {
+    fn default() -> Self {
+        Self::EARTH
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Degrees;
+
+    #[test]
+    fn sphereModel_earth() {
+        let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        let nyc = GeoCoord::new(Degrees(40.689167), Degrees(-74.044444));
+        let cell = sf.geoToH3(Resolution::R9);
+        let earth = SphereModel::default();
+
+        assert_eq!(earth, SphereModel::EARTH, "default");
+        assert_eq!(
+            earth.point_dist_km(&sf, &nyc),
+            GeoCoord::pointDistKm(&sf, &nyc)
+        );
+        assert_eq!(
+            earth.point_dist_m(&sf, &nyc),
+            GeoCoord::pointDistM(&sf, &nyc)
+        );
+        assert_eq!(earth.cell_area_km2(cell), cell.cellAreaKm2());
+        assert_eq!(earth.cell_area_m2(cell), cell.cellAreaM2());
+        assert_eq!(
+            earth.average_area_km2(Resolution::R9),
+            Resolution::R9.areaKm2()
+        );
+        assert_eq!(
+            earth.average_edge_length_km(Resolution::R9),
+            Resolution::R9.edgeLengthKm()
+        );
+
+        let mean = SphereModel::EARTH_MEAN;
+        let ratio = mean.point_dist_km(&sf, &nyc) / earth.point_dist_km(&sf, &nyc);
+        assert!((ratio - 1.0).abs() < 1e-6, "within a meter per 1000 km");
+    }
+
+    #[test]
+    fn sphereModel_scaling() {
+        let unit = SphereModel::new(1.0).expect("unit sphere");
+        let mars = SphereModel::new(3389.5).expect("mars");
+        let cell = GeoCoord::new(Degrees(18.65), Degrees(-133.8)).geoToH3(Resolution::R5);
+
+        assert_eq!(
+            unit.cell_area_km2(cell),
+            cell.cellAreaRads2(),
+            "unit sphere"
+        );
+        assert_eq!(
+            mars.rads_to_km(std::f64::consts::PI),
+            3389.5 * std::f64::consts::PI
+        );
+        assert!(
+            (mars.cell_area_km2(cell) - unit.cell_area_km2(cell) * 3389.5 * 3389.5).abs() < 1e-9,
+            "scales with the radius squared"
+        );
+        let ratio = mars.average_edge_length_km(Resolution::R5)
+            / SphereModel::EARTH.average_edge_length_km(Resolution::R5);
+        assert!(
+            (ratio - 3389.5 / EARTH_RADIUS_KM).abs() < 1e-12,
+            "edge length"
+        );
+
+        for &bad in [0.0, -1.0, f64::NAN, f64::INFINITY].iter() {
+            assert_eq!(SphereModel::new(bad), Err(H3Error::Domain), "{}", bad);
+        }
+    }
+}
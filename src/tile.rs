@@ -0,0 +1,489 @@
+//! Web mercator map tiles, the z/x/y tiles of slippy maps and tile servers,
+//! and the cells covering them.
+
+use std::fmt;
+
+use crate::{
+    budget::Budget,
+    constants::{M_2PI, M_PI},
+    flood_fill_budgeted, BBox, GeoCoord, H3Error, H3Index, Resolution,
+};
+
+/**
+ * A web mercator tile: at zoom z the map is split into 2^z by 2^z tiles,
+ * numbered from x 0 at the antimeridian going east and y 0 at the top going
+ * south. The map ends at about 85.05 degrees north and south, where web
+ * mercator makes it square.
+ *
+ * ```
+ * use h3rs::{prelude::*, tiles_for_cells, Tile};
+ *
+ * let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+ * let tile = Tile::from_geo(&sf, 12).unwrap();
+ * assert_eq!((tile.x, tile.y), (655, 1583));
+ *
+ * // every cell touching the tile, and the tile among those they touch
+ * let cells = tile.cells(Resolution::R8);
+ * assert!(cells.contains(&sf.geoToH3(Resolution::R8)));
+ * assert!(tiles_for_cells(&cells, 12).unwrap().contains(&tile));
+ * ```
+ */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tile {
+    /// zoom level
+    pub z: u8,
+    /// column, from the west
+    pub x: u32,
+    /// row, from the north
+    pub y: u32,
+}
+
+impl Tile {
+    /// Deepest zoom level supported, where tiles are a few centimeters wide.
+    pub const MAX_ZOOM: u8 = 30;
+
+    /// A tile from its zoom level and position, or `H3Error::Domain` if the
+    /// zoom is past `MAX_ZOOM` or the position is off the map.
+    pub fn new(z: u8, x: u32, y: u32) -> Result<Self, H3Error> {
+        if z > Self::MAX_ZOOM || x >= 1 << z || y >= 1 << z {
+            return Err(H3Error::Domain);
+        }
+        Ok(Tile { z, x, y })
+    }
+
+    /**
+     * The tile containing a point at a zoom level. Points beyond the
+     * latitudes web mercator reaches are in the top or bottom row, and points
+     * on a shared edge are in the tile to their south east.
+     *
+     * @param g The point
+     * @param z The zoom level
+     * @return The tile, or `H3Error::Domain` if the zoom is past `MAX_ZOOM`
+     *         or the point is not finite
+     */
+    pub fn from_geo(g: &GeoCoord, z: u8) -> Result<Self, H3Error> {
+        if z > Self::MAX_ZOOM || !g.lat.is_finite() || !g.lon.is_finite() {
+            return Err(H3Error::Domain);
+        }
+        let n = (1u64 << z) as f64;
+        let lng = GeoCoord::constrainLng(g.lon);
+        let x = tileX(lng, n).rem_euclid(n);
+        let y = tileY(g.lat, n).max(0.0).min(n - 1.0);
+        Tile::new(z, x as u32, y as u32)
+    }
+
+    /// The tile's sides, in radians. The west side of the tiles in column 0
+    /// is -180 degrees and the east side of the last column +180.
+    pub fn bbox(&self) -> BBox {
+        let n = (1u64 << self.z) as f64;
+        BBox::new(
+            tileLat(self.y as f64, n),
+            tileLat(self.y as f64 + 1.0, n),
+            tileLng(self.x as f64 + 1.0, n),
+            tileLng(self.x as f64, n),
+        )
+    }
+
+    /**
+     * The cells of a resolution covering the tile: every cell that overlaps
+     * it, so that drawing them fills the tile without gaps, including cells
+     * that stick out past its edges.
+     *
+     * Cells are tested against the tile with straight edges in latitude and
+     * longitude, as `Geofence` does, which is exact for the tile and close
+     * for cells much smaller than a face of the icosahedron. The number of
+     * cells grows fourfold with each zoom level coarser or resolution finer;
+     * see `cells_budgeted` to cap it.
+     *
+     * @param res The resolution of the cells
+     * @return The cells, breadth first from the one at the tile's center
+     */
+    pub fn cells(&self, res: Resolution) -> Vec<H3Index> {
+        match self.cells_budgeted(res, &mut Budget::unlimited()) {
+            Ok(cells) => cells,
+            Err(_) => unreachable!("unlimited budget"),
+        }
+    }
+
+    /// Like `cells`, but gives up with `H3Error::Cancelled` once the cells
+    /// run past a budget.
+    pub fn cells_budgeted(
+        &self,
+        res: Resolution,
+        budget: &mut Budget,
+    ) -> Result<Vec<H3Index>, H3Error> {
+        let bbox = self.bbox();
+        let seed = bbox.center().geoToH3(res);
+        let poles = PoleCells::new(res);
+        flood_fill_budgeted(vec![seed], |h| cellOverlaps(h, &bbox, &poles), budget)
+    }
+}
+
+impl fmt::Display for Tile {
+    /// The tile as "z/x/y", the way tile URLs spell it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.z, self.x, self.y)
+    }
+}
+
+/**
+ * The tiles of a zoom level touched by a set of cells, for invalidating a
+ * tile cache when the values of some cells change. A tile is touched if a
+ * cell overlaps it, in the same sense as `Tile::cells`, so each cell's tiles
+ * are exactly those whose `cells` include it.
+ *
+ * A coarse cell spans a number of tiles growing fourfold with each zoom
+ * level, up to about 2^60 for a resolution 0 cell at `Tile::MAX_ZOOM`; see
+ * `tiles_for_cells_budgeted` to cap it.
+ *
+ * @param cells The cells, at any resolutions
+ * @param z The zoom level
+ * @return The tiles, sorted and without duplicates, or `H3Error::Domain` if
+ *         the zoom is past `Tile::MAX_ZOOM`, or `H3Error::CellInvalid` if a
+ *         cell is not valid
+ */
+pub fn tiles_for_cells(cells: &[H3Index], z: u8) -> Result<Vec<Tile>, H3Error> {
+    tiles_for_cells_budgeted(cells, z, &mut Budget::unlimited())
+}
+
+/**
+ * Finds tiles like `tiles_for_cells`, charging the tiles of each cell's
+ * bounding box against a budget before testing them, so a coarse cell at a
+ * fine zoom can be stopped before it runs for hours.
+ *
+ * @param cells The cells, at any resolutions
+ * @param z The zoom level
+ * @param budget Limit on the tiles tested, counted as cells
+ * @return The tiles, the errors of `tiles_for_cells`, or
+ *         `H3Error::Cancelled` if the budget ran out
+ */
+pub fn tiles_for_cells_budgeted(
+    cells: &[H3Index],
+    z: u8,
+    budget: &mut Budget,
+) -> Result<Vec<Tile>, H3Error> {
+    if z > Tile::MAX_ZOOM {
+        return Err(H3Error::Domain);
+    }
+    let n = (1u64 << z) as f64;
+    let mut poles: Vec<PoleCells> = Vec::new();
+    let mut tiles = Vec::new();
+
+    for &h in cells {
+        if !h.is_valid() {
+            return Err(H3Error::CellInvalid);
+        }
+        let res = h.get_resolution();
+        let cellPoles = match poles.iter().find(|p| p.res == res) {
+            Some(p) => *p,
+            None => {
+                poles.push(PoleCells::new(res));
+                poles[poles.len() - 1]
+            }
+        };
+
+        // The columns and rows of the cell's bounding box, then the tiles
+        // among them the cell overlaps.
+        let center = h.h3ToGeo();
+        let (mut north, mut south, mut east, mut west) = (center.lat, center.lat, 0.0, 0.0);
+        let boundary = h.h3ToGeoBoundary();
+        for v in boundary.verts[..boundary.numVerts].iter() {
+            let lng = unwrapLng(v.lon, center.lon) - center.lon;
+            north = f64::max(north, v.lat);
+            south = f64::min(south, v.lat);
+            east = f64::max(east, lng);
+            west = f64::min(west, lng);
+        }
+        let (xs, ys) = if h == cellPoles.north {
+            ((0.0, n - 1.0), (0.0, tileY(south, n)))
+        } else if h == cellPoles.south {
+            ((0.0, n - 1.0), (tileY(north, n), n - 1.0))
+        } else {
+            (
+                (tileX(center.lon + west, n), tileX(center.lon + east, n)),
+                (tileY(north, n), tileY(south, n)),
+            )
+        };
+        let (y0, y1) = (ys.0.max(0.0).min(n - 1.0), ys.1.max(0.0).min(n - 1.0));
+
+        for x in xs.0 as i64..=xs.1 as i64 {
+            budget.charge((y1 - y0) as usize + 1)?;
+            for y in y0 as u32..=y1 as u32 {
+                let tile = Tile {
+                    z,
+                    x: x.rem_euclid(n as i64) as u32,
+                    y,
+                };
+                if cellOverlaps(h, &tile.bbox(), &cellPoles) {
+                    tiles.push(tile);
+                }
+            }
+        }
+    }
+
+    tiles.sort_unstable();
+    tiles.dedup();
+    Ok(tiles)
+}
+
+/// Column of a longitude, not wrapped onto the map.
+fn tileX(lng: f64, n: f64) -> f64 {
+    ((lng + M_PI) / M_2PI * n).floor()
+}
+
+/// Row of a latitude, counting from the north edge of the map, not clamped
+/// onto it.
+fn tileY(lat: f64, n: f64) -> f64 {
+    let mercator = lat.tan().asinh();
+    ((1.0 - mercator / M_PI) / 2.0 * n).floor()
+}
+
+/// Longitude of the west edge of a column.
+fn tileLng(x: f64, n: f64) -> f64 {
+    x / n * M_2PI - M_PI
+}
+
+/// Latitude of the north edge of a row.
+fn tileLat(y: f64, n: f64) -> f64 {
+    (M_PI * (1.0 - 2.0 * y / n)).sinh().atan()
+}
+
+/// A longitude moved by whole turns to within half a turn of another.
+fn unwrapLng(lng: f64, near: f64) -> f64 {
+    let mut lng = lng;
+    while lng - near > M_PI {
+        lng -= M_2PI;
+    }
+    while near - lng > M_PI {
+        lng += M_2PI;
+    }
+    lng
+}
+
+/// The cells of a resolution containing the poles, whose boundaries go all
+/// the way around in longitude rather than making a polygon.
+#[derive(Copy, Clone)]
+struct PoleCells {
+    res: Resolution,
+    north: H3Index,
+    south: H3Index,
+}
+
+impl PoleCells {
+    fn new(res: Resolution) -> Self {
+        PoleCells {
+            res,
+            north: GeoCoord::from_rads(M_PI / 2.0, 0.0).geoToH3(res),
+            south: GeoCoord::from_rads(-M_PI / 2.0, 0.0).geoToH3(res),
+        }
+    }
+}
+
+/**
+ * Whether a cell overlaps a box by more than a shared edge or corner, with
+ * the cell's edges straight in latitude and longitude. The cell's vertexes
+ * are unwrapped around the box so cells across the antimeridian line up.
+ *
+ * A cell around a pole is taken to be the cap past its vertex nearest the
+ * equator.
+ */
+fn cellOverlaps(h: H3Index, bbox: &BBox, poles: &PoleCells) -> bool {
+    let boundary = h.h3ToGeoBoundary();
+    let verts = &boundary.verts[..boundary.numVerts];
+
+    if h == poles.north {
+        let south = verts.iter().map(|v| v.lat).fold(f64::INFINITY, f64::min);
+        return bbox.north > south;
+    }
+    if h == poles.south {
+        let north = verts
+            .iter()
+            .map(|v| v.lat)
+            .fold(f64::NEG_INFINITY, f64::max);
+        return bbox.south < north;
+    }
+
+    // The box is never transmeridian, being a tile.
+    let mid = (bbox.east + bbox.west) / 2.0;
+    let points: Vec<(f64, f64)> = verts
+        .iter()
+        .map(|v| (unwrapLng(v.lon, mid), v.lat))
+        .collect();
+    let corners = [
+        (bbox.west, bbox.south),
+        (bbox.east, bbox.south),
+        (bbox.east, bbox.north),
+        (bbox.west, bbox.north),
+    ];
+
+    // Separating axes: the box's sides, then the cell's edges.
+    if points.iter().all(|p| p.0 <= bbox.west)
+        || points.iter().all(|p| p.0 >= bbox.east)
+        || points.iter().all(|p| p.1 <= bbox.south)
+        || points.iter().all(|p| p.1 >= bbox.north)
+    {
+        return false;
+    }
+    let area: f64 = (0..points.len())
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum();
+    let orientation = area.signum();
+    !(0..points.len()).any(|i| {
+        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+        corners.iter().all(|c| {
+            let cross = (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+            cross * orientation <= 0.0
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Degrees;
+    use std::collections::HashSet;
+
+    #[test]
+    fn tile_bbox() {
+        let world = Tile::new(0, 0, 0).unwrap().bbox();
+        assert!(
+            (world.north.to_degrees() - 85.0511287798).abs() < 1e-9,
+            "top"
+        );
+        assert!((world.south + world.north).abs() < 1e-12, "bottom");
+        assert_eq!((world.west, world.east), (-M_PI, M_PI), "sides");
+
+        let tile = Tile::new(3, 5, 2).unwrap();
+        let bbox = tile.bbox();
+        assert_eq!(bbox.west.to_degrees(), 45.0, "west");
+        assert_eq!(bbox.east.to_degrees(), 90.0, "east");
+        assert_eq!(tile.to_string(), "3/5/2");
+        for &(dy, dx) in [(0.1, 0.1), (0.5, 0.5), (0.9, 0.9)].iter() {
+            let g = GeoCoord::from_rads(
+                bbox.south + (bbox.north - bbox.south) * dy,
+                bbox.west + (bbox.east - bbox.west) * dx,
+            );
+            assert_eq!(Tile::from_geo(&g, 3), Ok(tile), "{:?} round trip", g);
+        }
+
+        let north = GeoCoord::new(Degrees(89.0), Degrees(180.0));
+        assert_eq!(Tile::from_geo(&north, 2), Ok(Tile { z: 2, x: 0, y: 0 }));
+        let south = GeoCoord::new(Degrees(-90.0), Degrees(179.9));
+        assert_eq!(Tile::from_geo(&south, 2), Ok(Tile { z: 2, x: 3, y: 3 }));
+
+        assert_eq!(Tile::new(2, 4, 0), Err(H3Error::Domain), "off the map");
+        assert_eq!(Tile::new(31, 0, 0), Err(H3Error::Domain), "too deep");
+        assert_eq!(
+            Tile::from_geo(&GeoCoord::from_rads(f64::NAN, 0.0), 4),
+            Err(H3Error::Domain),
+            "not finite"
+        );
+        assert!(Tile::new(30, (1 << 30) - 1, 0).is_ok(), "deepest");
+    }
+
+    #[test]
+    fn tile_cells() {
+        let tiles = [
+            Tile::new(11, 327, 791).unwrap(),     // San Francisco
+            Tile::new(11, 0, 1000).unwrap(),      // west of the antimeridian
+            Tile::new(11, 2047, 1000).unwrap(),   // east of the antimeridian
+            Tile::new(9, 80, 4).unwrap(),         // far north
+            Tile::new(15, 16384, 16383).unwrap(), // a corner of the equator
+        ];
+        let res = Resolution::R7;
+        for tile in tiles.iter() {
+            let cells = tile.cells(res);
+            let set: HashSet<H3Index> = cells.iter().copied().collect();
+            assert_eq!(set.len(), cells.len(), "{}: no duplicates", tile);
+
+            // every point of the tile is covered
+            let bbox = tile.bbox();
+            for i in 0..=20 {
+                for j in 0..=20 {
+                    let g = GeoCoord::from_rads(
+                        bbox.south + (bbox.north - bbox.south) * i as f64 / 20.0,
+                        bbox.west + (bbox.east - bbox.west) * j as f64 / 20.0,
+                    );
+                    let h = g.geoToH3(res);
+                    assert!(set.contains(&h), "{}: {:?} covered", tile, g);
+                }
+            }
+            let center = bbox.center().geoToH3(res);
+            assert!(set.contains(&center), "{}: center", tile);
+
+            // and every cell overlaps it, so the tile is among its tiles
+            for h in cells.iter() {
+                let touched = tiles_for_cells(&[*h], tile.z).unwrap();
+                assert!(touched.contains(tile), "{}: {} touches it", tile, h);
+                for t in touched.iter() {
+                    assert!(t.cells(res).contains(h), "{}: {} in {}", tile, h, t);
+                }
+            }
+        }
+
+        // a tile smaller than a cell is covered by the cells it is in
+        let tiny =
+            Tile::from_geo(&GeoCoord::from_rads(0.659966917655, -2.1364398519396), 18).unwrap();
+        let cells = tiny.cells(Resolution::R5);
+        assert!(!cells.is_empty() && cells.len() <= 3, "{:?}", cells);
+    }
+
+    #[test]
+    fn tilesForCells() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let cell = sf.geoToH3(Resolution::R9);
+        let tile = Tile::from_geo(&sf, 8).unwrap();
+        assert_eq!(tiles_for_cells(&[cell], 8), Ok(vec![tile]), "small cell");
+
+        let disk = sf.geoToH3(Resolution::R5).kRing(2);
+        let tiles = tiles_for_cells(&disk, 9).unwrap();
+        assert!(tiles.windows(2).all(|w| w[0] < w[1]), "sorted, unique");
+        let covered: HashSet<H3Index> =
+            tiles.iter().flat_map(|t| t.cells(Resolution::R5)).collect();
+        assert!(disk.iter().all(|h| covered.contains(h)), "round trip");
+
+        // A pole cell touches the top rows all the way around.
+        let pole = GeoCoord::new(Degrees(90.0), Degrees(0.0)).geoToH3(Resolution::R0);
+        let tiles = tiles_for_cells(&[pole], 3).unwrap();
+        assert!(
+            tiles.iter().filter(|t| t.y == 0).count() == 8,
+            "{:?}",
+            tiles
+        );
+
+        assert_eq!(tiles_for_cells(&[], 4), Ok(vec![]), "none");
+        assert_eq!(
+            tiles_for_cells(&[H3Index::H3_NULL], 4),
+            Err(H3Error::CellInvalid)
+        );
+        assert_eq!(tiles_for_cells(&[cell], 31), Err(H3Error::Domain));
+    }
+
+    #[test]
+    fn tilesForCells_budgeted() {
+        let sf = GeoCoord::from_rads(0.659966917655, -2.1364398519396);
+        let disk = sf.geoToH3(Resolution::R5).kRing(2);
+        let mut budget = Budget::max_cells(10_000);
+        assert_eq!(
+            tiles_for_cells_budgeted(&disk, 9, &mut budget),
+            tiles_for_cells(&disk, 9),
+            "within budget"
+        );
+        assert!(budget.spent() > 0, "tiles charged");
+
+        // trillions of tiles, cancelled long before they are all tested
+        let coarse = sf.geoToH3(Resolution::R0);
+        assert_eq!(
+            tiles_for_cells_budgeted(&[coarse], Tile::MAX_ZOOM, &mut Budget::max_cells(1_000_000)),
+            Err(H3Error::Cancelled)
+        );
+        assert_eq!(
+            tiles_for_cells_budgeted(&[sf.geoToH3(Resolution::R9)], 31, &mut Budget::max_cells(0)),
+            Err(H3Error::Domain),
+            "zoom checked first"
+        );
+    }
+}
@@ -0,0 +1,548 @@
+//! Export of regions made of cells as shared boundary arcs, like TopoJSON.
+//!
+//! Exporting each region as its own polygons writes every border between two
+//! regions twice, and exporting each cell writes every vertex about three
+//! times. A `Topology` stores each stretch of border once, as an arc, and
+//! describes the rings of each region as references to those arcs.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    constants::{M_2PI, M_PI},
+    geopolygon::{GeoMultiPolygon, GeoPolygon, Geofence},
+    GeoCoord, H3Error, H3Index,
+};
+
+/// A reference from a ring to one of the arcs of a `Topology`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArcRef {
+    /// Index into `Topology::arcs`
+    pub arc: usize,
+    /// Whether the ring follows the arc from its last vertex to its first
+    pub reversed: bool,
+}
+
+impl ArcRef {
+    /// The reference as TopoJSON encodes it: the arc index, or its ones'
+    /// complement when reversed.
+    pub fn topojson_index(&self) -> i64 {
+        if self.reversed {
+            !(self.arc as i64)
+        } else {
+            self.arc as i64
+        }
+    }
+}
+
+/// A polygon of a region, as rings of arc references. The first ring is the
+/// exterior, counter-clockwise; any others are holes, clockwise.
+pub type TopoPolygon = Vec<Vec<ArcRef>>;
+
+/// The outlines of a set of regions, with each border stored once.
+#[derive(Clone, Debug, Default)]
+pub struct Topology {
+    /// Polylines in radians. A border between two regions, or between a
+    /// region and the outside, is split into arcs where three regions meet.
+    /// Consecutive arcs of a ring share their end vertex.
+    pub arcs: Vec<Vec<GeoCoord>>,
+    /// The polygons of each region, in the order the regions were given.
+    pub regions: Vec<Vec<TopoPolygon>>,
+}
+
+/// Grid the vertexes are snapped to when matching the copies computed from
+/// each cell around them. Far coarser than their rounding error, and far
+/// finer than the ~8e-8 rad between the vertexes of a res 15 cell.
+const VERTEX_GRID_RADS: f64 = 1e-10;
+
+/// Gives each cell vertex an id, the same for every cell it is computed from.
+#[derive(Default)]
+struct VertexIds {
+    ids: HashMap<(i64, i64), usize>,
+    coords: Vec<GeoCoord>,
+}
+
+impl VertexIds {
+    fn id(&mut self, v: &GeoCoord) -> usize {
+        // a vertex on the antimeridian may be given as either -π or π
+        let lon = if v.lon < -M_PI + VERTEX_GRID_RADS {
+            v.lon + M_2PI
+        } else {
+            v.lon
+        };
+        let key = (
+            (v.lat / VERTEX_GRID_RADS).round() as i64,
+            (lon / VERTEX_GRID_RADS).round() as i64,
+        );
+
+        // copies of a vertex can round to either side of a grid line
+        for di in -1..=1 {
+            for dj in -1..=1 {
+                if let Some(&id) = self.ids.get(&(key.0 + di, key.1 + dj)) {
+                    return id;
+                }
+            }
+        }
+
+        let id = self.coords.len();
+        self.ids.insert(key, id);
+        self.coords.push(*v);
+        id
+    }
+}
+
+impl Topology {
+    /**
+     * Builds the topology of a single region.
+     *
+     * @param h3Set Cells of the region, all at the same resolution
+     * @return The topology, or an error for invalid or mixed resolution cells
+     */
+    pub fn from_cells(h3Set: &[H3Index]) -> Result<Self, H3Error> {
+        Self::from_regions(&[h3Set])
+    }
+
+    /**
+     * Builds the topology of several regions, such as the zones of a
+     * partition, so that the borders between them are stored once.
+     *
+     * Repeated cells within a region are ignored. Regions may be empty, or
+     * made of several disconnected parts, but must not share cells.
+     *
+     * @param regions Cells of each region, all at the same resolution
+     * @return The topology, or `CellInvalid` for invalid cells,
+     * `ResolutionMismatch` for mixed resolutions and `Domain` for a cell in
+     * more than one region
+     */
+    pub fn from_regions<S: AsRef<[H3Index]>>(regions: &[S]) -> Result<Self, H3Error> {
+        let mut vertexes = VertexIds::default();
+        // directed boundary edges, by the region on their left
+        let mut owners: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut cellRegions: HashMap<H3Index, usize> = HashMap::new();
+        let mut res = None;
+
+        for (r, cells) in regions.iter().enumerate() {
+            for &h in cells.as_ref() {
+                if !h.is_valid() {
+                    return Err(H3Error::CellInvalid);
+                }
+                if *res.get_or_insert(h.get_resolution()) != h.get_resolution() {
+                    return Err(H3Error::ResolutionMismatch);
+                }
+                match cellRegions.insert(h, r) {
+                    Some(other) if other != r => return Err(H3Error::Domain),
+                    Some(_) => continue,
+                    None => {}
+                }
+
+                let gb = h.h3ToGeoBoundary();
+                let ids: Vec<usize> = gb.verts[..gb.numVerts]
+                    .iter()
+                    .map(|v| vertexes.id(v))
+                    .collect();
+                for (j, &from) in ids.iter().enumerate() {
+                    let to = ids[(j + 1) % ids.len()];
+                    // an edge between two cells of the region is inside it
+                    if owners.get(&(to, from)) == Some(&r) {
+                        owners.remove(&(to, from));
+                    } else {
+                        owners.insert((from, to), r);
+                    }
+                }
+            }
+        }
+
+        // Arcs end where the boundary branches: at vertexes on more than two
+        // boundary edges, where three regions meet.
+        let mut degree = vec![0u8; vertexes.coords.len()];
+        for &(from, to) in owners.keys() {
+            if from < to || !owners.contains_key(&(to, from)) {
+                degree[from] += 1;
+                degree[to] += 1;
+            }
+        }
+
+        let next: HashMap<(usize, usize), usize> = owners
+            .iter()
+            .map(|(&(from, to), &r)| ((r, from), to))
+            .collect();
+        let mut edges: Vec<((usize, usize), usize)> = owners.into_iter().collect();
+        edges.sort_unstable_by_key(|&((from, to), r)| (r, from, to));
+
+        let mut topology = Topology {
+            arcs: Vec::new(),
+            regions: vec![Vec::new(); regions.len()],
+        };
+        // arcs by their last edge, reversed, which is the first edge of the
+        // region on their other side
+        let mut arcsByEnd: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut rings: Vec<Vec<(Vec<ArcRef>, Geofence)>> =
+            regions.iter().map(|_| Vec::new()).collect();
+
+        for &((from, _), r) in edges.iter() {
+            if visited.contains(&(r, from)) {
+                continue;
+            }
+
+            let mut ring = vec![from];
+            let mut v = from;
+            while let Some(&to) = next.get(&(r, v)) {
+                if !visited.insert((r, v)) || to == from {
+                    break;
+                }
+                ring.push(to);
+                v = to;
+            }
+
+            // Start at a junction if there is one, and otherwise at the
+            // smallest id, so both sides of a border cut it into the same arcs.
+            let start = (0..ring.len())
+                .find(|&i| degree[ring[i]] > 2)
+                .unwrap_or_else(|| (0..ring.len()).min_by_key(|&i| ring[i]).unwrap_or(0));
+            ring.rotate_left(start);
+            ring.push(ring[0]);
+
+            let mut refs = Vec::new();
+            let mut arcStart = 0;
+            for i in 1..ring.len() {
+                if i + 1 < ring.len() && degree[ring[i]] <= 2 {
+                    continue;
+                }
+                let arc = &ring[arcStart..=i];
+                let key = (arc[0], arc[1]);
+                refs.push(match arcsByEnd.get(&key) {
+                    Some(&arc) => ArcRef {
+                        arc,
+                        reversed: true,
+                    },
+                    None => {
+                        let n = arc.len();
+                        arcsByEnd.insert((arc[n - 1], arc[n - 2]), topology.arcs.len());
+                        topology
+                            .arcs
+                            .push(arc.iter().map(|&id| vertexes.coords[id]).collect());
+                        ArcRef {
+                            arc: topology.arcs.len() - 1,
+                            reversed: false,
+                        }
+                    }
+                });
+                arcStart = i;
+            }
+
+            ring.pop();
+            let geofence = Geofence {
+                verts: ring.iter().map(|&id| vertexes.coords[id]).collect(),
+            };
+            rings[r].push((refs, geofence));
+        }
+
+        for (r, regionRings) in rings.into_iter().enumerate() {
+            topology.regions[r] = Self::assignHoles(regionRings);
+        }
+        Ok(topology)
+    }
+
+    /// Groups the rings of a region into polygons, putting each hole with the
+    /// smallest exterior ring around it.
+    fn assignHoles(rings: Vec<(Vec<ArcRef>, Geofence)>) -> Vec<TopoPolygon> {
+        let (holes, outers): (Vec<_>, Vec<_>) =
+            rings.into_iter().partition(|(_, g)| g.isClockwise());
+        let bboxes: Vec<_> = outers.iter().map(|(_, g)| g.bboxFromGeofence()).collect();
+
+        let mut polygons: Vec<TopoPolygon> = Vec::with_capacity(outers.len());
+        let mut fences = Vec::with_capacity(outers.len());
+        for (refs, fence) in outers {
+            polygons.push(vec![refs]);
+            fences.push(fence);
+        }
+
+        for (refs, hole) in holes {
+            let container = (0..fences.len())
+                .filter(|&i| fences[i].pointInsideGeofence(&bboxes[i], &hole.verts[0]))
+                .min_by(|&a, &b| {
+                    let area = |i: usize| {
+                        let bbox = &bboxes[i];
+                        (bbox.north - bbox.south) * (bbox.east - bbox.west).abs()
+                    };
+                    area(a).total_cmp(&area(b))
+                });
+            // a region's holes are always inside one of its exteriors
+            if let Some(i) = container.or(if polygons.is_empty() { None } else { Some(0) }) {
+                polygons[i].push(refs);
+            }
+        }
+        polygons
+    }
+
+    /// Vertices of a ring, open as `Geofence` stores them.
+    fn ringVerts(&self, ring: &[ArcRef]) -> Vec<GeoCoord> {
+        let mut verts: Vec<GeoCoord> = Vec::new();
+        for r in ring {
+            let arc = &self.arcs[r.arc];
+            // consecutive arcs share their end vertex
+            if r.reversed {
+                verts.extend(arc.iter().rev().skip(1));
+            } else {
+                verts.extend(arc.iter().skip(1));
+            }
+        }
+        verts
+    }
+
+    /**
+     * Resolves the arcs of a region back into polygons.
+     *
+     * @param region Index of the region, in the order given to `from_regions`
+     * @return The region's polygons, or None if there is no such region
+     */
+    pub fn to_multi_polygon(&self, region: usize) -> Option<GeoMultiPolygon> {
+        let polygons = self.regions.get(region)?;
+        Some(GeoMultiPolygon {
+            polygons: polygons
+                .iter()
+                .map(|rings| GeoPolygon {
+                    geofence: Geofence {
+                        verts: self.ringVerts(&rings[0]),
+                    },
+                    holes: rings[1..]
+                        .iter()
+                        .map(|ring| Geofence {
+                            verts: self.ringVerts(ring),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Total number of arc vertices, the size of the exported geometry.
+    pub fn num_verts(&self) -> usize {
+        self.arcs.iter().map(|a| a.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Degrees, Resolution};
+
+    /// Checks a topology against the vertex graph of each region: the rings
+    /// trace every boundary edge once, and each arc is used once per side.
+    fn assertTopology(regions: &[Vec<H3Index>]) -> Topology {
+        let topology = Topology::from_regions(regions).expect("topology");
+        assert_eq!(
+            topology.regions.len(),
+            regions.len(),
+            "one entry per region"
+        );
+
+        let mut uses = vec![(0, 0); topology.arcs.len()];
+        for (r, polygons) in topology.regions.iter().enumerate() {
+            for ring in polygons.iter().flatten() {
+                for (a, b) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+                    let end = |r: &ArcRef, last: bool| {
+                        let arc = &topology.arcs[r.arc];
+                        if last != r.reversed {
+                            arc[arc.len() - 1]
+                        } else {
+                            arc[0]
+                        }
+                    };
+                    assert_eq!(end(a, true), end(b, false), "arcs of a ring connect");
+                }
+                for arcRef in ring {
+                    if arcRef.reversed {
+                        uses[arcRef.arc].1 += 1;
+                    } else {
+                        uses[arcRef.arc].0 += 1;
+                    }
+                }
+            }
+
+            let graph = H3Index::h3SetToVertexGraph(&regions[r]);
+            let multiPolygon = topology.to_multi_polygon(r).expect("region");
+            let mut numEdges = 0;
+            for polygon in multiPolygon.polygons.iter() {
+                for ring in std::iter::once(polygon.exterior()).chain(polygon.holes()) {
+                    for (from, to) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+                        assert!(
+                            graph.findNodeForEdge(from, Some(to)).is_some(),
+                            "region {} edge on its boundary",
+                            r
+                        );
+                    }
+                    numEdges += ring.len();
+                }
+            }
+            assert_eq!(numEdges, graph.size(), "region {} boundary traced once", r);
+        }
+
+        for (arc, &(forward, reversed)) in uses.iter().enumerate() {
+            assert_eq!(forward, 1, "arc {} created once", arc);
+            assert!(reversed <= 1, "arc {} shared by two regions at most", arc);
+        }
+        topology
+    }
+
+    fn sf(res: Resolution) -> H3Index {
+        GeoCoord::new(Degrees(37.779265), Degrees(-122.419277)).geoToH3(res)
+    }
+
+    #[test]
+    fn topology_singleRegion() {
+        let cell = sf(Resolution::R9);
+        let t = assertTopology(&[vec![cell]]);
+        assert_eq!(t.arcs.len(), 1, "one closed arc");
+        assert_eq!(t.arcs[0].len(), 7, "closed hexagon");
+        assert_eq!(t.arcs[0].first(), t.arcs[0].last(), "closed");
+
+        let t = assertTopology(&[cell.kRing(3)]);
+        assert_eq!(t.arcs.len(), 1, "one closed arc");
+        assert_eq!(t.regions[0].len(), 1, "one polygon");
+        assert_eq!(t.regions[0][0].len(), 1, "no holes");
+        let exterior = t.to_multi_polygon(0).unwrap().polygons[0]
+            .exterior()
+            .to_vec();
+        assert!(
+            !Geofence { verts: exterior }.isClockwise(),
+            "exterior is ccw"
+        );
+
+        let mut annulus = cell.grid_ring(1).unwrap();
+        annulus.extend(cell.grid_ring(2).unwrap());
+        let t = assertTopology(&[annulus]);
+        assert_eq!(t.regions[0].len(), 1, "one polygon");
+        assert_eq!(t.regions[0][0].len(), 2, "with a hole");
+        let polygon = &t.to_multi_polygon(0).unwrap().polygons[0];
+        let hole: Vec<&[GeoCoord]> = polygon.holes().collect();
+        assert_eq!(hole[0].len(), 6, "the center cell");
+        assert!(
+            Geofence {
+                verts: hole[0].to_vec()
+            }
+            .isClockwise(),
+            "hole is cw"
+        );
+
+        let far = GeoCoord::new(Degrees(-33.9), Degrees(151.2)).geoToH3(Resolution::R9);
+        let t = assertTopology(&[vec![cell, far, far]]);
+        assert_eq!(t.regions[0].len(), 2, "two parts, repeats ignored");
+        assert_eq!(t.to_multi_polygon(1).map(|_| ()), None, "no second region");
+    }
+
+    #[test]
+    fn topology_sharedBorders() {
+        let cell = sf(Resolution::R9);
+        let t = assertTopology(&[
+            cell.kRing(1),
+            cell.grid_ring(2).unwrap(),
+            cell.grid_ring(3).unwrap(),
+        ]);
+        assert_eq!(t.arcs.len(), 3, "one arc per nested border");
+        assert_eq!(
+            t.regions[1][0],
+            vec![
+                vec![ArcRef {
+                    arc: 1,
+                    reversed: false
+                }],
+                vec![ArcRef {
+                    arc: 0,
+                    reversed: true
+                }]
+            ],
+            "the middle ring's hole is the inner border, reversed"
+        );
+
+        // a partition with borders meeting at junctions
+        let parents = sf(Resolution::R7).kRing(2);
+        let regions: Vec<Vec<H3Index>> = parents
+            .iter()
+            .map(|p| p.h3ToChildren(Resolution::R9))
+            .collect();
+        let t = assertTopology(&regions);
+
+        let naive: usize = regions
+            .iter()
+            .flatten()
+            .map(|h| h.h3ToGeoBoundary().numVerts + 1)
+            .sum();
+        let separate: usize = regions
+            .iter()
+            .map(|cells| H3Index::h3SetToVertexGraph(cells).size() + 1)
+            .sum();
+        assert!(
+            t.num_verts() * 4 < separate * 3 && separate * 4 < naive,
+            "{} arc vertexes, {} as separate regions, {} as cells",
+            t.num_verts(),
+            separate,
+            naive
+        );
+    }
+
+    #[test]
+    fn topology_distortion() {
+        // around a pentagon, whose neighbors cross icosahedron face edges
+        for &res in [Resolution::R1, Resolution::R2].iter() {
+            let pentagon = res.getPentagonIndexes()[3];
+            let fine = match res {
+                Resolution::R1 => Resolution::R3,
+                _ => Resolution::R5,
+            };
+            let regions: Vec<Vec<H3Index>> = pentagon
+                .kRing(1)
+                .iter()
+                .map(|p| p.h3ToChildren(fine))
+                .collect();
+            assertTopology(&regions);
+            assertTopology(&[pentagon.kRing(2)]);
+        }
+
+        // across the antimeridian
+        let fiji = GeoCoord::new(Degrees(-16.8), Degrees(179.9)).geoToH3(Resolution::R4);
+        let (west, east): (Vec<H3Index>, Vec<H3Index>) = fiji
+            .kRing(3)
+            .into_iter()
+            .partition(|h| h.h3ToGeo().lon > 0.0);
+        assertTopology(&[west, east]);
+    }
+
+    #[test]
+    fn topology_errors() {
+        let cell = sf(Resolution::R9);
+        assert_eq!(
+            Topology::from_cells(&[cell, H3Index::H3_NULL]).map(|_| ()),
+            Err(H3Error::CellInvalid),
+            "invalid cell"
+        );
+        assert_eq!(
+            Topology::from_regions(&[vec![cell], vec![sf(Resolution::R8)]]).map(|_| ()),
+            Err(H3Error::ResolutionMismatch),
+            "mixed resolutions"
+        );
+        assert_eq!(
+            Topology::from_regions(&[cell.kRing(1), vec![cell]]).map(|_| ()),
+            Err(H3Error::Domain),
+            "overlapping regions"
+        );
+
+        let empty = Topology::from_regions(&[Vec::<H3Index>::new()]).unwrap();
+        assert!(empty.arcs.is_empty(), "no arcs");
+        assert!(empty.regions[0].is_empty(), "no polygons");
+    }
+
+    #[test]
+    fn arcRef_topojsonIndex() {
+        let forward = ArcRef {
+            arc: 3,
+            reversed: false,
+        };
+        let reversed = ArcRef {
+            arc: 3,
+            reversed: true,
+        };
+        assert_eq!(forward.topojson_index(), 3, "forward");
+        assert_eq!(reversed.topojson_index(), -4, "ones' complement");
+    }
+}
@@ -0,0 +1,268 @@
+//! Scalar SQL function kernels over Arrow arrays.
+//!
+//! Each function takes its arguments as columns and returns one output column
+//! of the same length, matching the `Fn(&[ArrayRef]) -> Result<ArrayRef>`
+//! shape that DataFusion's `make_scalar_function` wraps into a scalar UDF.
+//! Cells are `UInt64` columns; resolutions and k values are `Int64` columns,
+//! as integer literals are typed in SQL.
+//!
+//! Rows with a null argument, an invalid cell or an out of range resolution
+//! or k produce a null.
+
+use std::{convert::TryFrom, sync::Arc};
+
+use arrow_array::{
+    builder::{ListBuilder, UInt64Builder},
+    cast::AsArray,
+    types::{Float64Type, Int64Type, UInt64Type},
+    Array, ArrayRef, PrimitiveArray, UInt64Array,
+};
+use arrow_schema::{ArrowError, DataType, Field};
+
+use crate::{Degrees, GeoCoord, H3Index, Resolution};
+
+/// Checks the argument count and types, and that all columns have the same length.
+fn checkArgs(name: &str, args: &[ArrayRef], types: &[DataType]) -> Result<usize, ArrowError> {
+    if args.len() != types.len() {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "{} expects {} arguments, got {}",
+            name,
+            types.len(),
+            args.len()
+        )));
+    }
+
+    for (i, (arg, t)) in args.iter().zip(types).enumerate() {
+        if arg.data_type() != t {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "{} argument {} must be {}, got {}",
+                name,
+                i + 1,
+                t,
+                arg.data_type()
+            )));
+        }
+    }
+
+    let len = args[0].len();
+    if args.iter().any(|a| a.len() != len) {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "{} arguments have different lengths",
+            name
+        )));
+    }
+
+    Ok(len)
+}
+
+/// Resolution in a row of an `Int64` column, if present and in range.
+fn resolutionAt(res: &PrimitiveArray<Int64Type>, i: usize) -> Option<Resolution> {
+    if res.is_null(i) {
+        return None;
+    }
+    let r = res.value(i);
+    if r < 0 || r > Resolution::MAX_H3_RES as i64 {
+        return None;
+    }
+    Some(Resolution::from(r))
+}
+
+/// Valid cell in a row of a `UInt64` column, if present.
+fn cellAt(cells: &PrimitiveArray<UInt64Type>, i: usize) -> Option<H3Index> {
+    if cells.is_null(i) {
+        return None;
+    }
+    let h = H3Index::from(cells.value(i));
+    if h.is_valid() {
+        Some(h)
+    } else {
+        None
+    }
+}
+
+/// Largest k `k_ring` expands, 3,003,001 cells for one row, so that a query
+/// cannot exhaust the memory of the engine running it; rows with a larger k
+/// produce a null.
+pub const K_RING_MAX_K: u32 = 1_000;
+
+/// Data type of the column returned by `k_ring`.
+pub fn k_ring_return_type() -> DataType {
+    DataType::List(Arc::new(Field::new("item", DataType::UInt64, true)))
+}
+
+/**
+ * `lat_lng_to_cell(lat, lng, res)`: the cell containing each point.
+ *
+ * @param args `Float64` latitude and longitude in degrees, `Int64` resolution
+ * @return `UInt64` cells
+ */
+pub fn lat_lng_to_cell(args: &[ArrayRef]) -> Result<ArrayRef, ArrowError> {
+    let len = checkArgs(
+        "lat_lng_to_cell",
+        args,
+        &[DataType::Float64, DataType::Float64, DataType::Int64],
+    )?;
+    let lats = args[0].as_primitive::<Float64Type>();
+    let lngs = args[1].as_primitive::<Float64Type>();
+    let res = args[2].as_primitive::<Int64Type>();
+
+    let out: UInt64Array = (0..len)
+        .map(|i| {
+            if lats.is_null(i) || lngs.is_null(i) {
+                return None;
+            }
+            let (lat, lng) = (lats.value(i), lngs.value(i));
+            if !lat.is_finite() || !lng.is_finite() {
+                return None;
+            }
+            let coord = GeoCoord::new(Degrees(lat), Degrees(lng));
+            resolutionAt(res, i).map(|r| u64::from(coord.geoToH3(r)))
+        })
+        .collect();
+
+    Ok(Arc::new(out))
+}
+
+/**
+ * `cell_to_parent(cell, res)`: the parent of each cell at a coarser resolution.
+ *
+ * @param args `UInt64` cells, `Int64` parent resolution
+ * @return `UInt64` parents; null where the resolution is finer than the cell's
+ */
+pub fn cell_to_parent(args: &[ArrayRef]) -> Result<ArrayRef, ArrowError> {
+    let len = checkArgs("cell_to_parent", args, &[DataType::UInt64, DataType::Int64])?;
+    let cells = args[0].as_primitive::<UInt64Type>();
+    let res = args[1].as_primitive::<Int64Type>();
+
+    let out: UInt64Array = (0..len)
+        .map(|i| {
+            let parent = cellAt(cells, i)?.h3ToParent(resolutionAt(res, i)?);
+            parent.ok().map(u64::from)
+        })
+        .collect();
+
+    Ok(Arc::new(out))
+}
+
+/**
+ * `k_ring(cell, k)`: the cells within distance k of each cell, as a list.
+ *
+ * @param args `UInt64` cells, `Int64` k, 0 to `K_RING_MAX_K`
+ * @return list of `UInt64` cells, in no particular order; see `k_ring_return_type`
+ */
+pub fn k_ring(args: &[ArrayRef]) -> Result<ArrayRef, ArrowError> {
+    let len = checkArgs("k_ring", args, &[DataType::UInt64, DataType::Int64])?;
+    let cells = args[0].as_primitive::<UInt64Type>();
+    let ks = args[1].as_primitive::<Int64Type>();
+
+    let mut builder = ListBuilder::new(UInt64Builder::new());
+    for i in 0..len {
+        let k = if ks.is_null(i) || ks.value(i) < 0 {
+            None
+        } else {
+            u32::try_from(ks.value(i))
+                .ok()
+                .filter(|&k| k <= K_RING_MAX_K)
+        };
+
+        match (cellAt(cells, i), k) {
+            (Some(h), Some(k)) => {
+                for neighbor in h.kRing(k) {
+                    builder.values().append_value(u64::from(neighbor));
+                }
+                builder.append(true);
+            }
+            _ => builder.append(false),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Float64Array, Int64Array};
+
+    #[test]
+    fn latLngToCell() {
+        let args: Vec<ArrayRef> = vec![
+            Arc::new(Float64Array::from(vec![Some(37.779265), None, Some(0.0)])),
+            Arc::new(Float64Array::from(vec![
+                Some(-122.419277),
+                Some(0.0),
+                Some(0.0),
+            ])),
+            Arc::new(Int64Array::from(vec![9, 9, 16])),
+        ];
+        let out = lat_lng_to_cell(&args).unwrap();
+        let out = out.as_primitive::<UInt64Type>();
+
+        let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        assert_eq!(
+            out.value(0),
+            u64::from(sf.geoToH3(Resolution::R9)),
+            "indexes point"
+        );
+        assert!(out.is_null(1), "null latitude");
+        assert!(out.is_null(2), "resolution out of range");
+    }
+
+    #[test]
+    fn cellToParent() {
+        let sf = GeoCoord::new(Degrees(37.779265), Degrees(-122.419277));
+        let cell = u64::from(sf.geoToH3(Resolution::R9));
+
+        let args: Vec<ArrayRef> = vec![
+            Arc::new(UInt64Array::from(vec![cell, cell, 0])),
+            Arc::new(Int64Array::from(vec![5, 10, 5])),
+        ];
+        let out = cell_to_parent(&args).unwrap();
+        let out = out.as_primitive::<UInt64Type>();
+
+        assert_eq!(
+            out.value(0),
+            u64::from(sf.geoToH3(Resolution::R5)),
+            "parent"
+        );
+        assert!(out.is_null(1), "finer resolution");
+        assert!(out.is_null(2), "invalid cell");
+    }
+
+    #[test]
+    fn kRing() {
+        let cell = u64::from(H3Index::getRes0Indexes()[0x29 >> 1]);
+
+        let args: Vec<ArrayRef> = vec![
+            Arc::new(UInt64Array::from(vec![cell; 5])),
+            Arc::new(Int64Array::from(vec![
+                Some(1),
+                None,
+                Some(-1),
+                Some(i64::from(K_RING_MAX_K) + 1),
+                Some(40_000),
+            ])),
+        ];
+        let out = k_ring(&args).unwrap();
+        assert_eq!(out.data_type(), &k_ring_return_type(), "list of cells");
+
+        let out = out.as_list::<i32>();
+        assert_eq!(out.value(0).len(), 7, "k-ring of a hexagon");
+        assert!(out.is_null(1), "null k");
+        assert!(out.is_null(2), "negative k");
+        assert!(out.is_null(3), "k past the cap");
+        assert!(out.is_null(4), "k past MAX_KRING_K");
+    }
+
+    #[test]
+    fn argumentErrors() {
+        let args: Vec<ArrayRef> = vec![Arc::new(UInt64Array::from(vec![0u64]))];
+        assert!(cell_to_parent(&args).is_err(), "too few arguments");
+
+        let args: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(vec![0])),
+            Arc::new(Int64Array::from(vec![0])),
+        ];
+        assert!(cell_to_parent(&args).is_err(), "wrong argument type");
+    }
+}
@@ -0,0 +1,65 @@
+//! Angle units.
+//!
+//! Like the C library, this crate works in radians throughout: `GeoCoord`
+//! stores radians, and functions ending in `Rads` take and return them.
+//! Degrees only appear at the edges, in `degsToRads`, `radsToDegs` and
+//! functions ending in `Degs` or `_deg`.
+//!
+//! Functions that take an angle from callers accept `impl Into<Radians>`, so
+//! the unit is spelled out at the call site: `Radians(0.66)` or
+//! `Degrees(37.8)`. A bare `f64` is deliberately not accepted.
+
+use crate::geocoord::{degsToRads, radsToDegs};
+
+/// An angle in radians.
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Radians(pub f64);
+
+/// An angle in decimal degrees.
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Degrees(pub f64);
+
+impl From<Degrees> for Radians {
+    fn from(degrees: Degrees) -> Self {
+        Radians(degsToRads(degrees.0))
+    }
+}
+
+impl From<Radians> for Degrees {
+    fn from(radians: Radians) -> Self {
+        Degrees(radsToDegs(radians.0))
+    }
+}
+
+impl From<Radians> for f64 {
+    fn from(radians: Radians) -> Self {
+        radians.0
+    }
+}
+
+impl From<Degrees> for f64 {
+    fn from(degrees: Degrees) -> Self {
+        degrees.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::M_PI, GeoCoord};
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Radians::from(Degrees(180.0)), Radians(M_PI), "to radians");
+        assert_eq!(Degrees::from(Radians(M_PI)), Degrees(180.0), "to degrees");
+        assert_eq!(f64::from(Radians(1.5)), 1.5, "radians unwrap");
+        assert_eq!(f64::from(Degrees(90.0)), 90.0, "degrees unwrap");
+    }
+
+    #[test]
+    fn geoCoordNew() {
+        let g = GeoCoord::new(Degrees(45.0), Radians(1.0));
+        assert_eq!(g.lat, M_PI / 4.0, "latitude converted");
+        assert_eq!(g.lon, 1.0, "longitude unchanged");
+    }
+}
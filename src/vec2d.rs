@@ -1,6 +1,11 @@
-use crate::{GeoCoord, Resolution, constants::{self, M_SIN60}, coordijk::CoordIJK, faceCenterGeo, geocoord::{_geoAzDistanceRads, _posAngleRads}, vec3d::Vec3d};
-
-
+use crate::{
+    constants::{self, M_SIN60},
+    coordijk::CoordIJK,
+    faceCenterGeo,
+    geocoord::{_geoAzDistanceRads, _posAngleRads},
+    vec3d::Vec3d,
+    GeoCoord, Resolution,
+};
 
 /** @brief icosahedron face centers in x/y/z on the unit sphere */
 pub(crate) const faceCenterPoint: [Vec3d; constants::NUM_ICOSA_FACES] = [
@@ -154,7 +159,7 @@ const INVALID_FACE: i32 = -1;
 /** @brief direction from the origin face to the destination face, relative to
  * the origin face's coordinate system, or -1 if not adjacent.
  */
-const adjacentFaceDir: [[i32; constants::NUM_ICOSA_FACES]; constants::NUM_ICOSA_FACES] = [
+pub(crate) const adjacentFaceDir: [[i32; constants::NUM_ICOSA_FACES]; constants::NUM_ICOSA_FACES] = [
     [
         0, KI, -1, -1, IJ, JK, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
     ], // face 0
@@ -268,6 +273,12 @@ impl Vec2d {
         Self::new(p0.x + (t * s1.x), p0.y + (t * s1.y))
     }
 
+    /// Whether two 2D vectors are almost equal, within some threshold.
+    pub(crate) fn _v2dAlmostEquals(&self, other: &Self) -> bool {
+        (self.x - other.x).abs() < f32::EPSILON as f64
+            && (self.y - other.y).abs() < f32::EPSILON as f64
+    }
+
     /* Whether two 2D vectors are equal. Does not consider possible false
      * negatives due to floating-point errors.
      * @param v1 First vector to compare
@@ -293,7 +304,7 @@ impl Vec2d {
     pub(crate) fn _hex2dToGeo(
         &self, /* v */
         face: i32,
-        res: Resolution,
+        res: usize,
         substrate: bool,
     ) -> GeoCoord {
         // calculate (r, theta) in hex2d
@@ -303,21 +314,17 @@ impl Vec2d {
             return faceCenterGeo[face as usize];
         }
 
-        if r < crate::constants::EPSILON {
-            return faceCenterGeo[face as usize];
-        }
-
         let mut theta = f64::atan2(self.y, self.x);
 
         // scale for current resolution length u
-        for _ in 0..res as usize {
+        for _ in 0..res {
             r /= constants::M_SQRT7;
         }
 
         // scale accordingly if this is a substrate grid
         if substrate {
             r /= 3.0;
-            if res.isResClassIII() {
+            if res % 2 == 1 {
                 r /= constants::M_SQRT7;
             }
         }
@@ -329,7 +336,7 @@ impl Vec2d {
 
         // adjust theta for Class III
         // if a substrate grid, then it's already been adjusted for Class III
-        if !substrate && res.isResClassIII() {
+        if !substrate && res % 2 == 1 {
             theta = _posAngleRads(theta + constants::M_AP7_ROT_RADS);
         }
 
@@ -423,17 +430,17 @@ impl Vec2d {
             {
                 let axisi = j / 2;
                 let diff = i - axisi;
-                i = i - 2 * diff;
+                i -= 2 * diff;
             } else {
                 let axisi = (j + 1) / 2;
                 let diff = i - axisi;
-                i = i - (2 * diff + 1);
+                i -= 2 * diff + 1;
             }
         }
 
         if self.y < 0.0 {
-            i = i - (2 * j + 1) / 2;
-            j = -1 * j;
+            i -= (2 * j + 1) / 2;
+            j = -j;
         }
 
         let mut h = CoordIJK::new(i, j, k);
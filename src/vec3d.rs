@@ -22,7 +22,29 @@ impl Vec3d {
         _square(self.x - other.x) + _square(self.y - other.y) + _square(self.z - other.z)
     }
 
+    /// Dot product of two vectors.
+    pub(crate) fn dot(&self, other: &Self) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Cross product of two vectors.
+    pub(crate) fn cross(&self, other: &Self) -> Self {
+        Vec3d::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
 
+    /// Length of the vector.
+    pub(crate) fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    /// The vector scaled by a factor.
+    pub(crate) fn scale(&self, factor: f64) -> Self {
+        Vec3d::new(self.x * factor, self.y * factor, self.z * factor)
+    }
 }
 
 #[cfg(test)]
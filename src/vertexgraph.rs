@@ -0,0 +1,537 @@
+use crate::{
+    constants::{M_2PI, M_PI},
+    GeoCoord, H3Error, Resolution,
+};
+
+/// A directed edge of a region boundary, from one cell vertex to the next
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VertexNode {
+    /// start vertex
+    pub from: GeoCoord,
+    /// end vertex
+    pub to: GeoCoord,
+}
+
+/// A graph of boundary edges, hashed by their start vertex.
+///
+/// Built by `H3Index::h3SetToVertexGraph`, it holds the edges of a set of
+/// cells that are not shared with another cell of the set, so it traces the
+/// outlines and holes of the region. Following each edge to the one starting
+/// at its end vertex walks a loop.
+///
+/// The copies of a vertex computed from each cell around it differ in their
+/// last bits, most of all where the cells are on different icosahedron faces,
+/// and a vertex on the antimeridian may come as either -π or π. Vertexes are
+/// therefore the same when their latitudes and longitudes are each within
+/// the graph's epsilon, `DEFAULT_EPSILON_RADS` unless built with
+/// `with_epsilon`.
+#[derive(Clone, Debug)]
+pub struct VertexGraph {
+    buckets: Vec<Vec<VertexNode>>,
+    size: usize,
+    res: Resolution,
+    epsilon: f64,
+}
+
+impl VertexGraph {
+    /// Default tolerance for two vertexes to be the same, in radians: far
+    /// coarser than the ~1e-14 rad the copies of a vertex differ by, and far
+    /// finer than the ~8e-8 rad between the vertexes of a res 15 cell.
+    pub const DEFAULT_EPSILON_RADS: f64 = 1e-10;
+
+    /**
+     * Initialize a new VertexGraph
+     * @param  numBuckets Number of buckets to include in the graph; 0 is
+     *                    taken as 1
+     * @param  res        Resolution of the hexagons whose vertices we're storing
+     */
+    pub fn new(numBuckets: usize, res: Resolution) -> Self {
+        Self {
+            buckets: vec![Vec::new(); numBuckets.max(1)],
+            size: 0,
+            res,
+            epsilon: Self::DEFAULT_EPSILON_RADS,
+        }
+    }
+
+    /**
+     * Initialize a new VertexGraph that takes vertexes within a tolerance of
+     * each other to be the same.
+     * @param  numBuckets Number of buckets to include in the graph
+     * @param  res        Resolution of the hexagons whose vertices we're storing
+     * @param  epsilon    Tolerance in radians, for latitude and longitude each
+     * @return The graph, or `H3Error::Domain` unless epsilon is finite and
+     *         positive
+     */
+    pub fn with_epsilon(numBuckets: usize, res: Resolution, epsilon: f64) -> Result<Self, H3Error> {
+        if !(epsilon.is_finite() && epsilon > 0.0) {
+            return Err(H3Error::Domain);
+        }
+        Ok(Self {
+            epsilon,
+            ..Self::new(numBuckets, res)
+        })
+    }
+
+    /// Number of edges in the graph.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Resolution of the hexagons whose vertices are stored.
+    pub fn res(&self) -> Resolution {
+        self.res
+    }
+
+    /// Tolerance for two vertexes to be the same, in radians.
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    /// Iterate over the edges of the graph, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &VertexNode> {
+        self.buckets.iter().flatten()
+    }
+
+    /// Whether two vertexes are within epsilon of each other, across the
+    /// antimeridian if need be.
+    fn sameVertex(&self, a: &GeoCoord, b: &GeoCoord) -> bool {
+        let dLon = (a.lon - b.lon).abs();
+        (a.lat - b.lat).abs() <= self.epsilon && dLon.min(M_2PI - dLon) <= self.epsilon
+    }
+
+    fn sameEdge(&self, a: &VertexNode, b: &VertexNode) -> bool {
+        self.sameVertex(&a.from, &b.from) && self.sameVertex(&a.to, &b.to)
+    }
+
+    /// Position of a vertex on a grid of epsilon-sized squares. A vertex
+    /// within epsilon of another is in the same square or one next to it.
+    fn gridKey(&self, vertex: &GeoCoord) -> (i64, i64) {
+        // a vertex on the antimeridian may be given as either -π or π
+        let lon = if vertex.lon < -M_PI + self.epsilon {
+            vertex.lon + M_2PI
+        } else {
+            vertex.lon
+        };
+        (
+            (vertex.lat / self.epsilon).round() as i64,
+            (lon / self.epsilon).round() as i64,
+        )
+    }
+
+    fn bucketForKey(&self, key: (i64, i64)) -> usize {
+        let h = (key.0 as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ key.1 as u64;
+        (h.wrapping_mul(0xff51_afd7_ed55_8ccd) >> 7) as usize % self.buckets.len()
+    }
+
+    /**
+     * Get an integer hash for a lat/lon point, from the square of the
+     * epsilon grid it is in.
+     * @param  vertex     Lat/lon vertex to hash
+     * @return            Integer hash
+     */
+    fn _hashVertex(&self, vertex: &GeoCoord) -> usize {
+        self.bucketForKey(self.gridKey(vertex))
+    }
+
+    /// The buckets that may hold a vertex within epsilon of this one: those
+    /// of its grid square and the 8 around it, each once.
+    fn bucketsNear(&self, vertex: &GeoCoord) -> impl Iterator<Item = usize> {
+        let (lat, lon) = self.gridKey(vertex);
+        let mut near = [0; 9];
+        let mut len = 0;
+        for dLat in -1..=1 {
+            for dLon in -1..=1 {
+                let bucket = self.bucketForKey((lat + dLat, lon + dLon));
+                if !near[..len].contains(&bucket) {
+                    near[len] = bucket;
+                    len += 1;
+                }
+            }
+        }
+        IntoIterator::into_iter(near).take(len)
+    }
+
+    /// Bucket and position of an edge of the graph.
+    fn position(&self, node: &VertexNode) -> Option<(usize, usize)> {
+        self.bucketsNear(&node.from).find_map(|b| {
+            self.buckets[b]
+                .iter()
+                .position(|n| self.sameEdge(n, node))
+                .map(|i| (b, i))
+        })
+    }
+
+    /**
+     * Add a edge to the graph
+     * @param fromVtx Start vertex
+     * @param toVtx   End vertex
+     * @return        The new node, or the existing one if the edge was
+     *                already in the graph
+     */
+    pub fn addVertexNode(&mut self, fromVtx: &GeoCoord, toVtx: &GeoCoord) -> VertexNode {
+        let node = VertexNode {
+            from: *fromVtx,
+            to: *toVtx,
+        };
+
+        // Check the the edge we're adding doesn't already exist
+        if let Some((b, i)) = self.position(&node) {
+            return self.buckets[b][i];
+        }
+
+        let index = self._hashVertex(fromVtx);
+        self.buckets[index].push(node);
+        self.size += 1;
+        node
+    }
+
+    /**
+     * Remove a node from the graph.
+     * @param node  Node to remove
+     * @return      Whether the node was found and removed
+     */
+    pub fn removeVertexNode(&mut self, node: &VertexNode) -> bool {
+        match self.position(node) {
+            Some((b, i)) => {
+                self.buckets[b].remove(i);
+                self.size -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /**
+     * Find the Vertex node for a given edge, if it exists
+     * @param  fromVtx Start vertex
+     * @param  toVtx   End vertex, or None if we don't care
+     * @return         The vertex node, if found
+     */
+    pub fn findNodeForEdge(
+        &self,
+        fromVtx: &GeoCoord,
+        toVtx: Option<&GeoCoord>,
+    ) -> Option<&VertexNode> {
+        // Look through the buckets near the start and see if we find the edge
+        self.bucketsNear(fromVtx).find_map(|b| {
+            self.buckets[b].iter().find(|n| {
+                self.sameVertex(&n.from, fromVtx)
+                    && toVtx.is_none_or(|to| self.sameVertex(&n.to, to))
+            })
+        })
+    }
+
+    /**
+     * Find a Vertex node starting at the given vertex
+     * @param  fromVtx Start vertex
+     * @return         The vertex node, if found
+     */
+    pub fn findNodeForVertex(&self, fromVtx: &GeoCoord) -> Option<&VertexNode> {
+        self.findNodeForEdge(fromVtx, None)
+    }
+
+    /**
+     * Get the first vertex node in the graph.
+     * @return       Vertex node, or None if the graph is empty
+     */
+    pub fn firstVertexNode(&self) -> Option<&VertexNode> {
+        self.iter().next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Degrees;
+
+    fn geoDegs(lat: f64, lon: f64) -> GeoCoord {
+        GeoCoord::new(Degrees(lat), Degrees(lon))
+    }
+
+    fn vertexes() -> [GeoCoord; 4] {
+        [
+            geoDegs(87.372002166, 166.160981117),
+            geoDegs(87.370101364, 166.160184306),
+            geoDegs(87.369088356, 166.196239997),
+            geoDegs(87.369975080, 166.233115768),
+        ]
+    }
+
+    #[test]
+    fn makeVertexGraph() {
+        let graph = VertexGraph::new(10, Resolution::R9);
+        assert_eq!(graph.buckets.len(), 10, "numBuckets set");
+        assert_eq!(graph.size(), 0, "size set");
+
+        let mut graph = VertexGraph::new(0, Resolution::R9);
+        assert_eq!(graph.buckets.len(), 1, "at least one bucket");
+        let (a, b) = (geoDegs(37.77, -122.41), geoDegs(37.78, -122.42));
+        graph.addVertexNode(&a, &b);
+        assert!(graph.findNodeForEdge(&a, Some(&b)).is_some(), "edge added");
+        assert_eq!(graph.size(), 1);
+    }
+
+    #[test]
+    fn vertexHash() {
+        let center = geoDegs(37.77362016769341, -122.41673772517154);
+
+        for res in 0..11 {
+            let res = Resolution::from(res);
+            let graph = VertexGraph::new(1000, res);
+            let outline = center.geoToH3(res).h3ToGeoBoundary();
+            for i in 0..outline.numVerts {
+                let hash1 = graph._hashVertex(&outline.verts[i]);
+                let hash2 = graph._hashVertex(&outline.verts[(i + 1) % outline.numVerts]);
+                assert_ne!(hash1, hash2, "Hashes must not be equal");
+            }
+        }
+    }
+
+    #[test]
+    fn vertexHashNegative() {
+        let graph = VertexGraph::new(10, Resolution::R5);
+        assert!(
+            graph._hashVertex(&geoDegs(0.0, 0.0)) < 10,
+            "zero vertex hashes correctly"
+        );
+        assert!(
+            graph._hashVertex(&geoDegs(-10.0, -10.0)) < 10,
+            "negative coordinates vertex hashes correctly"
+        );
+    }
+
+    #[test]
+    fn addVertexNode() {
+        let [v1, v2, v3, v4] = vertexes();
+        let mut graph = VertexGraph::new(10, Resolution::R9);
+
+        // Basic add
+        let added = graph.addVertexNode(&v1, &v2);
+        assert_eq!(
+            graph.findNodeForEdge(&v1, Some(&v2)),
+            Some(&added),
+            "Node found"
+        );
+        assert_eq!(graph.size(), 1, "Graph size incremented");
+
+        // Collision add
+        let added = graph.addVertexNode(&v1, &v3);
+        assert_eq!(
+            graph.findNodeForEdge(&v1, Some(&v3)),
+            Some(&added),
+            "Node found after hash collision"
+        );
+        assert_eq!(graph.size(), 2, "Graph size incremented");
+
+        // Collision add #2
+        let added = graph.addVertexNode(&v1, &v4);
+        assert_eq!(
+            graph.findNodeForEdge(&v1, Some(&v4)),
+            Some(&added),
+            "Node found after 2nd hash collision"
+        );
+        assert_eq!(graph.size(), 3, "Graph size incremented");
+
+        // Exact match no-op
+        let added = graph.addVertexNode(&v1, &v2);
+        assert_eq!(added.to, v2, "Old node returned");
+        assert_eq!(graph.size(), 3, "Graph size was not changed");
+    }
+
+    #[test]
+    fn findNodeForEdge() {
+        let [v1, v2, v3, v4] = vertexes();
+        let mut graph = VertexGraph::new(10, Resolution::R9);
+
+        assert!(
+            graph.findNodeForEdge(&v1, Some(&v2)).is_none(),
+            "Node lookup failed correctly for empty graph"
+        );
+
+        graph.addVertexNode(&v1, &v2);
+        assert!(
+            graph.findNodeForEdge(&v3, Some(&v2)).is_none(),
+            "Node lookup failed correctly for different hash"
+        );
+        assert!(
+            graph.findNodeForEdge(&v1, Some(&v3)).is_none(),
+            "Node lookup failed correctly for hash collision"
+        );
+
+        graph.addVertexNode(&v1, &v4);
+        assert!(
+            graph.findNodeForEdge(&v1, Some(&v3)).is_none(),
+            "Node lookup failed correctly for collision w/iteration"
+        );
+    }
+
+    #[test]
+    fn findNodeForVertex() {
+        let [v1, v2, v3, _] = vertexes();
+        let mut graph = VertexGraph::new(10, Resolution::R9);
+
+        assert!(
+            graph.findNodeForVertex(&v1).is_none(),
+            "Node lookup failed correctly for empty graph"
+        );
+
+        graph.addVertexNode(&v1, &v2);
+        assert!(
+            graph.findNodeForVertex(&v1).is_some(),
+            "Node lookup succeeded for correct node"
+        );
+        assert!(
+            graph.findNodeForVertex(&v3).is_none(),
+            "Node lookup failed correctly for different node"
+        );
+    }
+
+    #[test]
+    fn removeVertexNode() {
+        let [v1, v2, v3, v4] = vertexes();
+        let mut graph = VertexGraph::new(10, Resolution::R9);
+
+        // Straight removal
+        let node = graph.addVertexNode(&v1, &v2);
+        assert!(graph.removeVertexNode(&node), "Removal successful");
+        assert!(
+            graph.findNodeForVertex(&v1).is_none(),
+            "Node lookup cannot find node"
+        );
+        assert_eq!(graph.size(), 0, "Graph size decremented");
+
+        // Remove end of list
+        graph.addVertexNode(&v1, &v2);
+        let node = graph.addVertexNode(&v1, &v3);
+        assert!(graph.removeVertexNode(&node), "Removal successful");
+        assert!(
+            graph.findNodeForEdge(&v1, Some(&v3)).is_none(),
+            "Node lookup cannot find node"
+        );
+        assert_eq!(graph.size(), 1, "Graph size decremented");
+
+        // Remove beginning of list
+        let node = graph.addVertexNode(&v1, &v3);
+        let first = *graph.findNodeForEdge(&v1, Some(&v2)).unwrap();
+        assert!(graph.removeVertexNode(&first), "Removal successful");
+        assert!(
+            graph.findNodeForEdge(&v1, Some(&v2)).is_none(),
+            "Node lookup cannot find node"
+        );
+        assert_eq!(
+            graph.findNodeForEdge(&v1, Some(&v3)),
+            Some(&node),
+            "Node lookup can find previous end of list"
+        );
+        assert_eq!(graph.size(), 1, "Graph size decremented");
+
+        // Remove middle of list
+        graph.addVertexNode(&v1, &v2);
+        graph.addVertexNode(&v1, &v4);
+        assert!(graph.removeVertexNode(&node), "Removal successful");
+        assert!(
+            graph.findNodeForEdge(&v1, Some(&v4)).is_some(),
+            "Node lookup can find previous end of list"
+        );
+        assert_eq!(graph.size(), 2, "Graph size decremented");
+
+        // Remove non-existent node
+        let missing = VertexNode {
+            from: GeoCoord::default(),
+            to: GeoCoord::default(),
+        };
+        assert!(
+            !graph.removeVertexNode(&missing),
+            "Removal of non-existent node fails"
+        );
+        assert_eq!(graph.size(), 2, "Graph size unchanged");
+    }
+
+    #[test]
+    fn firstVertexNode() {
+        let [v1, v2, _, _] = vertexes();
+        let mut graph = VertexGraph::new(10, Resolution::R9);
+        assert!(
+            graph.firstVertexNode().is_none(),
+            "No node found for empty graph"
+        );
+
+        let added = graph.addVertexNode(&v1, &v2);
+        assert_eq!(graph.firstVertexNode(), Some(&added), "Node found");
+    }
+
+    #[test]
+    fn vertexGraph_epsilon() {
+        let [v1, v2, v3, _] = vertexes();
+        let mut graph = VertexGraph::new(10, Resolution::R9);
+        assert_eq!(graph.epsilon(), VertexGraph::DEFAULT_EPSILON_RADS);
+        graph.addVertexNode(&v1, &v2);
+
+        let jitter = |v: GeoCoord, d: f64| GeoCoord::from_rads(v.lat + d, v.lon - d);
+        let near = jitter(v1, 0.9 * graph.epsilon());
+        let far = jitter(v1, 1.1 * graph.epsilon());
+        assert!(graph.findNodeForVertex(&near).is_some(), "within epsilon");
+        assert!(graph.findNodeForVertex(&far).is_none(), "past epsilon");
+        let node = graph.addVertexNode(&near, &jitter(v2, -0.5 * graph.epsilon()));
+        assert_eq!(graph.size(), 1, "jittered copy is the same edge");
+        assert!(graph.removeVertexNode(&node), "removed by a copy");
+        assert_eq!(graph.size(), 0, "removed");
+
+        // jitter across every hash boundary still matches
+        for i in 0..1000 {
+            let v = GeoCoord::from_rads(v3.lat + i as f64 * 1e-11, v3.lon);
+            let node = graph.addVertexNode(&v, &v1);
+            let copy = jitter(v, 1e-14);
+            assert_eq!(
+                graph.findNodeForEdge(&copy, Some(&v1)),
+                Some(&node),
+                "{}",
+                i
+            );
+            assert!(graph.removeVertexNode(&node));
+        }
+
+        // the antimeridian as -π and π
+        let east = GeoCoord::from_rads(0.1, M_PI);
+        let west = GeoCoord::from_rads(0.1, -M_PI);
+        graph.addVertexNode(&east, &v1);
+        assert!(
+            graph.findNodeForVertex(&west).is_some(),
+            "across the antimeridian"
+        );
+
+        let coarse = VertexGraph::with_epsilon(10, Resolution::R9, 1e-3).unwrap();
+        assert_eq!(coarse.epsilon(), 1e-3);
+        assert_eq!(coarse.res(), Resolution::R9);
+        for &bad in [0.0, -1e-9, f64::NAN, f64::INFINITY].iter() {
+            assert!(
+                VertexGraph::with_epsilon(10, Resolution::R9, bad).is_err(),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn singleBucketVertexGraph() {
+        let [v1, v2, v3, v4] = vertexes();
+        let mut graph = VertexGraph::new(1, Resolution::R9);
+        assert!(
+            graph.firstVertexNode().is_none(),
+            "No node found for empty graph"
+        );
+
+        let node = graph.addVertexNode(&v1, &v2);
+        assert_eq!(graph.firstVertexNode(), Some(&node), "First node is node");
+
+        graph.addVertexNode(&v2, &v3);
+        graph.addVertexNode(&v3, &v4);
+        assert_eq!(
+            graph.firstVertexNode(),
+            Some(&node),
+            "First node is still node"
+        );
+        assert_eq!(graph.size(), 3, "Graph size updated");
+    }
+}
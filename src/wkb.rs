@@ -0,0 +1,437 @@
+//! Well-Known Binary polygons in, behind the `wkb` feature.
+//!
+//! Reads Polygon and MultiPolygon geometries, and GeometryCollections of
+//! them, in either byte order, as written by PostGIS, GEOS, GDAL and
+//! GeoParquet. Z and M values, flagged the ISO way (type 1003, 2003, 3003,
+//! ...) or the EWKB way (high bits of the type), are read and dropped, and an
+//! EWKB SRID is skipped: coordinates are taken as longitude and latitude in
+//! degrees, whatever the SRID says. GeometryCollections may nest up to 64
+//! deep.
+
+use crate::{GeoMultiPolygon, GeoPolygon, Geofence, H3Index, ParseGeometryError, Resolution};
+
+/// WKB geometry type of a Polygon.
+const WKB_POLYGON: u32 = 3;
+/// WKB geometry type of a MultiPolygon.
+const WKB_MULTIPOLYGON: u32 = 6;
+/// WKB geometry type of a GeometryCollection.
+const WKB_GEOMETRYCOLLECTION: u32 = 7;
+
+/// Deepest nesting of GeometryCollections read. Each level is a recursive
+/// call, so untrusted bytes made of nothing but collection headers must not
+/// be followed all the way down.
+const MAX_COLLECTION_DEPTH: usize = 64;
+
+/// EWKB flags in the high bits of the geometry type.
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// Reads values off the front of WKB bytes.
+struct WkbReader<'a> {
+    bytes: &'a [u8],
+    littleEndian: bool,
+}
+
+impl<'a> WkbReader<'a> {
+    /// The next `n` bytes.
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ParseGeometryError> {
+        if self.bytes.len() < n {
+            return Err(ParseGeometryError::Syntax);
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    /// The byte order marker of a geometry, which applies to the rest of it.
+    fn byteOrder(&mut self) -> Result<(), ParseGeometryError> {
+        self.littleEndian = match self.take(1)?[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(ParseGeometryError::Syntax),
+        };
+        Ok(())
+    }
+
+    fn u32(&mut self) -> Result<u32, ParseGeometryError> {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(if self.littleEndian {
+            u32::from_le_bytes(buf)
+        } else {
+            u32::from_be_bytes(buf)
+        })
+    }
+
+    fn f64(&mut self) -> Result<f64, ParseGeometryError> {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Ok(if self.littleEndian {
+            f64::from_le_bytes(buf)
+        } else {
+            f64::from_be_bytes(buf)
+        })
+    }
+
+    /// A count of `size` byte items, checked against the bytes left so that
+    /// a corrupt count cannot ask for a huge allocation.
+    fn count(&mut self, size: usize) -> Result<usize, ParseGeometryError> {
+        let n = self.u32()? as usize;
+        if n.saturating_mul(size) > self.bytes.len() {
+            return Err(ParseGeometryError::Syntax);
+        }
+        Ok(n)
+    }
+
+    /**
+     * Reads the header of a geometry: its byte order, type and SRID.
+     *
+     * @return The base geometry type, such as WKB_POLYGON, and the number
+     *         of Z and M values following each longitude and latitude
+     */
+    fn header(&mut self) -> Result<(u32, usize), ParseGeometryError> {
+        self.byteOrder()?;
+        let code = self.u32()?;
+        let flags = code & (EWKB_Z | EWKB_M | EWKB_SRID);
+        if flags & EWKB_SRID != 0 {
+            self.u32()?;
+        }
+        let iso = code & !flags;
+        let extra = match iso / 1000 {
+            0 => 0,
+            1 | 2 => 1,
+            3 => 2,
+            _ => return Err(ParseGeometryError::UnsupportedGeometry),
+        };
+        let ewkbExtra = (flags & EWKB_Z != 0) as usize + (flags & EWKB_M != 0) as usize;
+        Ok((iso % 1000, extra.max(ewkbExtra)))
+    }
+
+    /// The rings of a polygon, after its header; none for an empty polygon.
+    fn polygon(&mut self, extra: usize) -> Result<Option<GeoPolygon>, ParseGeometryError> {
+        let pointSize = 8 * (2 + extra);
+        let numRings = self.count(4)?;
+        let mut rings = Vec::with_capacity(numRings);
+        for _ in 0..numRings {
+            let numPoints = self.count(pointSize)?;
+            let mut positions = Vec::with_capacity(numPoints);
+            for _ in 0..numPoints {
+                let lng = self.f64()?;
+                let lat = self.f64()?;
+                self.take(8 * extra)?;
+                positions.push([lng, lat]);
+            }
+            rings.push(Geofence::from_lng_lat_ring(positions)?);
+        }
+        if rings.is_empty() {
+            return Ok(None);
+        }
+        let exterior = rings.remove(0);
+        Ok(Some(GeoPolygon::from_geofences(exterior, rings)?))
+    }
+
+    /// Adds the polygons of the next geometry, inside `depth` collections,
+    /// to `out`.
+    fn geometry(
+        &mut self,
+        out: &mut Vec<GeoPolygon>,
+        depth: usize,
+    ) -> Result<(), ParseGeometryError> {
+        let (kind, extra) = self.header()?;
+        match kind {
+            WKB_POLYGON => out.extend(self.polygon(extra)?),
+            WKB_MULTIPOLYGON => {
+                for _ in 0..self.count(1)? {
+                    let (kind, extra) = self.header()?;
+                    if kind != WKB_POLYGON {
+                        return Err(ParseGeometryError::Syntax);
+                    }
+                    out.extend(self.polygon(extra)?);
+                }
+            }
+            WKB_GEOMETRYCOLLECTION => {
+                if depth == MAX_COLLECTION_DEPTH {
+                    return Err(ParseGeometryError::Syntax);
+                }
+                for _ in 0..self.count(1)? {
+                    self.geometry(out, depth + 1)?;
+                }
+            }
+            _ => return Err(ParseGeometryError::UnsupportedGeometry),
+        }
+        Ok(())
+    }
+}
+
+impl GeoMultiPolygon {
+    /**
+     * Reads the polygons of a WKB or EWKB geometry: a Polygon or
+     * MultiPolygon, or a GeometryCollection of them.
+     *
+     * @param bytes The geometry, with nothing after it
+     * @return The polygons, in the order they appear, without empty ones;
+     *         Syntax if the bytes are not one WKB geometry or nest
+     *         collections more than 64 deep,
+     *         UnsupportedGeometry if it holds another kind of geometry, or
+     *         Polygon if a ring is not valid
+     */
+    pub fn from_wkb(bytes: &[u8]) -> Result<Self, ParseGeometryError> {
+        let mut reader = WkbReader {
+            bytes,
+            littleEndian: true,
+        };
+        let mut polygons = Vec::new();
+        reader.geometry(&mut polygons, 0)?;
+        if !reader.bytes.is_empty() {
+            return Err(ParseGeometryError::Syntax);
+        }
+        Ok(GeoMultiPolygon { polygons })
+    }
+
+    /// The cells at a resolution whose centers are inside the polygons of a
+    /// WKB geometry; see `from_wkb` and `polyfill`.
+    pub fn polyfill_wkb(bytes: &[u8], res: Resolution) -> Result<Vec<H3Index>, ParseGeometryError> {
+        Ok(Self::from_wkb(bytes)?.polyfill(res))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PolygonError;
+
+    /// Writes WKB for tests, in either byte order.
+    struct Writer {
+        bytes: Vec<u8>,
+        littleEndian: bool,
+    }
+
+    impl Writer {
+        fn new(littleEndian: bool) -> Self {
+            Writer {
+                bytes: Vec::new(),
+                littleEndian,
+            }
+        }
+
+        fn header(&mut self, code: u32) {
+            self.bytes.push(self.littleEndian as u8);
+            self.u32(code);
+        }
+
+        fn u32(&mut self, v: u32) {
+            if self.littleEndian {
+                self.bytes.extend_from_slice(&v.to_le_bytes());
+            } else {
+                self.bytes.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+
+        fn f64(&mut self, v: f64) {
+            if self.littleEndian {
+                self.bytes.extend_from_slice(&v.to_le_bytes());
+            } else {
+                self.bytes.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+
+        /// A polygon's rings of `[lng, lat]`, each point followed by
+        /// `extra` more values.
+        fn rings(&mut self, rings: &[&[[f64; 2]]], extra: usize) {
+            self.u32(rings.len() as u32);
+            for ring in rings {
+                self.u32(ring.len() as u32);
+                for &[lng, lat] in ring.iter() {
+                    self.f64(lng);
+                    self.f64(lat);
+                    for _ in 0..extra {
+                        self.f64(100.0);
+                    }
+                }
+            }
+        }
+    }
+
+    const SQUARE: [[f64; 2]; 5] = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]];
+    const HOLE: [[f64; 2]; 4] = [[0.2, 0.2], [0.2, 0.4], [0.4, 0.4], [0.2, 0.2]];
+
+    fn expected(res: Resolution) -> Vec<H3Index> {
+        let exterior = Geofence::from_lng_lat_ring(SQUARE.iter().copied()).unwrap();
+        let hole = Geofence::from_lng_lat_ring(HOLE.iter().copied()).unwrap();
+        GeoPolygon::from_geofences(exterior, vec![hole])
+            .unwrap()
+            .polyfill(res)
+    }
+
+    #[test]
+    fn fromWkb_polygon() {
+        for &littleEndian in [true, false].iter() {
+            let mut w = Writer::new(littleEndian);
+            w.header(WKB_POLYGON);
+            w.rings(&[&SQUARE, &HOLE], 0);
+            let polygons = GeoMultiPolygon::from_wkb(&w.bytes).unwrap();
+            assert_eq!(polygons.polygons.len(), 1, "one polygon");
+            assert_eq!(
+                GeoMultiPolygon::polyfill_wkb(&w.bytes, Resolution::R5),
+                Ok(expected(Resolution::R5)),
+                "little endian {}",
+                littleEndian
+            );
+        }
+    }
+
+    #[test]
+    fn fromWkb_dimensionsAndSrid() {
+        // ISO Z, M and ZM, then EWKB Z with an SRID
+        for &(code, extra) in [(1003, 1), (2003, 1), (3003, 2)].iter() {
+            let mut w = Writer::new(true);
+            w.header(code);
+            w.rings(&[&SQUARE, &HOLE], extra);
+            assert_eq!(
+                GeoMultiPolygon::polyfill_wkb(&w.bytes, Resolution::R5),
+                Ok(expected(Resolution::R5)),
+                "type {}",
+                code
+            );
+        }
+        let mut w = Writer::new(false);
+        w.header(WKB_POLYGON | EWKB_Z | EWKB_SRID);
+        w.u32(4326);
+        w.rings(&[&SQUARE, &HOLE], 1);
+        assert_eq!(
+            GeoMultiPolygon::polyfill_wkb(&w.bytes, Resolution::R5),
+            Ok(expected(Resolution::R5)),
+            "ewkb"
+        );
+    }
+
+    #[test]
+    fn fromWkb_multiPolygon() {
+        let shifted: Vec<[f64; 2]> = SQUARE.iter().map(|&[x, y]| [x + 1.0, y]).collect();
+        let mut w = Writer::new(true);
+        w.header(WKB_GEOMETRYCOLLECTION);
+        w.u32(2);
+        w.header(WKB_MULTIPOLYGON);
+        w.u32(2);
+        w.header(WKB_POLYGON);
+        w.rings(&[&SQUARE], 0);
+        w.header(WKB_POLYGON);
+        w.rings(&[], 0);
+        // an item in its own byte order
+        w.littleEndian = false;
+        w.header(WKB_POLYGON);
+        w.rings(&[&shifted], 0);
+
+        let polygons = GeoMultiPolygon::from_wkb(&w.bytes).unwrap();
+        assert_eq!(polygons.polygons.len(), 2, "empty polygon dropped");
+
+        let strip = GeoPolygon::from_geofences(
+            Geofence::from_lng_lat_ring(vec![[0.0, 0.0], [2.0, 0.0], [2.0, 1.0], [0.0, 1.0]])
+                .unwrap(),
+            Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            polygons.polyfill(Resolution::R5),
+            strip.polyfill(Resolution::R5),
+            "the squares tile a strip"
+        );
+    }
+
+    #[test]
+    fn fromWkb_nestedCollections() {
+        let nested = |levels: usize| {
+            let mut w = Writer::new(true);
+            for _ in 0..levels {
+                w.header(WKB_GEOMETRYCOLLECTION);
+                w.u32(1);
+            }
+            w.header(WKB_POLYGON);
+            w.rings(&[&SQUARE], 0);
+            w.bytes
+        };
+        assert_eq!(
+            GeoMultiPolygon::from_wkb(&nested(MAX_COLLECTION_DEPTH)).map(|m| m.polygons.len()),
+            Ok(1),
+            "deepest allowed"
+        );
+        assert_eq!(
+            GeoMultiPolygon::from_wkb(&nested(MAX_COLLECTION_DEPTH + 1)).err(),
+            Some(ParseGeometryError::Syntax),
+            "one level too deep"
+        );
+        // Deep enough to overflow the stack if followed.
+        assert_eq!(
+            GeoMultiPolygon::from_wkb(&nested(200_000)).err(),
+            Some(ParseGeometryError::Syntax),
+            "no stack overflow"
+        );
+    }
+
+    #[test]
+    fn fromWkb_errors() {
+        let mut w = Writer::new(true);
+        w.header(WKB_POLYGON);
+        w.rings(&[&SQUARE], 0);
+        let valid = w.bytes.clone();
+
+        let parse = GeoMultiPolygon::from_wkb;
+        assert_eq!(parse(&[]).err(), Some(ParseGeometryError::Syntax), "empty");
+        assert_eq!(
+            parse(&valid[..valid.len() - 1]).err(),
+            Some(ParseGeometryError::Syntax),
+            "truncated"
+        );
+        let mut trailing = valid.clone();
+        trailing.push(0);
+        assert_eq!(
+            parse(&trailing).err(),
+            Some(ParseGeometryError::Syntax),
+            "trailing"
+        );
+        let mut badOrder = valid.clone();
+        badOrder[0] = 2;
+        assert_eq!(
+            parse(&badOrder).err(),
+            Some(ParseGeometryError::Syntax),
+            "byte order"
+        );
+        let mut hugeCount = valid;
+        hugeCount[5..9].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            parse(&hugeCount).err(),
+            Some(ParseGeometryError::Syntax),
+            "count past the end"
+        );
+
+        let mut point = Writer::new(true);
+        point.header(1);
+        point.f64(0.0);
+        point.f64(0.0);
+        assert_eq!(
+            parse(&point.bytes).err(),
+            Some(ParseGeometryError::UnsupportedGeometry),
+            "point"
+        );
+
+        let mut notPolygon = Writer::new(true);
+        notPolygon.header(WKB_MULTIPOLYGON);
+        notPolygon.u32(1);
+        notPolygon.header(1);
+        assert_eq!(
+            parse(&notPolygon.bytes).err(),
+            Some(ParseGeometryError::Syntax),
+            "multipolygon of points"
+        );
+
+        let mut line = Writer::new(true);
+        line.header(WKB_POLYGON);
+        line.rings(&[&[[0.0, 0.0], [1.0, 0.0], [0.0, 0.0]]], 0);
+        assert_eq!(
+            parse(&line.bytes).err(),
+            Some(ParseGeometryError::Polygon(PolygonError::TooFewVertices)),
+            "ring"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Checks h3ToGeoBoundary against boundaries computed by the reference
+//! implementation, for every base cell and the resolutions just below it.
+
+#![allow(non_snake_case)]
+
+use h3rs::{BaseCell, Degrees, GeoCoord, H3Index};
+
+/// Largest allowed distance between a computed and expected vertex, in radians.
+const EPSILON: f64 = 1e-9;
+
+/// Cells with their expected boundary vertexes, from the fixture file.
+fn boundaries() -> Vec<(H3Index, Vec<GeoCoord>)> {
+    let mut out = Vec::new();
+
+    for line in include_str!("inputs/boundaries.txt").lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let h: H3Index = fields.next().unwrap().parse().unwrap();
+        let numVerts: usize = fields.next().unwrap().parse().unwrap();
+        let verts: Vec<GeoCoord> = fields
+            .map(|vert| {
+                let (lat, lng) = vert.split_once(',').unwrap();
+                GeoCoord::new(Degrees(lat.parse().unwrap()), Degrees(lng.parse().unwrap()))
+            })
+            .collect();
+        assert_eq!(verts.len(), numVerts, "fixture for {} is complete", h);
+        out.push((h, verts));
+    }
+
+    out
+}
+
+#[test]
+fn h3ToGeoBoundary_baseCells() {
+    let boundaries = boundaries();
+    assert_eq!(
+        boundaries.len(),
+        2 * BaseCell::NUM_BASE_CELLS + 842,
+        "fixtures loaded"
+    );
+
+    for (h, expected) in boundaries.iter() {
+        let gb = h.h3ToGeoBoundary();
+        assert_eq!(gb.numVerts, expected.len(), "vertex count of {}", h);
+
+        for (i, (vert, expected)) in gb.verts[..gb.numVerts].iter().zip(expected).enumerate() {
+            assert!(
+                GeoCoord::pointDistRads(vert, expected) < EPSILON,
+                "vertex {} of {} is {:?}, expected {:?}",
+                i,
+                h,
+                vert,
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn h3ToGeoBoundary_vertexCounts() {
+    let mut distorted = 0;
+
+    for (h, _) in boundaries() {
+        let numVerts = h.h3ToGeoBoundary().numVerts;
+        let sides = if h.is_pentagon() { 5 } else { 6 };
+
+        if !is_class_iii(&h) {
+            // Class II cell edges meet icosahedron edges only at vertexes,
+            // so no distortion vertexes are needed.
+            assert_eq!(numVerts, sides, "{} has only topological vertexes", h);
+        } else if h.is_pentagon() {
+            // Each side of a class III pentagon crosses an icosahedron edge.
+            assert_eq!(
+                numVerts,
+                2 * sides,
+                "{} has a distortion vertex per side",
+                h
+            );
+        } else {
+            // Class III hexagons get a distortion vertex per edge crossed.
+            assert!(
+                numVerts >= sides && numVerts <= 2 * sides,
+                "{} has {} vertexes",
+                h,
+                numVerts
+            );
+            if numVerts > sides {
+                distorted += 1;
+            }
+        }
+    }
+
+    assert_eq!(distorted, 270, "hexagons crossing icosahedron edges");
+}
+
+/// Whether the cell is at a class III (odd) resolution.
+fn is_class_iii(h: &H3Index) -> bool {
+    h.bit_layout().resolution % 2 == 1
+}
@@ -0,0 +1,132 @@
+//! Uses the public API of each optional feature, under that feature's cfg,
+//! so that `cargo test --features <feature>` checks the feature builds and
+//! its items are reachable from outside the crate. The core test runs in
+//! every build, the default one with no features included.
+
+#![allow(non_snake_case)]
+
+use h3rs::{Degrees, GeoCoord, H3Index, Resolution};
+
+fn sf() -> H3Index {
+    GeoCoord::new(Degrees(37.779265), Degrees(-122.419277)).geoToH3(Resolution::R9)
+}
+
+#[test]
+fn core() {
+    let h = sf();
+    assert!(h.is_valid());
+    assert_eq!(h.kRing(1).len(), 7);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+    let h = sf();
+    let json = serde_json::to_string(&h).unwrap();
+    assert_eq!(json, format!("\"{}\"", h));
+    let back: H3Index = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, h);
+}
+
+#[cfg(feature = "geojson")]
+#[test]
+fn geojson() {
+    let text = r#"{"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 1]]]}"#;
+    let cells = h3rs::GeoMultiPolygon::polyfill_geojson(text, Resolution::R5).unwrap();
+    assert!(!cells.is_empty());
+}
+
+#[cfg(feature = "wkb")]
+#[test]
+fn wkb() {
+    // POLYGON ((0 0, 1 0, 1 1, 0 1, 0 0)), little endian
+    let mut bytes = vec![1];
+    bytes.extend_from_slice(&3u32.to_le_bytes());
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes.extend_from_slice(&5u32.to_le_bytes());
+    for &(x, y) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)].iter() {
+        bytes.extend_from_slice(&f64::to_le_bytes(x));
+        bytes.extend_from_slice(&f64::to_le_bytes(y));
+    }
+    let cells = h3rs::GeoMultiPolygon::polyfill_wkb(&bytes, Resolution::R5).unwrap();
+    assert!(!cells.is_empty());
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn rand() {
+    use ::rand::SeedableRng;
+    use rand_pcg::Pcg64;
+
+    let h = sf();
+    let points = h.sample_points(10, &mut Pcg64::seed_from_u64(1));
+    assert_eq!(points.len(), 10);
+    assert!(points.iter().all(|p| p.geoToH3(Resolution::R9) == h));
+}
+
+#[cfg(feature = "rstar")]
+#[test]
+fn rstar() {
+    use ::rstar::{RTree, AABB};
+
+    let cells = sf().kRing(1);
+    let tree = RTree::bulk_load(cells.clone());
+    let (lng, lat) = sf().h3ToGeo().lng_lat();
+    let found: Vec<&H3Index> = tree
+        .locate_in_envelope_intersecting(&AABB::from_point([lng.0, lat.0]))
+        .collect();
+    assert!(found.contains(&&sf()));
+}
+
+#[cfg(feature = "f32")]
+#[test]
+fn f32() {
+    let gb = sf().h3ToGeoBoundary();
+    let small = h3rs::GeoBoundaryF32::from(&gb);
+    assert_eq!(small.len(), gb.numVerts);
+    let back = h3rs::GeoBoundary::from(&small);
+    assert!(
+        h3rs::GeoCoord::pointDistRads(&back.verts[0], &gb.verts[0])
+            <= h3rs::F32_BOUNDARY_MAX_ERROR_RADS
+    );
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_chunks() {
+    use std::{
+        future::Future,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    // The chunks are ready at once, so one poll finishes the future.
+    let mut chunks = 0;
+    let polled = {
+        let future = h3rs::chunked::for_each_chunk(sf().kRing(2), 10, |_| {
+            chunks += 1;
+            async { Ok::<(), ()>(()) }
+        });
+        let mut context = Context::from_waker(Waker::noop());
+        pin!(future).as_mut().poll(&mut context)
+    };
+    assert_eq!(polled, Poll::Ready(Ok(19)));
+    assert_eq!(chunks, 2);
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn arrow() {
+    use arrow_array::{cast::AsArray, types::UInt64Type, ArrayRef, Int64Array, UInt64Array};
+    use std::sync::Arc;
+
+    let args: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from(vec![u64::from(sf())])),
+        Arc::new(Int64Array::from(vec![5])),
+    ];
+    let parents = h3rs::udf::cell_to_parent(&args).unwrap();
+    assert_eq!(
+        parents.as_primitive::<UInt64Type>().value(0),
+        u64::from(sf().h3ToParent(Resolution::R5).unwrap())
+    );
+}
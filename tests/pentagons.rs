@@ -0,0 +1,138 @@
+//! Runs the grid algorithms around every pentagon at res 1 to 3, where the
+//! deleted k subsequence makes them most likely to go wrong, and checks that
+//! they fail exactly where the reference implementation does.
+
+#![allow(non_snake_case)]
+
+use h3rs::{CoordIJ, H3Index, Resolution};
+use std::collections::BTreeMap;
+
+/// Expected results of a grid operation from an origin to a destination.
+struct Expected {
+    distance: Option<i32>,
+    localIj: Option<CoordIJ>,
+    lineSize: Option<usize>,
+}
+
+fn field<T: std::str::FromStr>(s: &str) -> Option<T> {
+    match s {
+        "-" => None,
+        _ => Some(s.parse().ok().unwrap()),
+    }
+}
+
+/// Expected results by origin, then destination, from the fixture file.
+fn expected() -> BTreeMap<u64, BTreeMap<u64, Expected>> {
+    let mut out: BTreeMap<u64, BTreeMap<u64, Expected>> = BTreeMap::new();
+
+    for line in include_str!("inputs/pentagons.txt").lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let origin: H3Index = fields[0].parse().unwrap();
+        let destination: H3Index = fields[1].parse().unwrap();
+        let localIj = field::<String>(fields[3]).map(|ij| {
+            let (i, j) = ij.split_once(',').unwrap();
+            CoordIJ {
+                i: i.parse().unwrap(),
+                j: j.parse().unwrap(),
+            }
+        });
+        let expected = Expected {
+            distance: field(fields[2]),
+            localIj,
+            lineSize: field(fields[4]),
+        };
+        out.entry(u64::from(origin))
+            .or_default()
+            .insert(u64::from(destination), expected);
+    }
+
+    out
+}
+
+#[test]
+fn pentagonNeighborhoods() {
+    let expected = expected();
+    let mut origins = 0;
+
+    for &res in [Resolution::R1, Resolution::R2, Resolution::R3].iter() {
+        for pentagon in res.getPentagonIndexes().iter() {
+            for origin in pentagon.kRing(1) {
+                origins += 1;
+                checkNeighborhood(origin, &expected[&u64::from(origin)]);
+                checkChildren(origin, res);
+            }
+        }
+    }
+
+    // 12 pentagons and their 5 neighbors at each resolution
+    assert_eq!(origins, 3 * 12 * 6, "every origin checked");
+}
+
+fn checkNeighborhood(origin: H3Index, destinations: &BTreeMap<u64, Expected>) {
+    let mut disk: Vec<u64> = origin.kRing(2).into_iter().map(u64::from).collect();
+    disk.sort();
+    assert!(
+        disk.iter().eq(destinations.keys()),
+        "kRing of {} matches",
+        origin
+    );
+
+    for (&destination, e) in destinations.iter() {
+        let destination = H3Index::from(destination);
+        assert_eq!(
+            origin.h3Distance(&destination).ok(),
+            e.distance,
+            "h3Distance from {} to {}",
+            origin,
+            destination
+        );
+
+        let ij = H3Index::experimentalH3ToLocalIj(origin, destination).ok();
+        assert_eq!(ij, e.localIj, "local IJ of {} from {}", destination, origin);
+        if let Some(ij) = ij {
+            assert_eq!(
+                origin.experimentalLocalIjToH3(&ij),
+                Ok(destination),
+                "local IJ of {} from {} round trips",
+                destination,
+                origin
+            );
+        }
+
+        assert_eq!(
+            H3Index::h3Line(origin, destination).ok().map(|l| l.len()),
+            e.lineSize,
+            "h3Line from {} to {}",
+            origin,
+            destination
+        );
+    }
+}
+
+fn checkChildren(origin: H3Index, res: Resolution) {
+    let levels = 4 - usize::from(res) as u32;
+    // a pentagon has a pentagon center child and five hexagon children, the
+    // k axis child being deleted
+    let expectedCount = if origin.is_pentagon() {
+        1 + 5 * (7usize.pow(levels) - 1) / 6
+    } else {
+        7usize.pow(levels)
+    };
+
+    let children = origin.h3ToChildren(Resolution::R4);
+    assert_eq!(children.len(), expectedCount, "children of {}", origin);
+    for child in children.iter() {
+        assert!(child.is_valid(), "child {} of {} is valid", child, origin);
+        assert_eq!(
+            child.h3ToParent(res),
+            Ok(origin),
+            "child {} has {} as parent",
+            child,
+            origin
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Covers simple shapes with cells end to end and checks the coverings
+//! against the shapes' analytic areas and against point-in-polygon.
+//!
+//! The coverings come from `GeoPolygon::polyfill`, which takes the cells
+//! whose centers are inside the polygon.
+
+#![allow(non_snake_case)]
+
+use h3rs::{BBox, Degrees, GeoCoord, GeoPolygon, Geofence, H3Index, Resolution};
+use std::collections::HashSet;
+
+const EARTH_RADIUS_KM: f64 = 6371.007180918475;
+
+fn rads(degrees: f64) -> f64 {
+    degrees.to_radians()
+}
+
+/// The exterior of a polygon, for point-in-polygon tests.
+fn exterior(polygon: &GeoPolygon) -> Geofence {
+    Geofence::new(polygon.exterior().iter().copied()).expect("valid exterior")
+}
+
+/// Points spread over a box, `n` by `n`, edges excluded.
+fn samples(bbox: &BBox, n: usize) -> Vec<GeoCoord> {
+    let width = lngSpan(bbox);
+    let mut points = Vec::with_capacity(n * n);
+    for i in 0..n {
+        for j in 0..n {
+            let lat = bbox.south + (bbox.north - bbox.south) * (i as f64 + 0.5) / n as f64;
+            let lng = bbox.west + width * (j as f64 + 0.5) / n as f64;
+            points.push(GeoCoord::new(
+                Degrees(lat.to_degrees()),
+                Degrees(lng.to_degrees()),
+            ));
+        }
+    }
+    points
+}
+
+/// Width of a box in radians of longitude, across the antimeridian if it
+/// crosses it.
+fn lngSpan(bbox: &BBox) -> f64 {
+    if bbox.bboxIsTransmeridian() {
+        bbox.east - bbox.west + 2.0 * std::f64::consts::PI
+    } else {
+        bbox.east - bbox.west
+    }
+}
+
+/// The cells of a resolution whose centers are inside a box's polygon.
+fn cover(bbox: &BBox, res: Resolution) -> Vec<H3Index> {
+    GeoPolygon::from_bbox(bbox)
+        .expect("valid box")
+        .polyfill(res)
+}
+
+/// Area of a box between two parallels and two meridians, in km^2.
+fn bboxAreaKm2(bbox: &BBox) -> f64 {
+    EARTH_RADIUS_KM * EARTH_RADIUS_KM * (bbox.north.sin() - bbox.south.sin()) * lngSpan(bbox)
+}
+
+/// Length of a box's boundary, in km.
+fn bboxPerimeterKm(bbox: &BBox) -> f64 {
+    let meridians = 2.0 * (bbox.north - bbox.south);
+    let parallels = (bbox.north.cos() + bbox.south.cos()) * lngSpan(bbox);
+    EARTH_RADIUS_KM * (meridians + parallels)
+}
+
+/**
+ * Checks a covering of a box: every cell's center is inside, every sample
+ * point whose cell's center is inside is covered, and the covered area is
+ * the box's area give or take a band of cells along its boundary.
+ */
+fn assertCovering(name: &str, bbox: &BBox, res: Resolution, cells: &[H3Index]) {
+    let polygon = GeoPolygon::from_bbox(bbox).expect("valid box");
+    let fence = exterior(&polygon);
+
+    let set: HashSet<H3Index> = cells.iter().copied().collect();
+    assert_eq!(set.len(), cells.len(), "{}: no duplicates", name);
+    for h in cells {
+        assert!(h.is_valid(), "{}: {} valid", name, h);
+        assert_eq!(h.h3ToParent(res), Ok(*h), "{}: {} resolution", name, h);
+        assert!(
+            fence.contains(&h.h3ToGeo()),
+            "{}: {} center inside",
+            name,
+            h
+        );
+    }
+
+    for g in samples(bbox, 100) {
+        let h = g.geoToH3(res);
+        assert_eq!(
+            set.contains(&h),
+            fence.contains(&h.h3ToGeo()),
+            "{}: cell {} of {:?}",
+            name,
+            h,
+            g
+        );
+    }
+
+    let areaKm2 = bboxAreaKm2(bbox);
+    let coveredKm2: f64 = cells.iter().map(|h| h.cellAreaKm2()).sum();
+    // Cells straddling the boundary are in or out by their centers, so the
+    // difference is at most a band one cell spacing wide.
+    let bandKm2 = bboxPerimeterKm(bbox) * res.edgeLengthKm() * 3f64.sqrt();
+    assert!(
+        (coveredKm2 - areaKm2).abs() <= bandKm2,
+        "{}: covered {} km2 of {} km2, band {} km2",
+        name,
+        coveredKm2,
+        areaKm2,
+        bandKm2
+    );
+
+    let meanCellKm2 = coveredKm2 / cells.len() as f64;
+    let (low, high) = (
+        (areaKm2 - bandKm2) / meanCellKm2,
+        (areaKm2 + bandKm2) / meanCellKm2,
+    );
+    assert!(
+        (low..=high).contains(&(cells.len() as f64)),
+        "{}: {} cells, expected {} to {}",
+        name,
+        cells.len(),
+        low,
+        high
+    );
+}
+
+#[test]
+fn polyfill_equatorialSquare() {
+    let bbox = BBox::new(rads(0.5), rads(-0.5), rads(0.5), rads(-0.5));
+    for &res in [Resolution::R5, Resolution::R6].iter() {
+        let cells = cover(&bbox, res);
+        assertCovering("equatorial square", &bbox, res, &cells);
+    }
+}
+
+#[test]
+fn polyfill_transmeridianRectangle() {
+    let bbox = BBox::new(rads(12.0), rads(10.0), rads(-178.5), rads(178.0));
+    let res = Resolution::R5;
+    let cells = cover(&bbox, res);
+    assertCovering("transmeridian rectangle", &bbox, res, &cells);
+
+    // The same cells as the two halves on either side of the antimeridian.
+    let west = BBox::new(bbox.north, bbox.south, std::f64::consts::PI, bbox.west);
+    let east = BBox::new(bbox.north, bbox.south, bbox.east, -std::f64::consts::PI);
+    let whole: HashSet<H3Index> = cells.into_iter().collect();
+    let halves: HashSet<H3Index> = cover(&west, res)
+        .into_iter()
+        .chain(cover(&east, res))
+        .collect();
+    assert_eq!(whole, halves, "halves");
+}
+
+#[test]
+fn polyfill_polarCap() {
+    // Loops around a pole are not supported, so this is the cap around the
+    // north pole short of enclosing it: a quarter of the ring of latitudes
+    // from 80 to 89 degrees, where cells are most distorted.
+    let bbox = BBox::new(rads(89.0), rads(80.0), rads(90.0), rads(0.0));
+    let res = Resolution::R4;
+    let cells = cover(&bbox, res);
+    assertCovering("polar cap", &bbox, res, &cells);
+
+    let pole = GeoCoord::new(Degrees(90.0), Degrees(0.0)).geoToH3(res);
+    assert!(!cells.contains(&pole), "pole cell is outside");
+}
@@ -0,0 +1,199 @@
+//! Golden files for the text the library writes: index strings and their
+//! debugging forms, GeoJSON built from cell boundaries, and the polygons of
+//! `h3SetToLinkedGeo`. A change to any of these formats shows up as a diff
+//! against tests/snapshots/*.txt.
+//!
+//! After an intended format change, rewrite the files and review the diff:
+//!
+//! ```text
+//! UPDATE_SNAPSHOTS=1 cargo test --test snapshots
+//! ```
+
+#![allow(non_snake_case)]
+
+use std::{fmt::Write, fs, path::PathBuf};
+
+use h3rs::{audit_ids, CoordIJ, Degrees, GeoCoord, H3Error, H3Index, Resolution, Tile};
+use serde_json::json;
+
+/// Compares text with its golden file, or writes the file when
+/// UPDATE_SNAPSHOTS is set.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.txt", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).expect("snapshot written");
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "no snapshot at {}; run with UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        )
+    });
+    if expected == actual {
+        return;
+    }
+    let (line, (e, a)) = expected
+        .lines()
+        .chain(std::iter::repeat("<end>"))
+        .zip(actual.lines().chain(std::iter::repeat("<end>")))
+        .enumerate()
+        .find(|(_, (e, a))| e != a)
+        .expect("texts differ in a line");
+    panic!(
+        "{} differs at line {}:\n expected: {}\n   actual: {}\nrun with UPDATE_SNAPSHOTS=1 to accept",
+        path.display(),
+        line + 1,
+        e,
+        a
+    );
+}
+
+fn sf(res: Resolution) -> H3Index {
+    GeoCoord::new(Degrees(37.779265), Degrees(-122.419277)).geoToH3(res)
+}
+
+/// A coordinate rounded to 1e-9 degrees, past which the last bits of the
+/// math library's trigonometry could show.
+fn rounded(x: f64) -> f64 {
+    (x * 1e9).round() / 1e9
+}
+
+#[test]
+fn indexStrings() {
+    let pentagon = Resolution::R7.getPentagonIndexes()[3];
+    let edge = sf(Resolution::R9)
+        .getH3UnidirectionalEdgesFromHexagon()
+        .as_slice()[2];
+    let vertex = sf(Resolution::R9).cellToVertex(4);
+    let mut cells = vec![
+        sf(Resolution::R0),
+        sf(Resolution::R5),
+        sf(Resolution::R9),
+        sf(Resolution::R15),
+        Resolution::R0.getPentagonIndexes()[0],
+        pentagon,
+    ];
+
+    let mut out = String::new();
+    for &h in cells.iter() {
+        let center = h.h3ToGeo();
+        writeln!(out, "{}", h).unwrap();
+        writeln!(out, "  bits:   {}", h.bit_layout()).unwrap();
+        writeln!(out, "  digits: {}", h.to_digit_rle()).unwrap();
+        writeln!(out, "  center: {:.9} {:#.6}", center, center).unwrap();
+    }
+    for &h in [edge, vertex].iter() {
+        writeln!(out, "{}", h).unwrap();
+        writeln!(out, "  bits:   {}", h.bit_layout()).unwrap();
+    }
+
+    let ij: CoordIJ =
+        H3Index::experimentalH3ToLocalIj(sf(Resolution::R9), sf(Resolution::R9).kRing(2)[10])
+            .unwrap();
+    writeln!(out, "local ij: {}", ij).unwrap();
+    writeln!(out, "tile: {}", Tile::new(12, 655, 1583).unwrap()).unwrap();
+    for e in [
+        H3Error::ResolutionMismatch,
+        H3Error::CellInvalid,
+        H3Error::Domain,
+        H3Error::Cancelled,
+        H3Error::Pentagon,
+        H3Error::DuplicateInput,
+    ]
+    .iter()
+    {
+        writeln!(out, "error: {}", e).unwrap();
+    }
+
+    cells.extend([edge, vertex].iter());
+    let mut ids: Vec<u64> = cells.iter().map(|&h| u64::from(h)).collect();
+    ids.push(0xffff_ffff_ffff_ffff);
+    write!(out, "audit: {}", audit_ids(&ids)).unwrap();
+
+    assert_snapshot("index_strings", &out);
+}
+
+#[test]
+fn boundariesGeojson() {
+    let pentagon = Resolution::R3.getPentagonIndexes()[7];
+    let mut cells = sf(Resolution::R8).kRingDistances(1);
+    cells.sort();
+    cells.push((pentagon, 0));
+
+    let features: Vec<_> = cells
+        .iter()
+        .map(|&(h, distance)| {
+            let ring: Vec<[f64; 2]> = h
+                .h3ToGeoBoundary()
+                .to_lng_lat_ring()
+                .into_iter()
+                .map(|[lng, lat]| [rounded(lng), rounded(lat)])
+                .collect();
+            json!({
+                "type": "Feature",
+                "properties": { "h3": h.to_string(), "distance": distance },
+                "geometry": { "type": "Polygon", "coordinates": [ring] },
+            })
+        })
+        .collect();
+    let collection = json!({ "type": "FeatureCollection", "features": features });
+
+    let mut out = serde_json::to_string_pretty(&collection).unwrap();
+    out.push('\n');
+    assert_snapshot("boundaries_geojson", &out);
+}
+
+#[test]
+fn linkedGeo() {
+    // A ring around a missing center, for a hole, and a pentagon's disk.
+    let origin = sf(Resolution::R9);
+    let mut cells: Vec<H3Index> = origin
+        .kRing(2)
+        .into_iter()
+        .filter(|&h| h != origin)
+        .collect();
+    cells.extend(Resolution::R9.getPentagonIndexes()[2].kRing(1));
+    let multi = H3Index::h3SetToLinkedGeo(&cells).unwrap();
+
+    // Polygons come in no particular order, and rings start at any vertex;
+    // start each ring at its smallest vertex, and sort.
+    let ring = |verts: &[GeoCoord]| {
+        let text: Vec<String> = verts.iter().map(|v| format!("{:.9}", v)).collect();
+        let start = (0..text.len()).min_by_key(|&i| &text[i]).unwrap_or(0);
+        let mut rotated = text[start..].to_vec();
+        rotated.extend_from_slice(&text[..start]);
+        rotated.join(" ")
+    };
+    let mut polygons: Vec<String> = multi
+        .polygons
+        .iter()
+        .map(|p| {
+            let mut holes: Vec<String> = p
+                .holes()
+                .map(|h| format!("  hole: {}\n", ring(h)))
+                .collect();
+            holes.sort();
+            format!(
+                "polygon\n  exterior: {}\n{}",
+                ring(p.exterior()),
+                holes.concat()
+            )
+        })
+        .collect();
+    polygons.sort();
+
+    assert_snapshot("linked_geo", &polygons.concat());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serdeJson() {
+    let cells = vec![sf(Resolution::R9), Resolution::R0.getPentagonIndexes()[0]];
+    let mut out = serde_json::to_string(&cells).unwrap();
+    out.push('\n');
+    assert_snapshot("serde_json", &out);
+}
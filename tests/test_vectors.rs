@@ -0,0 +1,59 @@
+//! Checks h3ToGeo and geoToH3 against cell centers computed by the
+//! reference implementation.
+
+#![allow(non_snake_case)]
+
+use h3rs::{Degrees, GeoCoord, H3Index, Resolution};
+
+/// Largest allowed distance between a computed and expected center, in radians.
+const EPSILON: f64 = 1e-9;
+
+/// Cells with their resolution and expected center, from the fixture file.
+fn centers() -> Vec<(H3Index, Resolution, GeoCoord)> {
+    let mut out = Vec::new();
+    let mut res = Resolution::R0;
+
+    for line in include_str!("inputs/centers.txt").lines() {
+        if let Some(r) = line.strip_prefix("# res ") {
+            res = Resolution::from(r.parse::<usize>().unwrap());
+            continue;
+        } else if line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let h: H3Index = fields.next().unwrap().parse().unwrap();
+        let lat: f64 = fields.next().unwrap().parse().unwrap();
+        let lng: f64 = fields.next().unwrap().parse().unwrap();
+        let coord = GeoCoord::new(Degrees(lat), Degrees(lng));
+        out.push((h, res, coord));
+    }
+
+    out
+}
+
+#[test]
+fn h3ToGeo_centers() {
+    let centers = centers();
+    assert!(centers.len() > 800, "fixtures loaded");
+
+    for (h, _, expected) in centers.iter() {
+        assert!(h.is_valid(), "{} is a valid cell", h);
+
+        let center = h.h3ToGeo();
+        assert!(
+            GeoCoord::pointDistRads(&center, expected) < EPSILON,
+            "center of {} is {:?}, expected {:?}",
+            h,
+            center,
+            expected
+        );
+    }
+}
+
+#[test]
+fn geoToH3_centers() {
+    for (h, res, center) in centers() {
+        assert_eq!(center.geoToH3(res), h, "center of {} indexes to it", h);
+    }
+}